@@ -399,4 +399,28 @@ impl<T: frame_system::Config> pallet_xcm::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(4))
 			.saturating_add(T::DbWeight::get().writes(3))
 	}
+	/// Storage: `PolkadotXcm::QueryTimeouts` (r:2 w:1)
+	/// Proof: `PolkadotXcm::QueryTimeouts` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `PolkadotXcm::Queries` (r:1 w:1)
+	/// Proof: `PolkadotXcm::Queries` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn expire_query() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `6091`
+		Weight::from_parts(27_432_000, 0)
+			.saturating_add(Weight::from_parts(0, 6091))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: `PolkadotXcm::QueryTimeouts` (r:2 w:1)
+	/// Proof: `PolkadotXcm::QueryTimeouts` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `PolkadotXcm::Queries` (r:1 w:1)
+	/// Proof: `PolkadotXcm::Queries` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn expire_query_with_timeout_notify() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `6117`
+		Weight::from_parts(29_525_000, 0)
+			.saturating_add(Weight::from_parts(0, 6117))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
 }
@@ -248,6 +248,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = PolkadotXcm;
+	type XcmTracer = PolkadotXcm;
 }
 
 pub type PriceForParentDelivery =
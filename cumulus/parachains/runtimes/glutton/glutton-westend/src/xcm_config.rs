@@ -93,6 +93,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = ();
+	type XcmTracer = ();
 }
 
 impl cumulus_pallet_xcm::Config for Runtime {
@@ -160,6 +160,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = PolkadotXcm;
+	type XcmTracer = ();
 }
 
 /// No local origins on this chain are allowed to dispatch XCM sends/executions.
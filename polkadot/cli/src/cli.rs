@@ -56,6 +56,32 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Export the recent disputes recorded in the parachains db, with the votes cast in them,
+	/// as JSON.
+	ExportDisputes(ExportDisputesCmd),
+}
+
+/// The `export-disputes` subcommand.
+#[derive(Debug, Clone, Parser)]
+pub struct ExportDisputesCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for ExportDisputesCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
 }
 
 #[allow(missing_docs)]
@@ -142,6 +168,19 @@ pub struct RunCmd {
 	///  **Dangerous!** Do not touch unless explicitly advised to.
 	#[arg(long)]
 	pub prepare_workers_hard_max_num: Option<usize>,
+	/// Size in MiB of the cache of PVF execution results of backed candidates, which are reused
+	/// when approving the same candidates. Disabled if not specified.
+	#[arg(long, value_name = "MIB")]
+	pub pvf_execution_cache_size: Option<usize>,
+	/// Directory where prepared PVF artifacts are shared with other nodes of the same version
+	/// running on this host, so each PVF is only compiled once. Must differ from the node's own
+	/// artifact cache. Disabled if not specified.
+	///
+	/// The nodes sharing the directory execute each other's artifacts, so they must trust each
+	/// other. The directory must be owned by the user running the node and must not be writable by
+	/// other users, so in practice the nodes sharing it run as the same user.
+	#[arg(long, value_name = "PATH")]
+	pub pvf_shared_artifacts_dir: Option<PathBuf>,
 	/// TESTING ONLY: disable the version check between nodes and workers.
 	#[arg(long, hide = true)]
 	pub disable_worker_version_check: bool,
@@ -158,6 +197,13 @@ pub struct RunCmd {
 	/// networks.
 	#[arg(long)]
 	pub keep_finalized_for: Option<u32>,
+
+	/// Size in MiB the data and chunks in the availability store may take up. When exceeded, the
+	/// full available data and chunks of finalized candidates are dropped before their time,
+	/// then the full available data of candidates which are unfinalized or not included yet.
+	/// Unlimited if not specified.
+	#[arg(long, value_name = "MIB")]
+	pub availability_storage_budget: Option<u64>,
 }
 
 #[allow(missing_docs)]
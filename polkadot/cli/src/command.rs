@@ -234,8 +234,11 @@ where
 				execute_workers_max_num: cli.run.execute_workers_max_num,
 				prepare_workers_hard_max_num: cli.run.prepare_workers_hard_max_num,
 				prepare_workers_soft_max_num: cli.run.prepare_workers_soft_max_num,
+				pvf_execution_cache_size: cli.run.pvf_execution_cache_size,
+				pvf_shared_artifacts_dir: cli.run.pvf_shared_artifacts_dir,
 				enable_approval_voting_parallel: cli.run.enable_approval_voting_parallel,
 				keep_finalized_for: cli.run.keep_finalized_for,
+				availability_storage_budget: cli.run.availability_storage_budget,
 			},
 		)
 		.map(|full| full.task_manager)?;
@@ -463,6 +466,15 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			Ok(runner.sync_run(|config| cmd.run::<polkadot_service::Block>(&config))?)
 		},
+		Some(Subcommand::ExportDisputes(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let disputes = polkadot_service::export_disputes(&config)?;
+				serde_json::to_writer_pretty(std::io::stdout(), &disputes)
+					.map_err(|e| format!("Error writing JSON: {}", e))?;
+				Ok(())
+			})
+		},
 	}?;
 
 	#[cfg(feature = "pyroscope")]
@@ -53,6 +53,25 @@ fn construct_and_reconstruct_5mb_pov(c: &mut Criterion) {
 	}
 	group.finish();
 
+	let mut group = c.benchmark_group("construct_streaming");
+	for n_validators in N_VALIDATORS {
+		let expected_root = erasure_root(n_validators, &pov);
+
+		group.throughput(Throughput::Bytes(pov.len() as u64));
+		group.bench_with_input(
+			BenchmarkId::from_parameter(n_validators),
+			&n_validators,
+			|b, &n| {
+				b.iter(|| {
+					let chunks = polkadot_erasure_coding::obtain_chunks_streaming(n, &pov).unwrap();
+					let root = polkadot_erasure_coding::branches(&chunks).root();
+					assert_eq!(root, expected_root);
+				});
+			},
+		);
+	}
+	group.finish();
+
 	let mut group = c.benchmark_group("reconstruct_regular");
 	for n_validators in N_VALIDATORS {
 		let all_chunks = chunks(n_validators, &pov);
@@ -36,6 +36,13 @@ use thiserror::Error;
 
 use novelpoly::{CodeParams, WrappedShard};
 
+mod streaming;
+
+pub use streaming::{
+	obtain_chunks_streaming, obtain_chunks_streaming_v1, IncrementalReconstruction,
+	StreamingEncoder, DEFAULT_WINDOW_SYMBOLS,
+};
+
 // we are limited to the field order of GF(2^16), which is 65536
 const MAX_VALIDATORS: usize = novelpoly::f2e16::FIELD_SIZE;
 
@@ -0,0 +1,444 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Streaming erasure coding.
+//!
+//! The underlying code works on independent pieces of `2k` payload bytes, every piece yielding
+//! exactly one 2 byte symbol for each of the `n` chunks. This allows encoding a payload while it
+//! is being produced and reconstructing it while it is being consumed, keeping only a bounded
+//! window of payload bytes in memory. The produced chunks are byte for byte identical to the ones
+//! of [`obtain_chunks`](crate::obtain_chunks).
+//!
+//! Field operations use the AVX accelerated code paths of the underlying library when the `simd`
+//! feature is enabled and the target supports AVX, with a pure Rust fallback otherwise.
+
+use codec::{Decode, Encode, Input, Output};
+use polkadot_node_primitives::{AvailableData, Proof};
+use polkadot_primitives::{BlakeTwo256, Hash as H256, HashT};
+
+use novelpoly::{CodeParams, WrappedShard};
+
+use crate::{branch_hash, code_params, recovery_threshold, Error};
+
+/// The default number of symbols per chunk processed at once.
+pub const DEFAULT_WINDOW_SYMBOLS: usize = 512;
+
+/// Encodes a payload into erasure-coded chunks as it is written.
+///
+/// Payload bytes are buffered until a full window is available, which is then encoded and
+/// appended to the chunks. Implements [`codec::Output`], so any [`Encode`] type can be streamed
+/// into it directly without materializing its encoding.
+pub struct StreamingEncoder {
+	params: CodeParams,
+	window: Vec<u8>,
+	window_len: usize,
+	chunks: Vec<Vec<u8>>,
+	payload_len: usize,
+}
+
+impl StreamingEncoder {
+	/// Create a new encoder for `n_validators`, using the default window size.
+	pub fn new(n_validators: usize) -> Result<Self, Error> {
+		Self::with_window_symbols(n_validators, DEFAULT_WINDOW_SYMBOLS)
+	}
+
+	/// Create a new encoder for `n_validators`, encoding `window_symbols` symbols of every chunk
+	/// at once.
+	pub fn with_window_symbols(n_validators: usize, window_symbols: usize) -> Result<Self, Error> {
+		let params = code_params(n_validators)?;
+		let window_len = window_symbols.max(1) * params.k() * 2;
+
+		Ok(Self {
+			params,
+			window: Vec::with_capacity(window_len),
+			window_len,
+			chunks: vec![Vec::new(); n_validators],
+			payload_len: 0,
+		})
+	}
+
+	/// Reserve space in the chunks for a payload of `payload_len` bytes in total.
+	pub fn reserve(&mut self, payload_len: usize) {
+		let chunk_len = chunk_len(&self.params, payload_len);
+		for chunk in self.chunks.iter_mut() {
+			chunk.reserve(chunk_len.saturating_sub(chunk.len()));
+		}
+	}
+
+	/// Feed more payload bytes into the encoder.
+	pub fn update(&mut self, mut bytes: &[u8]) {
+		self.payload_len += bytes.len();
+
+		while !bytes.is_empty() {
+			let take = (self.window_len - self.window.len()).min(bytes.len());
+			self.window.extend_from_slice(&bytes[..take]);
+			bytes = &bytes[take..];
+
+			if self.window.len() == self.window_len {
+				self.flush_window();
+			}
+		}
+	}
+
+	/// Encode the remaining buffered bytes and return one chunk per validator.
+	pub fn finish(mut self) -> Result<Vec<Vec<u8>>, Error> {
+		if self.payload_len == 0 {
+			return Err(Error::BadPayload)
+		}
+
+		if !self.window.is_empty() {
+			self.flush_window();
+		}
+
+		Ok(self.chunks)
+	}
+
+	fn flush_window(&mut self) {
+		let shards = self.params.make_encoder().encode::<WrappedShard>(&self.window[..]).expect(
+			"Window non-empty, shard sizes are uniform, and validator numbers checked; qed",
+		);
+
+		for (chunk, shard) in self.chunks.iter_mut().zip(shards) {
+			chunk.extend_from_slice(&shard.into_inner());
+		}
+		self.window.clear();
+	}
+}
+
+impl Output for StreamingEncoder {
+	fn write(&mut self, bytes: &[u8]) {
+		self.update(bytes)
+	}
+}
+
+/// Obtain erasure-coded chunks for v1 `AvailableData` without materializing its encoding.
+///
+/// Yields the same chunks as [`obtain_chunks_v1`](crate::obtain_chunks_v1).
+pub fn obtain_chunks_streaming_v1(
+	n_validators: usize,
+	data: &AvailableData,
+) -> Result<Vec<Vec<u8>>, Error> {
+	obtain_chunks_streaming(n_validators, data)
+}
+
+/// Obtain erasure-coded chunks without materializing the encoding of `data`.
+///
+/// Yields the same chunks as [`obtain_chunks`](crate::obtain_chunks).
+pub fn obtain_chunks_streaming<T: Encode>(
+	n_validators: usize,
+	data: &T,
+) -> Result<Vec<Vec<u8>>, Error> {
+	let mut encoder = StreamingEncoder::new(n_validators)?;
+	encoder.reserve(data.size_hint());
+	data.encode_to(&mut encoder);
+	encoder.finish()
+}
+
+/// Collects chunks one at a time, verifying each against the erasure root as it arrives, and
+/// reconstructs the payload once enough of them are present.
+pub struct IncrementalReconstruction {
+	n_validators: usize,
+	threshold: usize,
+	erasure_root: H256,
+	chunks: Vec<Option<Vec<u8>>>,
+	chunk_len: Option<usize>,
+	received: usize,
+	window_symbols: usize,
+}
+
+impl IncrementalReconstruction {
+	/// Create a new reconstruction of data coded for `n_validators` with the given erasure root.
+	pub fn new(n_validators: usize, erasure_root: H256) -> Result<Self, Error> {
+		let threshold = recovery_threshold(n_validators)?;
+		Ok(Self {
+			n_validators,
+			threshold,
+			erasure_root,
+			chunks: vec![None; n_validators],
+			chunk_len: None,
+			received: 0,
+			window_symbols: DEFAULT_WINDOW_SYMBOLS,
+		})
+	}
+
+	/// Reconstruct `window_symbols` symbols of every chunk at once.
+	pub fn with_window_symbols(mut self, window_symbols: usize) -> Self {
+		self.window_symbols = window_symbols.max(1);
+		self
+	}
+
+	/// Verify the chunk at `index` against the erasure root and add it.
+	///
+	/// Returns `true` once enough chunks are present for reconstruction. Chunks which were added
+	/// before are ignored.
+	pub fn push_chunk(
+		&mut self,
+		index: usize,
+		chunk: Vec<u8>,
+		proof: &Proof,
+	) -> Result<bool, Error> {
+		if index >= self.n_validators {
+			return Err(Error::ChunkIndexOutOfBounds {
+				chunk_index: index,
+				n_validators: self.n_validators,
+			})
+		}
+		if self.chunks[index].is_some() {
+			return Ok(self.is_complete())
+		}
+		if chunk.len() % 2 != 0 {
+			return Err(Error::UnevenLength)
+		}
+		if chunk.is_empty() || self.chunk_len.map_or(false, |len| len != chunk.len()) {
+			return Err(Error::NonUniformChunks)
+		}
+		if branch_hash(&self.erasure_root, proof, index)? != BlakeTwo256::hash(&chunk) {
+			return Err(Error::InvalidBranchProof)
+		}
+
+		self.chunk_len = Some(chunk.len());
+		self.chunks[index] = Some(chunk);
+		self.received += 1;
+
+		Ok(self.is_complete())
+	}
+
+	/// The number of verified chunks received so far.
+	pub fn received(&self) -> usize {
+		self.received
+	}
+
+	/// Whether enough chunks are present for reconstruction.
+	pub fn is_complete(&self) -> bool {
+		self.received >= self.threshold
+	}
+
+	/// Reconstruct the v1 available data.
+	pub fn reconstruct_v1(&self) -> Result<AvailableData, Error> {
+		self.reconstruct()
+	}
+
+	/// Reconstruct decodable data, decoding it window by window.
+	pub fn reconstruct<T: Decode>(&self) -> Result<T, Error> {
+		if !self.is_complete() {
+			return Err(Error::NotEnoughChunks)
+		}
+
+		let mut reader = ReconstructionReader {
+			params: code_params(self.n_validators)?,
+			chunks: &self.chunks,
+			chunk_symbols: self.chunk_len.unwrap_or_default() / 2,
+			window_symbols: self.window_symbols,
+			next_symbol: 0,
+			buffer: Vec::new(),
+			buffer_pos: 0,
+			error: None,
+		};
+
+		T::decode(&mut reader).map_err(|err| reader.error.take().unwrap_or(Error::Decode(err)))
+	}
+}
+
+/// Reconstructs the payload lazily while it is being decoded.
+struct ReconstructionReader<'a> {
+	params: CodeParams,
+	chunks: &'a [Option<Vec<u8>>],
+	chunk_symbols: usize,
+	window_symbols: usize,
+	next_symbol: usize,
+	buffer: Vec<u8>,
+	buffer_pos: usize,
+	// Reconstruction errors are reported in favour of the resulting decoding error.
+	error: Option<Error>,
+}
+
+impl<'a> ReconstructionReader<'a> {
+	fn refill(&mut self) -> Result<(), codec::Error> {
+		let start = self.next_symbol;
+		let end = (start + self.window_symbols).min(self.chunk_symbols);
+		if start == end {
+			return Err("Not enough data to fill buffer".into())
+		}
+
+		let shards = self
+			.chunks
+			.iter()
+			.map(|chunk| {
+				chunk
+					.as_ref()
+					.map(|chunk| WrappedShard::new(chunk[start * 2..end * 2].to_vec()))
+			})
+			.collect();
+
+		self.buffer = self.params.make_encoder().reconstruct(shards).map_err(|err| {
+			self.error = Some(err.into());
+			codec::Error::from("Reconstruction failed")
+		})?;
+		self.buffer_pos = 0;
+		self.next_symbol = end;
+
+		Ok(())
+	}
+}
+
+impl<'a> Input for ReconstructionReader<'a> {
+	fn remaining_len(&mut self) -> Result<Option<usize>, codec::Error> {
+		let pending = (self.chunk_symbols - self.next_symbol) * self.params.k() * 2;
+		Ok(Some(self.buffer.len() - self.buffer_pos + pending))
+	}
+
+	fn read(&mut self, mut into: &mut [u8]) -> Result<(), codec::Error> {
+		while !into.is_empty() {
+			if self.buffer_pos == self.buffer.len() {
+				self.refill()?;
+			}
+
+			let take = (self.buffer.len() - self.buffer_pos).min(into.len());
+			into[..take].copy_from_slice(&self.buffer[self.buffer_pos..self.buffer_pos + take]);
+			self.buffer_pos += take;
+			into = &mut into[take..];
+		}
+
+		Ok(())
+	}
+}
+
+/// The chunk length in bytes for a payload of `payload_len` bytes.
+fn chunk_len(params: &CodeParams, payload_len: usize) -> usize {
+	let payload_symbols = (payload_len + 1) / 2;
+	payload_symbols.div_ceil(params.k()) * 2
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{branches, obtain_chunks, reconstruct};
+	use polkadot_node_primitives::{BlockData, PoV};
+	use quickcheck::{Arbitrary, Gen, QuickCheck};
+
+	#[derive(Clone, Debug)]
+	struct Payload(Vec<u8>);
+
+	impl Arbitrary for Payload {
+		fn arbitrary(g: &mut Gen) -> Self {
+			let len = (u32::arbitrary(g) % (64 * 1024)).max(1);
+			Payload((0..len).map(|_| u8::arbitrary(g)).collect())
+		}
+	}
+
+	fn available_data(len: usize) -> AvailableData {
+		let pov = PoV { block_data: BlockData((0..len).map(|i| i as u8).collect()) };
+		AvailableData { pov: pov.into(), validation_data: Default::default() }
+	}
+
+	#[test]
+	fn streaming_chunks_match_obtain_chunks() {
+		fn property(payload: Payload, n_validators: u16, window_symbols: u8) -> bool {
+			let n_validators = (n_validators % 1000).max(2) as usize;
+
+			let expected = obtain_chunks(n_validators, &payload.0).unwrap();
+
+			let mut encoder =
+				StreamingEncoder::with_window_symbols(n_validators, window_symbols as usize)
+					.unwrap();
+			// Feed the encoding in uneven pieces.
+			let encoded = payload.0.encode();
+			for piece in encoded.chunks(997) {
+				encoder.update(piece);
+			}
+
+			encoder.finish().unwrap() == expected
+		}
+
+		QuickCheck::new().quickcheck(property as fn(Payload, u16, u8) -> bool)
+	}
+
+	#[test]
+	fn streaming_v1_matches_obtain_chunks_v1() {
+		let data = available_data(100_000);
+		assert_eq!(
+			obtain_chunks_streaming_v1(100, &data).unwrap(),
+			crate::obtain_chunks_v1(100, &data).unwrap()
+		);
+	}
+
+	#[test]
+	fn empty_payload_is_rejected() {
+		assert_eq!(StreamingEncoder::new(10).unwrap().finish(), Err(Error::BadPayload));
+	}
+
+	#[test]
+	fn incremental_reconstruction_works() {
+		let n_validators = 10;
+		let data = available_data(10_000);
+		let chunks = obtain_chunks_streaming_v1(n_validators, &data).unwrap();
+		let branches = branches(&chunks);
+		let root = branches.root();
+		let proofs: Vec<_> = branches.map(|(proof, _)| proof).collect();
+
+		let mut reconstruction = IncrementalReconstruction::new(n_validators, root)
+			.unwrap()
+			.with_window_symbols(7);
+		for index in [9, 2, 5] {
+			assert!(!reconstruction
+				.push_chunk(index, chunks[index].clone(), &proofs[index])
+				.unwrap());
+		}
+		assert_eq!(reconstruction.reconstruct_v1(), Err(Error::NotEnoughChunks));
+
+		// Duplicates are not counted.
+		assert!(!reconstruction.push_chunk(9, chunks[9].clone(), &proofs[9]).unwrap());
+		assert!(reconstruction.push_chunk(0, chunks[0].clone(), &proofs[0]).unwrap());
+		assert_eq!(reconstruction.received(), 4);
+
+		let reconstructed = reconstruction.reconstruct_v1().unwrap();
+		assert_eq!(reconstructed, data);
+
+		let expected: AvailableData = reconstruct(
+			n_validators,
+			[9, 2, 5, 0].iter().map(|&index| (&chunks[index][..], index)),
+		)
+		.unwrap();
+		assert_eq!(reconstructed, expected);
+	}
+
+	#[test]
+	fn incremental_reconstruction_rejects_bad_chunks() {
+		let n_validators = 10;
+		let chunks = obtain_chunks_streaming_v1(n_validators, &available_data(1000)).unwrap();
+		let branches = branches(&chunks);
+		let root = branches.root();
+		let proofs: Vec<_> = branches.map(|(proof, _)| proof).collect();
+
+		let mut reconstruction = IncrementalReconstruction::new(n_validators, root).unwrap();
+
+		let mut corrupted = chunks[1].clone();
+		corrupted[0] ^= 0xff;
+		assert_eq!(
+			reconstruction.push_chunk(1, corrupted, &proofs[1]),
+			Err(Error::InvalidBranchProof)
+		);
+		assert_eq!(
+			reconstruction.push_chunk(2, chunks[1].clone(), &proofs[1]),
+			Err(Error::InvalidBranchProof)
+		);
+		assert_eq!(
+			reconstruction.push_chunk(10, chunks[1].clone(), &proofs[1]),
+			Err(Error::ChunkIndexOutOfBounds { chunk_index: 10, n_validators })
+		);
+		assert_eq!(reconstruction.received(), 0);
+	}
+}
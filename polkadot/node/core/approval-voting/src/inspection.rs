@@ -0,0 +1,291 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reports on the approval state of blocks, for diagnosing stalled finality.
+//!
+//! The report of a block lists for each included candidate the received assignments and approvals,
+//! the no-shows and the outcome of approval checking, computed the same way as when deciding
+//! whether to approve a candidate. The stored entries are also checked against the invariants
+//! the subsystem relies on.
+
+use polkadot_node_primitives::approval::{
+	inspection::{
+		ApprovalCheckStatus, BlockApprovalReport, CandidateApprovalReport, TrancheAssignments,
+	},
+	time::Tick,
+	v1::DelayTranche,
+};
+use polkadot_node_subsystem::SubsystemResult;
+use polkadot_primitives::{CandidateHash, ValidatorIndex};
+
+use crate::{
+	approval_checking::{self, Check, RequiredTranches, TranchesToApproveResult},
+	backend::{Backend, OverlayedBackend},
+	persisted_entries::{ApprovalEntry, BlockEntry, CandidateEntry},
+};
+
+/// Parameters of approval checking for the session of a block.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CheckingParams {
+	/// The tick of the block's slot.
+	pub block_tick: Tick,
+	/// The number of ticks after which an assigned validator without approval is a no-show.
+	pub no_show_duration: Tick,
+	/// The number of approvals needed for a candidate.
+	pub needed_approvals: usize,
+}
+
+/// Build the report for the given block.
+///
+/// Without `params` approval checking can't be performed, which is reported as
+/// [`ApprovalCheckStatus::MissingSessionInfo`] for every candidate.
+pub(crate) fn block_report(
+	db: &OverlayedBackend<'_, impl Backend>,
+	block_entry: &BlockEntry,
+	tranche_now: DelayTranche,
+	params: Option<CheckingParams>,
+) -> SubsystemResult<BlockApprovalReport> {
+	let block_hash = block_entry.block_hash();
+	let mut inconsistencies = block_inconsistencies(db, block_entry)?;
+
+	let mut candidates = Vec::with_capacity(block_entry.candidates().len());
+	for (index, (core_index, candidate_hash)) in block_entry.candidates().iter().enumerate() {
+		let approved = block_entry.approved_bitfield.get(index).map_or(false, |bit| *bit);
+		let candidate_entry = db.load_candidate_entry(candidate_hash)?;
+		let approval_entry =
+			candidate_entry.as_ref().and_then(|entry| entry.approval_entry(&block_hash));
+
+		let (Some(candidate_entry), Some(approval_entry)) = (&candidate_entry, approval_entry)
+		else {
+			inconsistencies.push(match candidate_entry {
+				None => format!("Candidate {:?} has no candidate entry", candidate_hash),
+				Some(_) =>
+					format!("Candidate {:?} has no approval entry for the block", candidate_hash),
+			});
+			candidates.push(CandidateApprovalReport {
+				candidate_hash: candidate_hash.0,
+				core_index: core_index.0,
+				approved,
+				assignments: Vec::new(),
+				approvals: Vec::new(),
+				no_shows: Vec::new(),
+				our_assignment: None,
+				status: ApprovalCheckStatus::MissingEntry,
+			});
+			continue
+		};
+
+		inconsistencies.extend(candidate_inconsistencies(
+			block_entry,
+			*candidate_hash,
+			approved,
+			candidate_entry,
+			approval_entry,
+		));
+
+		let (no_shows, status) = match params {
+			Some(params) => check_status(candidate_entry, approval_entry, tranche_now, params),
+			None => (Vec::new(), ApprovalCheckStatus::MissingSessionInfo),
+		};
+
+		candidates.push(CandidateApprovalReport {
+			candidate_hash: candidate_hash.0,
+			core_index: core_index.0,
+			approved,
+			assignments: approval_entry
+				.tranches()
+				.iter()
+				.map(|tranche| TrancheAssignments {
+					tranche: tranche.tranche(),
+					validators: tranche.assignments().to_vec(),
+				})
+				.collect(),
+			approvals: candidate_entry
+				.approvals()
+				.iter_ones()
+				.map(|index| ValidatorIndex(index as _))
+				.collect(),
+			no_shows,
+			our_assignment: approval_entry.our_assignment().map(|assignment| assignment.tranche()),
+			status,
+		});
+	}
+
+	Ok(BlockApprovalReport {
+		block_hash,
+		block_number: block_entry.block_number(),
+		session: block_entry.session(),
+		slot: block_entry.slot().into(),
+		tranche_now,
+		approved: block_entry.is_fully_approved(),
+		candidates,
+		inconsistencies,
+	})
+}
+
+// Run approval checking for the candidate and explain the outcome.
+fn check_status(
+	candidate_entry: &CandidateEntry,
+	approval_entry: &ApprovalEntry,
+	tranche_now: DelayTranche,
+	params: CheckingParams,
+) -> (Vec<ValidatorIndex>, ApprovalCheckStatus) {
+	let TranchesToApproveResult { required_tranches, no_show_validators, .. } =
+		approval_checking::tranches_to_approve(
+			approval_entry,
+			candidate_entry.approvals(),
+			tranche_now,
+			params.block_tick,
+			params.no_show_duration,
+			params.needed_approvals,
+		);
+
+	let status = match approval_checking::check_approval(
+		candidate_entry,
+		approval_entry,
+		required_tranches.clone(),
+	) {
+		Check::Approved(no_shows, _) => ApprovalCheckStatus::Approved { no_shows },
+		Check::ApprovedOneThird => ApprovalCheckStatus::ApprovedOneThird,
+		Check::Unapproved => match required_tranches {
+			RequiredTranches::All => ApprovalCheckStatus::AllRequired {
+				approved: candidate_entry.approvals().count_ones(),
+				n_validators: candidate_entry.approvals().len(),
+			},
+			RequiredTranches::Pending { considered, next_no_show, .. } =>
+				ApprovalCheckStatus::AwaitingAssignments { considered, next_no_show },
+			RequiredTranches::Exact { needed, tolerated_missing, next_no_show, .. } => {
+				let mut assigned = approval_entry.assignments_up_to(needed);
+				let n_assigned = assigned.count_ones();
+				assigned &= candidate_entry.approvals();
+
+				ApprovalCheckStatus::AwaitingApprovals {
+					needed,
+					assigned: n_assigned,
+					approved: assigned.count_ones(),
+					tolerated_missing,
+					next_no_show,
+				}
+			},
+		},
+	};
+
+	(no_show_validators, status)
+}
+
+// Check the block entry against its neighbours and the block range index.
+fn block_inconsistencies(
+	db: &OverlayedBackend<'_, impl Backend>,
+	block_entry: &BlockEntry,
+) -> SubsystemResult<Vec<String>> {
+	let block_hash = block_entry.block_hash();
+	let block_number = block_entry.block_number();
+	let mut inconsistencies = Vec::new();
+
+	if block_entry.approved_bitfield.len() != block_entry.candidates().len() {
+		inconsistencies.push(format!(
+			"Approved bitfield has {} bits for {} candidates",
+			block_entry.approved_bitfield.len(),
+			block_entry.candidates().len(),
+		));
+	}
+
+	match db.load_stored_blocks()? {
+		Some(range) if range.0 <= block_number && block_number < range.1 => {},
+		range => inconsistencies.push(format!(
+			"Block number {} is outside of the stored block range {:?}",
+			block_number, range,
+		)),
+	}
+
+	if !db.load_blocks_at_height(&block_number)?.contains(&block_hash) {
+		inconsistencies
+			.push(format!("Block is missing from the blocks at height {}", block_number));
+	}
+
+	if let Some(parent) = db.load_block_entry(&block_entry.parent_hash())? {
+		if !parent.children.contains(&block_hash) {
+			inconsistencies.push("Block is not listed as a child of its parent".to_owned());
+		}
+	}
+
+	for child_hash in &block_entry.children {
+		match db.load_block_entry(child_hash)? {
+			None => inconsistencies.push(format!("Child {:?} has no block entry", child_hash)),
+			Some(child) if child.parent_hash() != block_hash => inconsistencies.push(format!(
+				"Child {:?} has parent {:?}",
+				child_hash,
+				child.parent_hash()
+			)),
+			Some(_) => {},
+		}
+	}
+
+	Ok(inconsistencies)
+}
+
+// Check the entries of a candidate against the block including it.
+fn candidate_inconsistencies(
+	block_entry: &BlockEntry,
+	candidate_hash: CandidateHash,
+	approved: bool,
+	candidate_entry: &CandidateEntry,
+	approval_entry: &ApprovalEntry,
+) -> Vec<String> {
+	let mut inconsistencies = Vec::new();
+
+	if candidate_entry.candidate_receipt().hash() != candidate_hash {
+		inconsistencies
+			.push(format!("Candidate {:?} has a receipt with another hash", candidate_hash));
+	}
+	if candidate_entry.session != block_entry.session() {
+		inconsistencies.push(format!(
+			"Candidate {:?} is in session {}, the block in session {}",
+			candidate_hash,
+			candidate_entry.session,
+			block_entry.session(),
+		));
+	}
+	if approval_entry.n_validators() != candidate_entry.approvals().len() {
+		inconsistencies.push(format!(
+			"Candidate {:?} has {} validators in assignments and {} in approvals",
+			candidate_hash,
+			approval_entry.n_validators(),
+			candidate_entry.approvals().len(),
+		));
+	}
+	if approval_entry.is_approved() != approved {
+		inconsistencies.push(format!(
+			"Candidate {:?} is {} in the approval entry but {} in the block entry",
+			candidate_hash,
+			if approval_entry.is_approved() { "approved" } else { "unapproved" },
+			if approved { "approved" } else { "unapproved" },
+		));
+	}
+
+	// Every assigned validator, including ourselves once triggered, is recorded in a tranche.
+	let assigned = approval_entry.assignments_up_to(DelayTranche::MAX);
+	if assigned.count_ones() != approval_entry.n_assignments() {
+		inconsistencies.push(format!(
+			"Candidate {:?} has {} assigned validators but {} recorded assignments",
+			candidate_hash,
+			approval_entry.n_assignments(),
+			assigned.count_ones(),
+		));
+	}
+
+	inconsistencies
+}
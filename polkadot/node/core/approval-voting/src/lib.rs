@@ -24,6 +24,7 @@
 use futures_timer::Delay;
 use polkadot_node_primitives::{
 	approval::{
+		inspection::BlockApprovalReport,
 		v1::{BlockApprovalMeta, DelayTranche},
 		v2::{
 			AssignmentCertKindV2, BitfieldError, CandidateBitfield, CoreBitfield,
@@ -101,6 +102,7 @@ pub mod approval_db;
 mod backend;
 pub mod criteria;
 mod import;
+mod inspection;
 mod ops;
 mod persisted_entries;
 
@@ -2163,12 +2165,58 @@ async fn handle_from_overseer<
 				.await?;
 				Vec::new()
 			},
+			ApprovalVotingMessage::InspectBlock(block_hash, tx) => {
+				let report = inspect_block(sender, state, db, session_info_provider, block_hash)
+					.await
+					.unwrap_or_else(|err| {
+						gum::warn!(
+							target: LOG_TARGET,
+							?block_hash,
+							?err,
+							"Failed to inspect the approval state of a block",
+						);
+						None
+					});
+				let _ = tx.send(report);
+
+				Vec::new()
+			},
 		},
 	};
 
 	Ok(actions)
 }
 
+/// Report the approval state of a block, see [`ApprovalVotingMessage::InspectBlock`].
+async fn inspect_block<Sender: SubsystemSender<RuntimeApiMessage>>(
+	sender: &mut Sender,
+	state: &State,
+	db: &OverlayedBackend<'_, impl Backend>,
+	session_info_provider: &mut RuntimeInfo,
+	block_hash: Hash,
+) -> SubsystemResult<Option<BlockApprovalReport>> {
+	let Some(block_entry) = db.load_block_entry(&block_hash)? else { return Ok(None) };
+
+	let params = get_session_info(
+		session_info_provider,
+		sender,
+		block_entry.parent_hash(),
+		block_entry.session(),
+	)
+	.await
+	.map(|session_info| inspection::CheckingParams {
+		block_tick: slot_number_to_tick(state.slot_duration_millis, block_entry.slot()),
+		no_show_duration: slot_number_to_tick(
+			state.slot_duration_millis,
+			Slot::from(u64::from(session_info.no_show_slots)),
+		),
+		needed_approvals: session_info.needed_approvals as _,
+	});
+	let tranche_now = state.clock.tranche_now(state.slot_duration_millis, block_entry.slot());
+
+	inspection::block_report(db, &block_entry, tranche_now, params).map(Some)
+}
+
 /// Retrieve approval signatures.
 ///
 /// This involves an unbounded message send to approval-distribution, the caller has to ensure that
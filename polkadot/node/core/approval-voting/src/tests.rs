@@ -1632,6 +1632,116 @@ fn subsystem_accepts_and_imports_approval_after_assignment() {
 	});
 }
 
+async fn inspect_block(
+	overseer: &mut VirtualOverseer,
+	block_hash: Hash,
+) -> Option<BlockApprovalReport> {
+	let (tx, rx) = oneshot::channel();
+	overseer_send(
+		overseer,
+		FromOrchestra::Communication { msg: ApprovalVotingMessage::InspectBlock(block_hash, tx) },
+	)
+	.await;
+	rx.await.unwrap()
+}
+
+#[test]
+fn inspect_block_reports_assignments_and_approvals() {
+	use polkadot_node_primitives::approval::inspection::ApprovalCheckStatus;
+
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+
+		let candidate_hash = {
+			let mut candidate_receipt =
+				dummy_candidate_receipt_v2_bad_sig(block_hash, Some(Default::default()));
+			candidate_receipt.descriptor.set_para_id(ParaId::from(0_u32));
+			candidate_receipt.descriptor.set_relay_parent(block_hash);
+			candidate_receipt.hash()
+		};
+
+		let candidate_index = 0;
+		let validator = ValidatorIndex(0);
+		let session_index = 1;
+
+		assert_eq!(inspect_block(&mut virtual_overseer, block_hash).await, None);
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig {
+					slot: Slot::from(1),
+					candidates: None,
+					session_info: None,
+					end_syncing: false,
+				},
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		let rx =
+			import_assignment(&mut virtual_overseer, block_hash, candidate_index, validator, 0)
+				.await;
+		assert_eq!(rx.await, Ok(AssignmentCheckResult::Accepted));
+
+		let report = inspect_block(&mut virtual_overseer, block_hash).await.unwrap();
+		assert_eq!(report.block_number, 1);
+		assert!(!report.approved);
+		assert!(report.inconsistencies.is_empty(), "{:?}", report.inconsistencies);
+		assert_eq!(report.candidates.len(), 1);
+
+		let candidate = &report.candidates[0];
+		assert_eq!(candidate.candidate_hash, candidate_hash.0);
+		assert!(!candidate.approved);
+		assert_eq!(candidate.assignments.len(), 1);
+		assert_eq!(candidate.assignments[0].tranche, 0);
+		assert_eq!(candidate.assignments[0].validators[0].0, validator);
+		assert!(candidate.approvals.is_empty());
+		assert_matches!(
+			candidate.status,
+			ApprovalCheckStatus::AwaitingApprovals { assigned: 1, approved: 0, .. }
+		);
+
+		let rx = import_approval(
+			&mut virtual_overseer,
+			block_hash,
+			candidate_index,
+			validator,
+			candidate_hash,
+			session_index,
+			true,
+			None,
+		)
+		.await;
+		assert_eq!(rx.await, Ok(ApprovalCheckResult::Accepted));
+
+		let report = inspect_block(&mut virtual_overseer, block_hash).await.unwrap();
+		assert!(report.approved);
+		assert!(report.inconsistencies.is_empty(), "{:?}", report.inconsistencies);
+
+		let candidate = &report.candidates[0];
+		assert!(candidate.approved);
+		assert_eq!(candidate.approvals, vec![validator]);
+		assert_matches!(
+			candidate.status,
+			ApprovalCheckStatus::Approved { .. } | ApprovalCheckStatus::ApprovedOneThird
+		);
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn subsystem_second_approval_import_only_schedules_wakeups() {
 	test_harness(HarnessConfig::default(), |test_harness| async move {
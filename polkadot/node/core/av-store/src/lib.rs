@@ -20,7 +20,7 @@
 #![warn(missing_docs)]
 
 use std::{
-	collections::{BTreeSet, HashMap, HashSet},
+	collections::{hash_map::Entry, BTreeSet, HashMap, HashSet},
 	io,
 	sync::Arc,
 	time::{Duration, SystemTime, SystemTimeError, UNIX_EPOCH},
@@ -67,6 +67,8 @@ const CHUNK_PREFIX: &[u8; 5] = b"chunk";
 const META_PREFIX: &[u8; 4] = b"meta";
 const UNFINALIZED_PREFIX: &[u8; 11] = b"unfinalized";
 const PRUNE_BY_TIME_PREFIX: &[u8; 13] = b"prune_by_time";
+const STORED_SIZE_PREFIX: &[u8; 11] = b"stored_size";
+const STORED_TOTAL_KEY: &[u8; 12] = b"stored_total";
 
 // We have some keys we want to map to empty values because existence of the key is enough. We use
 // this because rocksdb doesn't support empty values.
@@ -78,6 +80,9 @@ const KEEP_UNAVAILABLE_FOR: Duration = Duration::from_secs(60 * 60);
 /// The pruning interval.
 const PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 5);
 
+/// Usage of the storage budget, in percent, above which a warning is logged on every pruning.
+const STORAGE_BUDGET_WARNING_PERCENT: u64 = 90;
+
 /// Unix time wrapper with big-endian encoding.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
 struct BETimestamp(u64);
@@ -157,6 +162,27 @@ struct CandidateMeta {
 	chunks_stored: BitVec<u8, BitOrderLsb0>,
 }
 
+// Sizes of the data stored for a candidate, accounted against the storage budget.
+//
+// Kept apart from `CandidateMeta` so that entries written by older versions remain readable.
+// Candidates stored before these records were introduced get them when the subsystem starts, see
+// `recompute_stored_total`.
+#[derive(Debug, Default, Clone, Copy, Encode, Decode)]
+struct StoredSize {
+	// The encoded size of the available data.
+	available_data: u64,
+	// The encoded size of a single chunk. The chunks of a candidate are of about the same size.
+	chunk: u64,
+}
+
+impl StoredSize {
+	// The number of bytes currently stored for the candidate described by `meta`.
+	fn bytes(&self, meta: &CandidateMeta) -> u64 {
+		let available_data = if meta.data_available { self.available_data } else { 0 };
+		available_data + self.chunk * meta.chunks_stored.count_ones() as u64
+	}
+}
+
 fn query_inner<D: Decode>(
 	db: &Arc<dyn Database>,
 	column: u32,
@@ -257,6 +283,41 @@ fn delete_meta(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
 	tx.delete(config.col_meta, &key[..])
 }
 
+fn load_stored_size(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	hash: &CandidateHash,
+) -> Result<Option<StoredSize>, Error> {
+	let key = (STORED_SIZE_PREFIX, hash).encode();
+
+	query_inner(db, config.col_meta, &key)
+}
+
+fn write_stored_size(
+	tx: &mut DBTransaction,
+	config: &Config,
+	hash: &CandidateHash,
+	size: &StoredSize,
+) {
+	let key = (STORED_SIZE_PREFIX, hash).encode();
+
+	tx.put_vec(config.col_meta, &key, size.encode());
+}
+
+fn delete_stored_size(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
+	let key = (STORED_SIZE_PREFIX, hash).encode();
+	tx.delete(config.col_meta, &key[..])
+}
+
+// The number of bytes stored for all candidates, kept up to date by the main loop of the subsystem.
+fn load_stored_total(db: &Arc<dyn Database>, config: &Config) -> Result<u64, Error> {
+	Ok(query_inner(db, config.col_meta, STORED_TOTAL_KEY)?.unwrap_or_default())
+}
+
+fn write_stored_total(tx: &mut DBTransaction, config: &Config, total: u64) {
+	tx.put_vec(config.col_meta, STORED_TOTAL_KEY, total.encode());
+}
+
 fn delete_unfinalized_height(tx: &mut DBTransaction, config: &Config, block_number: BlockNumber) {
 	let prefix = (UNFINALIZED_PREFIX, BEBlockNumber(block_number)).encode();
 	tx.delete_prefix(config.col_meta, &prefix);
@@ -331,6 +392,14 @@ fn decode_unfinalized_key(s: &[u8]) -> Result<(BlockNumber, Hash, CandidateHash)
 		.map(|(b, h, ch)| (b.0, h, ch))
 }
 
+fn decode_meta_key(s: &[u8]) -> Result<CandidateHash, CodecError> {
+	if !s.starts_with(META_PREFIX) {
+		return Err("missing magic string".into())
+	}
+
+	CandidateHash::decode(&mut &s[META_PREFIX.len()..])
+}
+
 fn decode_pruning_key(s: &[u8]) -> Result<(Duration, CandidateHash), CodecError> {
 	if !s.starts_with(PRUNE_BY_TIME_PREFIX) {
 		return Err("missing magic string".into())
@@ -429,6 +498,13 @@ pub struct Config {
 	pub col_meta: u32,
 	/// How long finalized data should be kept (in hours).
 	pub keep_finalized_for: u32,
+	/// How many bytes the stored data and chunks may take up, unlimited if `None`.
+	///
+	/// While the budget is exceeded data is evicted before its pruning time, oldest first: the
+	/// full available data and then the chunks of finalized candidates, then the full available
+	/// data of candidates which are unfinalized or not included yet. The chunks of the latter are
+	/// never evicted, since they are still needed for availability and approval checking.
+	pub storage_budget: Option<u64>,
 }
 
 trait Clock: Send + Sync {
@@ -546,6 +622,10 @@ impl<Context> AvailabilityStoreSubsystem {
 
 #[overseer::contextbounds(AvailabilityStore, prefix = self::overseer)]
 async fn run<Context>(mut subsystem: AvailabilityStoreSubsystem, mut ctx: Context) {
+	if let Err(e) = recompute_stored_total(&subsystem.db, &subsystem.config) {
+		gum::warn!(target: LOG_TARGET, err = ?e, "Failed to compute the size of the stored data");
+	}
+
 	let mut next_pruning = Delay::new(subsystem.pruning_config.pruning_interval).fuse();
 	// Pruning interval is in the order of minutes so we shouldn't have more than one task running
 	// at one moment in time, so 10 should be more than enough.
@@ -580,8 +660,8 @@ async fn run_iteration<Context>(
 	subsystem: &mut AvailabilityStoreSubsystem,
 	mut next_pruning: &mut future::Fuse<Delay>,
 	(pruning_result_tx, pruning_result_rx): (
-		&mut MpscSender<Result<(), Error>>,
-		&mut MpscReceiver<Result<(), Error>>,
+		&mut MpscSender<Result<(u64, Vec<Eviction>), Error>>,
+		&mut MpscReceiver<Result<(u64, Vec<Eviction>), Error>>,
 	),
 ) -> Result<bool, Error> {
 	select! {
@@ -637,7 +717,11 @@ async fn run_iteration<Context>(
 		// the main loop of the subsystem can exit graciously.
 		result = pruning_result_rx.next() => {
 			if let Some(result) = result {
-				result?;
+				// Evictions are applied here, as they update the metadata of candidates which may
+				// have been changed by the main loop since they were planned. The same goes for the
+				// total size of the stored data.
+				let (pruned, evictions) = result?;
+				evict(&subsystem.db, &subsystem.config, &subsystem.metrics, pruned, evictions)?;
 			}
 		},
 	}
@@ -652,7 +736,7 @@ async fn run_iteration<Context>(
 async fn start_prune_all<Context>(
 	ctx: &mut Context,
 	subsystem: &mut AvailabilityStoreSubsystem,
-	mut pruning_result_tx: MpscSender<Result<(), Error>>,
+	mut pruning_result_tx: MpscSender<Result<(u64, Vec<Eviction>), Error>>,
 ) -> Result<(), Error> {
 	let metrics = subsystem.metrics.clone();
 	let db = subsystem.db.clone();
@@ -665,7 +749,9 @@ async fn start_prune_all<Context>(
 			let _timer = metrics.time_pruning();
 
 			gum::debug!(target: LOG_TARGET, "Prunning started");
-			let result = prune_all(&db, &config, time_now);
+			let result = prune_all(&db, &config, time_now).and_then(|pruned| {
+				plan_evictions(&db, &config, &metrics, pruned).map(|evictions| (pruned, evictions))
+			});
 
 			if let Err(err) = pruning_result_tx.send(result).await {
 				// This usually means that the node is closing down, log it just in case
@@ -1257,6 +1343,14 @@ fn store_chunk(
 		Some(false) => {
 			meta.chunks_stored.set(validator_index.0 as usize, true);
 
+			let mut size = load_stored_size(db, config, &candidate_hash)?.unwrap_or_default();
+			if size.chunk == 0 {
+				size.chunk = chunk.encoded_size() as u64;
+				write_stored_size(&mut tx, config, &candidate_hash, &size);
+			}
+			let total = load_stored_total(db, config)?;
+			write_stored_total(&mut tx, config, total.saturating_add(size.chunk));
+
 			write_chunk(&mut tx, config, &candidate_hash, validator_index, &chunk);
 			write_meta(&mut tx, config, &candidate_hash, &meta);
 		},
@@ -1286,12 +1380,16 @@ fn store_available_data(
 ) -> Result<(), Error> {
 	let mut tx = DBTransaction::new();
 
+	let mut stored = 0;
 	let mut meta = match load_meta(&subsystem.db, &subsystem.config, &candidate_hash)? {
 		Some(m) => {
 			if m.data_available {
 				return Ok(()) // already stored.
 			}
 
+			stored = load_stored_size(&subsystem.db, &subsystem.config, &candidate_hash)?
+				.unwrap_or_default()
+				.bytes(&m);
 			m
 		},
 		None => {
@@ -1311,7 +1409,8 @@ fn store_available_data(
 
 	// Important note: This check below is critical for consensus and the `backing` subsystem relies
 	// on it to ensure candidate validity.
-	let chunks = polkadot_erasure_coding::obtain_chunks_v1(n_validators, &available_data)?;
+	let chunks =
+		polkadot_erasure_coding::obtain_chunks_streaming_v1(n_validators, &available_data)?;
 	let branches = polkadot_erasure_coding::branches(chunks.as_ref());
 
 	if branches.root() != expected_erasure_root {
@@ -1343,7 +1442,20 @@ fn store_available_data(
 	meta.data_available = true;
 	meta.chunks_stored = bitvec::bitvec![u8, BitOrderLsb0; 1; n_validators];
 
+	let size = StoredSize {
+		available_data: available_data.encoded_size() as u64,
+		chunk: erasure_chunks.first().map_or(0, |chunk| chunk.encoded_size() as u64),
+	};
+
+	let total = load_stored_total(&subsystem.db, &subsystem.config)?;
+	write_stored_total(
+		&mut tx,
+		&subsystem.config,
+		total.saturating_sub(stored).saturating_add(size.bytes(&meta)),
+	);
+
 	write_meta(&mut tx, &subsystem.config, &candidate_hash, &meta);
+	write_stored_size(&mut tx, &subsystem.config, &candidate_hash, &size);
 	write_available_data(&mut tx, &subsystem.config, &candidate_hash, &available_data);
 
 	subsystem.db.write(tx)?;
@@ -1353,8 +1465,12 @@ fn store_available_data(
 	Ok(())
 }
 
-fn prune_all(db: &Arc<dyn Database>, config: &Config, now: Duration) -> Result<(), Error> {
+// Prune the candidates whose pruning time has come, returning the number of bytes they took up.
+//
+// This runs in parallel to the main loop, which deducts the returned size from the total.
+fn prune_all(db: &Arc<dyn Database>, config: &Config, now: Duration) -> Result<u64, Error> {
 	let (range_start, range_end) = pruning_range(now);
+	let mut pruned = 0u64;
 
 	let mut tx = DBTransaction::new();
 	let iter = db
@@ -1371,9 +1487,13 @@ fn prune_all(db: &Arc<dyn Database>, config: &Config, now: Duration) -> Result<(
 		};
 
 		delete_meta(&mut tx, config, &candidate_hash);
+		delete_stored_size(&mut tx, config, &candidate_hash);
 
 		// Clean up all attached data of the candidate.
 		if let Some(meta) = load_meta(db, config, &candidate_hash)? {
+			let size = load_stored_size(db, config, &candidate_hash)?.unwrap_or_default();
+			pruned = pruned.saturating_add(size.bytes(&meta));
+
 			// delete available data.
 			if meta.data_available {
 				delete_available_data(&mut tx, config, &candidate_hash)
@@ -1402,6 +1522,242 @@ fn prune_all(db: &Arc<dyn Database>, config: &Config, now: Duration) -> Result<(
 		}
 	}
 
+	db.write(tx)?;
+	Ok(pruned)
+}
+
+// Whether the chunks of a candidate in `state` must be kept even if the storage budget is exceeded:
+// they are still needed for availability and approval checking.
+fn is_chunk_protected(state: &State) -> bool {
+	matches!(state, State::Unavailable(..) | State::Unfinalized(..))
+}
+
+// Data of a candidate to evict in order to stay within the storage budget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Eviction {
+	AvailableData(CandidateHash),
+	Chunks(CandidateHash),
+}
+
+// Determine the data to evict so that the stored data and chunks fit into
+// `config.storage_budget`, in the order described there, and report the storage used.
+//
+// The storage used is the running total, less the `pruned` bytes which the main loop did not
+// deduct yet. Only when it exceeds the budget are the candidates scanned.
+//
+// This only reads from the database, so it can run in parallel to the subsystem. The evictions
+// are applied by `evict`.
+fn plan_evictions(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	metrics: &Metrics,
+	pruned: u64,
+) -> Result<Vec<Eviction>, Error> {
+	let mut evictions = Vec::new();
+	let mut used = load_stored_total(db, config)?.saturating_sub(pruned);
+	let budget = match config.storage_budget {
+		Some(budget) if used > budget => budget,
+		budget => {
+			metrics.on_storage_used(used, budget);
+			if let Some(budget) = budget {
+				warn_if_almost_used_up(used, budget);
+			}
+			return Ok(evictions)
+		},
+	};
+
+	let mut candidates = Vec::new();
+	used = 0;
+	for r in db.iter_with_prefix(config.col_meta, META_PREFIX) {
+		let (k, v) = r?;
+		let candidate_hash = match decode_meta_key(&k[..]) {
+			Ok(h) => h,
+			Err(_) => continue, // sanity
+		};
+		let meta = CandidateMeta::decode(&mut &v[..])?;
+		let size = load_stored_size(db, config, &candidate_hash)?.unwrap_or_default();
+
+		used += size.bytes(&meta);
+		candidates.push((candidate_hash, meta, size));
+	}
+
+	// Evict the oldest data first.
+	candidates.sort_by_key(|(_, meta, _)| match meta.state {
+		State::Unavailable(at) | State::Unfinalized(at, _) | State::Finalized(at) => at,
+	});
+
+	let is_protected = |meta: &CandidateMeta| is_chunk_protected(&meta.state);
+	let chunks_size = |meta: &CandidateMeta, size: &StoredSize| {
+		size.chunk * meta.chunks_stored.count_ones() as u64
+	};
+
+	// The full available data and then the chunks of candidates which are not needed for
+	// approval checking anymore.
+	for (candidate_hash, meta, size) in &candidates {
+		if used <= budget {
+			break
+		}
+		if meta.data_available && size.available_data > 0 && !is_protected(meta) {
+			used -= size.available_data;
+			evictions.push(Eviction::AvailableData(*candidate_hash));
+		}
+	}
+	for (candidate_hash, meta, size) in &candidates {
+		if used <= budget {
+			break
+		}
+		if chunks_size(meta, size) > 0 && !is_protected(meta) {
+			used -= chunks_size(meta, size);
+			evictions.push(Eviction::Chunks(*candidate_hash));
+		}
+	}
+	// Then the full available data of unfinalized candidates and of candidates which are not
+	// included yet, other validators can still fetch their chunks.
+	for (candidate_hash, meta, size) in &candidates {
+		if used <= budget {
+			break
+		}
+		if meta.data_available && size.available_data > 0 && is_protected(meta) {
+			used -= size.available_data;
+			evictions.push(Eviction::AvailableData(*candidate_hash));
+		}
+	}
+
+	metrics.on_storage_used(used, Some(budget));
+
+	if used > budget {
+		gum::warn!(
+			target: LOG_TARGET,
+			used,
+			budget,
+			"Storage budget exceeded by chunks which are still needed, and never evicted",
+		);
+	} else {
+		warn_if_almost_used_up(used, budget);
+	}
+
+	Ok(evictions)
+}
+
+fn warn_if_almost_used_up(used: u64, budget: u64) {
+	if used * 100 >= budget * STORAGE_BUDGET_WARNING_PERCENT {
+		gum::warn!(target: LOG_TARGET, used, budget, "Storage budget almost used up");
+	}
+}
+
+// Apply the evictions determined by `plan_evictions`, and deduct the evicted and `pruned` bytes
+// from the total.
+//
+// The candidate metadata may have changed in the meantime, so it is loaded again and the chunks of
+// candidates which became unfinalized or not included are kept.
+fn evict(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	metrics: &Metrics,
+	pruned: u64,
+	evictions: Vec<Eviction>,
+) -> Result<(), Error> {
+	if pruned == 0 && evictions.is_empty() {
+		return Ok(())
+	}
+
+	let mut tx = DBTransaction::new();
+	let mut metas = HashMap::new();
+	let mut evicted_data = 0;
+	let mut evicted_chunks = 0;
+	let mut evicted_bytes = 0u64;
+
+	for eviction in evictions {
+		let (Eviction::AvailableData(candidate_hash) | Eviction::Chunks(candidate_hash)) = eviction;
+		let meta = match metas.entry(candidate_hash) {
+			Entry::Occupied(entry) => entry.into_mut(),
+			Entry::Vacant(entry) => match load_meta(db, config, &candidate_hash)? {
+				Some(meta) => entry.insert(meta),
+				None => continue, // pruned in the meantime.
+			},
+		};
+
+		let size = load_stored_size(db, config, &candidate_hash)?.unwrap_or_default();
+		match eviction {
+			Eviction::AvailableData(_) =>
+				if meta.data_available {
+					meta.data_available = false;
+					delete_available_data(&mut tx, config, &candidate_hash);
+					evicted_data += 1;
+					evicted_bytes += size.available_data;
+				},
+			Eviction::Chunks(_) => {
+				if is_chunk_protected(&meta.state) {
+					continue
+				}
+				for i in meta.chunks_stored.iter_ones() {
+					delete_chunk(&mut tx, config, &candidate_hash, ValidatorIndex(i as _));
+				}
+				evicted_bytes += size.chunk * meta.chunks_stored.count_ones() as u64;
+				meta.chunks_stored.fill(false);
+				evicted_chunks += 1;
+			},
+		}
+	}
+
+	for (candidate_hash, meta) in metas {
+		write_meta(&mut tx, config, &candidate_hash, &meta);
+	}
+
+	let total = load_stored_total(db, config)?;
+	write_stored_total(&mut tx, config, total.saturating_sub(pruned).saturating_sub(evicted_bytes));
+
+	db.write(tx)?;
+	if evicted_data == 0 && evicted_chunks == 0 {
+		return Ok(())
+	}
+	metrics.on_evicted(evicted_data, evicted_chunks);
+
+	gum::info!(
+		target: LOG_TARGET,
+		evicted_data,
+		evicted_chunks,
+		"Evicted data to stay within the storage budget",
+	);
+
+	Ok(())
+}
+
+// Compute the total size of the stored data from scratch, when the subsystem starts.
+//
+// This corrects the total for the sizes pruned in the background but not deducted before a
+// restart, and records the sizes of the candidates stored before the storage budget was
+// introduced, from the sizes of their data in the database.
+fn recompute_stored_total(db: &Arc<dyn Database>, config: &Config) -> Result<(), Error> {
+	let mut tx = DBTransaction::new();
+	let mut total = 0u64;
+	for r in db.iter_with_prefix(config.col_meta, META_PREFIX) {
+		let (k, v) = r?;
+		let candidate_hash = match decode_meta_key(&k[..]) {
+			Ok(h) => h,
+			Err(_) => continue, // sanity
+		};
+		let meta = CandidateMeta::decode(&mut &v[..])?;
+		let size = match load_stored_size(db, config, &candidate_hash)? {
+			Some(size) => size,
+			None => {
+				let stored_len = |key: Vec<u8>| -> Result<u64, Error> {
+					Ok(db.get(config.col_data, &key)?.map_or(0, |v| v.len() as u64))
+				};
+				let available_data = stored_len((AVAILABLE_PREFIX, candidate_hash).encode())?;
+				let chunk = match meta.chunks_stored.first_one() {
+					Some(i) =>
+						stored_len((CHUNK_PREFIX, candidate_hash, ValidatorIndex(i as _)).encode())?,
+					None => 0,
+				};
+				let size = StoredSize { available_data, chunk };
+				write_stored_size(&mut tx, config, &candidate_hash, &size);
+				size
+			},
+		};
+		total += size.bytes(&meta);
+	}
+	write_stored_total(&mut tx, config, total);
 	db.write(tx)?;
 	Ok(())
 }
@@ -26,6 +26,9 @@ pub(crate) struct MetricsInner {
 	store_available_data: prometheus::Histogram,
 	store_chunk: prometheus::Histogram,
 	get_chunk: prometheus::Histogram,
+	storage_used: prometheus::Gauge<prometheus::U64>,
+	storage_budget: prometheus::Gauge<prometheus::U64>,
+	evicted_total: prometheus::CounterVec<prometheus::U64>,
 }
 
 /// Availability metrics.
@@ -41,6 +44,26 @@ impl Metrics {
 		}
 	}
 
+	/// Record the storage used by data and chunks, and the storage budget, if any.
+	pub(crate) fn on_storage_used(&self, used: u64, budget: Option<u64>) {
+		if let Some(metrics) = &self.0 {
+			metrics.storage_used.set(used);
+			metrics.storage_budget.set(budget.unwrap_or_default());
+		}
+	}
+
+	/// Record the number of candidates whose available data or chunks were evicted to stay
+	/// within the storage budget.
+	pub(crate) fn on_evicted(&self, available_data: u64, chunks: u64) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.evicted_total
+				.with_label_values(&["available_data"])
+				.inc_by(available_data);
+			metrics.evicted_total.with_label_values(&["chunks"]).inc_by(chunks);
+		}
+	}
+
 	/// Provide a timer for `prune_povs` which observes on drop.
 	pub(crate) fn time_pruning(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.pruning.start_timer())
@@ -152,6 +175,31 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			storage_used: prometheus::register(
+				prometheus::Gauge::new(
+					"polkadot_parachain_av_store_storage_used_bytes",
+					"Number of bytes taken up by stored available data and chunks.",
+				)?,
+				registry,
+			)?,
+			storage_budget: prometheus::register(
+				prometheus::Gauge::new(
+					"polkadot_parachain_av_store_storage_budget_bytes",
+					"Number of bytes stored available data and chunks may take up, 0 if unlimited.",
+				)?,
+				registry,
+			)?,
+			evicted_total: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_av_store_evicted_total",
+						"Number of candidates whose data was evicted to stay within the storage \
+						budget.",
+					),
+					&["data"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -43,8 +43,12 @@ mod columns {
 	pub const NUM_COLUMNS: u32 = 2;
 }
 
-const TEST_CONFIG: Config =
-	Config { col_data: columns::DATA, col_meta: columns::META, keep_finalized_for: 1 };
+const TEST_CONFIG: Config = Config {
+	col_data: columns::DATA,
+	col_meta: columns::META,
+	keep_finalized_for: 1,
+	storage_budget: None,
+};
 
 type VirtualOverseer =
 	polkadot_node_subsystem_test_helpers::TestSubsystemContextHandle<AvailabilityStoreMessage>;
@@ -1215,6 +1219,188 @@ fn forkfullness_works() {
 	});
 }
 
+#[test]
+fn storage_budget_evicts_in_priority_order() {
+	let n_validators = 10;
+	let test_state = TestState::default();
+	let available_data = AvailableData {
+		pov: Arc::new(PoV { block_data: BlockData(vec![4, 5, 6]) }),
+		validation_data: test_state.persisted_validation_data,
+	};
+	let chunk = ErasureChunk {
+		chunk: vec![1, 2, 3],
+		index: ChunkIndex(0),
+		proof: Proof::try_from(vec![vec![3, 4, 5]]).unwrap(),
+	};
+
+	let finalized = CandidateHash(Hash::repeat_byte(1));
+	let unfinalized = CandidateHash(Hash::repeat_byte(2));
+	let own_chunk_only = CandidateHash(Hash::repeat_byte(3));
+	let pending = CandidateHash(Hash::repeat_byte(4));
+
+	// Each candidate takes up 200 bytes with full data, 10 bytes with a single chunk.
+	let size = StoredSize { available_data: 100, chunk: 10 };
+	let store_candidate = |store: &Arc<dyn Database>, candidate_hash, state, with_data| {
+		with_tx(store, |tx| {
+			let mut chunks_stored = bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators];
+			for i in 0..n_validators {
+				if with_data || i == 0 {
+					chunks_stored.set(i, true);
+					write_chunk(tx, &TEST_CONFIG, &candidate_hash, ValidatorIndex(i as _), &chunk);
+				}
+			}
+			if with_data {
+				write_available_data(tx, &TEST_CONFIG, &candidate_hash, &available_data);
+			}
+			let meta = CandidateMeta { state, data_available: with_data, chunks_stored };
+			write_meta(tx, &TEST_CONFIG, &candidate_hash, &meta);
+			write_stored_size(tx, &TEST_CONFIG, &candidate_hash, &size);
+		});
+	};
+
+	let store = test_store();
+	store_candidate(&store, finalized, State::Finalized(BETimestamp(1)), true);
+	store_candidate(
+		&store,
+		unfinalized,
+		State::Unfinalized(BETimestamp(2), vec![(BEBlockNumber(1), Hash::repeat_byte(9))]),
+		true,
+	);
+	store_candidate(&store, own_chunk_only, State::Finalized(BETimestamp(3)), false);
+	// The oldest candidate, but not included yet.
+	store_candidate(&store, pending, State::Unavailable(BETimestamp(0)), true);
+	recompute_stored_total(&store, &TEST_CONFIG).unwrap();
+	assert_eq!(load_stored_total(&store, &TEST_CONFIG).unwrap(), 610);
+
+	let plan = |store: &Arc<dyn Database>, storage_budget| {
+		let config = Config { storage_budget, ..TEST_CONFIG };
+		plan_evictions(store, &config, &Metrics::default(), 0).unwrap()
+	};
+
+	assert!(plan(&store, None).is_empty());
+	assert!(plan(&store, Some(610)).is_empty());
+
+	// The full data of the finalized candidate goes first, then its chunks.
+	assert_eq!(
+		plan(&store, Some(450)),
+		vec![Eviction::AvailableData(finalized), Eviction::Chunks(finalized)],
+	);
+
+	// Chunks of unfinalized and pending candidates are kept, even if the budget can't be met.
+	let evictions = plan(&store, Some(50));
+	assert_eq!(
+		evictions,
+		vec![
+			Eviction::AvailableData(finalized),
+			Eviction::Chunks(finalized),
+			Eviction::Chunks(own_chunk_only),
+			Eviction::AvailableData(pending),
+			Eviction::AvailableData(unfinalized),
+		],
+	);
+
+	// Chunks which are still needed are also kept if they are planned to be evicted, e.g. because
+	// the candidate became unfinalized or pending in the meantime.
+	let evictions =
+		[evictions, vec![Eviction::Chunks(pending), Eviction::Chunks(unfinalized)]].concat();
+	evict(&store, &TEST_CONFIG, &Metrics::default(), 0, evictions).unwrap();
+	assert_eq!(load_stored_total(&store, &TEST_CONFIG).unwrap(), 200);
+
+	for candidate_hash in [finalized, unfinalized, pending] {
+		assert!(load_available_data(&store, &TEST_CONFIG, &candidate_hash).unwrap().is_none());
+		assert!(
+			!load_meta(&store, &TEST_CONFIG, &candidate_hash)
+				.unwrap()
+				.unwrap()
+				.data_available
+		);
+	}
+	for candidate_hash in [finalized, own_chunk_only] {
+		let meta = load_meta(&store, &TEST_CONFIG, &candidate_hash).unwrap().unwrap();
+		assert!(meta.chunks_stored.not_any());
+		assert!(load_chunk(&store, &TEST_CONFIG, &candidate_hash, ValidatorIndex(0))
+			.unwrap()
+			.is_none());
+	}
+	for candidate_hash in [unfinalized, pending] {
+		let meta = load_meta(&store, &TEST_CONFIG, &candidate_hash).unwrap().unwrap();
+		assert!(meta.chunks_stored.all());
+		assert!(load_chunk(&store, &TEST_CONFIG, &candidate_hash, ValidatorIndex(9))
+			.unwrap()
+			.is_some());
+	}
+
+	// Nothing left to evict.
+	assert_eq!(plan(&store, Some(50)), vec![]);
+
+	// Bytes pruned in the background are deducted when the evictions are applied.
+	assert!(plan(&store, Some(150)).is_empty());
+	let config = Config { storage_budget: Some(150), ..TEST_CONFIG };
+	assert!(plan_evictions(&store, &config, &Metrics::default(), 100).unwrap().is_empty());
+	evict(&store, &TEST_CONFIG, &Metrics::default(), 100, vec![]).unwrap();
+	assert_eq!(load_stored_total(&store, &TEST_CONFIG).unwrap(), 100);
+}
+
+#[test]
+fn stored_total_is_kept_up_to_date() {
+	let n_validators = 10;
+	let test_state = TestState::default();
+	let available_data = AvailableData {
+		pov: Arc::new(PoV { block_data: BlockData(vec![4, 5, 6]) }),
+		validation_data: test_state.persisted_validation_data,
+	};
+	let chunk = ErasureChunk {
+		chunk: vec![1, 2, 3],
+		index: ChunkIndex(0),
+		proof: Proof::try_from(vec![vec![3, 4, 5]]).unwrap(),
+	};
+	let legacy = CandidateHash(Hash::repeat_byte(1));
+	let new = CandidateHash(Hash::repeat_byte(2));
+
+	// Candidates stored by older versions have no recorded size.
+	let store = test_store();
+	with_tx(&store, |tx| {
+		let mut chunks_stored = bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators];
+		chunks_stored.set(3, true);
+		write_chunk(tx, &TEST_CONFIG, &legacy, ValidatorIndex(3), &chunk);
+		write_available_data(tx, &TEST_CONFIG, &legacy, &available_data);
+		let meta = CandidateMeta {
+			state: State::Finalized(BETimestamp(1)),
+			data_available: true,
+			chunks_stored,
+		};
+		write_meta(tx, &TEST_CONFIG, &legacy, &meta);
+	});
+
+	// Their sizes are recorded when the subsystem starts.
+	recompute_stored_total(&store, &TEST_CONFIG).unwrap();
+	let size = load_stored_size(&store, &TEST_CONFIG, &legacy).unwrap().unwrap();
+	assert_eq!(size.available_data, available_data.encoded_size() as u64);
+	assert_eq!(size.chunk, chunk.encoded_size() as u64);
+	let legacy_total = size.available_data + size.chunk;
+	assert_eq!(load_stored_total(&store, &TEST_CONFIG).unwrap(), legacy_total);
+
+	// Storing a chunk adds to the total.
+	with_tx(&store, |tx| {
+		let meta = CandidateMeta {
+			state: State::Unavailable(BETimestamp(2)),
+			data_available: false,
+			chunks_stored: bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators],
+		};
+		write_meta(tx, &TEST_CONFIG, &new, &meta);
+	});
+	assert!(store_chunk(&store, &TEST_CONFIG, new, ValidatorIndex(0), chunk.clone()).unwrap());
+	assert!(store_chunk(&store, &TEST_CONFIG, new, ValidatorIndex(0), chunk.clone()).unwrap());
+	assert_eq!(
+		load_stored_total(&store, &TEST_CONFIG).unwrap(),
+		legacy_total + chunk.encoded_size() as u64
+	);
+
+	// Pruning reports the size of the pruned candidates.
+	with_tx(&store, |tx| write_pruning_key(tx, &TEST_CONFIG, Duration::from_secs(5), &legacy));
+	assert_eq!(prune_all(&store, &TEST_CONFIG, Duration::from_secs(10)).unwrap(), legacy_total);
+}
+
 async fn query_available_data(
 	virtual_overseer: &mut VirtualOverseer,
 	candidate_hash: CandidateHash,
@@ -0,0 +1,155 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cache of PVF execution outcomes.
+//!
+//! A validator which backed a candidate is likely to be assigned to check it again in approval
+//! voting. Successful backing validations are remembered together with the produced commitments,
+//! so the approval check can be answered without executing the PVF again.
+//!
+//! Only outcomes of backing are stored and only approval checks are answered from the cache:
+//! backing performs a superset of the checks done in approval, while disputes always execute
+//! the PVF again, as the result of a dispute participation must not depend on past local state.
+
+use super::{perform_basic_checks, LOG_TARGET};
+use codec::Encode;
+use polkadot_node_primitives::PoV;
+use polkadot_node_subsystem::messages::PvfExecKind;
+use polkadot_primitives::{
+	executor_params::ExecutorParamsHash, vstaging::CandidateReceiptV2 as CandidateReceipt,
+	CandidateCommitments, CandidateHash, ExecutorParams, PersistedValidationData, ValidationCode,
+};
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::{Arc, Mutex},
+};
+
+type CacheKey = (CandidateHash, ExecutorParamsHash);
+
+/// Bounded cache of the commitments produced by successful backing validations.
+///
+/// Cloning is cheap, all clones share the same underlying cache.
+#[derive(Clone, Default)]
+pub(crate) struct ExecutionCache(Option<Arc<Mutex<Inner>>>);
+
+#[derive(Default)]
+struct Inner {
+	max_size: usize,
+	size: usize,
+	entries: HashMap<CacheKey, (CandidateCommitments, usize)>,
+	// Insertion order, used to evict the oldest entries first.
+	order: VecDeque<CacheKey>,
+}
+
+impl ExecutionCache {
+	/// Create a cache which uses at most `max_size` bytes for cached commitments. A size of `0`
+	/// disables caching.
+	pub(crate) fn new(max_size: usize) -> Self {
+		if max_size == 0 {
+			return Self(None)
+		}
+
+		Self(Some(Arc::new(Mutex::new(Inner { max_size, ..Default::default() }))))
+	}
+
+	/// Get the commitments of a previous successful backing validation, if the request can be
+	/// answered from the cache.
+	pub(crate) fn lookup(
+		&self,
+		exec_kind: PvfExecKind,
+		candidate_receipt: &CandidateReceipt,
+		persisted_validation_data: &PersistedValidationData,
+		validation_code: &ValidationCode,
+		pov: &PoV,
+		executor_params: &ExecutorParams,
+	) -> Option<CandidateCommitments> {
+		let inner = self.0.as_ref()?;
+		if exec_kind != PvfExecKind::Approval {
+			return None
+		}
+
+		let descriptor = &candidate_receipt.descriptor;
+		// The candidate hash commits to the hashes of all inputs, make sure the provided inputs
+		// match them, otherwise a full validation is needed to produce the right verdict.
+		if persisted_validation_data.hash() != descriptor.persisted_validation_data_hash() ||
+			perform_basic_checks(
+				descriptor,
+				persisted_validation_data.max_pov_size,
+				pov,
+				&validation_code.hash(),
+			)
+			.is_err()
+		{
+			return None
+		}
+
+		let candidate_hash = candidate_receipt.hash();
+		let key = (candidate_hash, executor_params.hash());
+		let commitments = inner
+			.lock()
+			.expect("Lock is never poisoned; qed")
+			.entries
+			.get(&key)
+			.map(|(commitments, _)| commitments.clone())?;
+
+		if commitments.hash() != candidate_receipt.commitments_hash {
+			return None
+		}
+
+		gum::debug!(
+			target: LOG_TARGET,
+			?candidate_hash,
+			"Reusing the result of backing validation for approval",
+		);
+		Some(commitments)
+	}
+
+	/// Remember the commitments produced by a successful validation.
+	pub(crate) fn insert(
+		&self,
+		exec_kind: PvfExecKind,
+		candidate_hash: CandidateHash,
+		executor_params: &ExecutorParams,
+		commitments: &CandidateCommitments,
+	) {
+		let Some(inner) = self.0.as_ref() else { return };
+		if !matches!(exec_kind, PvfExecKind::Backing(_) | PvfExecKind::BackingSystemParas(_)) {
+			return
+		}
+
+		let size = commitments.encoded_size();
+		let mut inner = inner.lock().expect("Lock is never poisoned; qed");
+		if size > inner.max_size {
+			return
+		}
+
+		let key = (candidate_hash, executor_params.hash());
+		if inner.entries.contains_key(&key) {
+			return
+		}
+
+		while inner.size + size > inner.max_size {
+			let Some(oldest) = inner.order.pop_front() else { break };
+			if let Some((_, oldest_size)) = inner.entries.remove(&oldest) {
+				inner.size -= oldest_size;
+			}
+		}
+
+		inner.entries.insert(key, (commitments.clone(), size));
+		inner.order.push_back(key);
+		inner.size += size;
+	}
+}
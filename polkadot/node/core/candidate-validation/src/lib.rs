@@ -74,8 +74,9 @@ use std::{
 
 use async_trait::async_trait;
 
+mod execution_cache;
 mod metrics;
-use self::metrics::Metrics;
+use self::{execution_cache::ExecutionCache, metrics::Metrics};
 
 #[cfg(test)]
 mod tests;
@@ -114,6 +115,12 @@ pub struct Config {
 	pub pvf_prepare_workers_soft_max_num: usize,
 	/// The absolute number of pvf workers that can be spawned in the pvf prepare pool.
 	pub pvf_prepare_workers_hard_max_num: usize,
+	/// The maximum size in bytes of the cache of backing validation results, which are reused
+	/// when approving the same candidate. `0` disables the cache.
+	pub pvf_execution_cache_size: usize,
+	/// The directory where compiled artifacts are shared with other nodes running on the same
+	/// host, if any.
+	pub pvf_shared_artifacts_path: Option<PathBuf>,
 }
 
 /// The candidate validation subsystem.
@@ -174,6 +181,7 @@ where
 fn handle_validation_message<S>(
 	mut sender: S,
 	validation_host: ValidationHost,
+	execution_cache: ExecutionCache,
 	metrics: Metrics,
 	msg: CandidateValidationMessage,
 ) -> Pin<Box<dyn Future<Output = ()> + Send>>
@@ -194,6 +202,21 @@ where
 			let _timer = metrics.time_validate_from_exhaustive();
 			let relay_parent = candidate_receipt.descriptor.relay_parent();
 
+			if let Some(commitments) = execution_cache.lookup(
+				exec_kind,
+				&candidate_receipt,
+				&validation_data,
+				&validation_code,
+				&pov,
+				&executor_params,
+			) {
+				metrics.on_execution_cache_hit();
+				let res = Ok(ValidationResult::Valid(commitments, validation_data));
+				metrics.on_validation_event(&res);
+				let _ = response_sender.send(res);
+				return
+			}
+
 			let maybe_claim_queue = claim_queue(relay_parent, &mut sender).await;
 			let Some(session_index) = get_session_index(&mut sender, relay_parent).await else {
 				let error = "cannot fetch session index from the runtime";
@@ -230,6 +253,8 @@ where
 				return
 			};
 
+			let candidate_hash = candidate_receipt.hash();
+			let cache_executor_params = executor_params.clone();
 			let res = validate_candidate_exhaustive(
 				session_index,
 				validation_host,
@@ -245,6 +270,15 @@ where
 			)
 			.await;
 
+			if let Ok(ValidationResult::Valid(ref commitments, _)) = res {
+				execution_cache.insert(
+					exec_kind,
+					candidate_hash,
+					&cache_executor_params,
+					commitments,
+				);
+			}
+
 			metrics.on_validation_event(&res);
 			let _ = response_sender.send(res);
 		}
@@ -317,24 +351,26 @@ async fn run<Context>(
 		pvf_execute_workers_max_num,
 		pvf_prepare_workers_soft_max_num,
 		pvf_prepare_workers_hard_max_num,
+		pvf_execution_cache_size,
+		pvf_shared_artifacts_path,
 	}: Config,
 ) -> SubsystemResult<()> {
-	let (mut validation_host, task) = polkadot_node_core_pvf::start(
-		polkadot_node_core_pvf::Config::new(
-			artifacts_cache_path,
-			node_version,
-			secure_validator_mode,
-			prep_worker_path,
-			exec_worker_path,
-			pvf_execute_workers_max_num,
-			pvf_prepare_workers_soft_max_num,
-			pvf_prepare_workers_hard_max_num,
-		),
-		pvf_metrics,
-	)
-	.await?;
+	let mut pvf_config = polkadot_node_core_pvf::Config::new(
+		artifacts_cache_path,
+		node_version,
+		secure_validator_mode,
+		prep_worker_path,
+		exec_worker_path,
+		pvf_execute_workers_max_num,
+		pvf_prepare_workers_soft_max_num,
+		pvf_prepare_workers_hard_max_num,
+	);
+	pvf_config.shared_artifacts_path = pvf_shared_artifacts_path;
+	let (mut validation_host, task) =
+		polkadot_node_core_pvf::start(pvf_config, pvf_metrics).await?;
 	ctx.spawn_blocking("pvf-validation-host", task.boxed())?;
 
+	let execution_cache = ExecutionCache::new(pvf_execution_cache_size);
 	let mut tasks = FuturesUnordered::new();
 	let mut prepare_state = PrepareValidationState::default();
 
@@ -355,7 +391,13 @@ async fn run<Context>(
 						Ok(FromOrchestra::Signal(OverseerSignal::BlockFinalized(..))) => {},
 						Ok(FromOrchestra::Signal(OverseerSignal::Conclude)) => return Ok(()),
 						Ok(FromOrchestra::Communication { msg }) => {
-							let task = handle_validation_message(ctx.sender().clone(), validation_host.clone(), metrics.clone(), msg);
+							let task = handle_validation_message(
+								ctx.sender().clone(),
+								validation_host.clone(),
+								execution_cache.clone(),
+								metrics.clone(),
+								msg,
+							);
 							tasks.push(task);
 							if tasks.len() >= TASK_LIMIT {
 								break
@@ -22,6 +22,7 @@ pub(crate) struct MetricsInner {
 	pub(crate) validation_requests: prometheus::CounterVec<prometheus::U64>,
 	pub(crate) validate_from_exhaustive: prometheus::Histogram,
 	pub(crate) validate_candidate_exhaustive: prometheus::Histogram,
+	pub(crate) execution_cache_hits: prometheus::Counter<prometheus::U64>,
 }
 
 /// Candidate validation metrics.
@@ -45,6 +46,13 @@ impl Metrics {
 		}
 	}
 
+	/// Note a validation request answered from the execution cache.
+	pub fn on_execution_cache_hit(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.execution_cache_hits.inc();
+		}
+	}
+
 	/// Provide a timer for `validate_from_exhaustive` which observes on drop.
 	pub fn time_validate_from_exhaustive(
 		&self,
@@ -89,6 +97,13 @@ impl metrics::Metrics for Metrics {
 				))?,
 				registry,
 			)?,
+			execution_cache_hits: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_candidate_validation_execution_cache_hits_total",
+					"Number of approval checks answered from the results of backing validation.",
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -2220,3 +2220,138 @@ fn maybe_prepare_validation_does_not_prepare_already_prepared_pvfs() {
 	assert!(state.is_next_session_authority);
 	assert_eq!(state.already_prepared_code_hashes.len(), 3);
 }
+
+fn execution_cache_candidate(
+	validation_data: &PersistedValidationData,
+	pov: &PoV,
+	validation_code: &ValidationCode,
+	commitments: &CandidateCommitments,
+) -> CandidateReceipt {
+	let descriptor = make_valid_candidate_descriptor_v2(
+		ParaId::from(1_u32),
+		dummy_hash(),
+		CoreIndex(0),
+		1,
+		validation_data.hash(),
+		pov.hash(),
+		validation_code.hash(),
+		commitments.head_data.hash(),
+		dummy_hash(),
+	);
+	CandidateReceipt { descriptor, commitments_hash: commitments.hash() }
+}
+
+#[test]
+fn execution_cache_reuses_backing_result_for_approval_only() {
+	let validation_data = PersistedValidationData { max_pov_size: 1024, ..Default::default() };
+	let pov = PoV { block_data: BlockData(vec![1; 32]) };
+	let validation_code = ValidationCode(vec![2; 16]);
+	let commitments =
+		CandidateCommitments { head_data: HeadData(vec![1, 1, 1]), ..Default::default() };
+	let candidate_receipt =
+		execution_cache_candidate(&validation_data, &pov, &validation_code, &commitments);
+	let executor_params = ExecutorParams::default();
+
+	let cache = ExecutionCache::new(1024 * 1024);
+	let lookup = |exec_kind, pov: &PoV, validation_data: &PersistedValidationData| {
+		cache.lookup(
+			exec_kind,
+			&candidate_receipt,
+			validation_data,
+			&validation_code,
+			pov,
+			&executor_params,
+		)
+	};
+
+	assert_eq!(lookup(PvfExecKind::Approval, &pov, &validation_data), None);
+
+	// Approval results are never cached.
+	cache.insert(PvfExecKind::Approval, candidate_receipt.hash(), &executor_params, &commitments);
+	assert_eq!(lookup(PvfExecKind::Approval, &pov, &validation_data), None);
+
+	cache.insert(
+		PvfExecKind::Backing(dummy_hash()),
+		candidate_receipt.hash(),
+		&executor_params,
+		&commitments,
+	);
+	assert_eq!(lookup(PvfExecKind::Approval, &pov, &validation_data), Some(commitments.clone()));
+
+	// Disputes and backing always execute the PVF.
+	assert_eq!(lookup(PvfExecKind::Dispute, &pov, &validation_data), None);
+	assert_eq!(lookup(PvfExecKind::Backing(dummy_hash()), &pov, &validation_data), None);
+
+	// Inputs not matching the candidate need a full validation.
+	let other_pov = PoV { block_data: BlockData(vec![2; 32]) };
+	assert_eq!(lookup(PvfExecKind::Approval, &other_pov, &validation_data), None);
+	let other_validation_data =
+		PersistedValidationData { max_pov_size: 2048, ..Default::default() };
+	assert_eq!(lookup(PvfExecKind::Approval, &pov, &other_validation_data), None);
+
+	// A disabled cache never stores anything.
+	let disabled = ExecutionCache::new(0);
+	disabled.insert(
+		PvfExecKind::Backing(dummy_hash()),
+		candidate_receipt.hash(),
+		&executor_params,
+		&commitments,
+	);
+	assert_eq!(
+		disabled.lookup(
+			PvfExecKind::Approval,
+			&candidate_receipt,
+			&validation_data,
+			&validation_code,
+			&pov,
+			&executor_params,
+		),
+		None
+	);
+}
+
+#[test]
+fn execution_cache_evicts_oldest_entries() {
+	let validation_data = PersistedValidationData { max_pov_size: 1024, ..Default::default() };
+	let pov = PoV { block_data: BlockData(vec![1; 32]) };
+	let validation_code = ValidationCode(vec![2; 16]);
+	let executor_params = ExecutorParams::default();
+
+	let candidates: Vec<_> = (0..3u8)
+		.map(|i| {
+			let commitments =
+				CandidateCommitments { head_data: HeadData(vec![i; 64]), ..Default::default() };
+			let receipt =
+				execution_cache_candidate(&validation_data, &pov, &validation_code, &commitments);
+			(receipt, commitments)
+		})
+		.collect();
+
+	// Room for two entries only.
+	let cache = ExecutionCache::new(2 * candidates[0].1.encoded_size());
+	for (receipt, commitments) in candidates.iter() {
+		cache.insert(
+			PvfExecKind::Backing(dummy_hash()),
+			receipt.hash(),
+			&executor_params,
+			commitments,
+		);
+	}
+
+	let cached: Vec<_> = candidates
+		.iter()
+		.map(|(receipt, _)| {
+			cache
+				.lookup(
+					PvfExecKind::Approval,
+					receipt,
+					&validation_data,
+					&validation_code,
+					&pov,
+					&executor_params,
+				)
+				.is_some()
+		})
+		.collect();
+	assert_eq!(cached, vec![false, true, true]);
+}
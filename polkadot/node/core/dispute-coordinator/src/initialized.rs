@@ -17,7 +17,7 @@
 //! Dispute coordinator subsystem in initialized state (after first active leaf is received).
 
 use std::{
-	collections::{BTreeMap, VecDeque},
+	collections::{BTreeMap, HashSet, VecDeque},
 	sync::Arc,
 };
 
@@ -29,8 +29,10 @@ use futures::{
 use sc_keystore::LocalKeystore;
 
 use polkadot_node_primitives::{
-	disputes::ValidCandidateVotes, CandidateVotes, DisputeStatus, SignedDisputeStatement,
-	Timestamp, DISPUTE_WINDOW,
+	disputes::{
+		DisputeCoordinatorState, DisputeParticipation, SpamSlotsUsage, ValidCandidateVotes,
+	},
+	CandidateVotes, DisputeStatus, SignedDisputeStatement, Timestamp, DISPUTE_WINDOW,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -64,7 +66,7 @@ use crate::{
 
 use super::{
 	backend::Backend,
-	make_dispute_message,
+	inspection, make_dispute_message,
 	participation::{
 		self, Participation, ParticipationPriority, ParticipationRequest, ParticipationStatement,
 		WorkerMessageReceiver,
@@ -931,6 +933,39 @@ impl Initialized {
 
 				let _ = tx.send(undisputed_chain);
 			},
+			DisputeCoordinatorMessage::InspectState(tx) => {
+				gum::trace!(target: LOG_TARGET, "DisputeCoordinatorMessage::InspectState");
+				let (priority_queue, best_effort_queue) = self.participation.queued();
+				let running: HashSet<_> = self.participation.running().copied().collect();
+
+				let disputes = inspection::load_disputes(overlay_db, now, |candidate_hash| {
+					Some(if running.contains(candidate_hash) {
+						DisputeParticipation::Running
+					} else if priority_queue.contains(candidate_hash) {
+						DisputeParticipation::QueuedPriority
+					} else if best_effort_queue.contains(candidate_hash) {
+						DisputeParticipation::QueuedBestEffort
+					} else {
+						DisputeParticipation::NotQueued
+					})
+				})?;
+
+				let mut spam_slots: Vec<_> = self
+					.spam_slots
+					.used()
+					.map(|(session, validator, used)| SpamSlotsUsage { session, validator, used })
+					.collect();
+				spam_slots.sort_by_key(|usage| (usage.session, usage.validator));
+
+				let _ = tx.send(DisputeCoordinatorState {
+					disputes,
+					spam_slots,
+					max_spam_slots: SpamSlots::max_spam_votes(),
+					priority_queue: priority_queue.into_iter().map(|hash| hash.0).collect(),
+					best_effort_queue: best_effort_queue.into_iter().map(|hash| hash.0).collect(),
+					running_participations: running.into_iter().map(|hash| hash.0).collect(),
+				});
+			},
 		}
 
 		Ok(Box::new(|| Ok(())))
@@ -0,0 +1,86 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Inspection of the disputes known to the coordinator, for node operators.
+
+use std::sync::Arc;
+
+use polkadot_node_primitives::{
+	dispute_is_inactive,
+	disputes::{DisputeInfo, DisputeParticipation},
+	Timestamp,
+};
+use polkadot_node_subsystem::SubsystemError;
+use polkadot_node_subsystem_util::database::Database;
+use polkadot_primitives::CandidateHash;
+
+use crate::{
+	backend::{Backend, OverlayedBackend},
+	db::v1::DbBackend,
+	error::FatalResult,
+	metrics::Metrics,
+	status::{Clock, SystemClock},
+	Config, LOG_TARGET,
+};
+
+/// Load all recent disputes together with their votes.
+///
+/// `participation` tells about our participation in the dispute for the given candidate.
+pub(crate) fn load_disputes(
+	overlay_db: &OverlayedBackend<'_, impl Backend>,
+	now: Timestamp,
+	participation: impl Fn(&CandidateHash) -> Option<DisputeParticipation>,
+) -> FatalResult<Vec<DisputeInfo>> {
+	let recent_disputes = overlay_db.load_recent_disputes()?.unwrap_or_default();
+
+	let mut disputes = Vec::with_capacity(recent_disputes.len());
+	for ((session, candidate_hash), status) in recent_disputes {
+		let Some(votes) = overlay_db.load_candidate_votes(session, &candidate_hash)? else {
+			gum::debug!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				session,
+				"No votes found for recent dispute",
+			);
+			continue
+		};
+
+		disputes.push(DisputeInfo::new(
+			session,
+			candidate_hash,
+			status,
+			!dispute_is_inactive(&status, &now),
+			&votes.into(),
+			participation(&candidate_hash),
+		));
+	}
+
+	Ok(disputes)
+}
+
+/// Read all recent disputes and their votes from the database of the dispute coordinator.
+///
+/// The database must not be in use by a running dispute coordinator.
+pub fn export_disputes(
+	store: Arc<dyn Database>,
+	config: Config,
+) -> Result<Vec<DisputeInfo>, SubsystemError> {
+	let backend = DbBackend::new(store, config.column_config(), Metrics::default());
+	let overlay_db = OverlayedBackend::new(&backend);
+
+	load_disputes(&overlay_db, SystemClock.now(), |_| None)
+		.map_err(|e| SubsystemError::with_origin("dispute-coordinator", e))
+}
@@ -109,6 +109,10 @@ mod metrics;
 /// Status tracking of disputes (`DisputeStatus`).
 mod status;
 
+/// Inspection of the recorded disputes, for node operators.
+mod inspection;
+pub use inspection::export_disputes;
+
 use crate::status::Clock;
 
 #[cfg(test)]
@@ -238,6 +238,20 @@ impl Participation {
 		Ok(())
 	}
 
+	/// Candidates queued for participation, with priority and best effort respectively, in
+	/// participation order.
+	pub fn queued(&self) -> (Vec<CandidateHash>, Vec<CandidateHash>) {
+		(
+			self.queue.priority_candidates().copied().collect(),
+			self.queue.best_effort_candidates().copied().collect(),
+		)
+	}
+
+	/// Candidates for which participation is currently running.
+	pub fn running(&self) -> impl Iterator<Item = &CandidateHash> {
+		self.running_participations.iter()
+	}
+
 	/// Dequeue until `MAX_PARALLEL_PARTICIPATIONS` is reached.
 	async fn dequeue_until_capacity<Context>(
 		&mut self,
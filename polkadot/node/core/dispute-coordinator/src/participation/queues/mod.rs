@@ -218,6 +218,16 @@ impl Queues {
 		None
 	}
 
+	/// Candidates in the priority queue, in participation order.
+	pub fn priority_candidates(&self) -> impl Iterator<Item = &CandidateHash> {
+		self.priority.values().map(|req| req.candidate_hash())
+	}
+
+	/// Candidates in the best effort queue, in participation order.
+	pub fn best_effort_candidates(&self) -> impl Iterator<Item = &CandidateHash> {
+		self.best_effort.values().map(|req| req.candidate_hash())
+	}
+
 	/// Reprioritizes any participation requests pertaining to the
 	/// passed candidates from best effort to priority.
 	pub async fn prioritize_if_present(
@@ -132,4 +132,16 @@ impl SpamSlots {
 		self.unconfirmed.retain(|(session, _), _| *session >= oldest_index);
 		self.slots.retain(|(session, _), _| *session >= oldest_index);
 	}
+
+	/// The spam slots currently in use, per session and validator.
+	pub fn used(&self) -> impl Iterator<Item = (SessionIndex, ValidatorIndex, SpamCount)> + '_ {
+		self.slots
+			.iter()
+			.map(|((session, validator), count)| (*session, *validator, *count))
+	}
+
+	/// The number of spam slots available to each validator per session.
+	pub fn max_spam_votes() -> SpamCount {
+		MAX_SPAM_VOTES
+	}
 }
@@ -32,8 +32,8 @@ use futures::{
 use polkadot_node_subsystem_util::database::Database;
 
 use polkadot_node_primitives::{
-	DisputeMessage, DisputeStatus, SignedDisputeStatement, SignedFullStatement, Statement,
-	DISPUTE_WINDOW,
+	disputes::DisputeParticipation, DisputeMessage, DisputeStatus, SignedDisputeStatement,
+	SignedFullStatement, Statement, DISPUTE_WINDOW,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -1486,6 +1486,98 @@ fn conflicting_votes_lead_to_dispute_participation() {
 	});
 }
 
+#[test]
+fn inspect_state_reports_dispute_votes() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt = make_valid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+
+			test_state
+				.activate_leaf_at_session(
+					&mut virtual_overseer,
+					session,
+					1,
+					vec![make_candidate_backed_event(candidate_receipt.clone())],
+				)
+				.await;
+
+			let (valid_vote, invalid_vote) = generate_opposing_votes_pair(
+				&test_state,
+				ValidatorIndex(3),
+				ValidatorIndex(1),
+				candidate_hash,
+				session,
+				VoteType::Explicit,
+			)
+			.await;
+
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ImportStatements {
+						candidate_receipt: candidate_receipt.clone(),
+						session,
+						statements: vec![
+							(valid_vote, ValidatorIndex(3)),
+							(invalid_vote, ValidatorIndex(1)),
+						],
+						pending_confirmation: None,
+					},
+				})
+				.await;
+			handle_disabled_validators_queries(&mut virtual_overseer, Vec::new()).await;
+			handle_approval_vote_request(&mut virtual_overseer, &candidate_hash, HashMap::new())
+				.await;
+
+			participation_with_distribution(
+				&mut virtual_overseer,
+				&candidate_hash,
+				candidate_receipt.commitments_hash,
+			)
+			.await;
+
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::InspectState(tx),
+				})
+				.await;
+
+			let state = rx.await.unwrap();
+			assert_eq!(state.disputes.len(), 1);
+			let dispute = &state.disputes[0];
+			assert_eq!(dispute.session, session);
+			assert_eq!(dispute.candidate_hash, candidate_hash.0);
+			assert_eq!(dispute.status, DisputeStatus::Active);
+			assert!(dispute.active);
+			assert_eq!(dispute.invalid_votes, 1);
+			assert_eq!(dispute.valid_votes as usize + 1, dispute.votes.len());
+			assert!(dispute
+				.votes
+				.iter()
+				.any(|vote| vote.validator == ValidatorIndex(3) && vote.valid));
+			assert!(dispute
+				.votes
+				.iter()
+				.any(|vote| vote.validator == ValidatorIndex(1) && !vote.valid));
+			assert_eq!(dispute.participation, Some(DisputeParticipation::NotQueued));
+			assert!(state.priority_queue.is_empty());
+			assert!(state.best_effort_queue.is_empty());
+			assert!(state.spam_slots.is_empty());
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
 #[test]
 fn positive_votes_dont_trigger_participation() {
 	test_harness(|mut test_state, mut virtual_overseer| {
@@ -53,15 +53,29 @@
 //! 7. There is a separate process for pruning the prepared artifacts whose `last_time_needed` is
 //!    older by a predefined parameter. This process is run very rarely (say, once a day). Once the
 //!    artifact is expired it is removed from disk eagerly atomically.
+//!
+//! # Shared artifacts
+//!
+//! Several nodes running on the same host may be configured with a [`SharedArtifacts`] directory.
+//! Before compiling a PVF, the host looks up the artifact there and, if a verified copy exists,
+//! links it into its own cache instead of compiling it again. After a successful preparation the
+//! artifact is published to the shared directory for the other nodes. The shared directory is never
+//! cleared on start-up and is not subject to pruning.
+//!
+//! The checksum stored next to a shared artifact only detects corruption, like a truncated write,
+//! not tampering: whoever can write to the directory can replace both files. An artifact is
+//! executed as native code, so the nodes sharing a directory must trust each other. The directory
+//! is therefore only used if it is owned by the user running the node and not writable by anyone
+//! else, which in practice means the nodes sharing it run as the same user.
 
-use crate::{host::PrecheckResultSender, worker_interface::WORKER_DIR_PREFIX};
+use crate::{host::PrecheckResultSender, worker_interface::WORKER_DIR_PREFIX, LOG_TARGET};
 use always_assert::always;
 use polkadot_node_core_pvf_common::{error::PrepareError, pvf::PvfPrepData};
 use polkadot_parachain_primitives::primitives::ValidationCodeHash;
 use polkadot_primitives::ExecutorParamsPrepHash;
 use std::{
 	collections::HashMap,
-	fs,
+	fs, io,
 	path::{Path, PathBuf},
 	time::{Duration, SystemTime},
 };
@@ -72,6 +86,18 @@ const ARTIFACT_EXTENSION: &str = "pvf";
 /// The prefix that artifacts used to start with under the old naming scheme.
 const ARTIFACT_OLD_PREFIX: &str = "wasmtime_";
 
+/// The extension to use for artifacts in the shared directory.
+const SHARED_ARTIFACT_EXTENSION: &str = "shared-pvf";
+
+/// The extension of the file holding the checksum of a shared artifact.
+const SHARED_CHECKSUM_EXTENSION: &str = "checksum";
+
+/// The extension of the file which is held while a shared artifact is being published.
+const SHARED_LOCK_EXTENSION: &str = "lock";
+
+/// A lock file older than this is considered to be left over by a crashed process.
+const SHARED_LOCK_STALE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
 pub fn generate_artifact_path(cache_path: &Path) -> PathBuf {
 	let file_name = {
 		use array_bytes::Hex;
@@ -128,6 +154,217 @@ impl ArtifactPathId {
 	}
 }
 
+/// A content-addressed directory of prepared artifacts, shared by the nodes running on the same
+/// host.
+///
+/// The file name of a shared artifact is derived from the [`ArtifactId`] and the node version, so
+/// all the nodes of the same version look for the same file, while artifacts compiled by a
+/// different version are never picked up. Every artifact is stored next to a checksum file, and
+/// the checksum is verified before the artifact is reused. Publishing is guarded by a lock file, so
+/// only one process writes a given artifact at a time. Both files are moved into place atomically.
+///
+/// The checksum does not protect against tampering, see the [module docs](self#shared-artifacts)
+/// for the trust model.
+#[derive(Debug, Clone)]
+pub struct SharedArtifacts {
+	path: PathBuf,
+	node_version: String,
+}
+
+impl SharedArtifacts {
+	/// Create the shared directory on-disk if it doesn't exist, only accessible by the current
+	/// user. Unlike [`Artifacts::new`], the content of the directory is left untouched, as it may
+	/// be in use by other nodes.
+	///
+	/// Fails if the directory can't be created, or if it is not owned by the current user or is
+	/// writable by other users, as the artifacts found there could not be trusted.
+	pub async fn new(path: PathBuf, node_version: String) -> io::Result<Self> {
+		let mut builder = tokio::fs::DirBuilder::new();
+		builder.recursive(true);
+		#[cfg(unix)]
+		builder.mode(0o700);
+		builder.create(&path).await?;
+		ensure_private(&tokio::fs::metadata(&path).await?)?;
+		Ok(Self { path, node_version })
+	}
+
+	fn artifact_path(&self, artifact_id: &ArtifactId) -> PathBuf {
+		let node_version: String = self
+			.node_version
+			.chars()
+			.map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+			.collect();
+		// The version may contain dots, so the extension is not set with `set_extension`.
+		self.path.join(format!(
+			"{:x}_{:x}_{}.{}",
+			artifact_id.code_hash,
+			artifact_id.executor_params_prep_hash,
+			node_version,
+			SHARED_ARTIFACT_EXTENSION,
+		))
+	}
+
+	/// Look up a verified shared artifact and link (or copy) it into the cache. Returns the path
+	/// of the artifact in the cache and its size.
+	///
+	/// Any failure is treated as a cache miss, in which case the artifact must be prepared.
+	pub async fn fetch(
+		&self,
+		artifact_id: &ArtifactId,
+		cache_path: &Path,
+	) -> Option<(PathBuf, u64)> {
+		let shared_path = self.artifact_path(artifact_id);
+		let checksum =
+			tokio::fs::read_to_string(shared_path.with_extension(SHARED_CHECKSUM_EXTENSION))
+				.await
+				.ok()?;
+		// The directory being private, this only fails for files left over from before it was.
+		if let Err(error) = ensure_private(&tokio::fs::metadata(&shared_path).await.ok()?) {
+			gum::warn!(
+				target: LOG_TARGET,
+				?artifact_id,
+				?error,
+				"shared artifact {} is not trusted",
+				shared_path.display(),
+			);
+			return None
+		}
+
+		let artifact_path = generate_artifact_path(cache_path);
+		if tokio::fs::hard_link(&shared_path, &artifact_path).await.is_err() {
+			// The shared directory may be on another file system.
+			tokio::fs::copy(&shared_path, &artifact_path).await.ok()?;
+		}
+
+		// Verify the local file, so the shared one being replaced in the meantime doesn't matter.
+		let verified = match tokio::fs::read(&artifact_path).await {
+			Ok(artifact) => blake3::hash(&artifact).to_hex().as_str() == checksum.trim(),
+			Err(_) => false,
+		};
+		let size = tokio::fs::metadata(&artifact_path).await.map(|m| m.len());
+		match size {
+			Ok(size) if verified => Some((artifact_path, size)),
+			_ => {
+				gum::warn!(
+					target: LOG_TARGET,
+					?artifact_id,
+					"shared artifact {} failed verification",
+					shared_path.display(),
+				);
+				let _ = tokio::fs::remove_file(&artifact_path).await;
+				// Remove the corrupted artifact, unless it is being published right now, so it can
+				// be published again.
+				let lock_path = shared_path.with_extension(SHARED_LOCK_EXTENSION);
+				if let Ok(Some(_lock)) = SharedLock::acquire(lock_path).await {
+					let _ = tokio::fs::remove_file(
+						shared_path.with_extension(SHARED_CHECKSUM_EXTENSION),
+					)
+					.await;
+					let _ = tokio::fs::remove_file(&shared_path).await;
+				}
+				None
+			},
+		}
+	}
+
+	/// Publish a prepared artifact with the given checksum to the shared directory.
+	///
+	/// Does nothing if the artifact is already shared or another process is publishing it.
+	pub async fn publish(
+		&self,
+		artifact_id: &ArtifactId,
+		artifact_path: &Path,
+		checksum: &str,
+	) -> io::Result<()> {
+		let shared_path = self.artifact_path(artifact_id);
+		let checksum_path = shared_path.with_extension(SHARED_CHECKSUM_EXTENSION);
+		if tokio::fs::metadata(&checksum_path).await.is_ok() {
+			return Ok(())
+		}
+
+		let lock_path = shared_path.with_extension(SHARED_LOCK_EXTENSION);
+		let Some(_lock) = SharedLock::acquire(lock_path).await? else { return Ok(()) };
+		// Another process may have published it while we were acquiring the lock.
+		if tokio::fs::metadata(&checksum_path).await.is_ok() {
+			return Ok(())
+		}
+
+		// Write both files under temporary names first, so readers never see partial content.
+		// The checksum is renamed last, as it is what readers look for first.
+		let tmp_artifact_path = generate_artifact_path(&self.path).with_extension("tmp");
+		let tmp_checksum_path = tmp_artifact_path.with_extension("checksum.tmp");
+		let result = async {
+			tokio::fs::copy(artifact_path, &tmp_artifact_path).await?;
+			tokio::fs::write(&tmp_checksum_path, checksum).await?;
+			tokio::fs::rename(&tmp_artifact_path, &shared_path).await?;
+			tokio::fs::rename(&tmp_checksum_path, &checksum_path).await
+		}
+		.await;
+		if result.is_err() {
+			let _ = tokio::fs::remove_file(&tmp_artifact_path).await;
+			let _ = tokio::fs::remove_file(&tmp_checksum_path).await;
+		}
+
+		result
+	}
+}
+
+/// Ensure a shared file or directory is owned by the current user and not writable by other users.
+#[cfg(unix)]
+fn ensure_private(metadata: &fs::Metadata) -> io::Result<()> {
+	use std::os::unix::fs::MetadataExt;
+
+	// SAFETY: `geteuid` is always successful.
+	let uid = unsafe { libc::geteuid() };
+	if metadata.uid() != uid {
+		return Err(io::Error::new(io::ErrorKind::PermissionDenied, "not owned by the current user"))
+	}
+	if metadata.mode() & 0o022 != 0 {
+		return Err(io::Error::new(io::ErrorKind::PermissionDenied, "writable by other users"))
+	}
+	Ok(())
+}
+
+#[cfg(not(unix))]
+fn ensure_private(_metadata: &fs::Metadata) -> io::Result<()> {
+	Ok(())
+}
+
+/// A lock file which is removed on drop.
+struct SharedLock(PathBuf);
+
+impl SharedLock {
+	/// Try to create the lock file. Returns `None` if it is held by another process.
+	async fn acquire(path: PathBuf) -> io::Result<Option<Self>> {
+		for _ in 0..2 {
+			match tokio::fs::OpenOptions::new().write(true).create_new(true).open(&path).await {
+				Ok(_) => return Ok(Some(Self(path))),
+				Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+					let stale = tokio::fs::metadata(&path)
+						.await
+						.and_then(|metadata| metadata.modified())
+						.ok()
+						.and_then(|modified| modified.elapsed().ok())
+						.map_or(false, |age| age > SHARED_LOCK_STALE_TIMEOUT);
+					if !stale {
+						return Ok(None)
+					}
+					let _ = tokio::fs::remove_file(&path).await;
+				},
+				Err(err) => return Err(err),
+			}
+		}
+
+		Ok(None)
+	}
+}
+
+impl Drop for SharedLock {
+	fn drop(&mut self) {
+		let _ = fs::remove_file(&self.0);
+	}
+}
+
 #[derive(Debug)]
 pub enum ArtifactState {
 	/// The artifact is ready to be used by the executor.
@@ -446,4 +683,110 @@ mod tests {
 		assert!(!artifacts.artifact_ids().contains(&artifact_id3));
 		assert!(pruned.contains(&(artifact_id3, path3)));
 	}
+
+	#[tokio::test]
+	async fn shared_artifact_is_reused() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path1 = tempdir.path().join("node1");
+		let cache_path2 = tempdir.path().join("node2");
+		fs::create_dir(&cache_path1).unwrap();
+		fs::create_dir(&cache_path2).unwrap();
+		let shared = SharedArtifacts::new(tempdir.path().join("shared"), "1.0.0-abcdef".into())
+			.await
+			.unwrap();
+
+		assert!(shared.fetch(&artifact_id(1), &cache_path2).await.is_none());
+
+		let path = generate_artifact_path(&cache_path1);
+		fs::write(&path, b"artifact").unwrap();
+		let checksum = blake3::hash(b"artifact").to_hex().to_string();
+		shared.publish(&artifact_id(1), &path, &checksum).await.unwrap();
+
+		let (fetched_path, size) = shared.fetch(&artifact_id(1), &cache_path2).await.unwrap();
+		assert!(fetched_path.starts_with(&cache_path2));
+		assert_eq!(fs::read(&fetched_path).unwrap(), b"artifact");
+		assert_eq!(size, 8);
+
+		// Artifacts of other PVFs or other node versions are not picked up.
+		assert!(shared.fetch(&artifact_id(2), &cache_path2).await.is_none());
+		let other_version =
+			SharedArtifacts::new(tempdir.path().join("shared"), "1.0.1-abcdef".into())
+				.await
+				.unwrap();
+		assert!(other_version.fetch(&artifact_id(1), &cache_path2).await.is_none());
+
+		// Starting up a node does not clear the shared directory.
+		let _ = Artifacts::new(&tempdir.path().join("shared")).await;
+		assert!(shared.fetch(&artifact_id(1), &cache_path2).await.is_some());
+	}
+
+	#[tokio::test]
+	async fn corrupted_shared_artifact_is_not_reused() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path = tempdir.path().join("cache");
+		fs::create_dir(&cache_path).unwrap();
+		let shared = SharedArtifacts::new(tempdir.path().join("shared"), "1.0.0".into())
+			.await
+			.unwrap();
+
+		let path = generate_artifact_path(&cache_path);
+		fs::write(&path, b"artifact").unwrap();
+		let checksum = blake3::hash(b"other artifact").to_hex().to_string();
+		shared.publish(&artifact_id(1), &path, &checksum).await.unwrap();
+
+		assert!(shared.fetch(&artifact_id(1), &cache_path).await.is_none());
+		// Only the original artifact is left in the cache and the corrupted one is removed from
+		// the shared directory, so it can be published again.
+		assert_eq!(fs::read_dir(&cache_path).unwrap().count(), 1);
+		assert_eq!(fs::read_dir(tempdir.path().join("shared")).unwrap().count(), 0);
+
+		let checksum = blake3::hash(b"artifact").to_hex().to_string();
+		shared.publish(&artifact_id(1), &path, &checksum).await.unwrap();
+		assert!(shared.fetch(&artifact_id(1), &cache_path).await.is_some());
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn shared_directory_writable_by_others_is_not_used() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let tempdir = tempfile::tempdir().unwrap();
+		let created_path = tempdir.path().join("created");
+		let _ = SharedArtifacts::new(created_path.clone(), "1.0.0".into()).await.unwrap();
+		assert_eq!(fs::metadata(&created_path).unwrap().permissions().mode() & 0o777, 0o700);
+
+		let shared_path = tempdir.path().join("shared");
+		fs::create_dir(&shared_path).unwrap();
+		fs::set_permissions(&shared_path, fs::Permissions::from_mode(0o777)).unwrap();
+		assert!(SharedArtifacts::new(shared_path.clone(), "1.0.0".into()).await.is_err());
+
+		fs::set_permissions(&shared_path, fs::Permissions::from_mode(0o755)).unwrap();
+		assert!(SharedArtifacts::new(shared_path, "1.0.0".into()).await.is_ok());
+	}
+
+	#[tokio::test]
+	async fn shared_artifact_is_not_published_while_locked() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path = tempdir.path().join("cache");
+		fs::create_dir(&cache_path).unwrap();
+		let shared = SharedArtifacts::new(tempdir.path().join("shared"), "1.0.0".into())
+			.await
+			.unwrap();
+
+		let path = generate_artifact_path(&cache_path);
+		fs::write(&path, b"artifact").unwrap();
+		let checksum = blake3::hash(b"artifact").to_hex().to_string();
+
+		let lock_path = shared.artifact_path(&artifact_id(1)).with_extension(SHARED_LOCK_EXTENSION);
+		let lock = SharedLock::acquire(lock_path.clone()).await.unwrap().unwrap();
+		assert!(SharedLock::acquire(lock_path.clone()).await.unwrap().is_none());
+
+		shared.publish(&artifact_id(1), &path, &checksum).await.unwrap();
+		assert!(shared.fetch(&artifact_id(1), &cache_path).await.is_none());
+
+		drop(lock);
+		assert!(!lock_path.exists());
+		shared.publish(&artifact_id(1), &path, &checksum).await.unwrap();
+		assert!(shared.fetch(&artifact_id(1), &cache_path).await.is_some());
+	}
 }
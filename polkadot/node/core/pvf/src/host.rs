@@ -21,7 +21,10 @@
 //! [`ValidationHost`], that allows communication with that event-loop.
 
 use crate::{
-	artifacts::{ArtifactId, ArtifactPathId, ArtifactState, Artifacts, ArtifactsCleanupConfig},
+	artifacts::{
+		ArtifactId, ArtifactPathId, ArtifactState, Artifacts, ArtifactsCleanupConfig,
+		SharedArtifacts,
+	},
 	execute::{self, PendingExecutionRequest},
 	metrics::Metrics,
 	prepare, Priority, SecurityStatus, ValidationError, LOG_TARGET,
@@ -183,6 +186,9 @@ struct ExecutePvfInputs {
 pub struct Config {
 	/// The root directory where the prepared artifacts can be stored.
 	pub cache_path: PathBuf,
+	/// The directory where prepared artifacts are shared with other nodes running on the same
+	/// host, if any. Must not be the same as `cache_path`. Only used when `node_version` is set.
+	pub shared_artifacts_path: Option<PathBuf>,
 	/// The version of the node. `None` can be passed to skip the version check (only for tests).
 	pub node_version: Option<String>,
 	/// Whether the node is attempting to run as a secure validator.
@@ -220,6 +226,7 @@ impl Config {
 	) -> Self {
 		Self {
 			cache_path,
+			shared_artifacts_path: None,
 			node_version,
 			secure_validator_mode,
 
@@ -285,10 +292,35 @@ pub async fn start(
 
 	let validation_host = ValidationHost { to_host_tx, security_status: security_status.clone() };
 
+	let shared_artifacts = match (&config.shared_artifacts_path, &config.node_version) {
+		(Some(path), Some(node_version)) =>
+			match SharedArtifacts::new(path.clone(), node_version.clone()).await {
+				Ok(shared_artifacts) => Some(shared_artifacts),
+				Err(err) => {
+					gum::warn!(
+						target: LOG_TARGET,
+						"Cannot use {} to share PVF artifacts, disabling it: {}",
+						path.display(),
+						err,
+					);
+					None
+				},
+			},
+		(Some(_), None) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				"Sharing of PVF artifacts requires the node version to be known, disabling it",
+			);
+			None
+		},
+		(None, _) => None,
+	};
+
 	let (to_prepare_pool, from_prepare_pool, run_prepare_pool) = prepare::start_pool(
 		metrics.clone(),
 		config.prepare_worker_program_path.clone(),
 		config.cache_path.clone(),
+		shared_artifacts,
 		config.prepare_worker_spawn_timeout,
 		config.node_version.clone(),
 		security_status.clone(),
@@ -16,6 +16,7 @@
 
 use super::worker_interface::{self, Outcome};
 use crate::{
+	artifacts::SharedArtifacts,
 	metrics::Metrics,
 	worker_interface::{IdleWorker, WorkerHandle},
 	LOG_TARGET,
@@ -114,6 +115,7 @@ struct Pool {
 	// Some variables related to the current session.
 	program_path: PathBuf,
 	cache_path: PathBuf,
+	shared_artifacts: Option<SharedArtifacts>,
 	spawn_timeout: Duration,
 	node_version: Option<String>,
 	security_status: SecurityStatus,
@@ -133,6 +135,7 @@ async fn run(
 	Pool {
 		program_path,
 		cache_path,
+		shared_artifacts,
 		spawn_timeout,
 		node_version,
 		security_status,
@@ -162,6 +165,7 @@ async fn run(
 					&metrics,
 					&program_path,
 					&cache_path,
+					shared_artifacts.as_ref(),
 					spawn_timeout,
 					node_version.clone(),
 					security_status.clone(),
@@ -210,6 +214,7 @@ fn handle_to_pool(
 	metrics: &Metrics,
 	program_path: &Path,
 	cache_path: &Path,
+	shared_artifacts: Option<&SharedArtifacts>,
 	spawn_timeout: Duration,
 	node_version: Option<String>,
 	security_status: SecurityStatus,
@@ -243,6 +248,7 @@ fn handle_to_pool(
 							idle,
 							pvf,
 							cache_path,
+							shared_artifacts.cloned(),
 							preparation_timer,
 						)
 						.boxed(),
@@ -304,9 +310,11 @@ async fn start_work_task<Timer>(
 	idle: IdleWorker,
 	pvf: PvfPrepData,
 	cache_path: PathBuf,
+	shared_artifacts: Option<SharedArtifacts>,
 	_preparation_timer: Option<Timer>,
 ) -> PoolEvent {
-	let outcome = worker_interface::start_work(&metrics, idle, pvf, cache_path).await;
+	let outcome =
+		worker_interface::start_work(&metrics, idle, pvf, cache_path, shared_artifacts).await;
 	PoolEvent::StartWork(worker, outcome)
 }
 
@@ -496,6 +504,7 @@ pub fn start(
 	metrics: Metrics,
 	program_path: PathBuf,
 	cache_path: PathBuf,
+	shared_artifacts: Option<SharedArtifacts>,
 	spawn_timeout: Duration,
 	node_version: Option<String>,
 	security_status: SecurityStatus,
@@ -507,6 +516,7 @@ pub fn start(
 		metrics,
 		program_path,
 		cache_path,
+		shared_artifacts,
 		spawn_timeout,
 		node_version,
 		security_status,
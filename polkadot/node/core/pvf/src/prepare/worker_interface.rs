@@ -17,7 +17,7 @@
 //! Host interface to the prepare worker.
 
 use crate::{
-	artifacts::generate_artifact_path,
+	artifacts::{generate_artifact_path, ArtifactId, SharedArtifacts},
 	metrics::Metrics,
 	worker_interface::{
 		clear_worker_dir_path, framed_recv, framed_send, spawn_with_program_path, IdleWorker,
@@ -108,6 +108,9 @@ pub enum Outcome {
 /// Given the idle token of a worker and parameters of work, communicates with the worker and
 /// returns the outcome.
 ///
+/// If a verified artifact for the PVF is found in the shared artifact directory, it is reused and
+/// the worker is not involved. Otherwise, a successfully prepared artifact is published there.
+///
 /// NOTE: Returning the `TimedOut`, `IoErr` or `Unreachable` outcomes will trigger the child process
 /// being killed.
 pub async fn start_work(
@@ -115,7 +118,24 @@ pub async fn start_work(
 	worker: IdleWorker,
 	pvf: PvfPrepData,
 	cache_path: PathBuf,
+	shared_artifacts: Option<SharedArtifacts>,
 ) -> Outcome {
+	let artifact_id = ArtifactId::from_pvf_prep_data(&pvf);
+	if let Some(ref shared_artifacts) = shared_artifacts {
+		if let Some((path, size)) = shared_artifacts.fetch(&artifact_id, &cache_path).await {
+			gum::debug!(
+				target: LOG_TARGET,
+				?artifact_id,
+				"reusing shared artifact {}",
+				path.display(),
+			);
+			return Outcome::Concluded {
+				worker,
+				result: Ok(PrepareSuccess { path, size, stats: PrepareStats::default() }),
+			}
+		}
+	}
+
 	let IdleWorker { stream, pid, worker_dir } = worker;
 
 	gum::debug!(
@@ -166,6 +186,7 @@ pub async fn start_work(
 						tmp_artifact_file,
 						&cache_path,
 						preparation_timeout,
+						shared_artifacts.as_ref().map(|shared| (shared, &artifact_id)),
 					)
 					.await,
 				Ok(Err(err)) => {
@@ -205,11 +226,12 @@ async fn handle_response(
 	tmp_file: PathBuf,
 	cache_path: &Path,
 	preparation_timeout: Duration,
+	shared_artifacts: Option<(&SharedArtifacts, &ArtifactId)>,
 ) -> Outcome {
 	// TODO: Add `checksum` to `ArtifactPathId`. See:
 	//       https://github.com/paritytech/polkadot-sdk/issues/2399
 	let PrepareWorkerSuccess {
-		checksum: _,
+		checksum,
 		stats: PrepareStats { cpu_time_elapsed, memory_stats, observed_wasm_code_len },
 	} = match result.clone() {
 		Ok(result) => result,
@@ -263,17 +285,35 @@ async fn handle_response(
 	);
 
 	let outcome = match tokio::fs::rename(&tmp_file, &artifact_path).await {
-		Ok(()) => Outcome::Concluded {
-			worker,
-			result: Ok(PrepareSuccess {
-				path: artifact_path,
-				size,
-				stats: PrepareStats {
-					cpu_time_elapsed,
-					memory_stats: memory_stats.clone(),
-					observed_wasm_code_len,
-				},
-			}),
+		Ok(()) => {
+			if let Some((shared_artifacts, artifact_id)) = shared_artifacts {
+				if let Err(err) =
+					shared_artifacts.publish(artifact_id, &artifact_path, &checksum).await
+				{
+					// Not fatal, the artifact is still usable locally.
+					gum::warn!(
+						target: LOG_TARGET,
+						%worker_pid,
+						?artifact_id,
+						"failed to publish the artifact {} to the shared directory: {:?}",
+						artifact_path.display(),
+						err,
+					);
+				}
+			}
+
+			Outcome::Concluded {
+				worker,
+				result: Ok(PrepareSuccess {
+					path: artifact_path,
+					size,
+					stats: PrepareStats {
+						cpu_time_elapsed,
+						memory_stats: memory_stats.clone(),
+						observed_wasm_code_len,
+					},
+				}),
+			}
 		},
 		Err(err) => {
 			gum::warn!(
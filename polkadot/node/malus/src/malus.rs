@@ -42,6 +42,10 @@ enum NemesisVariant {
 	DisputeFinalizedCandidates(DisputeFinalizedCandidatesOptions),
 	/// Spam many request statements instead of sending a single one.
 	SpamStatementRequests(SpamStatementRequestsOptions),
+	/// Refuse to serve erasure chunks and available data of candidates.
+	WithholdAvailabilityChunks(WithholdAvailabilityChunksOptions),
+	/// Assign ourselves to approval checks, but never send the approval vote.
+	ApprovalNoShow(ApprovalNoShowOptions),
 }
 
 #[derive(Debug, Parser)]
@@ -105,6 +109,20 @@ impl MalusCli {
 
 				polkadot_cli::run_node(cli, SpamStatementRequests { spam_factor }, finality_delay)?
 			},
+			NemesisVariant::WithholdAvailabilityChunks(opts) => {
+				let WithholdAvailabilityChunksOptions { percentage, cli } = opts;
+
+				polkadot_cli::run_node(
+					cli,
+					WithholdAvailabilityChunks { percentage },
+					finality_delay,
+				)?
+			},
+			NemesisVariant::ApprovalNoShow(opts) => {
+				let ApprovalNoShowOptions { percentage, cli } = opts;
+
+				polkadot_cli::run_node(cli, ApprovalNoShow { percentage }, finality_delay)?
+			},
 		}
 		Ok(())
 	}
@@ -244,4 +262,42 @@ mod tests {
 			assert!(opts.cli.run.base.bob);
 		});
 	}
+
+	#[test]
+	fn percentage_works_withhold_availability_chunks() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"withhold-availability-chunks",
+			"--percentage",
+			"50",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::WithholdAvailabilityChunks(opts),
+			..
+		} => {
+			assert_eq!(opts.percentage, 50);
+			assert!(opts.cli.run.base.bob);
+		});
+	}
+
+	#[test]
+	fn percentage_works_approval_no_show() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"approval-no-show",
+			"--percentage",
+			"30",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::ApprovalNoShow(opts),
+			..
+		} => {
+			assert_eq!(opts.percentage, 30);
+			assert!(opts.cli.run.base.bob);
+		});
+	}
 }
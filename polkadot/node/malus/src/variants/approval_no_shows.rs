@@ -0,0 +1,182 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node variant that assigns itself to approval checks but never shows up.
+//!
+//! This malus variant behaves honestly in approval voting, its assignments are distributed as
+//! usual. However, the approval votes it issues are swallowed before reaching approval
+//! distribution, so the rest of the network sees a no-show and escalates to the next tranches.
+//! This works both with and without approval voting parallel.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	service::{
+		AuxStore, Error, ExtendedOverseerGenArgs, Overseer, OverseerConnector, OverseerGen,
+		OverseerGenArgs, OverseerHandle,
+	},
+	validator_overseer_builder, Cli,
+};
+use polkadot_node_primitives::approval::v2::IndirectSignedApprovalVoteV2;
+use polkadot_node_subsystem::SpawnGlue;
+use polkadot_node_subsystem_types::{ChainApiBackend, RuntimeApiSubsystemClient};
+use sp_core::traits::SpawnNamed;
+
+use rand::distributions::{Bernoulli, Distribution};
+
+// Filter wrapping related types.
+use crate::{interceptor::*, shared::MALUS};
+
+use std::sync::Arc;
+
+/// Wraps around approval distribution and drops some of our own approval votes.
+#[derive(Clone)]
+struct ApprovalSwallower {
+	/// The probability of not showing up with an approval vote.
+	distribution: Bernoulli,
+}
+
+impl ApprovalSwallower {
+	/// Decide whether our own approval `vote` is dropped.
+	fn swallow(&self, vote: &IndirectSignedApprovalVoteV2) -> bool {
+		if !self.distribution.sample(&mut rand::thread_rng()) {
+			return false
+		}
+		gum::info!(
+			target: MALUS,
+			block_hash = ?vote.block_hash,
+			candidate_indices = ?vote.candidate_indices,
+			validator_index = ?vote.validator,
+			"😈 Not showing up with approval vote.",
+		);
+		true
+	}
+}
+
+impl<Sender> MessageInterceptor<Sender> for ApprovalSwallower
+where
+	Sender: overseer::ApprovalDistributionSenderTrait + Clone + Send + 'static,
+{
+	type Message = ApprovalDistributionMessage;
+
+	/// Intercept `ApprovalDistributionMessage::DistributeApproval` coming from approval voting,
+	/// which carries our own approval vote, and drop it.
+	fn intercept_incoming(
+		&self,
+		_subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg: ApprovalDistributionMessage::DistributeApproval(vote),
+			} => (!self.swallow(&vote)).then(|| FromOrchestra::Communication {
+				msg: ApprovalDistributionMessage::DistributeApproval(vote),
+			}),
+			FromOrchestra::Communication { msg } => Some(FromOrchestra::Communication { msg }),
+			FromOrchestra::Signal(signal) => Some(FromOrchestra::Signal(signal)),
+		}
+	}
+}
+
+/// Wraps around approval voting parallel, which distributes the approval votes itself when it is
+/// enabled, and drops some of our own approval votes.
+#[derive(Clone)]
+struct ParallelApprovalSwallower(ApprovalSwallower);
+
+impl<Sender> MessageInterceptor<Sender> for ParallelApprovalSwallower
+where
+	Sender: overseer::ApprovalVotingParallelSenderTrait + Clone + Send + 'static,
+{
+	type Message = ApprovalVotingParallelMessage;
+
+	/// Intercept `ApprovalVotingParallelMessage::DistributeApproval` coming from the approval
+	/// voting workers, which carries our own approval vote, and drop it.
+	fn intercept_incoming(
+		&self,
+		_subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg: ApprovalVotingParallelMessage::DistributeApproval(vote),
+			} => (!self.0.swallow(&vote)).then(|| FromOrchestra::Communication {
+				msg: ApprovalVotingParallelMessage::DistributeApproval(vote),
+			}),
+			FromOrchestra::Communication { msg } => Some(FromOrchestra::Communication { msg }),
+			FromOrchestra::Signal(signal) => Some(FromOrchestra::Signal(signal)),
+		}
+	}
+}
+
+//----------------------------------------------------------------------------------
+
+#[derive(Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct ApprovalNoShowOptions {
+	/// Determines the percentage of approval votes that are never sent out.
+	/// Defaults to 100% of approval votes.
+	#[clap(short, long, ignore_case = true, default_value_t = 100, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub percentage: u8,
+
+	#[clap(flatten)]
+	pub cli: Cli,
+}
+
+/// ApprovalNoShow implementation wrapper which implements `OverseerGen` glue.
+pub(crate) struct ApprovalNoShow {
+	/// The probability of not showing up with an approval vote.
+	pub percentage: u8,
+}
+
+impl OverseerGen for ApprovalNoShow {
+	fn generate<Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'_, Spawner, RuntimeClient>,
+		ext_args: Option<ExtendedOverseerGenArgs>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		gum::info!(
+			target: MALUS,
+			"😈 Started Malus node that does not show up with {:?}% of its approval votes.",
+			&self.percentage,
+		);
+
+		let distribution = Bernoulli::new(f64::from(self.percentage) / 100.0)
+			.expect("Invalid probability! Percentage must be in range [0..=100].");
+		let approval_swallower = ApprovalSwallower { distribution };
+		let parallel_approval_swallower = ParallelApprovalSwallower(approval_swallower.clone());
+
+		// Only one of the two subsystems is running, depending on whether approval voting
+		// parallel is enabled.
+		validator_overseer_builder(
+			args,
+			ext_args.expect("Extended arguments required to build validator overseer are provided"),
+		)?
+		.replace_approval_distribution(move |cb| InterceptedSubsystem::new(cb, approval_swallower))
+		.replace_approval_voting_parallel(move |cb| {
+			InterceptedSubsystem::new(cb, parallel_approval_swallower)
+		})
+		.build_with_connector(connector)
+		.map_err(|e| e.into())
+	}
+}
@@ -0,0 +1,223 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node variant that never serves its erasure chunks.
+//!
+//! This malus variant behaves honestly in backing, availability distribution and bitfield signing,
+//! so it keeps attesting availability of the chunks it stores. However, when other validators
+//! request a chunk or the full available data of a withheld candidate, it pretends not to have it.
+//! Withholding is decided once per candidate, so all requests for the same candidate get the same
+//! treatment. The decisions are forgotten `DECISION_RETENTION` finalized blocks after they were
+//! made.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	service::{
+		AuxStore, Error, ExtendedOverseerGenArgs, Overseer, OverseerConnector, OverseerGen,
+		OverseerGenArgs, OverseerHandle,
+	},
+	validator_overseer_builder, Cli,
+};
+use polkadot_node_subsystem::{OverseerSignal, SpawnGlue};
+use polkadot_node_subsystem_types::{ChainApiBackend, RuntimeApiSubsystemClient};
+use polkadot_primitives::{BlockNumber, CandidateHash};
+use sp_core::traits::SpawnNamed;
+
+use rand::distributions::{Bernoulli, Distribution};
+
+// Filter wrapping related types.
+use crate::{interceptor::*, shared::MALUS};
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+/// The number of finalized blocks for which the decision about a candidate is kept.
+///
+/// Chunks are requested while the candidate is approved or disputed, which happens well within this
+/// window.
+const DECISION_RETENTION: BlockNumber = 1200;
+
+/// Per candidate decisions of the [`ChunkWithholder`].
+#[derive(Default)]
+struct Decisions {
+	/// Whether the chunks of a candidate are withheld, and the last finalized block number when
+	/// that was decided.
+	withheld: HashMap<CandidateHash, (bool, BlockNumber)>,
+	/// The last finalized block number we know of.
+	finalized: BlockNumber,
+}
+
+/// Wraps around the availability store and hides the stored chunks of some candidates.
+#[derive(Clone)]
+struct ChunkWithholder {
+	/// The probability of withholding the chunks of a candidate.
+	distribution: Bernoulli,
+	/// Per candidate decision, so we consistently withhold or serve.
+	decisions: Arc<Mutex<Decisions>>,
+}
+
+impl ChunkWithholder {
+	fn new(percentage: f64) -> Self {
+		let distribution = Bernoulli::new(percentage / 100.0)
+			.expect("Invalid probability! Percentage must be in range [0..=100].");
+		Self { distribution, decisions: Default::default() }
+	}
+
+	fn withholds(&self, candidate_hash: CandidateHash) -> bool {
+		let mut decisions = self.decisions.lock().expect("Lock is never poisoned; qed");
+		let finalized = decisions.finalized;
+		decisions
+			.withheld
+			.entry(candidate_hash)
+			.or_insert_with(|| {
+				let withhold = self.distribution.sample(&mut rand::thread_rng());
+				if withhold {
+					gum::info!(
+						target: MALUS,
+						?candidate_hash,
+						"😈 Withholding erasure chunks of candidate.",
+					);
+				}
+				(withhold, finalized)
+			})
+			.0
+	}
+
+	/// Forget the decisions made more than `DECISION_RETENTION` blocks before `finalized`.
+	fn prune(&self, finalized: BlockNumber) {
+		let mut decisions = self.decisions.lock().expect("Lock is never poisoned; qed");
+		decisions.finalized = finalized;
+		decisions
+			.withheld
+			.retain(|_, (_, decided_at)| decided_at.saturating_add(DECISION_RETENTION) > finalized);
+	}
+}
+
+impl<Sender> MessageInterceptor<Sender> for ChunkWithholder
+where
+	Sender: overseer::AvailabilityStoreSenderTrait + Clone + Send + 'static,
+{
+	type Message = AvailabilityStoreMessage;
+
+	/// Chunks and full available data are only queried to answer requests from the network or to
+	/// short cut our own recovery, in both cases we pretend not to have them.
+	fn intercept_incoming(
+		&self,
+		_subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg: AvailabilityStoreMessage::QueryChunk(candidate_hash, validator_index, tx),
+			} =>
+				if self.withholds(candidate_hash) {
+					gum::debug!(
+						target: MALUS,
+						?candidate_hash,
+						?validator_index,
+						"😈 Refusing to serve chunk.",
+					);
+					let _ = tx.send(None);
+					None
+				} else {
+					Some(FromOrchestra::Communication {
+						msg: AvailabilityStoreMessage::QueryChunk(
+							candidate_hash,
+							validator_index,
+							tx,
+						),
+					})
+				},
+			FromOrchestra::Communication {
+				msg: AvailabilityStoreMessage::QueryAvailableData(candidate_hash, tx),
+			} =>
+				if self.withholds(candidate_hash) {
+					gum::debug!(
+						target: MALUS,
+						?candidate_hash,
+						"😈 Refusing to serve available data.",
+					);
+					let _ = tx.send(None);
+					None
+				} else {
+					Some(FromOrchestra::Communication {
+						msg: AvailabilityStoreMessage::QueryAvailableData(candidate_hash, tx),
+					})
+				},
+			FromOrchestra::Communication { msg } => Some(FromOrchestra::Communication { msg }),
+			FromOrchestra::Signal(OverseerSignal::BlockFinalized(hash, number)) => {
+				self.prune(number);
+				Some(FromOrchestra::Signal(OverseerSignal::BlockFinalized(hash, number)))
+			},
+			FromOrchestra::Signal(signal) => Some(FromOrchestra::Signal(signal)),
+		}
+	}
+}
+
+//----------------------------------------------------------------------------------
+
+#[derive(Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct WithholdAvailabilityChunksOptions {
+	/// Determines the percentage of candidates for which chunks are withheld.
+	/// Defaults to 100% of candidates.
+	#[clap(short, long, ignore_case = true, default_value_t = 100, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub percentage: u8,
+
+	#[clap(flatten)]
+	pub cli: Cli,
+}
+
+/// WithholdAvailabilityChunks implementation wrapper which implements `OverseerGen` glue.
+pub(crate) struct WithholdAvailabilityChunks {
+	/// The probability of withholding the chunks of a candidate.
+	pub percentage: u8,
+}
+
+impl OverseerGen for WithholdAvailabilityChunks {
+	fn generate<Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'_, Spawner, RuntimeClient>,
+		ext_args: Option<ExtendedOverseerGenArgs>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		gum::info!(
+			target: MALUS,
+			"😈 Started Malus node that withholds erasure chunks of {:?}% of candidates.",
+			&self.percentage,
+		);
+
+		let chunk_withholder = ChunkWithholder::new(f64::from(self.percentage));
+
+		validator_overseer_builder(
+			args,
+			ext_args.expect("Extended arguments required to build validator overseer are provided"),
+		)?
+		.replace_availability_store(move |cb| InterceptedSubsystem::new(cb, chunk_withholder))
+		.build_with_connector(connector)
+		.map_err(|e| e.into())
+	}
+}
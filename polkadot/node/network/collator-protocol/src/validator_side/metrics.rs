@@ -15,6 +15,9 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
+use polkadot_primitives::Id as ParaId;
+
+use super::stats::StatsEvent;
 
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
@@ -65,6 +68,29 @@ impl Metrics {
 			.as_ref()
 			.map(|metrics| metrics.request_unblocked_collations.start_timer())
 	}
+
+	/// Count an advertisement or collation event of a para.
+	pub fn on_collation_event(&self, para_id: ParaId, event: StatsEvent) {
+		if let Some(metrics) = &self.0 {
+			let para_id = u32::from(para_id).to_string();
+			match event {
+				StatsEvent::Advertised => metrics
+					.collation_events
+					.with_label_values(&[para_id.as_str(), "advertised"])
+					.inc(),
+				StatsEvent::Fetched =>
+					metrics.collation_events.with_label_values(&[para_id.as_str(), "fetched"]).inc(),
+				StatsEvent::ClaimConsumed => metrics
+					.collation_events
+					.with_label_values(&[para_id.as_str(), "claim_consumed"])
+					.inc(),
+				StatsEvent::Rejected(reason) => metrics
+					.collations_rejected
+					.with_label_values(&[para_id.as_str(), reason.as_str()])
+					.inc(),
+			}
+		}
+	}
 }
 
 #[derive(Clone)]
@@ -75,6 +101,8 @@ struct MetricsInner {
 	collator_peer_count: prometheus::Gauge<prometheus::U64>,
 	collation_request_duration: prometheus::Histogram,
 	request_unblocked_collations: prometheus::Histogram,
+	collation_events: prometheus::CounterVec<prometheus::U64>,
+	collations_rejected: prometheus::CounterVec<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -135,6 +163,26 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			collation_events: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_collator_protocol_validator_collation_events_total",
+						"Number of collations advertised, fetched and seconded, per para.",
+					),
+					&["para_id", "event"],
+				)?,
+				registry,
+			)?,
+			collations_rejected: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_collator_protocol_validator_collations_rejected_total",
+						"Number of advertisements and collations dropped, per para and reason.",
+					),
+					&["para_id", "reason"],
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
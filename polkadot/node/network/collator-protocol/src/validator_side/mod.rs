@@ -37,7 +37,9 @@ use polkadot_node_network_protocol::{
 	v1 as protocol_v1, v2 as protocol_v2, CollationProtocols, OurView, PeerId,
 	UnifiedReputationChange as Rep, View,
 };
-use polkadot_node_primitives::{SignedFullStatement, Statement};
+use polkadot_node_primitives::{
+	collation_stats::CollationRejection, SignedFullStatement, Statement,
+};
 use polkadot_node_subsystem::{
 	messages::{
 		CanSecondRequest, CandidateBackingMessage, CollatorProtocolMessage, IfDisconnected,
@@ -64,6 +66,7 @@ mod claim_queue_state;
 mod collation;
 mod error;
 mod metrics;
+mod stats;
 
 use claim_queue_state::ClaimQueueState;
 use collation::{
@@ -72,6 +75,7 @@ use collation::{
 	PendingCollationFetch, ProspectiveCandidate,
 };
 use error::{Error, FetchError, Result, SecondingError};
+use stats::{CollationStats, StatsEvent};
 
 #[cfg(test)]
 mod tests;
@@ -405,9 +409,17 @@ struct State {
 
 	/// Aggregated reputation change
 	reputation: ReputationAggregator,
+
+	/// What happened to the advertisements and collations of each collator peer.
+	collation_stats: CollationStats,
 }
 
 impl State {
+	/// Count an event in the collation statistics and metrics.
+	fn note_collation_stats(&mut self, para_id: ParaId, peer_id: PeerId, event: StatsEvent) {
+		self.collation_stats.note(para_id, peer_id, event, &self.metrics);
+	}
+
 	// Returns the number of seconded and pending collations for a specific `ParaId`. Pending
 	// collations are:
 	// 1. Collations being fetched from a collator.
@@ -866,10 +878,13 @@ async fn process_incoming_peer_message<Context>(
 				disconnect_peer(ctx.sender(), origin).await;
 			}
 		},
-		CollationProtocols::V1(V1::AdvertiseCollation(relay_parent)) =>
-			if let Err(err) =
-				handle_advertisement(ctx.sender(), state, relay_parent, origin, None).await
-			{
+		CollationProtocols::V1(V1::AdvertiseCollation(relay_parent)) => {
+			let para_id = state.peer_data.get(&origin).and_then(PeerData::collating_para);
+			let result =
+				handle_advertisement(ctx.sender(), state, relay_parent, origin, None).await;
+			note_advertisement(state, origin, para_id, &result);
+
+			if let Err(err) = result {
 				gum::debug!(
 					target: LOG_TARGET,
 					peer_id = ?origin,
@@ -881,21 +896,25 @@ async fn process_incoming_peer_message<Context>(
 				if let Some(rep) = err.reputation_changes() {
 					modify_reputation(&mut state.reputation, ctx.sender(), origin, rep).await;
 				}
-			},
+			}
+		},
 		CollationProtocols::V2(V2::AdvertiseCollation {
 			relay_parent,
 			candidate_hash,
 			parent_head_data_hash,
 		}) => {
-			if let Err(err) = handle_advertisement(
+			let para_id = state.peer_data.get(&origin).and_then(PeerData::collating_para);
+			let result = handle_advertisement(
 				ctx.sender(),
 				state,
 				relay_parent,
 				origin,
 				Some((candidate_hash, parent_head_data_hash)),
 			)
-			.await
-			{
+			.await;
+			note_advertisement(state, origin, para_id, &result);
+
+			if let Err(err) = result {
 				gum::debug!(
 					target: LOG_TARGET,
 					peer_id = ?origin,
@@ -956,6 +975,37 @@ impl AdvertisementError {
 			UnknownPeer | SecondedLimitReached | BlockedByBacking => None,
 		}
 	}
+
+	/// The reason to report in the collation statistics, `None` if the advertisement can't be
+	/// attributed to a para.
+	fn rejection(&self) -> Option<CollationRejection> {
+		use AdvertisementError::*;
+		match self {
+			RelayParentUnknown => Some(CollationRejection::RelayParentUnknown),
+			InvalidAssignment => Some(CollationRejection::InvalidAssignment),
+			SecondedLimitReached => Some(CollationRejection::SecondedLimitReached),
+			ProtocolMisuse => Some(CollationRejection::ProtocolMisuse),
+			Invalid(_) => Some(CollationRejection::InvalidAdvertisement),
+			BlockedByBacking => Some(CollationRejection::BlockedByBacking),
+			UnknownPeer | UndeclaredCollator => None,
+		}
+	}
+}
+
+/// Count an advertisement of a collator declared for `para_id` in the collation statistics, along
+/// with the reason it was rejected, if it was. Advertisements of undeclared peers are not counted.
+fn note_advertisement(
+	state: &mut State,
+	peer_id: PeerId,
+	para_id: Option<ParaId>,
+	result: &std::result::Result<(), AdvertisementError>,
+) {
+	let Some(para_id) = para_id else { return };
+
+	state.note_collation_stats(para_id, peer_id, StatsEvent::Advertised);
+	if let Some(reason) = result.as_ref().err().and_then(AdvertisementError::rejection) {
+		state.note_collation_stats(para_id, peer_id, StatsEvent::Rejected(reason));
+	}
 }
 
 // Requests backing to sanity check the advertisement.
@@ -1020,6 +1070,11 @@ async fn second_unblocked_collations<Context>(
 			let relay_parent = unblocked_collation.candidate_receipt.descriptor.relay_parent();
 
 			if let Err(err) = kick_off_seconding(ctx, state, unblocked_collation).await {
+				state.note_collation_stats(
+					para_id,
+					peer_id,
+					StatsEvent::Rejected(CollationRejection::SecondingFailed),
+				);
 				gum::warn!(
 					target: LOG_TARGET,
 					?relay_parent,
@@ -1530,6 +1585,7 @@ async fn process_msg<Context>(
 				if let Some(rp_state) = state.per_relay_parent.get_mut(&parent) {
 					rp_state.collations.note_seconded(para_id);
 				}
+				state.note_collation_stats(para_id, peer_id, StatsEvent::ClaimConsumed);
 
 				// See if we've unblocked other collations for seconding.
 				second_unblocked_collations(
@@ -1568,11 +1624,11 @@ async fn process_msg<Context>(
 
 			let fetched_collation = FetchedCollation::from(&candidate_receipt);
 			let candidate_hash = fetched_collation.candidate_hash;
-			let id = match state.fetched_candidates.entry(fetched_collation) {
+			let collation_event = match state.fetched_candidates.entry(fetched_collation) {
 				Entry::Occupied(entry)
 					if entry.get().pending_collation.commitments_hash ==
 						Some(candidate_receipt.commitments_hash) =>
-					entry.remove().collator_id,
+					entry.remove(),
 				Entry::Occupied(_) => {
 					gum::error!(
 						target: LOG_TARGET,
@@ -1584,12 +1640,27 @@ async fn process_msg<Context>(
 				},
 				Entry::Vacant(_) => return,
 			};
+			let PendingCollation { para_id, peer_id, .. } = collation_event.pending_collation;
+			let id = collation_event.collator_id;
+			state.note_collation_stats(
+				para_id,
+				peer_id,
+				StatsEvent::Rejected(CollationRejection::Invalid),
+			);
 
 			report_collator(&mut state.reputation, ctx.sender(), &state.peer_data, id.clone())
 				.await;
 
 			dequeue_next_collation_and_fetch(ctx, state, parent, (id, Some(candidate_hash))).await;
 		},
+		CollatorProtocolMessage::CollationStats(tx) => {
+			let report = state
+				.collation_stats
+				.report(state.current_assignments.keys().copied(), |peer_id| {
+					state.peer_data.contains_key(peer_id)
+				});
+			let _ = tx.send(report);
+		},
 	}
 }
 
@@ -1694,6 +1765,11 @@ async fn run_inner<Context>(
 							error = %err,
 							"Seconding aborted due to an error",
 						);
+						state.note_collation_stats(
+							pending_collation.para_id,
+							pending_collation.peer_id,
+							StatsEvent::Rejected(CollationRejection::SecondingFailed),
+						);
 
 						if err.is_malicious() {
 							// Report malicious peer.
@@ -2003,6 +2079,8 @@ async fn handle_collation_fetch_response(
 	let _timer = state.metrics.time_handle_collation_request_result();
 
 	let mut metrics_result = Err(());
+	let mut stats_event = None;
+	let (para_id, peer_id) = (pending_collation.para_id, pending_collation.peer_id);
 
 	let result = match response {
 		Err(RequestError::InvalidResponse(err)) => {
@@ -2014,6 +2092,7 @@ async fn handle_collation_fetch_response(
 				err = ?err,
 				"Collator provided response that could not be decoded"
 			);
+			stats_event = Some(StatsEvent::Rejected(CollationRejection::InvalidResponse));
 			Err(Some((pending_collation.peer_id, COST_CORRUPTED_MESSAGE)))
 		},
 		Err(err) if err.is_timed_out() => {
@@ -2026,6 +2105,7 @@ async fn handle_collation_fetch_response(
 			);
 			// For now we don't want to change reputation on timeout, to mitigate issues like
 			// this: https://github.com/paritytech/polkadot/issues/4617
+			stats_event = Some(StatsEvent::Rejected(CollationRejection::FetchTimeout));
 			Err(None)
 		},
 		Err(RequestError::NetworkError(err)) => {
@@ -2039,6 +2119,7 @@ async fn handle_collation_fetch_response(
 				err = ?err,
 				"Fetching collation failed due to network error"
 			);
+			stats_event = Some(StatsEvent::Rejected(CollationRejection::NetworkError));
 			// A minor decrease in reputation for any network failure seems
 			// sensible. In theory this could be exploited, by DoSing this node,
 			// which would result in reduced reputation for proper nodes, but the
@@ -2071,6 +2152,7 @@ async fn handle_collation_fetch_response(
 				peer_id = ?pending_collation.peer_id,
 				"Got wrong para ID for requested collation."
 			);
+			stats_event = Some(StatsEvent::Rejected(CollationRejection::WrongPara));
 
 			Err(Some((pending_collation.peer_id, COST_WRONG_PARA)))
 		},
@@ -2084,6 +2166,7 @@ async fn handle_collation_fetch_response(
 			);
 
 			metrics_result = Ok(());
+			stats_event = Some(StatsEvent::Fetched);
 			Ok(PendingCollationFetch {
 				collation_event: CollationEvent {
 					collator_id,
@@ -2109,6 +2192,7 @@ async fn handle_collation_fetch_response(
 			);
 
 			metrics_result = Ok(());
+			stats_event = Some(StatsEvent::Fetched);
 			Ok(PendingCollationFetch {
 				collation_event: CollationEvent {
 					collator_id,
@@ -2122,6 +2206,9 @@ async fn handle_collation_fetch_response(
 		},
 	};
 	state.metrics.on_request(metrics_result);
+	if let Some(event) = stats_event {
+		state.note_collation_stats(para_id, peer_id, event);
+	}
 	result
 }
 
@@ -0,0 +1,113 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `CollationStats` counts what happened to the advertisements and collations of each collator
+//! peer, per para, so collator operators can find out why their collations were not backed.
+
+use std::collections::BTreeMap;
+
+use schnellru::{ByLength, LruMap};
+
+use polkadot_node_network_protocol::PeerId;
+use polkadot_node_primitives::collation_stats::{
+	CollationRejection, CollatorPeerStats, ParaCollationStats, PeerCollationStats,
+};
+use polkadot_primitives::Id as ParaId;
+
+use super::Metrics;
+
+/// The maximum number of para and peer pairs to keep statistics for. The least recently updated
+/// pair is dropped when a new one is seen.
+const MAX_TRACKED_PEERS: u32 = 1024;
+
+/// An event counted in the statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsEvent {
+	/// An advertisement was received.
+	Advertised,
+	/// A collation was fetched.
+	Fetched,
+	/// A collation was seconded, consuming a slot in the claim queue.
+	ClaimConsumed,
+	/// An advertisement or collation was dropped.
+	Rejected(CollationRejection),
+}
+
+/// Statistics per para and collator peer.
+pub struct CollationStats {
+	per_peer: LruMap<(ParaId, PeerId), PeerCollationStats, ByLength>,
+}
+
+impl Default for CollationStats {
+	fn default() -> Self {
+		Self { per_peer: LruMap::new(ByLength::new(MAX_TRACKED_PEERS)) }
+	}
+}
+
+impl CollationStats {
+	/// Count an event for a collator peer of a para.
+	pub fn note(&mut self, para_id: ParaId, peer_id: PeerId, event: StatsEvent, metrics: &Metrics) {
+		metrics.on_collation_event(para_id, event);
+
+		let Some(stats) = self.per_peer.get_or_insert((para_id, peer_id), Default::default) else {
+			return
+		};
+		match event {
+			StatsEvent::Advertised => stats.advertisements += 1,
+			StatsEvent::Fetched => stats.fetched += 1,
+			StatsEvent::ClaimConsumed => stats.claims_consumed += 1,
+			StatsEvent::Rejected(reason) => *stats.rejected.entry(reason).or_default() += 1,
+		}
+	}
+
+	/// Get the statistics of all tracked peers, grouped by para. Paras assigned to us are always
+	/// included, even if no collator advertised anything for them.
+	pub fn report(
+		&self,
+		assigned: impl Iterator<Item = ParaId>,
+		is_connected: impl Fn(&PeerId) -> bool,
+	) -> Vec<ParaCollationStats> {
+		let mut per_para: BTreeMap<ParaId, ParaCollationStats> = BTreeMap::new();
+		for para_id in assigned {
+			per_para
+				.insert(para_id, ParaCollationStats { para_id, assigned: true, peers: Vec::new() });
+		}
+
+		for ((para_id, peer_id), stats) in self.per_peer.iter() {
+			per_para
+				.entry(*para_id)
+				.or_insert_with(|| ParaCollationStats {
+					para_id: *para_id,
+					assigned: false,
+					peers: Vec::new(),
+				})
+				.peers
+				.push(CollatorPeerStats {
+					peer_id: peer_id.to_string(),
+					connected: is_connected(peer_id),
+					stats: stats.clone(),
+				});
+		}
+
+		per_para
+			.into_values()
+			.map(|mut para| {
+				para.peers.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+				para
+			})
+			.collect()
+	}
+}
@@ -856,6 +856,140 @@ fn fetch_next_collation_on_invalid_collation() {
 	});
 }
 
+#[test]
+fn collation_stats_track_advertisements_fetches_and_rejections() {
+	let mut test_state = TestState::with_one_scheduled_para();
+
+	test_harness(ReputationAggregator::new(|_| true), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+
+		let relay_parent = test_state.relay_parent;
+		update_view(&mut virtual_overseer, &mut test_state, vec![(relay_parent, 0)]).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b,
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+			CollationVersion::V1,
+		)
+		.await;
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_c,
+			test_state.collators[1].clone(),
+			test_state.chain_ids[0],
+			CollationVersion::V1,
+		)
+		.await;
+
+		advertise_collation(&mut virtual_overseer, peer_b, relay_parent, None).await;
+		advertise_collation(&mut virtual_overseer, peer_c, relay_parent, None).await;
+
+		let response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			relay_parent,
+			test_state.chain_ids[0],
+			None,
+		)
+		.await;
+
+		let pov = PoV { block_data: BlockData(vec![]) };
+		let mut candidate_a =
+			dummy_candidate_receipt_bad_sig(dummy_hash(), Some(Default::default()));
+		candidate_a.descriptor.para_id = test_state.chain_ids[0];
+		candidate_a.descriptor.relay_parent = relay_parent;
+		candidate_a.descriptor.persisted_validation_data_hash = dummy_pvd().hash();
+		response_channel
+			.send(Ok((
+				request_v1::CollationFetchingResponse::Collation(candidate_a.into(), pov.clone())
+					.encode(),
+				ProtocolName::from(""),
+			)))
+			.expect("Sending response should succeed");
+
+		let receipt = assert_candidate_backing_second(
+			&mut virtual_overseer,
+			relay_parent,
+			test_state.chain_ids[0],
+			&pov,
+			CollationVersion::V1,
+		)
+		.await;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::Invalid(relay_parent, receipt),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::ReportPeer(
+				ReportPeerMessage::Single(peer, rep),
+			)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep.value, COST_REPORT_BAD.cost_or_benefit());
+			}
+		);
+
+		assert_fetch_collation_request(
+			&mut virtual_overseer,
+			relay_parent,
+			test_state.chain_ids[0],
+			None,
+		)
+		.await;
+
+		// A V1 advertisement for a relay parent which is not a leaf is rejected.
+		advertise_collation(&mut virtual_overseer, peer_c, Hash::repeat_byte(0xAA), None).await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::ReportPeer(
+				ReportPeerMessage::Single(peer, rep),
+			)) => {
+				assert_eq!(peer, peer_c);
+				assert_eq!(rep.value, COST_PROTOCOL_MISUSE.cost_or_benefit());
+			}
+		);
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(&mut virtual_overseer, CollatorProtocolMessage::CollationStats(tx)).await;
+		let report = rx.await.unwrap();
+
+		assert_eq!(report.len(), 1);
+		assert_eq!(report[0].para_id, test_state.chain_ids[0]);
+		assert!(report[0].assigned);
+
+		let stats_of = |peer_id: PeerId| {
+			report[0]
+				.peers
+				.iter()
+				.find(|peer| peer.peer_id == peer_id.to_string())
+				.map(|peer| peer.stats.clone())
+				.unwrap()
+		};
+
+		let stats_b = stats_of(peer_b);
+		assert_eq!(stats_b.advertisements, 1);
+		assert_eq!(stats_b.fetched, 1);
+		assert_eq!(stats_b.claims_consumed, 0);
+		assert_eq!(stats_b.rejected, BTreeMap::from([(CollationRejection::Invalid, 1)]));
+
+		let stats_c = stats_of(peer_c);
+		assert_eq!(stats_c.advertisements, 2);
+		assert_eq!(stats_c.fetched, 0);
+		assert_eq!(stats_c.rejected, BTreeMap::from([(CollationRejection::ProtocolMisuse, 1)]));
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn inactive_disconnected() {
 	let mut test_state = TestState::default();
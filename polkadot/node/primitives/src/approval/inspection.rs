@@ -0,0 +1,122 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reports on the approval state of relay chain blocks, meant for diagnosing stalled finality.
+
+use serde::{Deserialize, Serialize};
+
+use polkadot_primitives::{BlockNumber, Hash, SessionIndex, ValidatorIndex};
+
+use super::{time::Tick, v1::DelayTranche};
+
+/// Approval state of a relay chain block, as recorded by approval-voting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockApprovalReport {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// The session of the block.
+	pub session: SessionIndex,
+	/// The slot of the block.
+	pub slot: u64,
+	/// The current tranche, relative to the slot of the block.
+	pub tranche_now: DelayTranche,
+	/// Whether all candidates included by the block are approved.
+	pub approved: bool,
+	/// The candidates included by the block.
+	pub candidates: Vec<CandidateApprovalReport>,
+	/// Violated database invariants. Empty if the stored entries are consistent.
+	pub inconsistencies: Vec<String>,
+}
+
+/// Approval state of a candidate, in the context of a block including it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateApprovalReport {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The core the candidate occupied.
+	pub core_index: u32,
+	/// Whether the candidate is marked as approved under the block.
+	pub approved: bool,
+	/// Received assignments, per tranche.
+	pub assignments: Vec<TrancheAssignments>,
+	/// Validators who approved the candidate.
+	pub approvals: Vec<ValidatorIndex>,
+	/// Assigned validators who did not approve in time.
+	pub no_shows: Vec<ValidatorIndex>,
+	/// The tranche of our own assignment, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub our_assignment: Option<DelayTranche>,
+	/// The outcome of approval checking.
+	pub status: ApprovalCheckStatus,
+}
+
+/// Assignments received in a tranche.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrancheAssignments {
+	/// The delay tranche.
+	pub tranche: DelayTranche,
+	/// The assigned validators, with the tick at which each assignment was received.
+	pub validators: Vec<(ValidatorIndex, Tick)>,
+}
+
+/// The outcome of approval checking for a candidate, explaining why it is unapproved if it is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum ApprovalCheckStatus {
+	/// Approved by the assigned validators, tolerating the given number of no-shows.
+	Approved {
+		/// The number of tolerated no-shows.
+		no_shows: usize,
+	},
+	/// Approved by more than a third of all validators.
+	ApprovedOneThird,
+	/// More assignments are needed before the candidate can be approved.
+	AwaitingAssignments {
+		/// The highest tranche considered so far.
+		considered: DelayTranche,
+		/// The tick at which the next no-show would occur, if any.
+		next_no_show: Option<Tick>,
+	},
+	/// Enough validators are assigned, but some of them did not approve yet.
+	AwaitingApprovals {
+		/// The tranche up to which assignments are counted.
+		needed: DelayTranche,
+		/// The number of counted assignments.
+		assigned: usize,
+		/// The number of counted assignments with an approval.
+		approved: usize,
+		/// The number of missing approvals which are tolerated.
+		tolerated_missing: usize,
+		/// The tick at which the next no-show would occur, if any.
+		next_no_show: Option<Tick>,
+	},
+	/// So many no-shows happened that all validators are required to approve.
+	AllRequired {
+		/// The number of approvals.
+		approved: usize,
+		/// The number of validators.
+		n_validators: usize,
+	},
+	/// The candidate entry or its approval entry for the block is missing from the database.
+	MissingEntry,
+	/// The session info for the block is not available.
+	MissingSessionInfo,
+}
@@ -0,0 +1,112 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Statistics about the collations a validator received, meant for giving collator operators
+//! feedback on why their collations were not fetched or not seconded.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use polkadot_primitives::Id as ParaId;
+
+/// Why the validator side of the collator protocol dropped an advertisement or a collation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CollationRejection {
+	/// The advertisement was for a relay parent out of our view.
+	RelayParentUnknown,
+	/// The para is not assigned to our backing group at the advertised relay parent.
+	InvalidAssignment,
+	/// The para has no free slot in the claim queue for the advertised relay parent.
+	SecondedLimitReached,
+	/// A collation for an async backing relay parent was advertised over the V1 protocol.
+	ProtocolMisuse,
+	/// The advertisement was a duplicate, out of view or exceeded the per-peer limit.
+	InvalidAdvertisement,
+	/// The backing subsystem does not allow seconding the advertised candidate.
+	BlockedByBacking,
+	/// The fetch response could not be decoded.
+	InvalidResponse,
+	/// The collator did not respond in time.
+	FetchTimeout,
+	/// Fetching failed due to a network error.
+	NetworkError,
+	/// The fetched collation is for another para.
+	WrongPara,
+	/// The fetched collation did not match its advertisement or could not be validated.
+	SecondingFailed,
+	/// Candidate validation found the collation invalid.
+	Invalid,
+}
+
+impl CollationRejection {
+	/// A short name for the rejection reason, as used in metric labels.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::RelayParentUnknown => "relay_parent_unknown",
+			Self::InvalidAssignment => "invalid_assignment",
+			Self::SecondedLimitReached => "seconded_limit_reached",
+			Self::ProtocolMisuse => "protocol_misuse",
+			Self::InvalidAdvertisement => "invalid_advertisement",
+			Self::BlockedByBacking => "blocked_by_backing",
+			Self::InvalidResponse => "invalid_response",
+			Self::FetchTimeout => "fetch_timeout",
+			Self::NetworkError => "network_error",
+			Self::WrongPara => "wrong_para",
+			Self::SecondingFailed => "seconding_failed",
+			Self::Invalid => "invalid",
+		}
+	}
+}
+
+/// What happened to the collations of a collator peer for a para.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerCollationStats {
+	/// Advertisements received.
+	pub advertisements: u64,
+	/// Collations fetched.
+	pub fetched: u64,
+	/// Collations seconded, each consuming a slot of the para in the claim queue.
+	pub claims_consumed: u64,
+	/// Advertisements and collations dropped, per reason.
+	pub rejected: BTreeMap<CollationRejection, u64>,
+}
+
+/// Statistics of a collator peer for a para.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollatorPeerStats {
+	/// The network identity of the peer.
+	pub peer_id: String,
+	/// Whether the peer is currently connected.
+	pub connected: bool,
+	/// The statistics.
+	pub stats: PeerCollationStats,
+}
+
+/// Statistics of all collator peers seen for a para.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParaCollationStats {
+	/// The para.
+	pub para_id: ParaId,
+	/// Whether the para is currently assigned to our backing group.
+	pub assigned: bool,
+	/// The collator peers which advertised collations for the para.
+	pub peers: Vec<CollatorPeerStats>,
+}
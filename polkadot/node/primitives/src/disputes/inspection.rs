@@ -0,0 +1,142 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Snapshots of the dispute coordinator state, meant for inspection by node operators.
+
+use serde::{Deserialize, Serialize};
+
+use polkadot_primitives::{CandidateHash, Hash, SessionIndex, ValidatorIndex};
+
+use super::{CandidateVotes, DisputeStatus};
+
+/// State of the dispute coordinator.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeCoordinatorState {
+	/// All recent disputes, including the concluded ones.
+	pub disputes: Vec<DisputeInfo>,
+	/// Spam slots currently in use.
+	pub spam_slots: Vec<SpamSlotsUsage>,
+	/// The number of spam slots available to each validator per session.
+	pub max_spam_slots: u32,
+	/// Candidates queued for participation with priority, in participation order.
+	pub priority_queue: Vec<Hash>,
+	/// Candidates queued for best-effort participation, in participation order.
+	pub best_effort_queue: Vec<Hash>,
+	/// Candidates for which participation is currently running.
+	pub running_participations: Vec<Hash>,
+}
+
+/// A dispute and the votes cast in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeInfo {
+	/// The session the candidate appeared in.
+	pub session: SessionIndex,
+	/// The disputed candidate.
+	pub candidate_hash: Hash,
+	/// The status of the dispute.
+	pub status: DisputeStatus,
+	/// Whether the dispute is still considered active.
+	pub active: bool,
+	/// The number of votes for the validity of the candidate.
+	pub valid_votes: u32,
+	/// The number of votes against the validity of the candidate.
+	pub invalid_votes: u32,
+	/// Votes, sorted by validator index.
+	pub votes: Vec<DisputeVote>,
+	/// Our participation in the dispute, if known.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub participation: Option<DisputeParticipation>,
+}
+
+impl DisputeInfo {
+	/// Summarize the given votes.
+	pub fn new(
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+		status: DisputeStatus,
+		active: bool,
+		votes: &CandidateVotes,
+		participation: Option<DisputeParticipation>,
+	) -> Self {
+		let mut all_votes: Vec<_> = votes
+			.valid
+			.raw()
+			.iter()
+			.map(|(validator, (kind, _))| DisputeVote {
+				validator: *validator,
+				valid: true,
+				kind: format!("{:?}", kind),
+			})
+			.chain(votes.invalid.iter().map(|(validator, (kind, _))| DisputeVote {
+				validator: *validator,
+				valid: false,
+				kind: format!("{:?}", kind),
+			}))
+			.collect();
+		all_votes.sort_by_key(|vote| vote.validator);
+
+		Self {
+			session,
+			candidate_hash: candidate_hash.0,
+			status,
+			active,
+			valid_votes: votes.valid.raw().len() as u32,
+			invalid_votes: votes.invalid.len() as u32,
+			votes: all_votes,
+			participation,
+		}
+	}
+}
+
+/// A single vote in a dispute.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeVote {
+	/// The validator who cast the vote.
+	pub validator: ValidatorIndex,
+	/// Whether the vote is for the validity of the candidate.
+	pub valid: bool,
+	/// The kind of the statement, e.g. a backing or an explicit dispute statement.
+	pub kind: String,
+}
+
+/// Participation of this node in a dispute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DisputeParticipation {
+	/// Queued for participation with priority.
+	QueuedPriority,
+	/// Queued for best-effort participation.
+	QueuedBestEffort,
+	/// Participation is running.
+	Running,
+	/// Not queued, either we already participated or we are not going to.
+	NotQueued,
+}
+
+/// Spam slots used by a validator in a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpamSlotsUsage {
+	/// The session of the unconfirmed disputes.
+	pub session: SessionIndex,
+	/// The validator who voted in the unconfirmed disputes.
+	pub validator: ValidatorIndex,
+	/// The number of unconfirmed disputes the validator voted in.
+	pub used: u32,
+}
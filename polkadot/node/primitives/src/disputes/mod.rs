@@ -35,6 +35,10 @@ mod message;
 pub use message::{DisputeMessage, Error as DisputeMessageCheckError, UncheckedDisputeMessage};
 mod status;
 pub use status::{dispute_is_inactive, DisputeStatus, Timestamp, ACTIVE_DURATION_SECS};
+mod inspection;
+pub use inspection::{
+	DisputeCoordinatorState, DisputeInfo, DisputeParticipation, DisputeVote, SpamSlotsUsage,
+};
 
 /// A checked dispute statement from an associated validator.
 #[derive(Debug, Clone)]
@@ -15,6 +15,7 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
 
 /// Timestamp based on the 1 Jan 1970 UNIX base, which is persistent across node restarts and OS
 /// reboots.
@@ -26,7 +27,7 @@ pub type Timestamp = u64;
 ///
 /// NOTE: This status is persisted to the database, any changes have to be versioned and a db
 /// migration will be needed.
-#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq)]
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Serialize, Deserialize)]
 pub enum DisputeStatus {
 	/// The dispute is active and unconcluded.
 	#[codec(index = 0)]
@@ -49,6 +49,9 @@ pub use polkadot_parachain_primitives::primitives::{
 
 pub mod approval;
 
+/// Statistics of the collations received by the validator side of the collator protocol.
+pub mod collation_stats;
+
 /// Disputes related types.
 pub mod disputes;
 pub use disputes::{
@@ -83,8 +83,14 @@ pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub prepare_workers_soft_max_num: Option<usize>,
 	/// An optional absolute number of pvf workers that can be spawned in the pvf prepare pool.
 	pub prepare_workers_hard_max_num: Option<usize>,
+	/// An optional size in MiB of the cache of PVF execution results.
+	pub pvf_execution_cache_size: Option<usize>,
+	/// An optional directory where prepared PVF artifacts are shared with other nodes.
+	pub pvf_shared_artifacts_dir: Option<std::path::PathBuf>,
 	/// How long finalized data should be kept in the availability store (in hours)
 	pub keep_finalized_for: Option<u32>,
+	/// An optional size in MiB the data in the availability store may take up.
+	pub availability_storage_budget: Option<u64>,
 	pub overseer_gen: OverseerGenerator,
 	pub overseer_message_channel_capacity_override: Option<usize>,
 	#[allow(dead_code)]
@@ -150,8 +156,15 @@ where
 			SelectRelayChain::new_longest_chain(basics.backend.clone())
 		};
 
-		let partial_components =
-			new_partial::<SelectRelayChain<_>>(&mut config, basics, select_chain)?;
+		// The approvals and disputes RPCs need the overseer, which only runs for authorities and
+		// collators.
+		let partial_components = new_partial::<SelectRelayChain<_>>(
+			&mut config,
+			basics,
+			select_chain,
+			auth_or_collator.then_some(overseer_handle),
+			params.enable_approval_voting_parallel,
+		)?;
 
 		let net_config = sc_network::config::FullNetworkConfiguration::<_, _, Network>::new(
 			&config.network,
@@ -201,7 +214,10 @@ where
 					execute_workers_max_num,
 					prepare_workers_soft_max_num,
 					prepare_workers_hard_max_num,
+					pvf_execution_cache_size,
+					pvf_shared_artifacts_dir,
 					keep_finalized_for,
+					availability_storage_budget,
 					enable_approval_voting_parallel,
 				},
 			overseer_connector,
@@ -393,6 +409,9 @@ where
 					pvf_execute_workers_max_num: execute_workers_max_num.unwrap_or(4),
 					pvf_prepare_workers_soft_max_num: prepare_workers_soft_max_num.unwrap_or(1),
 					pvf_prepare_workers_hard_max_num: prepare_workers_hard_max_num.unwrap_or(2),
+					pvf_execution_cache_size: pvf_execution_cache_size
+						.map_or(0, |size| size.saturating_mul(1024 * 1024)),
+					pvf_shared_artifacts_path: pvf_shared_artifacts_dir,
 				})
 			} else {
 				None
@@ -428,6 +447,8 @@ where
 				} else {
 					KEEP_FINALIZED_FOR_LIVE_NETWORKS
 				},
+				storage_budget: availability_storage_budget
+					.map(|mib| mib.saturating_mul(1024 * 1024)),
 			};
 
 			Some(ExtendedOverseerGenArgs {
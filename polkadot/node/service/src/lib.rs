@@ -52,6 +52,8 @@ use {
 	polkadot_node_core_approval_voting as approval_voting_subsystem,
 	polkadot_node_core_av_store::Error as AvailabilityError,
 	polkadot_node_core_chain_selection as chain_selection_subsystem,
+	polkadot_node_core_dispute_coordinator as dispute_coordinator_subsystem,
+	polkadot_node_primitives::disputes::DisputeInfo,
 };
 
 use polkadot_node_subsystem_util::database::Database;
@@ -417,7 +419,13 @@ macro_rules! chain_ops {
 		let chain_selection = LongestChain::new(basics.backend.clone());
 
 		let sc_service::PartialComponents { client, backend, import_queue, task_manager, .. } =
-			new_partial::<LongestChain<_, Block>>(&mut config, basics, chain_selection)?;
+			new_partial::<LongestChain<_, Block>>(
+				&mut config,
+				basics,
+				chain_selection,
+				None,
+				false,
+			)?;
 		Ok((client, backend, import_queue, task_manager))
 	}};
 }
@@ -514,6 +522,20 @@ pub fn revert_backend(
 	Ok(())
 }
 
+/// Reads all recent disputes, with the votes cast in them, from the parachains-db.
+///
+/// The database must not be in use by a running node.
+#[cfg(feature = "full-node")]
+pub fn export_disputes(config: &Configuration) -> Result<Vec<DisputeInfo>, Error> {
+	let db = open_database(&config.database)?;
+	let dispute_config = dispute_coordinator_subsystem::Config {
+		col_dispute_data: parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
+	};
+
+	dispute_coordinator_subsystem::export_disputes(db, dispute_config)
+		.map_err(|err| sp_blockchain::Error::Backend(err.to_string()).into())
+}
+
 fn revert_chain_selection(db: Arc<dyn Database>, hash: Hash) -> sp_blockchain::Result<()> {
 	let config = chain_selection_subsystem::Config {
 		col_data: parachains_db::REAL_COLUMNS.col_chain_selection_data,
@@ -0,0 +1,314 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Validator side collator protocol benchmark.
+//!
+//! A set of emulated peers act as collators of the para assigned to the backing group of the node
+//! under test. For every relay chain block each of them advertises its own collation, the node
+//! under test fetches one of them over the emulated network and hands it over to a mocked
+//! candidate backing subsystem for seconding.
+//!
+//! To emulate connection churn, a share of the collators drops its connection in every block and
+//! reconnects and declares itself again before advertising.
+
+use crate::{
+	dummy_builder,
+	environment::{TestEnvironment, TestEnvironmentDependencies},
+	mock::{
+		candidate_backing::MockCandidateBacking,
+		chain_api::{ChainApiState, MockChainApi},
+		network_bridge::{MockNetworkBridgeRx, MockNetworkBridgeTx},
+		prospective_parachains::MockProspectiveParachains,
+		runtime_api::{MockRuntimeApi, MockRuntimeApiCoreState},
+		AlwaysSupportsParachains,
+	},
+	network::{new_network, NetworkEmulatorHandle, NetworkInterface, NetworkInterfaceReceiver},
+	usage::BenchmarkUsage,
+	NODE_UNDER_TEST,
+};
+use colored::Colorize;
+use itertools::Itertools;
+use polkadot_collator_protocol::{CollatorEvictionPolicy, CollatorProtocolSubsystem, ProtocolSide};
+use polkadot_node_metrics::metrics::Metrics;
+use polkadot_node_network_protocol::{
+	peer_set::CollationVersion, v2, CollationProtocols, ObservedRole, OurView,
+};
+use polkadot_node_subsystem::messages::{AllMessages, CollatorProtocolMessage, NetworkBridgeEvent};
+use polkadot_overseer::{
+	Handle as OverseerHandle, Overseer, OverseerConnector, OverseerMetrics, SpawnGlue,
+};
+use polkadot_primitives::{AuthorityDiscoveryId, ValidatorId};
+use sc_keystore::LocalKeystore;
+use sc_service::SpawnTaskHandle;
+use serde::{Deserialize, Serialize};
+use sp_core::Pair;
+use sp_keystore::{Keystore, KeystorePtr};
+use sp_runtime::RuntimeAppPublic;
+use std::{
+	sync::{atomic::Ordering, Arc},
+	time::{Duration, Instant},
+};
+use test_state::Collator;
+pub use test_state::TestState;
+
+mod test_state;
+
+const LOG_TARGET: &str = "subsystem-bench::collator-protocol";
+
+/// Parameters specific to the collator protocol benchmark
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct CollatorProtocolOptions {
+	#[clap(short, long, default_value_t = 20)]
+	/// The number of collators advertising a collation in every relay chain block.
+	pub n_collators: usize,
+	#[clap(long, default_value_t = 10, value_parser = clap::value_parser!(u8).range(0..=100))]
+	/// The percentage of collators which disconnect and reconnect in every relay chain block.
+	pub churn_percentage: u8,
+}
+
+fn make_keystore() -> KeystorePtr {
+	let keystore: KeystorePtr = Arc::new(LocalKeystore::in_memory());
+	Keystore::sr25519_generate_new(&*keystore, ValidatorId::ID, Some("//Node0"))
+		.expect("Insert key into keystore");
+	Keystore::sr25519_generate_new(&*keystore, AuthorityDiscoveryId::ID, Some("//Node0"))
+		.expect("Insert key into keystore");
+	keystore
+}
+
+fn build_overseer(
+	state: &TestState,
+	network: NetworkEmulatorHandle,
+	network_interface: NetworkInterface,
+	network_receiver: NetworkInterfaceReceiver,
+	dependencies: &TestEnvironmentDependencies,
+) -> (Overseer<SpawnGlue<SpawnTaskHandle>, AlwaysSupportsParachains>, OverseerHandle) {
+	let overseer_connector = OverseerConnector::with_event_capacity(64000);
+	let overseer_metrics = OverseerMetrics::try_register(&dependencies.registry).unwrap();
+	let spawn_task_handle = dependencies.task_manager.spawn_handle();
+	let mock_runtime_api = MockRuntimeApi::new(
+		state.config.clone(),
+		state.test_authorities.clone(),
+		state.candidate_receipts.clone(),
+		Default::default(),
+		Default::default(),
+		0,
+		MockRuntimeApiCoreState::Scheduled,
+	);
+	let chain_api_state = ChainApiState { block_headers: state.block_headers.clone() };
+	let mock_chain_api = MockChainApi::new(chain_api_state);
+	let mock_prospective_parachains =
+		MockProspectiveParachains::new().with_persisted_validation_data(state.pvd.clone());
+	let mock_candidate_backing = MockCandidateBacking::new(
+		state.config.clone(),
+		state
+			.test_authorities
+			.validator_pairs
+			.get(NODE_UNDER_TEST as usize)
+			.unwrap()
+			.clone(),
+		state.pvd.clone(),
+		state.own_backing_group.clone(),
+	)
+	.with_seconded_count(state.seconded_count.clone());
+	let subsystem = CollatorProtocolSubsystem::new(ProtocolSide::Validator {
+		keystore: make_keystore(),
+		eviction_policy: CollatorEvictionPolicy::default(),
+		metrics: Metrics::try_register(&dependencies.registry).unwrap(),
+	});
+	let network_bridge_tx = MockNetworkBridgeTx::new(
+		network,
+		network_interface.subsystem_sender(),
+		state.test_authorities.clone(),
+	);
+	let network_bridge_rx = MockNetworkBridgeRx::new(network_receiver, None, false);
+
+	let dummy = dummy_builder!(spawn_task_handle, overseer_metrics)
+		.replace_runtime_api(|_| mock_runtime_api)
+		.replace_chain_api(|_| mock_chain_api)
+		.replace_prospective_parachains(|_| mock_prospective_parachains)
+		.replace_candidate_backing(|_| mock_candidate_backing)
+		.replace_collator_protocol(|_| subsystem)
+		.replace_network_bridge_tx(|_| network_bridge_tx)
+		.replace_network_bridge_rx(|_| network_bridge_rx);
+	let (overseer, raw_handle) = dummy.build_with_connector(overseer_connector).unwrap();
+	let overseer_handle = OverseerHandle::new(raw_handle);
+
+	(overseer, overseer_handle)
+}
+
+pub fn prepare_test(state: &TestState, with_prometheus_endpoint: bool) -> TestEnvironment {
+	let dependencies = TestEnvironmentDependencies::default();
+	let (network, network_interface, network_receiver) = new_network(
+		&state.config,
+		&dependencies,
+		&state.test_authorities,
+		vec![Arc::new(state.clone())],
+	);
+	let (overseer, overseer_handle) =
+		build_overseer(state, network.clone(), network_interface, network_receiver, &dependencies);
+
+	TestEnvironment::new(
+		dependencies,
+		state.config.clone(),
+		network,
+		overseer,
+		overseer_handle,
+		state.test_authorities.clone(),
+		with_prometheus_endpoint,
+	)
+}
+
+fn collator_protocol_event(
+	event: NetworkBridgeEvent<polkadot_node_network_protocol::CollatorProtocolMessage>,
+) -> AllMessages {
+	AllMessages::CollatorProtocol(CollatorProtocolMessage::NetworkBridgeUpdate(event))
+}
+
+async fn connect_collator(env: &mut TestEnvironment, collator: &Collator) {
+	env.send_message(collator_protocol_event(NetworkBridgeEvent::PeerConnected(
+		collator.peer_id,
+		ObservedRole::Full,
+		CollationVersion::V2.into(),
+		None,
+	)))
+	.await;
+}
+
+async fn declare_collator(env: &mut TestEnvironment, state: &TestState, collator: &Collator) {
+	let signature = collator.pair.sign(&v2::declare_signature_payload(&collator.peer_id));
+	env.send_message(collator_protocol_event(NetworkBridgeEvent::PeerMessage(
+		collator.peer_id,
+		CollationProtocols::V2(v2::CollatorProtocolMessage::Declare(
+			collator.pair.public(),
+			state.para_id,
+			signature,
+		)),
+	)))
+	.await;
+}
+
+pub async fn benchmark_collator_protocol(
+	env: &mut TestEnvironment,
+	state: &TestState,
+) -> BenchmarkUsage {
+	let config = env.config().clone();
+	env.metrics().set_n_validators(config.n_validators);
+	env.metrics().set_n_cores(config.n_cores);
+
+	// Collators are only reachable if the emulated peer behind them is connected.
+	let connected_collators = state
+		.collators
+		.iter()
+		.enumerate()
+		.filter(|(_, collator)| {
+			let authority_id =
+				&state.test_authorities.validator_authority_id[collator.validator_index.0 as usize];
+			env.network().is_peer_connected(authority_id)
+		})
+		.collect_vec();
+	assert!(!connected_collators.is_empty(), "At least one collator must be connected");
+	gum::info!(target: LOG_TARGET,
+		"{}",
+		format!("{} collators of para {} are connected", connected_collators.len(), state.para_id).bright_blue()
+	);
+
+	for (_, collator) in connected_collators.iter() {
+		connect_collator(env, collator).await;
+	}
+
+	// The churning collators rotate through all connected collators from block to block.
+	let n_churning = connected_collators.len() * state.options.churn_percentage as usize / 100;
+
+	let test_start = Instant::now();
+	let mut advertisements = 0;
+	let mut reconnects = 0;
+	for block_info in state.block_infos.iter() {
+		let block_num = block_info.number as usize;
+		gum::info!(target: LOG_TARGET, "Current block {}/{} {:?}", block_num, config.num_blocks, block_info.hash);
+		env.metrics().set_current_block(block_num);
+		env.import_block(block_info.clone()).await;
+		env.send_message(collator_protocol_event(NetworkBridgeEvent::OurViewChange(OurView::new(
+			[block_info.hash],
+			0,
+		))))
+		.await;
+
+		// Collators can only declare once the para is assigned to us.
+		if block_num == 1 {
+			for (_, collator) in connected_collators.iter() {
+				declare_collator(env, state, collator).await;
+			}
+		} else {
+			let churning = connected_collators
+				.iter()
+				.cycle()
+				.skip((block_num * n_churning) % connected_collators.len())
+				.take(n_churning);
+			for (_, collator) in churning {
+				env.send_message(collator_protocol_event(NetworkBridgeEvent::PeerDisconnected(
+					collator.peer_id,
+				)))
+				.await;
+				connect_collator(env, collator).await;
+				declare_collator(env, state, collator).await;
+				reconnects += 1;
+			}
+		}
+
+		let collations = state.collations.get(&block_info.hash).unwrap();
+		for (collator_index, collator) in connected_collators.iter() {
+			env.send_message(collator_protocol_event(NetworkBridgeEvent::PeerMessage(
+				collator.peer_id,
+				CollationProtocols::V2(v2::CollatorProtocolMessage::AdvertiseCollation {
+					relay_parent: block_info.hash,
+					candidate_hash: collations[*collator_index],
+					parent_head_data_hash: state.pvd.parent_head.hash(),
+				}),
+			)))
+			.await;
+			advertisements += 1;
+		}
+
+		// Exactly one collation can be seconded per relay parent.
+		loop {
+			let seconded = state.seconded_count.load(Ordering::SeqCst);
+			gum::debug!(target: LOG_TARGET, "{}/{} collations seconded", seconded, block_num);
+
+			if seconded == block_num {
+				break
+			}
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+	}
+
+	let duration: u128 = test_start.elapsed().as_millis();
+	gum::info!(target: LOG_TARGET, "All blocks processed in {}", format!("{:?}ms", duration).cyan());
+	gum::info!(target: LOG_TARGET,
+		"Avg block time: {}",
+		format!("{} ms", test_start.elapsed().as_millis() / env.config().num_blocks as u128).red()
+	);
+	gum::info!(target: LOG_TARGET,
+		"Advertisements: {}, collations fetched: {}, reconnects: {}",
+		advertisements,
+		state.fetched_count.load(Ordering::SeqCst),
+		reconnects
+	);
+
+	env.stop().await;
+	env.collect_resource_usage(&["collator-protocol"], false)
+}
@@ -0,0 +1,213 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	collator_protocol::CollatorProtocolOptions,
+	configuration::{TestAuthorities, TestConfiguration},
+	mock::runtime_api::session_info_for_peers,
+	network::{HandleNetworkMessage, NetworkMessage},
+	NODE_UNDER_TEST,
+};
+use codec::Encode;
+use polkadot_node_network_protocol::request_response::{v2::CollationFetchingResponse, Requests};
+use polkadot_node_primitives::{BlockData, PoV};
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{CandidateReceiptV2 as CandidateReceipt, MutateDescriptorV2},
+	BlockNumber, CandidateCommitments, CandidateHash, CollatorPair, CoreIndex, Hash, Header, Id,
+	PersistedValidationData, ValidatorIndex,
+};
+use polkadot_primitives_test_helpers::{dummy_candidate_receipt_v2, dummy_head_data, dummy_pvd};
+use sc_network::ProtocolName;
+use sc_network_types::PeerId;
+use sp_core::{Pair, H256};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+const SESSION_INDEX: u32 = 0;
+
+/// An emulated collator, driven by the benchmark through one of the emulated peers.
+#[derive(Clone)]
+pub struct Collator {
+	// The validator index of the emulated peer acting as collator.
+	pub validator_index: ValidatorIndex,
+	// The network identity of the collator.
+	pub peer_id: PeerId,
+	// The collator key used to declare itself.
+	pub pair: CollatorPair,
+}
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Collator protocol specific options
+	pub options: CollatorProtocolOptions,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// Candidate receipts per relay chain block, one per core, used to build the claim queue
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// PersistedValidationData, we use one for all candidates
+	pub pvd: PersistedValidationData,
+	// The core the backing group of the node under test is assigned to
+	pub core_index: CoreIndex,
+	// The para scheduled on that core
+	pub para_id: Id,
+	// Indices in the backing group where the node under test is
+	pub own_backing_group: Vec<ValidatorIndex>,
+	// The collators of the para
+	pub collators: Vec<Collator>,
+	// Collations advertised per relay chain block, one per collator
+	pub collations: HashMap<H256, Vec<CandidateHash>>,
+	// All generated collations
+	pub collations_by_hash: HashMap<CandidateHash, (CandidateReceipt, PoV)>,
+	// Number of collations fetched by the node under test
+	pub fetched_count: Arc<AtomicUsize>,
+	// Number of collations seconded by the node under test
+	pub seconded_count: Arc<AtomicUsize>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &CollatorProtocolOptions) -> Self {
+		assert!(
+			options.n_collators < config.n_validators,
+			"Collators are emulated by the validator peers"
+		);
+
+		let test_authorities = config.generate_authorities();
+		let session_info = session_info_for_peers(config, &test_authorities);
+		let (group_index, own_backing_group) = session_info
+			.validator_groups
+			.iter()
+			.enumerate()
+			.find(|(_, g)| g.contains(&ValidatorIndex(NODE_UNDER_TEST)))
+			.map(|(index, group)| (index, group.clone()))
+			.unwrap();
+		assert!(group_index < config.n_cores, "Own backing group must be assigned to a core");
+
+		let collators = (1..=options.n_collators)
+			.map(|index| Collator {
+				validator_index: ValidatorIndex(index as u32),
+				peer_id: test_authorities.peer_ids[index],
+				pair: CollatorPair::from_string(&format!("//Collator{}", index), None)
+					.expect("Valid seed"),
+			})
+			.collect();
+
+		let mut state = Self {
+			config: config.clone(),
+			options: options.clone(),
+			test_authorities,
+			block_infos: (1..=config.num_blocks).map(generate_block_info).collect(),
+			block_headers: Default::default(),
+			candidate_receipts: Default::default(),
+			pvd: dummy_pvd(dummy_head_data(), 0),
+			core_index: CoreIndex(group_index as u32),
+			para_id: Id::new(group_index as u32 + 1),
+			own_backing_group,
+			collators,
+			collations: Default::default(),
+			collations_by_hash: Default::default(),
+			fetched_count: Default::default(),
+			seconded_count: Default::default(),
+		};
+
+		state.block_headers = state.block_infos.iter().map(generate_block_header).collect();
+
+		let pov_sizes = config.pov_sizes();
+		for block_info in state.block_infos.iter() {
+			for core_idx in 0..config.n_cores {
+				let mut receipt = dummy_candidate_receipt_v2(block_info.hash);
+				receipt.descriptor.set_para_id(Id::new(core_idx as u32 + 1));
+				receipt.descriptor.set_core_index(CoreIndex(core_idx as u32));
+				receipt.descriptor.set_session_index(SESSION_INDEX);
+				state.candidate_receipts.entry(block_info.hash).or_default().push(receipt);
+			}
+
+			// Every collator builds its own collation on top of the same parent.
+			for collator_index in 0..options.n_collators {
+				let pov_size = pov_sizes[collator_index % pov_sizes.len()];
+				let pov = PoV { block_data: BlockData(vec![collator_index as u8; pov_size]) };
+				let mut receipt = dummy_candidate_receipt_v2(block_info.hash);
+				receipt.descriptor.set_para_id(state.para_id);
+				receipt.descriptor.set_core_index(state.core_index);
+				receipt.descriptor.set_session_index(SESSION_INDEX);
+				receipt.descriptor.set_persisted_validation_data_hash(state.pvd.hash());
+				receipt.descriptor.set_pov_hash(pov.hash());
+				// The mocked validation always outputs default commitments.
+				receipt.commitments_hash = CandidateCommitments::default().hash();
+
+				let candidate_hash = receipt.hash();
+				state.collations.entry(block_info.hash).or_default().push(candidate_hash);
+				state.collations_by_hash.insert(candidate_hash, (receipt, pov));
+			}
+		}
+
+		state
+	}
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
+
+#[async_trait::async_trait]
+impl HandleNetworkMessage for TestState {
+	async fn handle(
+		&self,
+		message: NetworkMessage,
+		_node_sender: &mut futures::channel::mpsc::UnboundedSender<NetworkMessage>,
+	) -> Option<NetworkMessage> {
+		match message {
+			NetworkMessage::RequestFromNode(_authority_id, Requests::CollationFetchingV2(req)) => {
+				let (receipt, pov) = self
+					.collations_by_hash
+					.get(&req.payload.candidate_hash)
+					.expect("Only advertised collations are fetched")
+					.clone();
+				self.fetched_count.fetch_add(1, Ordering::SeqCst);
+
+				let response = CollationFetchingResponse::Collation(receipt, pov);
+				let _ = req.pending_response.send(Ok((response.encode(), ProtocolName::from(""))));
+				None
+			},
+			_ => Some(message),
+		}
+	}
+}
@@ -0,0 +1,197 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	configuration::{TestAuthorities, TestConfiguration},
+	disputes::DisputesOptions,
+	network::{HandleNetworkMessage, NetworkMessage},
+};
+use codec::Encode;
+use polkadot_node_network_protocol::request_response::{
+	v1::{DisputeRequest, DisputeResponse},
+	Requests,
+};
+use polkadot_node_primitives::SignedDisputeStatement;
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{CandidateEvent, CandidateReceiptV2 as CandidateReceipt, MutateDescriptorV2},
+	BlockNumber, CandidateHash, CoreIndex, GroupIndex, Hash, Header, Id, SessionIndex,
+	ValidatorIndex,
+};
+use polkadot_primitives_test_helpers::{dummy_candidate_receipt_v2, dummy_hash};
+use sc_network::ProtocolName;
+use sp_core::H256;
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+pub const SESSION_INDEX: SessionIndex = 0;
+
+// The validators raising the disputes, the node under test is never one of them.
+const VALID_VOTER: ValidatorIndex = ValidatorIndex(1);
+const INVALID_VOTER: ValidatorIndex = ValidatorIndex(2);
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Dispute specific options
+	pub options: DisputesOptions,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// Candidate receipts per relay chain block, one per core
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// Candidates included per relay chain block
+	pub candidate_events: HashMap<H256, Vec<CandidateEvent>>,
+	// Candidates disputed per relay chain block
+	pub disputed_candidates: HashMap<H256, Vec<CandidateReceipt>>,
+	// Pregenerated dispute votes, one valid and one invalid per disputed candidate
+	pub dispute_statements: HashMap<CandidateHash, Vec<(SignedDisputeStatement, ValidatorIndex)>>,
+	// Tracks if the node under test has sent out its own vote for a disputed candidate
+	pub disputes_tracker: HashMap<CandidateHash, Arc<AtomicBool>>,
+	// Total number of dispute requests received by the emulated peers
+	pub dispute_requests: Arc<AtomicUsize>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &DisputesOptions) -> Self {
+		assert!(config.n_validators > 2, "Disputes require at least three validators");
+
+		let test_authorities = config.generate_authorities();
+		let mut state = Self {
+			config: config.clone(),
+			options: options.clone(),
+			test_authorities,
+			block_infos: (1..=config.num_blocks).map(generate_block_info).collect(),
+			block_headers: Default::default(),
+			candidate_receipts: Default::default(),
+			candidate_events: Default::default(),
+			disputed_candidates: Default::default(),
+			dispute_statements: Default::default(),
+			disputes_tracker: Default::default(),
+			dispute_requests: Default::default(),
+		};
+
+		state.block_headers = state.block_infos.iter().map(generate_block_header).collect();
+
+		let n_disputes = options.n_disputes_per_block.min(config.n_cores);
+		let keystore = state.test_authorities.keyring.keystore();
+		for block_info in state.block_infos.iter() {
+			for core_idx in 0..config.n_cores {
+				let mut receipt = dummy_candidate_receipt_v2(dummy_hash());
+				receipt.descriptor.set_para_id(Id::new(core_idx as u32 + 1));
+				receipt.descriptor.set_relay_parent(block_info.hash);
+				receipt.descriptor.set_core_index(CoreIndex(core_idx as u32));
+				receipt.descriptor.set_session_index(SESSION_INDEX);
+
+				state.candidate_events.entry(block_info.hash).or_default().push(
+					CandidateEvent::CandidateIncluded(
+						receipt.clone(),
+						Vec::new().into(),
+						CoreIndex(core_idx as u32),
+						GroupIndex(core_idx as u32),
+					),
+				);
+				state.candidate_receipts.entry(block_info.hash).or_default().push(receipt);
+			}
+
+			let disputed = state.candidate_receipts[&block_info.hash][..n_disputes].to_vec();
+			for receipt in disputed.iter() {
+				let candidate_hash = receipt.hash();
+				let statements = [(true, VALID_VOTER), (false, INVALID_VOTER)]
+					.into_iter()
+					.map(|(valid, validator_index)| {
+						let public = state.test_authorities.validator_public
+							[validator_index.0 as usize]
+							.clone();
+						let statement = SignedDisputeStatement::sign_explicit(
+							&keystore,
+							valid,
+							candidate_hash,
+							SESSION_INDEX,
+							public,
+						)
+						.expect("Keystore works")
+						.expect("Keys are in the keystore");
+						(statement, validator_index)
+					})
+					.collect();
+				state.dispute_statements.insert(candidate_hash, statements);
+				state.disputes_tracker.insert(candidate_hash, Arc::new(AtomicBool::new(false)));
+			}
+			state.disputed_candidates.insert(block_info.hash, disputed);
+		}
+
+		state
+	}
+
+	/// Number of disputed candidates the node under test has voted on.
+	pub fn concluded_participations(&self) -> usize {
+		self.disputes_tracker.values().filter(|v| v.load(Ordering::SeqCst)).count()
+	}
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
+
+#[async_trait::async_trait]
+impl HandleNetworkMessage for TestState {
+	async fn handle(
+		&self,
+		message: NetworkMessage,
+		_node_sender: &mut futures::channel::mpsc::UnboundedSender<NetworkMessage>,
+	) -> Option<NetworkMessage> {
+		match message {
+			NetworkMessage::RequestFromNode(_authority_id, Requests::DisputeSendingV1(req)) => {
+				let DisputeRequest(message) = req.payload;
+				self.dispute_requests.fetch_add(1, Ordering::SeqCst);
+				if let Some(tracker) = self.disputes_tracker.get(&message.candidate_receipt.hash())
+				{
+					tracker.store(true, Ordering::SeqCst);
+				}
+
+				let _ = req
+					.pending_response
+					.send(Ok((DisputeResponse::Confirmed.encode(), ProtocolName::from(""))));
+				None
+			},
+			_ => Some(message),
+		}
+	}
+}
@@ -18,16 +18,30 @@
 
 use crate::{configuration::TestConfiguration, NODE_UNDER_TEST};
 use futures::FutureExt;
-use polkadot_node_primitives::{SignedFullStatementWithPVD, Statement, StatementWithPVD};
+use polkadot_node_primitives::{
+	SignedFullStatement, SignedFullStatementWithPVD, Statement, StatementWithPVD,
+};
 use polkadot_node_subsystem::{
-	messages::CandidateBackingMessage, overseer, SpawnedSubsystem, SubsystemError,
+	messages::{CandidateBackingMessage, CollatorProtocolMessage},
+	overseer, SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_types::OverseerSignal;
 use polkadot_primitives::{
-	CandidateHash, Hash, PersistedValidationData, SigningContext, ValidatorIndex, ValidatorPair,
+	vstaging::{
+		CandidateReceiptV2 as CandidateReceipt,
+		CommittedCandidateReceiptV2 as CommittedCandidateReceipt,
+	},
+	CandidateCommitments, CandidateHash, Hash, PersistedValidationData, SigningContext,
+	ValidatorIndex, ValidatorPair,
 };
 use sp_core::Pair;
-use std::collections::HashMap;
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
 
 const LOG_TARGET: &str = "subsystem-bench::candidate-backing-mock";
 
@@ -35,6 +49,8 @@ struct MockCandidateBackingState {
 	pair: ValidatorPair,
 	pvd: PersistedValidationData,
 	own_backing_group: Vec<ValidatorIndex>,
+	// Number of collations seconded so far.
+	seconded_count: Arc<AtomicUsize>,
 }
 
 pub struct MockCandidateBacking {
@@ -49,7 +65,54 @@ impl MockCandidateBacking {
 		pvd: PersistedValidationData,
 		own_backing_group: Vec<ValidatorIndex>,
 	) -> Self {
-		Self { config, state: MockCandidateBackingState { pair, pvd, own_backing_group } }
+		Self {
+			config,
+			state: MockCandidateBackingState {
+				pair,
+				pvd,
+				own_backing_group,
+				seconded_count: Default::default(),
+			},
+		}
+	}
+
+	/// Count the collations seconded by the mock using the given counter.
+	pub fn with_seconded_count(mut self, seconded_count: Arc<AtomicUsize>) -> Self {
+		self.state.seconded_count = seconded_count;
+		self
+	}
+
+	/// Handles a collation fetched by the collator protocol.
+	///
+	/// Validation is emulated: the mocked `candidate-validation` always produces default
+	/// commitments, so only collations committing to them are seconded.
+	fn handle_second(
+		&self,
+		relay_parent: Hash,
+		candidate_receipt: CandidateReceipt,
+	) -> CollatorProtocolMessage {
+		let commitments = CandidateCommitments::default();
+		if candidate_receipt.commitments_hash != commitments.hash() {
+			return CollatorProtocolMessage::Invalid(relay_parent, candidate_receipt)
+		}
+
+		let statement = Statement::Seconded(CommittedCandidateReceipt {
+			descriptor: candidate_receipt.descriptor,
+			commitments,
+		});
+		let context = SigningContext { parent_hash: relay_parent, session_index: 0 };
+		let payload = statement.to_compact().signing_payload(&context);
+		let statement = SignedFullStatement::new(
+			statement,
+			ValidatorIndex(NODE_UNDER_TEST),
+			self.state.pair.sign(&payload[..]),
+			&context,
+			&self.state.pair.public(),
+		)
+		.unwrap();
+		self.state.seconded_count.fetch_add(1, Ordering::SeqCst);
+
+		CollatorProtocolMessage::Seconded(relay_parent, statement)
 	}
 
 	fn handle_statement(
@@ -160,6 +223,13 @@ impl MockCandidateBacking {
 								ctx.send_message(message).await;
 							}
 						},
+						CandidateBackingMessage::CanSecond(_request, tx) => {
+							let _ = tx.send(true);
+						},
+						CandidateBackingMessage::Second(relay_parent, candidate_receipt, _, _) => {
+							let message = self.handle_second(relay_parent, candidate_receipt);
+							ctx.send_message(message).await;
+						},
 						_ => {
 							unimplemented!("Unexpected candidate-backing message")
 						},
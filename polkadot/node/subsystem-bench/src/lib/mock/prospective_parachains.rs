@@ -21,13 +21,22 @@ use polkadot_node_subsystem::{
 	messages::ProspectiveParachainsMessage, overseer, SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_types::OverseerSignal;
-use polkadot_primitives::Hash;
+use polkadot_primitives::{Hash, PersistedValidationData};
 
-pub struct MockProspectiveParachains {}
+pub struct MockProspectiveParachains {
+	// Validation data handed out for every prospective candidate.
+	pvd: Option<PersistedValidationData>,
+}
 
 impl MockProspectiveParachains {
 	pub fn new() -> Self {
-		Self {}
+		Self { pvd: None }
+	}
+
+	/// Answer `GetProspectiveValidationData` requests with the given validation data.
+	pub fn with_persisted_validation_data(mut self, pvd: PersistedValidationData) -> Self {
+		self.pvd = Some(pvd);
+		self
 	}
 }
 
@@ -64,6 +73,9 @@ impl MockProspectiveParachains {
 						)
 						.unwrap();
 					},
+					ProspectiveParachainsMessage::GetProspectiveValidationData(_req, tx) => {
+						tx.send(self.pvd.clone()).unwrap();
+					},
 					_ => {
 						unimplemented!("Unexpected chain-api message")
 					},
@@ -330,6 +330,19 @@ impl MockRuntimeApi {
 						RuntimeApiMessage::Request(_parent, RuntimeApiRequest::ClaimQueue(tx)) => {
 							tx.send(Ok(self.state.claim_queue.clone())).unwrap();
 						},
+						RuntimeApiMessage::Request(
+							_block_hash,
+							RuntimeApiRequest::FetchOnChainVotes(tx),
+						) => {
+							// No votes are ever included on chain in benchmarks.
+							let _ = tx.send(Ok(None));
+						},
+						RuntimeApiMessage::Request(
+							_block_hash,
+							RuntimeApiRequest::UnappliedSlashes(tx),
+						) => {
+							let _ = tx.send(Ok(Vec::new()));
+						},
 						// Long term TODO: implement more as needed.
 						message => {
 							unimplemented!("Unexpected runtime-api message: {:?}", message)
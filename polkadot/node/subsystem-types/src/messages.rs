@@ -33,9 +33,12 @@ use polkadot_node_network_protocol::{
 };
 use polkadot_node_primitives::{
 	approval::{
+		inspection::BlockApprovalReport,
 		v1::{BlockApprovalMeta, DelayTranche},
 		v2::{CandidateBitfield, IndirectAssignmentCertV2, IndirectSignedApprovalVoteV2},
 	},
+	collation_stats::ParaCollationStats,
+	disputes::DisputeCoordinatorState,
 	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
 	CollationSecondedSignal, DisputeMessage, DisputeStatus, ErasureChunk, PoV,
 	SignedDisputeStatement, SignedFullStatement, SignedFullStatementWithPVD, SubmitCollationParams,
@@ -262,6 +265,11 @@ pub enum CollatorProtocolMessage {
 	///
 	/// The hash is the relay parent.
 	Seconded(Hash, SignedFullStatement),
+	/// Get the statistics of the collations received per para and collator peer.
+	///
+	/// Only answered by the validator side of the protocol. Meant for inspection by node
+	/// operators.
+	CollationStats(oneshot::Sender<Vec<ParaCollationStats>>),
 }
 
 impl Default for CollatorProtocolMessage {
@@ -346,6 +354,11 @@ pub enum DisputeCoordinatorMessage {
 		/// The block to vote on, might be base in case there is no better.
 		tx: oneshot::Sender<(BlockNumber, Hash)>,
 	},
+	/// Get a snapshot of the state of the coordinator: all recent disputes with their votes, our
+	/// participation in them and the spam slots in use.
+	///
+	/// Meant for inspection by node operators, this is not cheap.
+	InspectState(oneshot::Sender<DisputeCoordinatorState>),
 }
 
 /// The result of `DisputeCoordinatorMessage::ImportStatements`.
@@ -1008,6 +1021,8 @@ pub enum ApprovalVotingParallelMessage {
 		CandidateHash,
 		oneshot::Sender<HashMap<ValidatorIndex, (Vec<CandidateHash>, ValidatorSignature)>>,
 	),
+	/// Gets mapped into `ApprovalVotingMessage::InspectBlock`
+	InspectBlock(Hash, oneshot::Sender<Option<BlockApprovalReport>>),
 	/// Gets mapped into `ApprovalDistributionMessage::NewBlocks`
 	NewBlocks(Vec<BlockApprovalMeta>),
 	/// Gets mapped into `ApprovalDistributionMessage::DistributeAssignment`
@@ -1037,6 +1052,8 @@ impl TryFrom<ApprovalVotingParallelMessage> for ApprovalVotingMessage {
 				Ok(ApprovalVotingMessage::ApprovedAncestor(hash, number, tx)),
 			ApprovalVotingParallelMessage::GetApprovalSignaturesForCandidate(candidate, tx) =>
 				Ok(ApprovalVotingMessage::GetApprovalSignaturesForCandidate(candidate, tx)),
+			ApprovalVotingParallelMessage::InspectBlock(hash, tx) =>
+				Ok(ApprovalVotingMessage::InspectBlock(hash, tx)),
 			_ => Err(()),
 		}
 	}
@@ -1176,6 +1193,12 @@ pub enum ApprovalVotingMessage {
 		CandidateHash,
 		oneshot::Sender<HashMap<ValidatorIndex, (Vec<CandidateHash>, ValidatorSignature)>>,
 	),
+
+	/// Report the approval state of the candidates included by a block and check the stored
+	/// entries for consistency. Returns `None` if the block is unknown or can't be read.
+	///
+	/// Meant for inspection by node operators.
+	InspectBlock(Hash, oneshot::Sender<Option<BlockApprovalReport>>),
 }
 
 /// Message to the Approval Distribution subsystem.
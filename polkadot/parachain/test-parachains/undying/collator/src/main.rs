@@ -107,10 +107,13 @@ fn main() -> Result<()> {
 						malus_finality_delay: None,
 						hwbench: None,
 						execute_workers_max_num: None,
+						pvf_execution_cache_size: None,
+						pvf_shared_artifacts_dir: None,
 						prepare_workers_hard_max_num: None,
 						prepare_workers_soft_max_num: None,
 						enable_approval_voting_parallel: false,
 						keep_finalized_for: None,
+						availability_storage_budget: None,
 					},
 				)
 				.map_err(|e| e.to_string())?;
@@ -0,0 +1,115 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the state of approval voting.
+
+use futures::{
+	channel::oneshot,
+	future::{self, Either},
+};
+use futures_timer::Delay;
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{ErrorCode, ErrorObject},
+	Extensions,
+};
+use polkadot_node_primitives::approval::inspection::BlockApprovalReport;
+use polkadot_node_subsystem_types::messages::{
+	ApprovalVotingMessage, ApprovalVotingParallelMessage,
+};
+use polkadot_overseer::Handle;
+use polkadot_primitives::Hash;
+use sc_rpc_api::check_if_safe;
+use std::time::Duration;
+
+/// How long to wait for approval voting to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Approval voting RPC methods.
+#[rpc(server)]
+pub trait ApprovalsApi {
+	/// Returns the approval state of the candidates included by the given relay chain block:
+	/// the assignments per tranche, the approvals, the no-shows and why a candidate is not
+	/// approved yet. Also lists the violated invariants of the stored entries, if any.
+	///
+	/// Returns `None` if approval voting does not know the block, e.g. because it is finalized.
+	#[method(name = "parachain_approvalBlockReport", with_extensions)]
+	async fn approval_block_report(
+		&self,
+		block_hash: Hash,
+	) -> RpcResult<Option<BlockApprovalReport>>;
+}
+
+/// Implementation of [`ApprovalsApiServer`], talking to approval voting via the overseer.
+pub struct Approvals {
+	overseer_handle: Handle,
+	approval_voting_parallel_enabled: bool,
+}
+
+impl Approvals {
+	/// Create a new instance, sending requests through the given overseer handle.
+	///
+	/// `approval_voting_parallel_enabled` must match the approval voting subsystem the overseer
+	/// was built with.
+	pub fn new(overseer_handle: Handle, approval_voting_parallel_enabled: bool) -> Self {
+		Self { overseer_handle, approval_voting_parallel_enabled }
+	}
+}
+
+#[async_trait]
+impl ApprovalsApiServer for Approvals {
+	async fn approval_block_report(
+		&self,
+		ext: &Extensions,
+		block_hash: Hash,
+	) -> RpcResult<Option<BlockApprovalReport>> {
+		check_if_safe(ext)?;
+
+		let (tx, rx) = oneshot::channel();
+		let mut overseer_handle = self.overseer_handle.clone();
+		let approval_voting_parallel_enabled = self.approval_voting_parallel_enabled;
+		let request = async move {
+			if approval_voting_parallel_enabled {
+				overseer_handle
+					.send_msg(
+						ApprovalVotingParallelMessage::InspectBlock(block_hash, tx),
+						"ApprovalsRpc",
+					)
+					.await;
+			} else {
+				overseer_handle
+					.send_msg(ApprovalVotingMessage::InspectBlock(block_hash, tx), "ApprovalsRpc")
+					.await;
+			}
+			rx.await
+		};
+
+		match future::select(Box::pin(request), Delay::new(REQUEST_TIMEOUT)).await {
+			Either::Left((Ok(report), _)) => Ok(report),
+			Either::Left((Err(_), _)) => Err(ErrorObject::owned(
+				ErrorCode::InternalError.code(),
+				"Approval voting did not answer",
+				None::<()>,
+			)),
+			Either::Right(_) => Err(ErrorObject::owned(
+				ErrorCode::InternalError.code(),
+				"Approval voting did not answer in time",
+				None::<()>,
+			)),
+		}
+	}
+}
@@ -0,0 +1,90 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the collations received by the collator protocol.
+
+use futures::{
+	channel::oneshot,
+	future::{self, Either},
+};
+use futures_timer::Delay;
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{ErrorCode, ErrorObject},
+	Extensions,
+};
+use polkadot_node_primitives::collation_stats::ParaCollationStats;
+use polkadot_node_subsystem_types::messages::CollatorProtocolMessage;
+use polkadot_overseer::Handle;
+use sc_rpc_api::check_if_safe;
+use std::time::Duration;
+
+/// How long to wait for the collator protocol to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Collator protocol RPC methods.
+#[rpc(server)]
+pub trait CollatorsApi {
+	/// Returns, per para and collator peer, the number of collations advertised, fetched and
+	/// seconded and the number of advertisements and collations dropped per reason.
+	///
+	/// Only available on validators. Peer identities are exposed, so this is an unsafe method.
+	#[method(name = "parachain_collationStats", with_extensions)]
+	async fn collation_stats(&self) -> RpcResult<Vec<ParaCollationStats>>;
+}
+
+/// Implementation of [`CollatorsApiServer`], talking to the collator protocol via the overseer.
+pub struct Collators {
+	overseer_handle: Handle,
+}
+
+impl Collators {
+	/// Create a new instance, sending requests through the given overseer handle.
+	pub fn new(overseer_handle: Handle) -> Self {
+		Self { overseer_handle }
+	}
+}
+
+#[async_trait]
+impl CollatorsApiServer for Collators {
+	async fn collation_stats(&self, ext: &Extensions) -> RpcResult<Vec<ParaCollationStats>> {
+		check_if_safe(ext)?;
+
+		let (tx, rx) = oneshot::channel();
+		let mut overseer_handle = self.overseer_handle.clone();
+		let request = async move {
+			overseer_handle
+				.send_msg(CollatorProtocolMessage::CollationStats(tx), "CollatorsRpc")
+				.await;
+			rx.await
+		};
+
+		match future::select(Box::pin(request), Delay::new(REQUEST_TIMEOUT)).await {
+			Either::Left((Ok(answer), _)) => Ok(answer),
+			Either::Left((Err(_), _)) => Err(ErrorObject::owned(
+				ErrorCode::InternalError.code(),
+				"The collator protocol did not answer, the node is not running as a validator",
+				None::<()>,
+			)),
+			Either::Right(_) => Err(ErrorObject::owned(
+				ErrorCode::InternalError.code(),
+				"The collator protocol did not answer in time",
+				None::<()>,
+			)),
+		}
+	}
+}
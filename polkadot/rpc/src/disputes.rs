@@ -0,0 +1,93 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the state of the dispute coordinator.
+
+use futures::{
+	channel::oneshot,
+	future::{self, Either},
+};
+use futures_timer::Delay;
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{ErrorCode, ErrorObject},
+	Extensions,
+};
+use polkadot_node_primitives::disputes::DisputeCoordinatorState;
+use polkadot_node_subsystem_types::messages::DisputeCoordinatorMessage;
+use polkadot_overseer::Handle;
+use sc_rpc_api::check_if_safe;
+use std::time::Duration;
+
+/// How long to wait for the dispute coordinator to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Dispute coordinator RPC methods.
+#[rpc(server)]
+pub trait DisputesApi {
+	/// Returns all recent disputes with the votes cast in them, our participation in them and the
+	/// spam slots in use.
+	///
+	/// Loading all the votes is costly, so this is only exposed as an unsafe method.
+	#[method(name = "parachain_disputeCoordinatorState", with_extensions)]
+	async fn dispute_coordinator_state(&self) -> RpcResult<DisputeCoordinatorState>;
+}
+
+/// Implementation of [`DisputesApiServer`], talking to the dispute coordinator via the overseer.
+pub struct Disputes {
+	overseer_handle: Handle,
+}
+
+impl Disputes {
+	/// Create a new instance, sending requests through the given overseer handle.
+	pub fn new(overseer_handle: Handle) -> Self {
+		Self { overseer_handle }
+	}
+}
+
+#[async_trait]
+impl DisputesApiServer for Disputes {
+	async fn dispute_coordinator_state(
+		&self,
+		ext: &Extensions,
+	) -> RpcResult<DisputeCoordinatorState> {
+		check_if_safe(ext)?;
+
+		let (tx, rx) = oneshot::channel();
+		let mut overseer_handle = self.overseer_handle.clone();
+		let request = async move {
+			overseer_handle
+				.send_msg(DisputeCoordinatorMessage::InspectState(tx), "DisputesRpc")
+				.await;
+			rx.await
+		};
+
+		match future::select(Box::pin(request), Delay::new(REQUEST_TIMEOUT)).await {
+			Either::Left((Ok(answer), _)) => Ok(answer),
+			Either::Left((Err(_), _)) => Err(ErrorObject::owned(
+				ErrorCode::InternalError.code(),
+				"The dispute coordinator did not answer",
+				None::<()>,
+			)),
+			Either::Right(_) => Err(ErrorObject::owned(
+				ErrorCode::InternalError.code(),
+				"The dispute coordinator did not answer in time",
+				None::<()>,
+			)),
+		}
+	}
+}
@@ -38,6 +38,10 @@ use sp_consensus_babe::BabeApi;
 use sp_consensus_beefy::AuthorityIdBound;
 use sp_keystore::KeystorePtr;
 
+pub mod approvals;
+pub mod collators;
+pub mod disputes;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = RpcModule<()>;
 
@@ -91,17 +95,26 @@ pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
 	pub beefy: BeefyDeps<AuthorityId>,
 	/// Backend used by the node.
 	pub backend: Arc<B>,
+	/// Handle to the overseer, if the node runs the parachain subsystems.
+	pub overseer_handle: Option<polkadot_overseer::Handle>,
+	/// Whether the overseer runs the parallel approval voting subsystem.
+	pub approval_voting_parallel_enabled: bool,
 }
 
 /// Instantiate all RPC extensions.
 pub fn create_full<C, P, SC, B, AuthorityId>(
-	FullDeps { client, pool, select_chain, chain_spec, babe, grandpa, beefy, backend }: FullDeps<
-		C,
-		P,
-		SC,
-		B,
-		AuthorityId,
-	>,
+	FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		babe,
+		grandpa,
+		beefy,
+		backend,
+		overseer_handle,
+		approval_voting_parallel_enabled,
+	}: FullDeps<C, P, SC, B, AuthorityId>,
 ) -> Result<RpcExtension, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>
@@ -123,6 +136,9 @@ where
 	AuthorityId: AuthorityIdBound,
 	<AuthorityId as RuntimeAppPublic>::Signature: Send + Sync,
 {
+	use approvals::{Approvals, ApprovalsApiServer};
+	use collators::{Collators, CollatorsApiServer};
+	use disputes::{Disputes, DisputesApiServer};
 	use mmr_rpc::{Mmr, MmrApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
@@ -180,5 +196,13 @@ where
 		.into_rpc(),
 	)?;
 
+	if let Some(overseer_handle) = overseer_handle {
+		io.merge(
+			Approvals::new(overseer_handle.clone(), approval_voting_parallel_enabled).into_rpc(),
+		)?;
+		io.merge(Collators::new(overseer_handle.clone()).into_rpc())?;
+		io.merge(Disputes::new(overseer_handle).into_rpc())?;
+	}
+
 	Ok(io)
 }
@@ -379,6 +379,8 @@ mod tests {
 
 	impl pallet_treasury::Config for Test {
 		type Currency = pallet_balances::Pallet<Test>;
+		type NativeBalance = pallet_balances::Pallet<Test>;
+		type RuntimeHoldReason = RuntimeHoldReason;
 		type RejectOrigin = frame_system::EnsureRoot<AccountId>;
 		type RuntimeEvent = RuntimeEvent;
 		type SpendPeriod = ();
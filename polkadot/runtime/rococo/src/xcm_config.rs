@@ -227,6 +227,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = XcmPallet;
+	type XcmTracer = XcmPallet;
 }
 
 parameter_types! {
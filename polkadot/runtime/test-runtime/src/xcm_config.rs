@@ -158,6 +158,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = ();
+	type XcmTracer = ();
 }
 
 impl pallet_xcm::Config for crate::Runtime {
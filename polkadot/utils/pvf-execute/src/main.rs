@@ -0,0 +1,245 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prepare and execute the PVF of a candidate offline.
+//!
+//! Useful to debug a disputed candidate without spinning up a node. The PVF goes through the same
+//! validation host, workers and sandbox as on a validator, with the production timeouts and limits
+//! for approval checking. Once done, the verdict, the timings, the memory statistics of the
+//! preparation and the outputs of a successful validation are printed.
+//!
+//! The inputs are the validation code, as stored on chain, and the SCALE encoded
+//! `PersistedValidationData` and `PoV` of the candidate.
+
+use clap::Parser;
+use codec::Decode;
+use polkadot_node_core_pvf::{
+	start, Config, Metrics, PrepareJobKind, Priority, PvfPrepData, EXECUTE_BINARY_NAME,
+	PREPARE_BINARY_NAME,
+};
+use polkadot_node_metrics::metrics::{prometheus::Registry, Metrics as _};
+use polkadot_node_primitives::PoV;
+use polkadot_node_subsystem::messages::PvfExecKind;
+use polkadot_primitives::{
+	executor_params::{DEFAULT_APPROVAL_EXECUTION_TIMEOUT, DEFAULT_LENIENT_PREPARATION_TIMEOUT},
+	ExecutorParams, PersistedValidationData, PvfExecKind as RuntimePvfExecKind, PvfPrepKind,
+	MAX_CODE_SIZE,
+};
+use std::{
+	path::{Path, PathBuf},
+	process::ExitCode,
+	sync::Arc,
+	time::Instant,
+};
+
+/// The validation code bomb limit of the default host configuration, `max_code_size` times the
+/// maximum validation code compression ratio.
+const DEFAULT_VALIDATION_CODE_BOMB_LIMIT: u32 = MAX_CODE_SIZE * 10;
+
+#[derive(Debug, Parser)]
+struct Opt {
+	/// The validation code of the parachain, compressed or not.
+	#[arg(long)]
+	code: PathBuf,
+
+	/// The SCALE encoded `PersistedValidationData` of the candidate.
+	#[arg(long)]
+	pvd: PathBuf,
+
+	/// The SCALE encoded `PoV` of the candidate.
+	#[arg(long)]
+	pov: PathBuf,
+
+	/// The SCALE encoded `ExecutorParams` of the candidate's session. The defaults are used if
+	/// not specified.
+	#[arg(long)]
+	executor_params: Option<PathBuf>,
+
+	/// The maximum size of the decompressed validation code.
+	#[arg(long, default_value_t = DEFAULT_VALIDATION_CODE_BOMB_LIMIT)]
+	validation_code_bomb_limit: u32,
+
+	/// The directory containing the prepare and execute worker binaries. Defaults to the directory
+	/// of this executable.
+	#[arg(long)]
+	workers_path: Option<PathBuf>,
+
+	/// Run without the security features enforced in secure validator mode. Results may differ
+	/// from a validator's.
+	#[arg(long)]
+	insecure_validator_i_know_what_i_do: bool,
+}
+
+fn decode_file<T: Decode>(path: &Path) -> Result<T, String> {
+	let encoded =
+		std::fs::read(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+	T::decode(&mut &encoded[..]).map_err(|e| format!("cannot decode {}: {}", path.display(), e))
+}
+
+/// Returns the sum of the observations of the given histogram, if any. Exactly one candidate is
+/// processed, so this is the value of the only observation.
+fn observed(registry: &Registry, name: &str) -> Option<f64> {
+	registry
+		.gather()
+		.into_iter()
+		.find(|family| family.get_name() == name)?
+		.get_metric()
+		.iter()
+		.map(|metric| metric.get_histogram())
+		.find(|histogram| histogram.get_sample_count() > 0)
+		.map(|histogram| histogram.get_sample_sum())
+}
+
+async fn run(opt: Opt) -> Result<bool, String> {
+	let Opt {
+		code,
+		pvd,
+		pov,
+		executor_params,
+		validation_code_bomb_limit,
+		workers_path,
+		insecure_validator_i_know_what_i_do,
+	} = opt;
+
+	let code =
+		std::fs::read(&code).map_err(|e| format!("cannot read {}: {}", code.display(), e))?;
+	let pvd: PersistedValidationData = decode_file(&pvd)?;
+	let pov: PoV = decode_file(&pov)?;
+	let executor_params: ExecutorParams = match executor_params {
+		Some(path) => decode_file(&path)?,
+		None => ExecutorParams::default(),
+	};
+
+	let workers_path = match workers_path {
+		Some(path) => path,
+		None => std::env::current_exe()
+			.ok()
+			.and_then(|exe| exe.parent().map(Path::to_path_buf))
+			.ok_or("cannot determine the workers path, please specify `--workers-path`")?,
+	};
+	// Use a fresh directory for the artifacts, so nothing is left over from previous runs.
+	let cache_dir =
+		tempfile::tempdir().map_err(|e| format!("cannot create the artifacts directory: {}", e))?;
+
+	// The workers are not checked against the version of this tool.
+	let config = Config::new(
+		cache_dir.path().to_owned(),
+		None,
+		!insecure_validator_i_know_what_i_do,
+		workers_path.join(PREPARE_BINARY_NAME),
+		workers_path.join(EXECUTE_BINARY_NAME),
+		1,
+		1,
+		1,
+	);
+	let registry = Registry::new();
+	let metrics = Metrics::register(Some(&registry)).map_err(|e| e.to_string())?;
+	let (mut host, task) = start(config, metrics).await.map_err(|e| e.to_string())?;
+	tokio::spawn(task);
+
+	let prep_timeout = executor_params
+		.pvf_prep_timeout(PvfPrepKind::Prepare)
+		.unwrap_or(DEFAULT_LENIENT_PREPARATION_TIMEOUT);
+	let exec_timeout = executor_params
+		.pvf_exec_timeout(RuntimePvfExecKind::Approval)
+		.unwrap_or(DEFAULT_APPROVAL_EXECUTION_TIMEOUT);
+	let pvf = PvfPrepData::from_code(
+		code,
+		executor_params,
+		prep_timeout,
+		PrepareJobKind::Compilation,
+		validation_code_bomb_limit,
+	);
+	println!("Validation code hash: {:?}", pvf.code_hash());
+	println!("Preparation timeout: {:?}, execution timeout: {:?}", prep_timeout, exec_timeout);
+
+	let started = Instant::now();
+	let (result_tx, result_rx) = futures::channel::oneshot::channel();
+	host.execute_pvf(
+		pvf,
+		exec_timeout,
+		Arc::new(pvd),
+		Arc::new(pov),
+		Priority::Critical,
+		PvfExecKind::Dispute,
+		result_tx,
+	)
+	.await
+	.map_err(|e| e.to_string())?;
+	let result = result_rx.await.map_err(|_| "the validation host went away")?;
+	let elapsed = started.elapsed();
+
+	println!("Total time: {:?}", elapsed);
+	for (label, name, unit) in [
+		("Preparation time", "polkadot_pvf_preparation_time", "s"),
+		("Execution time", "polkadot_pvf_execution_time", "s"),
+		("Preparation ru_maxrss", "polkadot_pvf_preparation_max_rss", "KiB"),
+		("Preparation max resident", "polkadot_pvf_preparation_max_resident", "KiB"),
+		("Preparation max allocated", "polkadot_pvf_preparation_max_allocated", "KiB"),
+		(
+			"Preparation peak tracked allocation",
+			"polkadot_pvf_preparation_peak_tracked_allocation",
+			"KiB",
+		),
+	] {
+		if let Some(value) = observed(&registry, name) {
+			println!("{}: {} {}", label, value, unit);
+		}
+	}
+
+	let outputs = match result {
+		Ok(outputs) => outputs,
+		Err(err) => {
+			println!("Result: failed ({:?})", err);
+			return Ok(false)
+		},
+	};
+
+	println!("Result: valid");
+	println!("Head data: {}", array_bytes::bytes2hex("0x", &outputs.head_data.0));
+	match outputs.new_validation_code {
+		Some(code) => println!("New validation code: {:?} ({} bytes)", code.hash(), code.0.len()),
+		None => println!("New validation code: none"),
+	}
+	println!("Upward messages: {}", outputs.upward_messages.len());
+	for message in outputs.upward_messages.iter() {
+		println!("  {}", array_bytes::bytes2hex("0x", message));
+	}
+	println!("Horizontal messages: {}", outputs.horizontal_messages.len());
+	for message in outputs.horizontal_messages.iter() {
+		println!(
+			"  to {}: {}",
+			u32::from(message.recipient),
+			array_bytes::bytes2hex("0x", &message.data)
+		);
+	}
+	println!("Processed downward messages: {}", outputs.processed_downward_messages);
+	println!("HRMP watermark: {}", outputs.hrmp_watermark);
+
+	Ok(true)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+	match run(Opt::parse()).await {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::from(1),
+		Err(err) => {
+			eprintln!("Error: {}", err);
+			ExitCode::from(2)
+		},
+	}
+}
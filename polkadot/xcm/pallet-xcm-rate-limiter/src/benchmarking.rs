@@ -0,0 +1,75 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_benchmarking::v2::*;
+use sp_runtime::traits::One;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn set_budget() -> Result<(), BenchmarkError> {
+		let origin =
+			T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let location = Location::new(1, [Parachain(1000)]);
+		let budget =
+			Budget { max_weight: Weight::from_parts(1_000_000_000, 64 * 1024), max_messages: 100 };
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, Box::new(location.clone().into()), Some(budget));
+
+		assert_eq!(Overrides::<T>::get(&location), Some(budget));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn check_rate_limit() {
+		// The worst case reads an override and records a usage.
+		let location = Location::new(1, [Parachain(1000)]);
+		let budget = Budget { max_weight: Weight::MAX, max_messages: u32::MAX };
+		Overrides::<T>::insert(&location, budget);
+
+		#[block]
+		{
+			assert!(Pallet::<T>::check_rate_limit(&location, Weight::from_parts(1_000, 1_000)));
+		}
+
+		assert_eq!(Pallet::<T>::usage(&location).messages, 1);
+	}
+
+	#[benchmark]
+	fn prune_usage() {
+		// The worst case removes a usage of a past period.
+		let location = Location::new(1, [Parachain(1000)]);
+		let usage = Usage { period_start: Zero::zero(), weight: Weight::zero(), messages: 1 };
+		UsageOf::<T>::insert(&location, usage);
+		frame_system::Pallet::<T>::set_block_number(T::Period::get().saturating_add(One::one()));
+		let mut meter = WeightMeter::with_limit(
+			T::DbWeight::get().reads_writes(1, 1).saturating_add(T::WeightInfo::prune_usage()),
+		);
+
+		#[block]
+		{
+			Pallet::<T>::prune_usage(&mut meter);
+		}
+
+		assert!(UsageOf::<T>::get(&location).is_none());
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
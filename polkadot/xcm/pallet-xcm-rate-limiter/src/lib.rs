@@ -0,0 +1,314 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet enforcing per-origin rate limits on inbound XCM programs.
+//!
+//! Every origin matched by [`Config::LimitedOrigins`] may execute, within each period of
+//! [`Config::Period`] blocks, programs of up to a total weight and up to a number of programs given
+//! by its [`Budget`]. The budget is [`Config::DefaultBudget`], unless governance overrides it for
+//! the origin with [`Pallet::set_budget`]. Overrides also apply to origins not matched by
+//! [`Config::LimitedOrigins`].
+//!
+//! Programs are let through as long as their origin has not exhausted its budget in the current
+//! period, so the last program of a period may overshoot the weight budget. This way a program
+//! heavier than the whole budget still gets executed, as the first program of a period. A budget
+//! with no messages or no weight blocks the origin altogether.
+//!
+//! The pallet implements [`CheckRateLimit`], to be used with the `RespectRateLimits` barrier of
+//! `xcm-builder`, which defers the programs of origins which exhausted their budget. The storage
+//! accessed by the barrier is paid for by the programs when the weigher of the executor is wrapped
+//! in [`WithRateLimitCheck`]. Deferred programs are only charged [`RateLimitCheckWeight`] when it
+//! is given as the weight of yielded messages to `ProcessXcmMessage`.
+//!
+//! The usage of origins in past periods is pruned in `on_idle`.
+//!
+//! The [`Event::Throttled`] event is emitted by the program exhausting the budget, rather than by
+//! the deferred programs. Callers processing programs in a storage transaction rolled back on
+//! deferral, like `pallet-message-queue`, would roll the event back otherwise.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use core::marker::PhantomData;
+use frame_support::{pallet_prelude::*, traits::Contains, weights::WeightMeter};
+use frame_system::pallet_prelude::*;
+use scale_info::TypeInfo;
+use sp_runtime::traits::{Saturating, Zero};
+use xcm::prelude::*;
+use xcm_executor::traits::{CheckRateLimit, WeightBounds};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+const LOG_TARGET: &str = "xcm::rate-limiter";
+
+/// The resources an origin may use within a period.
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Decode,
+	DecodeWithMemTracking,
+	Encode,
+	Eq,
+	MaxEncodedLen,
+	PartialEq,
+	TypeInfo,
+)]
+pub struct Budget {
+	/// The total weight of the programs.
+	pub max_weight: Weight,
+	/// The number of programs.
+	pub max_messages: u32,
+}
+
+/// The resources used by an origin within a period.
+#[derive(
+	Clone,
+	Debug,
+	Decode,
+	DecodeWithMemTracking,
+	Default,
+	Encode,
+	Eq,
+	MaxEncodedLen,
+	PartialEq,
+	TypeInfo,
+)]
+pub struct Usage<BlockNumber> {
+	/// The first block of the period.
+	pub period_start: BlockNumber,
+	/// The total weight of the programs executed in the period.
+	pub weight: Weight,
+	/// The number of programs executed in the period.
+	pub messages: u32,
+}
+
+impl<BlockNumber> Usage<BlockNumber> {
+	/// Whether no more programs may be executed in the period with the given `budget`.
+	pub fn is_exhausted(&self, budget: &Budget) -> bool {
+		self.weight.any_gte(budget.max_weight) || self.messages >= budget.max_messages
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origins subject to [`Config::DefaultBudget`].
+		type LimitedOrigins: Contains<Location>;
+
+		/// The budget of limited origins without an override.
+		#[pallet::constant]
+		type DefaultBudget: Get<Budget>;
+
+		/// The length of a period, in blocks. Usage is reset at the start of every period.
+		#[pallet::constant]
+		type Period: Get<BlockNumberFor<Self>>;
+
+		/// The origin allowed to override budgets.
+		type AdminOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The budget of an origin was overridden, or the override was removed if `budget` is
+		/// `None`.
+		BudgetSet { origin: Location, budget: Option<Budget> },
+		/// `origin` exhausted its budget with the resources in `usage`. Its programs are throttled
+		/// until the end of the period.
+		Throttled { origin: Location, usage: Usage<BlockNumberFor<T>> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The version of the location is not supported.
+		BadVersion,
+	}
+
+	/// The budgets overridden by governance.
+	#[pallet::storage]
+	pub type Overrides<T: Config> = StorageMap<_, Blake2_128Concat, Location, Budget, OptionQuery>;
+
+	/// The resources used by rate limited origins in their latest period.
+	#[pallet::storage]
+	pub type UsageOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, Location, Usage<BlockNumberFor<T>>, OptionQuery>;
+
+	/// The origin of the last entry of [`UsageOf`] visited by the pruning in `on_idle`, from which
+	/// the pruning resumes.
+	#[pallet::storage]
+	pub type PruneCursor<T: Config> = StorageValue<_, Location, OptionQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let mut meter = WeightMeter::with_limit(remaining_weight);
+			Self::prune_usage(&mut meter);
+			meter.consumed()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Override the budget of an origin.
+		///
+		/// - `origin`: Must be an origin specified by `AdminOrigin`.
+		/// - `location`: The origin of the programs to rate limit.
+		/// - `budget`: The new budget, or `None` to remove the override.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_budget())]
+		pub fn set_budget(
+			origin: OriginFor<T>,
+			location: Box<VersionedLocation>,
+			budget: Option<Budget>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let location = Location::try_from(*location).map_err(|()| Error::<T>::BadVersion)?;
+			Overrides::<T>::set(&location, budget);
+			Self::deposit_event(Event::BudgetSet { origin: location, budget });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The budget of `origin`, or `None` if it is not rate limited.
+	pub fn budget(origin: &Location) -> Option<Budget> {
+		Overrides::<T>::get(origin)
+			.or_else(|| T::LimitedOrigins::contains(origin).then(T::DefaultBudget::get))
+	}
+
+	/// The first block of the current period.
+	pub fn period_start() -> BlockNumberFor<T> {
+		let now = frame_system::Pallet::<T>::block_number();
+		let period = T::Period::get();
+		if period.is_zero() {
+			now
+		} else {
+			now.saturating_sub(now % period)
+		}
+	}
+
+	/// The resources used by `origin` in the current period.
+	pub fn usage(origin: &Location) -> Usage<BlockNumberFor<T>> {
+		let period_start = Self::period_start();
+		UsageOf::<T>::get(origin)
+			.filter(|usage| usage.period_start == period_start)
+			.unwrap_or(Usage { period_start, weight: Weight::zero(), messages: 0 })
+	}
+
+	/// Remove the usage recorded in past periods, within the weight left in `meter`.
+	///
+	/// Entries of [`UsageOf`] are visited in storage order, resuming after [`PruneCursor`], and the
+	/// cursor is reset once all of them were visited.
+	pub(crate) fn prune_usage(meter: &mut WeightMeter) {
+		// Reading and writing the cursor.
+		if meter.try_consume(T::DbWeight::get().reads_writes(1, 1)).is_err() {
+			return
+		}
+		let period_start = Self::period_start();
+		let mut cursor = PruneCursor::<T>::get();
+		let mut iter = match &cursor {
+			Some(cursor) => UsageOf::<T>::iter_from(UsageOf::<T>::hashed_key_for(cursor)),
+			None => UsageOf::<T>::iter(),
+		};
+		while meter.try_consume(T::WeightInfo::prune_usage()).is_ok() {
+			let Some((origin, usage)) = iter.next() else {
+				cursor = None;
+				break
+			};
+			if usage.period_start != period_start {
+				UsageOf::<T>::remove(&origin);
+			}
+			cursor = Some(origin);
+		}
+		PruneCursor::<T>::set(cursor);
+	}
+}
+
+/// Weigher adding the cost of [`CheckRateLimit::check_rate_limit`] to the weight given by `Inner`,
+/// so that the storage accessed by the `RespectRateLimits` barrier is charged to the program.
+pub struct WithRateLimitCheck<T, Inner>(PhantomData<(T, Inner)>);
+impl<T, Inner, Call> WeightBounds<Call> for WithRateLimitCheck<T, Inner>
+where
+	T: Config,
+	Inner: WeightBounds<Call>,
+{
+	fn weight(message: &mut Xcm<Call>) -> Result<Weight, ()> {
+		let weight = Inner::weight(message)?;
+		Ok(weight.saturating_add(T::WeightInfo::check_rate_limit()))
+	}
+
+	fn instr_weight(instruction: &mut Instruction<Call>) -> Result<Weight, ()> {
+		Inner::instr_weight(instruction)
+	}
+}
+
+/// The weight of [`CheckRateLimit::check_rate_limit`], charged for the programs deferred by the
+/// `RespectRateLimits` barrier instead of their whole weight.
+pub struct RateLimitCheckWeight<T>(PhantomData<T>);
+impl<T: Config> Get<Weight> for RateLimitCheckWeight<T> {
+	fn get() -> Weight {
+		T::WeightInfo::check_rate_limit()
+	}
+}
+
+impl<T: Config> CheckRateLimit for Pallet<T> {
+	fn check_rate_limit(origin: &Location, max_weight: Weight) -> bool {
+		let Some(budget) = Self::budget(origin) else { return true };
+		let mut usage = Self::usage(origin);
+		if usage.is_exhausted(&budget) {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?origin, ?max_weight, ?usage, ?budget,
+				"Origin exhausted its budget",
+			);
+			return false
+		}
+		usage.weight.saturating_accrue(max_weight);
+		usage.messages.saturating_inc();
+		if usage.is_exhausted(&budget) {
+			Self::deposit_event(Event::Throttled { origin: origin.clone(), usage: usage.clone() });
+		}
+		UsageOf::<T>::insert(origin, usage);
+		true
+	}
+}
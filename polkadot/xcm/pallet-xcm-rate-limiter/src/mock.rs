@@ -0,0 +1,117 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use codec::Decode;
+use frame_support::{
+	construct_runtime, derive_impl, parameter_types,
+	traits::{ConstU32, Contains, Everything, ProcessMessage, ProcessMessageError},
+	weights::WeightMeter,
+};
+use frame_system::EnsureRoot;
+use sp_runtime::BuildStorage;
+use xcm::prelude::*;
+use xcm_builder::{AllowUnpaidExecutionFrom, RespectRateLimits};
+use xcm_executor::traits::{Properties, ShouldExecute};
+
+use crate::{self as pallet_xcm_rate_limiter, Budget};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		XcmRateLimiter: pallet_xcm_rate_limiter,
+		MessageQueue: pallet_message_queue,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+/// Sibling parachains are rate limited, other origins are not.
+pub struct SiblingParachains;
+impl Contains<Location> for SiblingParachains {
+	fn contains(location: &Location) -> bool {
+		matches!(location.unpack(), (1, [Parachain(_)]))
+	}
+}
+
+parameter_types! {
+	pub const DefaultBudget: Budget =
+		Budget { max_weight: Weight::from_parts(1_000, 1_000), max_messages: 3 };
+	pub const Period: u64 = 10;
+}
+
+impl crate::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type LimitedOrigins = SiblingParachains;
+	type DefaultBudget = DefaultBudget;
+	type Period = Period;
+	type AdminOrigin = EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+
+/// Processes messages of sibling parachains consisting of the encoded weight of a program, only
+/// running them through the rate limiting barrier.
+pub struct RateLimitedProcessor;
+impl ProcessMessage for RateLimitedProcessor {
+	type Origin = u32;
+
+	fn process_message(
+		message: &[u8],
+		origin: Self::Origin,
+		meter: &mut WeightMeter,
+		_id: &mut XcmHash,
+	) -> Result<bool, ProcessMessageError> {
+		let amount = u64::decode(&mut &message[..]).map_err(|_| ProcessMessageError::BadFormat)?;
+		let weight = Weight::from_parts(amount, amount);
+		if !meter.can_consume(weight) {
+			return Err(ProcessMessageError::Overweight(weight))
+		}
+		RespectRateLimits::<AllowUnpaidExecutionFrom<Everything>, XcmRateLimiter>::should_execute::<
+			(),
+		>(
+			&Location::new(1, [Parachain(origin)]),
+			&mut [],
+			weight,
+			&mut Properties { weight_credit: Weight::zero(), message_id: None },
+		)?;
+		meter.consume(weight);
+		Ok(true)
+	}
+}
+
+impl pallet_message_queue::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type MessageProcessor = RateLimitedProcessor;
+	type Size = u32;
+	type QueueChangeHandler = ();
+	type QueuePausedQuery = ();
+	type HeapSize = ConstU32<{ 64 * 1024 }>;
+	type MaxStale = ConstU32<8>;
+	type ServiceWeight = ();
+	type IdleMaxServiceWeight = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,274 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok, parameter_types,
+	traits::{
+		ConstU32, DefensiveTruncateFrom, EnqueueMessage, Hooks, QueueFootprintQuery, ServiceQueues,
+	},
+	BoundedSlice,
+};
+use sp_runtime::DispatchError;
+use xcm::prelude::*;
+use xcm_builder::FixedWeightBounds;
+use xcm_executor::traits::{CheckRateLimit, WeightBounds};
+
+use crate::{
+	mock::*, Budget, Event, Overrides, PruneCursor, Usage, UsageOf, WeightInfo, WithRateLimitCheck,
+};
+
+fn sibling() -> Location {
+	Location::new(1, [Parachain(2000)])
+}
+
+fn weight(amount: u64) -> Weight {
+	Weight::from_parts(amount, amount)
+}
+
+fn check(origin: &Location, amount: u64) -> bool {
+	XcmRateLimiter::check_rate_limit(origin, weight(amount))
+}
+
+#[test]
+fn origins_without_budget_are_not_limited() {
+	new_test_ext().execute_with(|| {
+		let parent = Location::parent();
+		assert_eq!(XcmRateLimiter::budget(&parent), None);
+		for _ in 0..10 {
+			assert!(check(&parent, 1_000));
+		}
+		assert_eq!(XcmRateLimiter::usage(&parent), Usage { period_start: 0, ..Default::default() });
+	});
+}
+
+#[test]
+fn weight_budget_is_enforced() {
+	new_test_ext().execute_with(|| {
+		assert!(check(&sibling(), 600));
+		assert!(System::events().is_empty());
+		// The budget is not exhausted yet, so the program is let through.
+		assert!(check(&sibling(), 500));
+		System::assert_last_event(
+			Event::Throttled {
+				origin: sibling(),
+				usage: Usage { period_start: 0, weight: weight(1_100), messages: 2 },
+			}
+			.into(),
+		);
+		assert!(!check(&sibling(), 1));
+		// Throttled programs do not use the budget.
+		assert_eq!(
+			XcmRateLimiter::usage(&sibling()),
+			Usage { period_start: 0, weight: weight(1_100), messages: 2 }
+		);
+		assert_eq!(System::events().len(), 1);
+
+		// Other origins have their own budget.
+		assert!(check(&Location::new(1, [Parachain(2001)]), 1_000));
+	});
+}
+
+#[test]
+fn programs_heavier_than_the_budget_are_let_through_first() {
+	new_test_ext().execute_with(|| {
+		assert!(check(&sibling(), 5_000));
+		assert!(!check(&sibling(), 5_000));
+
+		System::set_block_number(10);
+		assert!(check(&sibling(), 5_000));
+
+		// An empty budget blocks the origin.
+		Overrides::<Test>::insert(sibling(), Budget { max_weight: weight(1_000), max_messages: 0 });
+		System::set_block_number(20);
+		assert!(!check(&sibling(), 0));
+	});
+}
+
+#[test]
+fn message_budget_is_enforced() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..3 {
+			assert!(check(&sibling(), 0));
+		}
+		assert!(!check(&sibling(), 0));
+		assert_eq!(
+			XcmRateLimiter::usage(&sibling()),
+			Usage { period_start: 0, weight: Weight::zero(), messages: 3 }
+		);
+	});
+}
+
+#[test]
+fn usage_is_reset_every_period() {
+	new_test_ext().execute_with(|| {
+		assert!(check(&sibling(), 1_000));
+		System::set_block_number(9);
+		assert!(!check(&sibling(), 1));
+
+		System::set_block_number(10);
+		assert_eq!(
+			XcmRateLimiter::usage(&sibling()),
+			Usage { period_start: 10, weight: Weight::zero(), messages: 0 }
+		);
+		assert!(check(&sibling(), 1_000));
+		assert!(!check(&sibling(), 1));
+	});
+}
+
+#[test]
+fn set_budget_overrides_default_budget() {
+	new_test_ext().execute_with(|| {
+		let budget = Budget { max_weight: weight(2_000), max_messages: 1 };
+		assert_noop!(
+			XcmRateLimiter::set_budget(
+				RuntimeOrigin::signed(1),
+				Box::new(sibling().into()),
+				Some(budget)
+			),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(XcmRateLimiter::set_budget(
+			RuntimeOrigin::root(),
+			Box::new(sibling().into()),
+			Some(budget)
+		));
+		System::assert_last_event(
+			Event::BudgetSet { origin: sibling(), budget: Some(budget) }.into(),
+		);
+		assert_eq!(Overrides::<Test>::get(sibling()), Some(budget));
+		assert!(check(&sibling(), 2_000));
+		assert!(!check(&sibling(), 0));
+
+		// Removing the override restores the default budget.
+		assert_ok!(XcmRateLimiter::set_budget(
+			RuntimeOrigin::root(),
+			Box::new(sibling().into()),
+			None
+		));
+		assert_eq!(XcmRateLimiter::budget(&sibling()), Some(DefaultBudget::get()));
+	});
+}
+
+#[test]
+fn set_budget_limits_origins_without_default_budget() {
+	new_test_ext().execute_with(|| {
+		let parent = Location::parent();
+		let budget = Budget { max_weight: weight(100), max_messages: 10 };
+		assert_ok!(XcmRateLimiter::set_budget(
+			RuntimeOrigin::root(),
+			Box::new(parent.clone().into()),
+			Some(budget)
+		));
+		assert!(check(&parent, 100));
+		assert!(!check(&parent, 1));
+	});
+}
+
+#[test]
+fn throttled_event_survives_the_message_queue() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..3 {
+			MessageQueue::enqueue_message(
+				BoundedSlice::defensive_truncate_from(&600u64.encode()[..]),
+				2000,
+			);
+		}
+		MessageQueue::service_queues(Weight::MAX);
+
+		// The second message exhausts the budget and the third one stays in the queue.
+		let throttled = RuntimeEvent::XcmRateLimiter(Event::Throttled {
+			origin: sibling(),
+			usage: Usage { period_start: 0, weight: weight(1_200), messages: 2 },
+		});
+		assert!(System::events().iter().any(|record| record.event == throttled));
+		let processed = System::events()
+			.iter()
+			.filter(|record| {
+				matches!(
+					record.event,
+					RuntimeEvent::MessageQueue(pallet_message_queue::Event::Processed { .. })
+				)
+			})
+			.count();
+		assert_eq!(processed, 2);
+		assert_eq!(MessageQueue::footprint(2000).storage.count, 1);
+
+		// The throttled message is processed in the next period.
+		System::set_block_number(10);
+		MessageQueue::service_queues(Weight::MAX);
+		assert_eq!(MessageQueue::footprint(2000).storage.count, 0);
+	});
+}
+
+#[test]
+fn usage_of_past_periods_is_pruned() {
+	new_test_ext().execute_with(|| {
+		let siblings: Vec<_> = (2000..2003).map(|id| Location::new(1, [Parachain(id)])).collect();
+		for origin in &siblings {
+			assert!(check(origin, 100));
+		}
+		System::set_block_number(10);
+		assert!(check(&siblings[0], 100));
+
+		XcmRateLimiter::on_idle(10, Weight::MAX);
+		assert_eq!(UsageOf::<Test>::iter_keys().collect::<Vec<_>>(), vec![siblings[0].clone()]);
+		assert_eq!(PruneCursor::<Test>::get(), None);
+		assert_eq!(XcmRateLimiter::usage(&siblings[0]).messages, 1);
+	});
+}
+
+#[test]
+fn pruning_resumes_where_it_stopped() {
+	new_test_ext().execute_with(|| {
+		for id in 2000..2003 {
+			assert!(check(&Location::new(1, [Parachain(id)]), 100));
+		}
+		System::set_block_number(10);
+
+		// Only one entry is visited per call.
+		let limit = <() as WeightInfo>::prune_usage();
+		for remaining in (0..3).rev() {
+			assert_eq!(XcmRateLimiter::on_idle(10, limit), limit);
+			assert_eq!(UsageOf::<Test>::iter().count(), remaining);
+		}
+		assert!(PruneCursor::<Test>::get().is_some());
+
+		// The cursor is reset once all entries were visited.
+		XcmRateLimiter::on_idle(10, limit);
+		assert_eq!(PruneCursor::<Test>::get(), None);
+	});
+}
+
+#[test]
+fn rate_limit_check_is_charged_to_the_program() {
+	parameter_types! {
+		pub const UnitWeightCost: Weight = Weight::from_parts(10, 10);
+	}
+	type Weigher = FixedWeightBounds<UnitWeightCost, (), ConstU32<100>>;
+
+	let mut message = Xcm::<()>(vec![ClearOrigin, ClearOrigin]);
+	assert_eq!(
+		WithRateLimitCheck::<Test, Weigher>::weight(&mut message),
+		Ok(Weight::from_parts(20, 20).saturating_add(<() as WeightInfo>::check_rate_limit())),
+	);
+	// Instructions weighed on their own, e.g. by error handlers, are not charged again.
+	assert_eq!(
+		WithRateLimitCheck::<Test, Weigher>::instr_weight(&mut ClearOrigin),
+		Ok(Weight::from_parts(10, 10)),
+	);
+}
@@ -0,0 +1,98 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_xcm_rate_limiter`.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+#![allow(dead_code)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for `pallet_xcm_rate_limiter`.
+pub trait WeightInfo {
+	fn set_budget() -> Weight;
+	fn check_rate_limit() -> Weight;
+	fn prune_usage() -> Weight;
+}
+
+/// Weights for `pallet_xcm_rate_limiter` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `XcmRateLimiter::Overrides` (r:0 w:1)
+	/// Proof: `XcmRateLimiter::Overrides` (`max_values`: None, `max_size`: Some(640), added: 3115, mode: `MaxEncodedLen`)
+	fn set_budget() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `0`
+		Weight::from_parts(11_811_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmRateLimiter::Overrides` (r:1 w:0)
+	/// Proof: `XcmRateLimiter::Overrides` (`max_values`: None, `max_size`: Some(640), added: 3115, mode: `MaxEncodedLen`)
+	/// Storage: `XcmRateLimiter::UsageOf` (r:1 w:1)
+	/// Proof: `XcmRateLimiter::UsageOf` (`max_values`: None, `max_size`: Some(644), added: 3119, mode: `MaxEncodedLen`)
+	fn check_rate_limit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `4109`
+		Weight::from_parts(17_455_000, 4109)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmRateLimiter::UsageOf` (r:1 w:1)
+	/// Proof: `XcmRateLimiter::UsageOf` (`max_values`: None, `max_size`: Some(644), added: 3119, mode: `MaxEncodedLen`)
+	fn prune_usage() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `4109`
+		Weight::from_parts(12_769_000, 4109)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `XcmRateLimiter::Overrides` (r:0 w:1)
+	/// Proof: `XcmRateLimiter::Overrides` (`max_values`: None, `max_size`: Some(640), added: 3115, mode: `MaxEncodedLen`)
+	fn set_budget() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `0`
+		Weight::from_parts(11_811_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmRateLimiter::Overrides` (r:1 w:0)
+	/// Proof: `XcmRateLimiter::Overrides` (`max_values`: None, `max_size`: Some(640), added: 3115, mode: `MaxEncodedLen`)
+	/// Storage: `XcmRateLimiter::UsageOf` (r:1 w:1)
+	/// Proof: `XcmRateLimiter::UsageOf` (`max_values`: None, `max_size`: Some(644), added: 3119, mode: `MaxEncodedLen`)
+	fn check_rate_limit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `4109`
+		Weight::from_parts(17_455_000, 4109)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmRateLimiter::UsageOf` (r:1 w:1)
+	/// Proof: `XcmRateLimiter::UsageOf` (`max_values`: None, `max_size`: Some(644), added: 3119, mode: `MaxEncodedLen`)
+	fn prune_usage() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `4109`
+		Weight::from_parts(12_769_000, 4109)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
@@ -0,0 +1,75 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_benchmarking::v2::*;
+
+/// Pallet we're benchmarking here.
+pub struct Pallet<T: Config>(crate::Pallet<T>);
+
+/// Trait that must be implemented by runtime to be able to benchmark pallet properly.
+pub trait Config: crate::Config {
+	/// An origin matched by `RefundableOrigins`, and assets trapped by it whose refund program
+	/// can be built, i.e. which can be transferred back and pay for the fees of their refund.
+	fn refundable_assets() -> (Location, Assets);
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn on_idle_base() {
+		let now = frame_system::Pallet::<T>::block_number();
+
+		#[block]
+		{
+			crate::Pallet::<T>::process_refunds(now, Weight::MAX);
+		}
+	}
+
+	#[benchmark]
+	fn schedule_refund() {
+		let (origin, assets) = T::refundable_assets();
+
+		#[block]
+		{
+			crate::Pallet::<T>::schedule_refund(&origin, assets.into());
+		}
+
+		assert_eq!(crate::Pallet::<T>::pending_refunds(), 1);
+	}
+
+	#[benchmark]
+	fn refund() -> Result<(), BenchmarkError> {
+		let (origin, assets) = T::refundable_assets();
+		crate::Pallet::<T>::schedule_refund(&origin, assets.into());
+		let message;
+
+		#[block]
+		{
+			let pending = PendingRefunds::<T>::take(0).ok_or(BenchmarkError::Weightless)?;
+			message = crate::Pallet::<T>::refund_message(&pending);
+			Refunding::<T>::put(true);
+			Refunding::<T>::kill();
+		}
+
+		assert!(message.is_ok());
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
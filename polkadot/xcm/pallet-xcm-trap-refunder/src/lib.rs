@@ -0,0 +1,449 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet automatically returning assets trapped by XCM programs to their origin.
+//!
+//! Assets left in holding at the end of an XCM program are trapped, and can only be recovered by
+//! their origin claiming them with a `ClaimAsset` instruction. This pallet wraps the asset trap of
+//! the XCM executor, usually `pallet-xcm`, and schedules a refund for assets trapped by origins
+//! matched by [`Config::RefundableOrigins`], typically accounts of sibling chains.
+//!
+//! Only assets worth more than the fees of their refund, as quoted when they are trapped, are
+//! scheduled for a refund. Once [`Config::RefundDelay`] blocks have passed, during which the origin
+//! may still claim the assets itself, the refund is processed in `on_idle`: the assets are claimed
+//! on behalf of their origin, the local execution and delivery fees are paid from them, and the
+//! rest is transferred back to the account of the origin, by teleport or reserve transfer. What is
+//! left of the asset paying the fees pays for the execution on the chain of the origin. Assets
+//! which cannot be refunded stay trapped.
+//!
+//! To opt in, set the `AssetTrap` of the XCM executor to this pallet and [`Config::AssetTrap`] to
+//! the previous asset trap, which must also be the `AssetClaims` of the XCM executor.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+extern crate alloc;
+
+use alloc::{vec, vec::Vec};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	pallet_prelude::*,
+	storage::{with_transaction, TransactionOutcome},
+	traits::{Contains, Get},
+	weights::WeightMeter,
+};
+use frame_system::pallet_prelude::*;
+use scale_info::TypeInfo;
+use sp_runtime::traits::Saturating;
+use xcm::{
+	latest::{AssetTransferFilter, MAX_ITEMS_IN_ASSETS},
+	prelude::*,
+};
+use xcm_executor::{
+	traits::{DropAssets, TransferType, WeightBounds, WeightTrader, XcmAssetTransfers},
+	AssetsInHolding,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+const LOG_TARGET: &str = "xcm::trap-refunder";
+
+/// The index of a refund in the queue.
+pub type RefundIndex = u32;
+
+/// A refund waiting to be processed.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq, TypeInfo, MaxEncodedLen)]
+pub struct PendingRefund<BlockNumber> {
+	/// The origin which trapped the assets.
+	pub origin: Location,
+	/// The trapped assets, in the version they were trapped with.
+	///
+	/// At most [`MAX_ITEMS_IN_ASSETS`] of them, the most `VersionedAssets` decodes, which bounds
+	/// its encoded length.
+	pub assets: VersionedAssets,
+	/// The block from which the refund may be processed.
+	pub due: BlockNumber,
+}
+
+/// Matches accounts of sibling parachains.
+pub struct SiblingAccounts;
+impl Contains<Location> for SiblingAccounts {
+	fn contains(location: &Location) -> bool {
+		matches!(location.unpack(), (1, [Parachain(_), AccountId32 { .. } | AccountKey20 { .. }]))
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The asset trap wrapped by this pallet, which must also be the `AssetClaims` of the XCM
+		/// executor.
+		type AssetTrap: DropAssets;
+
+		/// Something to execute the refunds.
+		type XcmExecutor: ExecuteXcm<<Self as frame_system::Config>::RuntimeCall>
+			+ XcmAssetTransfers;
+
+		/// Means of measuring the weight consumed by a refund.
+		type Weigher: WeightBounds<<Self as frame_system::Config>::RuntimeCall>;
+
+		/// The trader of the XCM executor, used to quote the execution fee of a refund.
+		type Trader: WeightTrader;
+
+		/// The router of the XCM executor, used to quote the delivery fee of a refund.
+		type XcmRouter: SendXcm;
+
+		/// This chain's Universal Location.
+		type UniversalLocation: Get<InteriorLocation>;
+
+		/// The origins whose trapped assets are refunded. The last junction of these origins must
+		/// be an account, and the rest the location of its chain.
+		type RefundableOrigins: Contains<Location>;
+
+		/// The number of blocks after which trapped assets are refunded.
+		#[pallet::constant]
+		type RefundDelay: Get<BlockNumberFor<Self>>;
+
+		/// The maximum number of refunds waiting to be processed. Assets trapped while the queue is
+		/// full are not refunded.
+		#[pallet::constant]
+		type MaxPendingRefunds: Get<u32>;
+
+		/// The maximum number of refunds processed in a block.
+		#[pallet::constant]
+		type MaxRefundsPerBlock: Get<u32>;
+
+		/// Weight information for the operations of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A refund of trapped assets was scheduled.
+		RefundScheduled {
+			index: RefundIndex,
+			origin: Location,
+			assets: VersionedAssets,
+			due: BlockNumberFor<T>,
+		},
+		/// Trapped assets were returned to their origin, less the fees.
+		Refunded {
+			index: RefundIndex,
+			origin: Location,
+			assets: VersionedAssets,
+			weight_used: Weight,
+		},
+		/// Trapped assets could not be returned to their origin, and stay trapped.
+		RefundFailed {
+			index: RefundIndex,
+			origin: Location,
+			assets: VersionedAssets,
+			error: XcmError,
+		},
+	}
+
+	/// The refunds waiting to be processed, by index.
+	#[pallet::storage]
+	pub type PendingRefunds<T: Config> =
+		StorageMap<_, Twox64Concat, RefundIndex, PendingRefund<BlockNumberFor<T>>, OptionQuery>;
+
+	/// The index of the next refund to be processed.
+	#[pallet::storage]
+	pub type RefundQueueHead<T: Config> = StorageValue<_, RefundIndex, ValueQuery>;
+
+	/// The index of the next refund to be scheduled.
+	#[pallet::storage]
+	pub type NextRefundIndex<T: Config> = StorageValue<_, RefundIndex, ValueQuery>;
+
+	/// Whether a refund is being executed, in which case the assets it traps are not refunded
+	/// again.
+	#[pallet::storage]
+	pub(super) type Refunding<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(now: BlockNumberFor<T>, limit: Weight) -> Weight {
+			Self::process_refunds(now, limit)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The number of refunds waiting to be processed.
+	pub fn pending_refunds() -> u32 {
+		NextRefundIndex::<T>::get().wrapping_sub(RefundQueueHead::<T>::get())
+	}
+
+	/// Schedule the refund of `assets` to `origin`, unless the queue is full.
+	///
+	/// Assets which would not cover the fees of their refund at the current prices are not
+	/// refunded, so that dust cannot fill the queue.
+	fn schedule_refund(origin: &Location, assets: VersionedAssets) -> Weight {
+		if Self::pending_refunds() >= T::MaxPendingRefunds::get() {
+			tracing::debug!(target: LOG_TARGET, ?origin, ?assets, "Refund queue is full");
+			return T::DbWeight::get().reads(2)
+		}
+		let due = frame_system::Pallet::<T>::block_number().saturating_add(T::RefundDelay::get());
+		let refund = PendingRefund { origin: origin.clone(), assets: assets.clone(), due };
+		if let Err(error) = Self::refund_message(&refund) {
+			tracing::debug!(target: LOG_TARGET, ?origin, ?assets, ?error, "Assets cannot be refunded");
+			return T::WeightInfo::schedule_refund()
+		}
+		let index = NextRefundIndex::<T>::get();
+		PendingRefunds::<T>::insert(index, refund);
+		NextRefundIndex::<T>::put(index.wrapping_add(1));
+		Self::deposit_event(Event::RefundScheduled { index, origin: origin.clone(), assets, due });
+		T::WeightInfo::schedule_refund()
+	}
+
+	/// Process the refunds due at `now`, in the order they were scheduled, within `limit`.
+	fn process_refunds(now: BlockNumberFor<T>, limit: Weight) -> Weight {
+		let mut meter = WeightMeter::with_limit(limit);
+		if meter.try_consume(T::WeightInfo::on_idle_base()).is_err() {
+			return Weight::zero()
+		}
+
+		let mut head = RefundQueueHead::<T>::get();
+		let tail = NextRefundIndex::<T>::get();
+		let mut processed = 0;
+		while head != tail && processed < T::MaxRefundsPerBlock::get() {
+			let Some(refund) = PendingRefunds::<T>::get(head) else {
+				head = head.wrapping_add(1);
+				processed += 1;
+				continue
+			};
+			if refund.due > now {
+				break
+			}
+			let message = Self::refund_message(&refund);
+			let weight = match &message {
+				Ok((_, weight)) => T::WeightInfo::refund().saturating_add(*weight),
+				Err(_) => T::WeightInfo::refund(),
+			};
+			if meter.try_consume(weight).is_err() {
+				break
+			}
+
+			let index = head;
+			PendingRefunds::<T>::remove(index);
+			head = head.wrapping_add(1);
+			processed += 1;
+
+			let PendingRefund { origin, assets, .. } = refund;
+			match message
+				.and_then(|(message, weight)| Self::execute_refund(&origin, message, weight))
+			{
+				Ok(weight_used) =>
+					Self::deposit_event(Event::Refunded { index, origin, assets, weight_used }),
+				Err(error) => {
+					tracing::debug!(target: LOG_TARGET, ?index, ?origin, ?assets, ?error, "Refund failed");
+					Self::deposit_event(Event::RefundFailed { index, origin, assets, error })
+				},
+			}
+		}
+		RefundQueueHead::<T>::put(head);
+		meter.consumed()
+	}
+
+	/// The program refunding the assets of `refund`, and its weight.
+	///
+	/// The program claims the assets, pays the local execution and delivery fees with the first
+	/// fungible asset and transfers the assets to the account of the origin. Whatever is left of
+	/// the fee asset pays for the execution of the deposit on the destination.
+	///
+	/// The local fees are quoted beforehand with [`Config::Trader`] and [`Config::XcmRouter`], so
+	/// that the program only sets aside what it spends locally.
+	fn refund_message(
+		refund: &PendingRefund<BlockNumberFor<T>>,
+	) -> Result<(Xcm<<T as frame_system::Config>::RuntimeCall>, Weight), XcmError> {
+		let version = refund.assets.identify_version();
+		let assets =
+			Assets::try_from(refund.assets.clone()).map_err(|()| XcmError::UnhandledXcmVersion)?;
+		let (dest, beneficiary) = match refund.origin.clone().split_last_interior() {
+			(dest, Some(account)) => (dest, Location::new(0, [account])),
+			(_, None) => return Err(XcmError::InvalidLocation),
+		};
+		let fees = assets
+			.inner()
+			.iter()
+			.find(|asset| matches!(asset.fun, Fungible(_)))
+			.cloned()
+			.ok_or(XcmError::FeesNotMet)?;
+		let Fungible(total_fees) = fees.fun else { return Err(XcmError::FeesNotMet) };
+
+		// All assets must be transferred the same way.
+		let mut transfer_type = None;
+		for asset in assets.inner() {
+			let asset_transfer_type =
+				T::XcmExecutor::determine_for(asset, &dest).map_err(|_| XcmError::Unroutable)?;
+			if *transfer_type.get_or_insert_with(|| asset_transfer_type.clone()) !=
+				asset_transfer_type
+			{
+				return Err(XcmError::Unroutable)
+			}
+		}
+		let (transfer_filter, remote_transfer): (
+			fn(AssetFilter) -> AssetTransferFilter,
+			fn(Assets) -> Instruction<()>,
+		) = match transfer_type {
+			Some(TransferType::Teleport) => (AssetTransferFilter::Teleport, ReceiveTeleportedAsset),
+			Some(TransferType::LocalReserve) =>
+				(AssetTransferFilter::ReserveDeposit, ReserveAssetDeposited),
+			Some(TransferType::DestinationReserve) =>
+				(AssetTransferFilter::ReserveWithdraw, WithdrawAsset),
+			Some(TransferType::RemoteReserve(_)) | None => return Err(XcmError::Unroutable),
+		};
+
+		let count = assets.len() as u32;
+		// Fees left in the fees register are deposited as well.
+		let remote_xcm =
+			Xcm(vec![RefundSurplus, DepositAsset { assets: Wild(AllCounted(count)), beneficiary }]);
+		let others: Vec<Asset> =
+			assets.inner().iter().filter(|asset| **asset != fees).cloned().collect();
+
+		// The program sent to the destination, as built by `InitiateTransfer`. The whole fee
+		// asset is quoted, which encodes to at least the size of what is left of it.
+		let universal_location = T::UniversalLocation::get();
+		let remote_fees = fees
+			.clone()
+			.reanchored(&dest, &universal_location)
+			.map_err(|()| XcmError::ReanchorFailed)?;
+		let mut sent =
+			vec![remote_transfer(remote_fees.clone().into()), PayFees { asset: remote_fees }];
+		if !others.is_empty() {
+			let others = Assets::from(others.clone())
+				.reanchored(&dest, &universal_location)
+				.map_err(|()| XcmError::ReanchorFailed)?;
+			sent.push(remote_transfer(others));
+		}
+		sent.push(ClearOrigin);
+		sent.extend(remote_xcm.0.iter().cloned());
+		let delivery_fee = Self::quote_delivery_fee(dest.clone(), Xcm(sent), &fees.id)?;
+
+		let transfers =
+			if others.is_empty() { vec![] } else { vec![transfer_filter(Wild(AllCounted(count)))] };
+		let mut message = Xcm(vec![
+			ClaimAsset { assets, ticket: GeneralIndex(version as u128).into() },
+			PayFees { asset: fees.clone() },
+			InitiateTransfer {
+				destination: dest,
+				remote_fees: Some(transfer_filter(Wild(AllOf {
+					id: fees.id.clone(),
+					fun: WildFungible,
+				}))),
+				preserve_origin: false,
+				assets: BoundedVec::truncate_from(transfers),
+				remote_xcm,
+			},
+		]);
+		let weight =
+			T::Weigher::weight(&mut message).map_err(|()| XcmError::WeightNotComputable)?;
+		let execution_fee = Self::quote_execution_fee(&refund.origin, weight, &fees)?;
+		let local_fees = execution_fee.saturating_add(delivery_fee);
+		if local_fees >= total_fees {
+			return Err(XcmError::TooExpensive)
+		}
+		if let Some(PayFees { asset }) = message.0.get_mut(1) {
+			asset.fun = Fungible(local_fees);
+		}
+		Ok((message, weight))
+	}
+
+	/// The fee for executing `weight` on behalf of `origin`, paid in `fees`.
+	///
+	/// The weight is bought from [`Config::Trader`] in a storage transaction which is rolled back.
+	fn quote_execution_fee(
+		origin: &Location,
+		weight: Weight,
+		fees: &Asset,
+	) -> Result<u128, XcmError> {
+		let Fungible(amount) = fees.fun else { return Err(XcmError::FeesNotMet) };
+		let context = XcmContext { origin: Some(origin.clone()), message_id: [0; 32], topic: None };
+		let unspent = with_transaction(|| -> TransactionOutcome<Result<_, DispatchError>> {
+			let unspent = T::Trader::new().buy_weight(weight, fees.clone().into(), &context);
+			TransactionOutcome::Rollback(Ok(unspent))
+		})
+		.map_err(|_| XcmError::FeesNotMet)??;
+		let unspent = unspent.fungible.get(&fees.id).copied().unwrap_or_default();
+		Ok(amount.saturating_sub(unspent))
+	}
+
+	/// The fee for delivering `message` to `dest`, which must be paid in `fee_asset`.
+	fn quote_delivery_fee(
+		dest: Location,
+		message: Xcm<()>,
+		fee_asset: &AssetId,
+	) -> Result<u128, XcmError> {
+		let (_, fees) = validate_send::<T::XcmRouter>(dest, message)?;
+		fees.into_inner().into_iter().try_fold(0u128, |total, fee| match fee {
+			Asset { id, fun: Fungible(amount) } if id == *fee_asset =>
+				Ok(total.saturating_add(amount)),
+			_ => Err(XcmError::FeesNotMet),
+		})
+	}
+
+	/// Execute a refund program on behalf of `origin`, returning the weight it used.
+	fn execute_refund(
+		origin: &Location,
+		message: Xcm<<T as frame_system::Config>::RuntimeCall>,
+		weight: Weight,
+	) -> Result<Weight, XcmError> {
+		let mut hash = message.using_encoded(sp_io::hashing::blake2_256);
+		Refunding::<T>::put(true);
+		let outcome =
+			T::XcmExecutor::prepare_and_execute(origin.clone(), message, &mut hash, weight, weight);
+		Refunding::<T>::kill();
+		let weight_used = outcome.weight_used();
+		outcome.ensure_complete().map(|()| weight_used)
+	}
+}
+
+impl<T: Config> DropAssets for Pallet<T> {
+	fn drop_assets(origin: &Location, assets: AssetsInHolding, context: &XcmContext) -> Weight {
+		let refundable = !assets.is_empty() &&
+			assets.len() <= MAX_ITEMS_IN_ASSETS &&
+			assets.fungible_assets_iter().next().is_some() &&
+			T::RefundableOrigins::contains(origin) &&
+			!Refunding::<T>::get();
+		// Trapped assets are hashed in their latest version, as done by `pallet-xcm`.
+		let versioned = refundable.then(|| VersionedAssets::from(Assets::from(assets.clone())));
+		let weight = T::AssetTrap::drop_assets(origin, assets, context)
+			.saturating_add(T::DbWeight::get().reads(1));
+		match versioned {
+			Some(versioned) => weight.saturating_add(Self::schedule_refund(origin, versioned)),
+			None => weight,
+		}
+	}
+}
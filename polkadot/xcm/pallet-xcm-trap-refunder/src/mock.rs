@@ -0,0 +1,241 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use codec::Encode;
+use core::cell::RefCell;
+use frame_support::{
+	construct_runtime, derive_impl, parameter_types,
+	traits::{ConstU32, Everything, Nothing},
+};
+use sp_runtime::{traits::IdentityLookup, AccountId32, BuildStorage};
+use xcm::prelude::*;
+use xcm_builder::{
+	AllowTopLevelPaidExecutionFrom, DescribeAllTerminal, DescribeFamily, FixedRateOfFungible,
+	FixedWeightBounds, FrameTransactionalProcessor, FungibleAdapter, HashedDescription, IsConcrete,
+	TakeWeightCredit,
+};
+use xcm_executor::{
+	traits::{ClaimAssets, ConvertLocation, DropAssets},
+	AssetsInHolding, XcmExecutor,
+};
+
+use crate::{self as pallet_xcm_trap_refunder, SiblingAccounts};
+
+pub type AccountId = AccountId32;
+pub type Balance = u128;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		Balances: pallet_balances,
+		XcmTrapRefunder: pallet_xcm_trap_refunder,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<AccountId>;
+	type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type Balance = Balance;
+	type AccountStore = System;
+}
+
+thread_local! {
+	pub static TRAPPED_ASSETS: RefCell<Vec<(Location, VersionedAssets)>> = RefCell::new(Vec::new());
+	pub static SENT_XCM: RefCell<Vec<(Location, Xcm<()>)>> = RefCell::new(Vec::new());
+}
+
+/// The assets trapped by `origin`, in their latest version.
+pub fn trapped_assets(origin: &Location) -> Vec<VersionedAssets> {
+	TRAPPED_ASSETS.with(|t| {
+		t.borrow()
+			.iter()
+			.filter(|(o, _)| o == origin)
+			.map(|(_, assets)| assets.clone())
+			.collect()
+	})
+}
+
+pub fn sent_xcm() -> Vec<(Location, Xcm<()>)> {
+	SENT_XCM.with(|q| q.borrow().clone())
+}
+
+/// Traps and claims assets like `pallet-xcm`, without the hashing.
+pub struct TestAssetTrap;
+impl DropAssets for TestAssetTrap {
+	fn drop_assets(origin: &Location, assets: AssetsInHolding, _context: &XcmContext) -> Weight {
+		let versioned = VersionedAssets::from(Assets::from(assets));
+		TRAPPED_ASSETS.with(|t| t.borrow_mut().push((origin.clone(), versioned)));
+		Weight::zero()
+	}
+}
+
+impl ClaimAssets for TestAssetTrap {
+	fn claim_assets(
+		origin: &Location,
+		ticket: &Location,
+		assets: &Assets,
+		_context: &XcmContext,
+	) -> bool {
+		let (0, [GeneralIndex(version)]) = ticket.unpack() else { return false };
+		let Ok(versioned) = VersionedAssets::from(assets.clone()).into_version(*version as u32)
+		else {
+			return false
+		};
+		TRAPPED_ASSETS.with(|t| {
+			let mut trapped = t.borrow_mut();
+			let key = (origin.clone(), versioned);
+			match trapped.iter().position(|entry| *entry == key) {
+				Some(index) => {
+					trapped.remove(index);
+					true
+				},
+				None => false,
+			}
+		})
+	}
+}
+
+/// Sender recording the messages, for a fixed delivery fee.
+pub struct TestSendXcm;
+impl SendXcm for TestSendXcm {
+	type Ticket = (Location, Xcm<()>);
+	fn validate(
+		dest: &mut Option<Location>,
+		msg: &mut Option<Xcm<()>>,
+	) -> SendResult<(Location, Xcm<()>)> {
+		let pair = (dest.take().unwrap(), msg.take().unwrap());
+		Ok((pair, (Here, DELIVERY_FEE).into()))
+	}
+	fn deliver(pair: (Location, Xcm<()>)) -> Result<XcmHash, SendError> {
+		let hash = pair.1.using_encoded(sp_io::hashing::blake2_256);
+		SENT_XCM.with(|q| q.borrow_mut().push(pair));
+		Ok(hash)
+	}
+}
+
+pub const PARA_ID: u32 = 1000;
+pub const SIBLING_PARA_ID: u32 = 2000;
+pub const DELIVERY_FEE: Balance = 10;
+/// The fee for executing a refund program of three instructions.
+pub const EXECUTION_FEE: Balance = 30;
+
+parameter_types! {
+	pub UniversalLocation: InteriorLocation =
+		[GlobalConsensus(ByGenesis([0; 32])), Parachain(PARA_ID)].into();
+	pub const BaseXcmWeight: Weight = Weight::from_parts(1_000_000_000, 1024);
+	pub NativePerSecondPerByte: (AssetId, u128, u128) = (AssetId(Here.into()), 10_000, 0);
+	pub const MaxInstructions: u32 = 100;
+	pub const MaxAssetsIntoHolding: u32 = 64;
+}
+
+pub type LocationToAccountId = HashedDescription<AccountId, DescribeFamily<DescribeAllTerminal>>;
+
+pub type AssetTransactor =
+	FungibleAdapter<Balances, IsConcrete<HereLocation>, LocationToAccountId, AccountId, ()>;
+
+parameter_types! {
+	pub HereLocation: Location = Here.into();
+}
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+	type RuntimeCall = RuntimeCall;
+	type XcmSender = TestSendXcm;
+	type XcmEventEmitter = ();
+	type AssetTransactor = AssetTransactor;
+	type OriginConverter = ();
+	type IsReserve = ();
+	type IsTeleporter = ();
+	type UniversalLocation = UniversalLocation;
+	type Barrier = (TakeWeightCredit, AllowTopLevelPaidExecutionFrom<Everything>);
+	type Weigher = FixedWeightBounds<BaseXcmWeight, RuntimeCall, MaxInstructions>;
+	type Trader = FixedRateOfFungible<NativePerSecondPerByte, ()>;
+	type ResponseHandler = ();
+	type AssetTrap = XcmTrapRefunder;
+	type AssetLocker = ();
+	type AssetExchanger = ();
+	type AssetClaims = TestAssetTrap;
+	type SubscriptionService = ();
+	type PalletInstancesInfo = ();
+	type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+	type FeeManager = ();
+	type MessageExporter = ();
+	type UniversalAliases = Nothing;
+	type CallDispatcher = RuntimeCall;
+	type SafeCallFilter = Everything;
+	type Aliasers = Nothing;
+	type TransactionalProcessor = FrameTransactionalProcessor;
+	type HrmpNewChannelOpenRequestHandler = ();
+	type HrmpChannelAcceptedHandler = ();
+	type HrmpChannelClosingHandler = ();
+	type XcmRecorder = ();
+	type XcmTracer = ();
+}
+
+parameter_types! {
+	pub const RefundDelay: u64 = 5;
+}
+
+impl crate::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type AssetTrap = TestAssetTrap;
+	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type Weigher = FixedWeightBounds<BaseXcmWeight, RuntimeCall, MaxInstructions>;
+	type Trader = FixedRateOfFungible<NativePerSecondPerByte, ()>;
+	type XcmRouter = TestSendXcm;
+	type UniversalLocation = UniversalLocation;
+	type RefundableOrigins = SiblingAccounts;
+	type RefundDelay = RefundDelay;
+	type MaxPendingRefunds = ConstU32<3>;
+	type MaxRefundsPerBlock = ConstU32<2>;
+	type WeightInfo = ();
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::benchmarking::Config for Test {
+	fn refundable_assets() -> (Location, Assets) {
+		(sibling_account(1), (Here, 1_000u128).into())
+	}
+}
+
+/// An account on the sibling parachain.
+pub fn sibling_account(id: u8) -> Location {
+	Location::new(
+		1,
+		[Parachain(SIBLING_PARA_ID), Junction::AccountId32 { network: None, id: [id; 32] }],
+	)
+}
+
+pub fn sibling_sovereign_account() -> AccountId {
+	LocationToAccountId::convert_location(&Location::new(1, [Parachain(SIBLING_PARA_ID)])).unwrap()
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	TRAPPED_ASSETS.with(|t| t.borrow_mut().clear());
+	SENT_XCM.with(|q| q.borrow_mut().clear());
+	let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
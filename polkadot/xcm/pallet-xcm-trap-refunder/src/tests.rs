@@ -0,0 +1,201 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use frame_support::traits::Hooks;
+use xcm::{latest::MAX_ITEMS_IN_ASSETS, prelude::*};
+use xcm_executor::{traits::DropAssets, AssetsInHolding};
+
+use crate::{mock::*, Event, PendingRefunds};
+
+fn context() -> XcmContext {
+	XcmContext { origin: None, message_id: [0; 32], topic: None }
+}
+
+fn trap(origin: &Location, asset: impl Into<Asset>) {
+	XcmTrapRefunder::drop_assets(origin, AssetsInHolding::from(asset.into()), &context());
+}
+
+fn refunder_events() -> Vec<Event<Test>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::XcmTrapRefunder(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
+
+fn versioned(asset: impl Into<Asset>) -> VersionedAssets {
+	VersionedAssets::from(Assets::from(asset.into()))
+}
+
+#[test]
+fn assets_trapped_by_sibling_accounts_are_refunded() {
+	new_test_ext().execute_with(|| {
+		let origin = sibling_account(1);
+		trap(&origin, (Here, 1_000u128));
+		assert_eq!(trapped_assets(&origin), vec![versioned((Here, 1_000u128))]);
+		assert_eq!(
+			refunder_events(),
+			vec![Event::RefundScheduled {
+				index: 0,
+				origin: origin.clone(),
+				assets: versioned((Here, 1_000u128)),
+				due: 6,
+			}]
+		);
+
+		// Nothing happens before the delay.
+		XcmTrapRefunder::on_idle(5, Weight::MAX);
+		assert_eq!(XcmTrapRefunder::pending_refunds(), 1);
+		assert!(sent_xcm().is_empty());
+
+		XcmTrapRefunder::on_idle(6, Weight::MAX);
+		assert_eq!(XcmTrapRefunder::pending_refunds(), 0);
+		assert!(PendingRefunds::<Test>::get(0).is_none());
+		assert!(trapped_assets(&origin).is_empty());
+		assert!(matches!(
+			refunder_events().last(),
+			Some(Event::Refunded { index: 0, origin: o, .. }) if o == &origin
+		));
+
+		// The execution and delivery fees are paid from the refunded assets, and the rest pays for
+		// the execution of the deposit.
+		let refunded = 1_000 - EXECUTION_FEE - DELIVERY_FEE;
+		assert_eq!(Balances::free_balance(sibling_sovereign_account()), refunded);
+		let this_chain = Location::new(1, [Parachain(PARA_ID)]);
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				Location::new(1, [Parachain(SIBLING_PARA_ID)]),
+				Xcm(vec![
+					ReserveAssetDeposited((this_chain.clone(), refunded).into()),
+					PayFees { asset: (this_chain, refunded).into() },
+					ClearOrigin,
+					RefundSurplus,
+					DepositAsset {
+						assets: Wild(AllCounted(1)),
+						beneficiary: AccountId32 { network: None, id: [1; 32] }.into(),
+					},
+				]),
+			)]
+		);
+	});
+}
+
+#[test]
+fn assets_trapped_by_other_origins_are_not_refunded() {
+	new_test_ext().execute_with(|| {
+		let origins = [
+			Location::parent(),
+			Location::new(1, [Parachain(SIBLING_PARA_ID)]),
+			Location::new(0, [AccountId32 { network: None, id: [1; 32] }]),
+		];
+		for origin in &origins {
+			trap(origin, (Here, 1_000u128));
+			assert_eq!(trapped_assets(origin).len(), 1);
+		}
+		// Non-fungible assets cannot pay for their refund.
+		trap(&sibling_account(1), Asset { id: AssetId(Here.into()), fun: NonFungible(Index(1)) });
+		assert_eq!(trapped_assets(&sibling_account(1)).len(), 1);
+
+		assert_eq!(XcmTrapRefunder::pending_refunds(), 0);
+		assert!(refunder_events().is_empty());
+	});
+}
+
+#[test]
+fn too_many_trapped_assets_are_not_refunded() {
+	new_test_ext().execute_with(|| {
+		let origin = sibling_account(1);
+		let mut assets = AssetsInHolding::new();
+		for index in 0..=MAX_ITEMS_IN_ASSETS as u128 {
+			assets.subsume((GeneralIndex(index), 1_000u128).into());
+		}
+		XcmTrapRefunder::drop_assets(&origin, assets, &context());
+
+		assert_eq!(trapped_assets(&origin).len(), 1);
+		assert_eq!(XcmTrapRefunder::pending_refunds(), 0);
+		assert!(refunder_events().is_empty());
+	});
+}
+
+#[test]
+fn refunds_are_bounded() {
+	new_test_ext().execute_with(|| {
+		for id in 1..=4 {
+			trap(&sibling_account(id), (Here, 1_000u128));
+		}
+		// The queue holds at most 3 refunds.
+		assert_eq!(XcmTrapRefunder::pending_refunds(), 3);
+		assert_eq!(trapped_assets(&sibling_account(4)).len(), 1);
+
+		// Not enough weight for a refund.
+		let weight = XcmTrapRefunder::on_idle(6, Weight::from_parts(20_000_000, 0));
+		assert!(weight.all_lte(Weight::from_parts(20_000_000, 0)));
+		assert_eq!(XcmTrapRefunder::pending_refunds(), 3);
+
+		// At most 2 refunds per block.
+		XcmTrapRefunder::on_idle(6, Weight::MAX);
+		assert_eq!(XcmTrapRefunder::pending_refunds(), 1);
+		assert_eq!(sent_xcm().len(), 2);
+
+		XcmTrapRefunder::on_idle(7, Weight::MAX);
+		assert_eq!(XcmTrapRefunder::pending_refunds(), 0);
+		assert_eq!(sent_xcm().len(), 3);
+		assert_eq!(trapped_assets(&sibling_account(4)).len(), 1);
+	});
+}
+
+#[test]
+fn assets_claimed_by_their_origin_are_not_refunded() {
+	new_test_ext().execute_with(|| {
+		trap(&sibling_account(1), (Here, 1_000u128));
+		TRAPPED_ASSETS.with(|t| t.borrow_mut().clear());
+
+		XcmTrapRefunder::on_idle(6, Weight::MAX);
+		assert!(matches!(
+			refunder_events().last(),
+			Some(Event::RefundFailed { index: 0, error: XcmError::UnknownClaim, .. })
+		));
+		assert!(sent_xcm().is_empty());
+	});
+}
+
+#[test]
+fn assets_not_covering_their_refund_are_not_refunded() {
+	new_test_ext().execute_with(|| {
+		// Not enough to pay for the execution.
+		trap(&sibling_account(1), (Here, 20u128));
+		// No way to transfer the asset back.
+		trap(&sibling_account(2), (Parent, 1_000u128));
+		// Nothing left to pay for the execution on the destination after the delivery fee.
+		trap(&sibling_account(3), (Here, EXECUTION_FEE + DELIVERY_FEE));
+
+		// The assets stay trapped, without taking room in the queue.
+		assert_eq!(trapped_assets(&sibling_account(1)), vec![versioned((Here, 20u128))]);
+		assert_eq!(trapped_assets(&sibling_account(2)), vec![versioned((Parent, 1_000u128))]);
+		assert_eq!(
+			trapped_assets(&sibling_account(3)),
+			vec![versioned((Here, EXECUTION_FEE + DELIVERY_FEE))]
+		);
+		assert_eq!(XcmTrapRefunder::pending_refunds(), 0);
+		assert!(refunder_events().is_empty());
+
+		XcmTrapRefunder::on_idle(6, Weight::MAX);
+		assert!(sent_xcm().is_empty());
+	});
+}
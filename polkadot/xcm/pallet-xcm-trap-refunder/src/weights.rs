@@ -0,0 +1,112 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_xcm_trap_refunder`.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+#![allow(dead_code)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for `pallet_xcm_trap_refunder`.
+pub trait WeightInfo {
+	fn on_idle_base() -> Weight;
+	fn schedule_refund() -> Weight;
+	fn refund() -> Weight;
+}
+
+/// Weights for `pallet_xcm_trap_refunder` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `XcmTrapRefunder::RefundQueueHead` (r:1 w:1)
+	/// Proof: `XcmTrapRefunder::RefundQueueHead` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `XcmTrapRefunder::NextRefundIndex` (r:1 w:0)
+	/// Proof: `XcmTrapRefunder::NextRefundIndex` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn on_idle_base() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `1489`
+		Weight::from_parts(2_003_000, 1489)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmTrapRefunder::RefundQueueHead` (r:1 w:0)
+	/// Proof: `XcmTrapRefunder::RefundQueueHead` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `XcmTrapRefunder::NextRefundIndex` (r:1 w:1)
+	/// Proof: `XcmTrapRefunder::NextRefundIndex` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `XcmTrapRefunder::PendingRefunds` (r:0 w:1)
+	/// Proof: `XcmTrapRefunder::PendingRefunds` (`max_values`: None, `max_size`: Some(13359), added: 15834, mode: `MaxEncodedLen`)
+	fn schedule_refund() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `1489`
+		Weight::from_parts(46_150_000, 1489)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `XcmTrapRefunder::PendingRefunds` (r:1 w:1)
+	/// Proof: `XcmTrapRefunder::PendingRefunds` (`max_values`: None, `max_size`: Some(13359), added: 15834, mode: `MaxEncodedLen`)
+	/// Storage: `XcmTrapRefunder::Refunding` (r:0 w:2)
+	/// Proof: `XcmTrapRefunder::Refunding` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	fn refund() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `16824`
+		Weight::from_parts(48_153_000, 16824)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `XcmTrapRefunder::RefundQueueHead` (r:1 w:1)
+	/// Proof: `XcmTrapRefunder::RefundQueueHead` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `XcmTrapRefunder::NextRefundIndex` (r:1 w:0)
+	/// Proof: `XcmTrapRefunder::NextRefundIndex` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn on_idle_base() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `1489`
+		Weight::from_parts(2_003_000, 1489)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmTrapRefunder::RefundQueueHead` (r:1 w:0)
+	/// Proof: `XcmTrapRefunder::RefundQueueHead` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `XcmTrapRefunder::NextRefundIndex` (r:1 w:1)
+	/// Proof: `XcmTrapRefunder::NextRefundIndex` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `XcmTrapRefunder::PendingRefunds` (r:0 w:1)
+	/// Proof: `XcmTrapRefunder::PendingRefunds` (`max_values`: None, `max_size`: Some(13359), added: 15834, mode: `MaxEncodedLen`)
+	fn schedule_refund() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `1489`
+		Weight::from_parts(46_150_000, 1489)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `XcmTrapRefunder::PendingRefunds` (r:1 w:1)
+	/// Proof: `XcmTrapRefunder::PendingRefunds` (`max_values`: None, `max_size`: Some(13359), added: 15834, mode: `MaxEncodedLen`)
+	/// Storage: `XcmTrapRefunder::Refunding` (r:0 w:2)
+	/// Proof: `XcmTrapRefunder::Refunding` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	fn refund() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `16824`
+		Weight::from_parts(48_153_000, 16824)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+}
@@ -573,6 +573,69 @@ mod benchmarks {
 		}
 	}
 
+	#[benchmark]
+	fn expire_query() {
+		let query_id = 0;
+		let timeout: BlockNumberFor<T> = 1u32.into();
+		crate::Queries::<T>::insert(
+			query_id,
+			QueryStatus::Pending {
+				responder: Location::from(Parent).into(),
+				maybe_match_querier: Some(Location::from(Here).into()),
+				maybe_notify: Some((0, 0)),
+				timeout,
+			},
+		);
+		crate::QueryTimeouts::<T>::insert(
+			crate::QueryTimeout::new(timeout),
+			query_id,
+			None::<(u8, u8)>,
+		);
+		let now = timeout + 1u32.into();
+
+		#[block]
+		{
+			crate::Pallet::<T>::expire_queries(now, Weight::MAX);
+		}
+
+		assert!(crate::Queries::<T>::get(query_id).is_none());
+	}
+
+	#[benchmark]
+	fn expire_query_with_timeout_notify() {
+		let query_id = 0;
+		let timeout: BlockNumberFor<T> = 1u32.into();
+		crate::Queries::<T>::insert(
+			query_id,
+			QueryStatus::Pending {
+				responder: Location::from(Parent).into(),
+				maybe_match_querier: Some(Location::from(Here).into()),
+				maybe_notify: Some((0, 0)),
+				timeout,
+			},
+		);
+		// The weight of the timeout notification itself is accounted for separately, so notify a
+		// call which every runtime has and which fails early with the `Response` origin.
+		let timeout_notify: <T as frame_system::Config>::RuntimeCall =
+			frame_system::Call::<T>::remark { remark: Vec::new() }.into();
+		let timeout_notify: (u8, u8) = timeout_notify
+			.using_encoded(|mut bytes| Decode::decode(&mut bytes))
+			.expect("`RuntimeCall` starts with the pallet and call indices; qed");
+		crate::QueryTimeouts::<T>::insert(
+			crate::QueryTimeout::new(timeout),
+			query_id,
+			Some(timeout_notify),
+		);
+		let now = timeout + 1u32.into();
+
+		#[block]
+		{
+			crate::Pallet::<T>::expire_queries(now, Weight::MAX);
+		}
+
+		assert!(crate::Queries::<T>::get(query_id).is_none());
+	}
+
 	#[benchmark]
 	fn claim_assets() -> Result<(), BenchmarkError> {
 		let claim_origin = RawOrigin::Signed(whitelisted_caller());
@@ -37,10 +37,12 @@ use frame_support::{
 		DispatchErrorWithPostInfo, GetDispatchInfo, PostDispatchInfo, WithPostDispatchInfo,
 	},
 	pallet_prelude::*,
+	storage::{transactional::with_transaction_opaque_err, TransactionOutcome},
 	traits::{
 		Consideration, Contains, ContainsPair, Currency, Defensive, EnsureOrigin, Footprint, Get,
 		LockableCurrency, OriginTrait, WithdrawReasons,
 	},
+	weights::WeightMeter,
 	PalletId,
 };
 use frame_system::pallet_prelude::{BlockNumberFor, *};
@@ -50,7 +52,7 @@ use sp_core::H256;
 use sp_runtime::{
 	traits::{
 		AccountIdConversion, BadOrigin, BlakeTwo256, BlockNumberProvider, Dispatchable, Hash,
-		Saturating, Zero,
+		Saturating, UniqueSaturatedFrom, UniqueSaturatedInto, Zero,
 	},
 	Either, RuntimeDebug, SaturatedConversion,
 };
@@ -62,16 +64,18 @@ use xcm_builder::{
 use xcm_executor::{
 	traits::{
 		AssetTransferError, CheckSuspension, ClaimAssets, ConvertLocation, ConvertOrigin,
-		DropAssets, EventEmitter, FeeManager, FeeReason, MatchesFungible, OnResponse, Properties,
-		QueryHandler, QueryResponseStatus, RecordXcm, TransactAsset, TransferType,
-		VersionChangeNotifier, WeightBounds, XcmAssetTransfers,
+		DropAssets, EventEmitter, FeeManager, FeeReason, InstructionTrace, MatchesFungible,
+		OnResponse, Properties, QueryHandler, QueryResponseStatus, RecordXcm, TraceXcm,
+		TransactAsset, TransferType, VersionChangeNotifier, WeightBounds, XcmAssetTransfers,
 	},
 	AssetsInHolding,
 };
 use xcm_runtime_apis::{
 	authorized_aliases::{Error as AuthorizedAliasersApiError, OriginAliaser},
-	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
-	fees::Error as XcmPaymentApiError,
+	dry_run::{
+		CallDryRunEffects, Error as XcmDryRunApiError, TracedXcmDryRunEffects, XcmDryRunEffects,
+	},
+	fees::{Error as XcmPaymentApiError, TransferFees},
 	trusted_query::Error as TrustedQueryApiError,
 };
 
@@ -101,6 +105,8 @@ pub trait WeightInfo {
 	fn claim_assets() -> Weight;
 	fn add_authorized_alias() -> Weight;
 	fn remove_authorized_alias() -> Weight;
+	fn expire_query() -> Weight;
+	fn expire_query_with_timeout_notify() -> Weight;
 }
 
 /// fallback implementation
@@ -193,6 +199,14 @@ impl WeightInfo for TestWeightInfo {
 	fn remove_authorized_alias() -> Weight {
 		Weight::from_parts(100_000, 0)
 	}
+
+	fn expire_query() -> Weight {
+		Weight::from_parts(100_000_000, 0)
+	}
+
+	fn expire_query_with_timeout_notify() -> Weight {
+		Weight::from_parts(100_000_000, 0)
+	}
 }
 
 #[derive(Clone, Debug, Encode, Decode, MaxEncodedLen, TypeInfo)]
@@ -226,7 +240,7 @@ pub mod pallet {
 		pub const MaxAuthorizedAliases: u32 = 10;
 	}
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -580,6 +594,15 @@ pub mod pallet {
 		AliasAuthorizationRemoved { aliaser: Location, target: Location },
 		/// `target` removed all alias authorizations.
 		AliasesAuthorizationsRemoved { target: Location },
+		/// Query timed out without a response and is removed. There is no registered timeout
+		/// notification call.
+		QueryTimedOut { query_id: QueryId },
+		/// Query timed out without a response and is removed. The registered timeout notification
+		/// has been dispatched and executed successfully.
+		QueryTimeoutNotified { query_id: QueryId, pallet_index: u8, call_index: u8 },
+		/// Query timed out without a response and is removed. The registered timeout notification
+		/// could not be decoded into a `Call` or its dispatch failed.
+		QueryTimeoutNotifyFailed { query_id: QueryId, pallet_index: u8, call_index: u8 },
 	}
 
 	#[pallet::origin]
@@ -719,6 +742,23 @@ pub mod pallet {
 		Ready { response: VersionedResponse, at: BlockNumber },
 	}
 
+	/// The block number after which a notify query times out, encoded in big-endian.
+	///
+	/// Used unhashed as the key of [`QueryTimeouts`], so that the map is iterated in the order in
+	/// which the queries time out.
+	#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct QueryTimeout([u8; 8]);
+
+	impl QueryTimeout {
+		pub(crate) fn new<BlockNumber: UniqueSaturatedInto<u64>>(timeout: BlockNumber) -> Self {
+			Self(timeout.unique_saturated_into().to_be_bytes())
+		}
+
+		pub(crate) fn block_number<BlockNumber: UniqueSaturatedFrom<u64>>(&self) -> BlockNumber {
+			BlockNumber::unique_saturated_from(u64::from_be_bytes(self.0))
+		}
+	}
+
 	#[derive(Copy, Clone)]
 	pub(crate) struct LatestVersionedLocation<'a>(pub(crate) &'a Location);
 	impl<'a> EncodeLike<VersionedLocation> for LatestVersionedLocation<'a> {}
@@ -754,6 +794,21 @@ pub mod pallet {
 	pub(super) type Queries<T: Config> =
 		StorageMap<_, Blake2_128Concat, QueryId, QueryStatus<BlockNumberFor<T>>, OptionQuery>;
 
+	/// The notify queries to expire, by the block number after which they time out.
+	///
+	/// The timeouts are not hashed, so the first entry is always the next query to expire. Values
+	/// are the timeout notification call of the query, if any.
+	#[pallet::storage]
+	pub(super) type QueryTimeouts<T: Config> = StorageDoubleMap<
+		_,
+		Identity,
+		QueryTimeout,
+		Twox64Concat,
+		QueryId,
+		Option<(u8, u8)>,
+		OptionQuery,
+	>;
+
 	/// The existing asset traps.
 	///
 	/// Key is the blake2 256 hash of (origin, versioned `Assets`) pair. Value is the number of
@@ -896,6 +951,25 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(crate) type RecordedXcm<T: Config> = StorageValue<_, Xcm<()>>;
 
+	/// Whether or not the state of the XCM executor should be traced after each executed
+	/// instruction.
+	/// This is meant to be used in runtime APIs, and it's advised it stays false
+	/// for all other use cases, so as to not degrade regular performance.
+	///
+	/// Only relevant if this pallet is being used as the [`xcm_executor::traits::TraceXcm`]
+	/// implementation in the XCM executor configuration.
+	#[pallet::storage]
+	pub(crate) type ShouldTraceXcm<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// If [`ShouldTraceXcm`] is set to true, then the trace of every instruction executed locally
+	/// since tracing was last enabled will be stored here.
+	/// Runtime APIs can fetch the trace by taking this value.
+	///
+	/// Only relevant if this pallet is being used as the [`xcm_executor::traits::TraceXcm`]
+	/// implementation in the XCM executor configuration.
+	#[pallet::storage]
+	pub(crate) type XcmTrace<T: Config> = StorageValue<_, Vec<InstructionTrace>, ValueQuery>;
+
 	/// Map of authorized aliasers of local origins. Each local location can authorize a list of
 	/// other locations to alias into it. Each aliaser is only valid until its inner `expiry`
 	/// block number.
@@ -967,6 +1041,10 @@ pub mod pallet {
 			weight_used
 		}
 
+		fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::expire_queries(now, remaining_weight)
+		}
+
 		#[cfg(feature = "try-runtime")]
 		fn try_state(_n: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
 			Self::do_try_state()
@@ -1737,7 +1815,7 @@ impl<T: Config> QueryHandler for Pallet<T> {
 		timeout: BlockNumberFor<T>,
 		match_querier: impl Into<Location>,
 	) -> QueryId {
-		Self::do_new_query(responder, None, timeout, match_querier)
+		Self::do_new_query(responder, None, None, timeout, match_querier)
 	}
 
 	/// To check the status of the query, use `fn query()` passing the resultant `QueryId`
@@ -2901,6 +2979,38 @@ impl<T: Config> Pallet<T> {
 		Ok(XcmDryRunEffects { forwarded_xcms, emitted_events: events, execution_result: result })
 	}
 
+	/// Dry-runs `xcm` with the given `origin_location`, tracing every executed instruction.
+	///
+	/// Returns the same effects as [`Self::dry_run_xcm`], along with the origin, holding
+	/// register, fees register, weight used and error after each instruction.
+	/// Meant to be used in the `xcm_runtime_apis::dry_run::DryRunApi` runtime API.
+	pub fn dry_run_xcm_with_trace<
+		Runtime,
+		Router,
+		RuntimeCall: Decode + GetDispatchInfo,
+		XcmConfig,
+	>(
+		origin_location: VersionedLocation,
+		xcm: VersionedXcm<RuntimeCall>,
+	) -> Result<
+		TracedXcmDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>,
+		XcmDryRunApiError,
+	>
+	where
+		Runtime: frame_system::Config,
+		Router: InspectMessageQueues,
+		XcmConfig: xcm_executor::Config<RuntimeCall = RuntimeCall>,
+	{
+		// Discard any leftover trace, so we only return the one of this program.
+		let _ = XcmConfig::XcmTracer::take_trace();
+		XcmConfig::XcmTracer::set_trace_xcm(true);
+		let effects =
+			Self::dry_run_xcm::<Runtime, Router, RuntimeCall, XcmConfig>(origin_location, xcm);
+		XcmConfig::XcmTracer::set_trace_xcm(false);
+		let trace = XcmConfig::XcmTracer::take_trace();
+		Ok(TracedXcmDryRunEffects { effects: effects?, trace })
+	}
+
 	fn convert_xcms(
 		xcm_version: XcmVersion,
 		xcms: Vec<VersionedXcm<()>>,
@@ -2988,6 +3098,82 @@ impl<T: Config> Pallet<T> {
 			})
 	}
 
+	/// Quotes the fees of dispatching the transfer `call` from `origin`.
+	///
+	/// The call is dry-run to get its local XCM, weighed and converted into a fee in `asset` by
+	/// `weight_to_asset_fee`, and the XCMs it forwards to other locations, whose delivery fees
+	/// are added up. The dry-run is rolled back before quoting the delivery fees. Meant to be used
+	/// in the `xcm_runtime_apis::fees::TransferFeesApi` runtime API, with `weight_to_asset_fee`
+	/// being the runtime's `XcmPaymentApi::query_weight_to_asset_fee`.
+	pub fn query_transfer_fees<Router, OriginCaller, RuntimeCall>(
+		origin: OriginCaller,
+		call: RuntimeCall,
+		asset: VersionedAssetId,
+		result_xcms_version: XcmVersion,
+		weight_to_asset_fee: impl Fn(Weight, VersionedAssetId) -> Result<u128, XcmPaymentApiError>,
+	) -> Result<TransferFees, XcmPaymentApiError>
+	where
+		Router: InspectMessageQueues,
+		RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>,
+		<RuntimeCall as Dispatchable>::RuntimeOrigin: From<OriginCaller>,
+	{
+		// The dry-run is rolled back, so that the delivery fees are quoted from the state before
+		// the call, e.g. before it raised the fee factor of the channels it sends over.
+		let effects = with_transaction_opaque_err(|| {
+			TransactionOutcome::Rollback(Self::dry_run_call::<T, Router, OriginCaller, RuntimeCall>(
+				origin,
+				call,
+				result_xcms_version,
+			))
+		})
+		.map_err(|()| {
+			tracing::error!(target: "xcm::pallet_xcm::query_transfer_fees", "Failed to roll back the dry-run");
+			XcmPaymentApiError::InvalidTransfer
+		})?
+		.map_err(|error| {
+			tracing::error!(target: "xcm::pallet_xcm::query_transfer_fees", ?error, "Failed to dry-run the call");
+			match error {
+				XcmDryRunApiError::Unimplemented => XcmPaymentApiError::Unimplemented,
+				XcmDryRunApiError::VersionedConversionFailed =>
+					XcmPaymentApiError::VersionedConversionFailed,
+			}
+		})?;
+		if let Err(error) = effects.execution_result {
+			tracing::error!(target: "xcm::pallet_xcm::query_transfer_fees", ?error, "The call failed");
+			return Err(XcmPaymentApiError::InvalidTransfer)
+		}
+		let local_xcm = effects.local_xcm.ok_or_else(|| {
+			tracing::error!(target: "xcm::pallet_xcm::query_transfer_fees", "The call did not execute a local XCM");
+			XcmPaymentApiError::InvalidTransfer
+		})?;
+
+		let local_execution_fee = weight_to_asset_fee(Self::query_xcm_weight(local_xcm)?, asset)?;
+
+		let mut local_delivery_fees = Assets::new();
+		for (destination, messages) in &effects.forwarded_xcms {
+			for message in messages {
+				let fees = Self::query_delivery_fees(destination.clone(), message.clone())?;
+				let fees: Assets = fees.try_into().map_err(|e| {
+					tracing::error!(target: "xcm::pallet_xcm::query_transfer_fees", ?e, "Failed to convert delivery fees");
+					XcmPaymentApiError::VersionedConversionFailed
+				})?;
+				fees.into_inner().into_iter().for_each(|fee| local_delivery_fees.push(fee));
+			}
+		}
+		let local_delivery_fees = VersionedAssets::from(local_delivery_fees)
+			.into_version(result_xcms_version)
+			.map_err(|e| {
+				tracing::error!(target: "xcm::pallet_xcm::query_transfer_fees", ?e, ?result_xcms_version, "Failed to convert fees into version");
+				XcmPaymentApiError::VersionedConversionFailed
+			})?;
+
+		Ok(TransferFees {
+			local_execution_fee,
+			local_delivery_fees,
+			forwarded_xcms: effects.forwarded_xcms,
+		})
+	}
+
 	/// Given an Asset and a Location, returns if the provided location is a trusted reserve for the
 	/// given asset.
 	pub fn is_trusted_reserve(
@@ -3096,15 +3282,22 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Create a new expectation of a query response with the querier being here.
+	///
+	/// Queries with a notification are registered to expire once `timeout` has passed, calling
+	/// `maybe_timeout_notify` if any.
 	fn do_new_query(
 		responder: impl Into<Location>,
 		maybe_notify: Option<(u8, u8)>,
+		maybe_timeout_notify: Option<(u8, u8)>,
 		timeout: BlockNumberFor<T>,
 		match_querier: impl Into<Location>,
 	) -> u64 {
 		QueryCounter::<T>::mutate(|q| {
 			let r = *q;
 			q.saturating_inc();
+			if maybe_notify.is_some() {
+				QueryTimeouts::<T>::insert(QueryTimeout::new(timeout), r, maybe_timeout_notify);
+			}
 			Queries::<T>::insert(
 				r,
 				QueryStatus::Pending {
@@ -3161,16 +3354,132 @@ impl<T: Config> Pallet<T> {
 
 	/// Attempt to create a new query ID and register it as a query that is yet to respond, and
 	/// which will call a dispatchable when a response happens.
+	///
+	/// The query is removed once `timeout` has passed without a response.
 	pub fn new_notify_query(
 		responder: impl Into<Location>,
 		notify: impl Into<<T as Config>::RuntimeCall>,
 		timeout: BlockNumberFor<T>,
 		match_querier: impl Into<Location>,
 	) -> u64 {
-		let notify = notify.into().using_encoded(|mut bytes| Decode::decode(&mut bytes)).expect(
-			"decode input is output of Call encode; Call guaranteed to have two enums; qed",
-		);
-		Self::do_new_query(responder, Some(notify), timeout, match_querier)
+		let notify = Self::call_indices(notify.into());
+		Self::do_new_query(responder, Some(notify), None, timeout, match_querier)
+	}
+
+	/// Attempt to create a new query ID and register it as a query that is yet to respond, and
+	/// which will call a dispatchable when a response happens, or another one when `timeout` has
+	/// passed without a response.
+	///
+	/// - `timeout_notify`: A dispatchable function which will be called once the query timed out.
+	///   Other than the usual origin, it must accept exactly one argument: `query_id: QueryId`. It
+	///   should expect that the origin is `Origin::Response` and will contain the `responder`.
+	pub fn new_notify_query_with_timeout_notify(
+		responder: impl Into<Location>,
+		notify: impl Into<<T as Config>::RuntimeCall>,
+		timeout_notify: impl Into<<T as Config>::RuntimeCall>,
+		timeout: BlockNumberFor<T>,
+		match_querier: impl Into<Location>,
+	) -> u64 {
+		let notify = Self::call_indices(notify.into());
+		let timeout_notify = Self::call_indices(timeout_notify.into());
+		Self::do_new_query(responder, Some(notify), Some(timeout_notify), timeout, match_querier)
+	}
+
+	/// The pallet and call indices of `call`.
+	fn call_indices(call: <T as Config>::RuntimeCall) -> (u8, u8) {
+		call.using_encoded(|mut bytes| Decode::decode(&mut bytes))
+			.expect("decode input is output of Call encode; Call guaranteed to have two enums; qed")
+	}
+
+	/// Expire the notify queries which timed out before `now`, using at most `weight_limit`.
+	///
+	/// The timeout notification of each query is dispatched, if any. Queries whose timeout
+	/// notification does not fit in the remaining weight are left for a later block, unless it is
+	/// heavier than any block could offer, in which case the query expires without it.
+	pub(crate) fn expire_queries(now: BlockNumberFor<T>, weight_limit: Weight) -> Weight {
+		let mut meter = WeightMeter::with_limit(weight_limit);
+		// Reads: Next query to time out
+		let next_query = T::DbWeight::get().reads(1);
+		while meter.can_consume(next_query.saturating_add(T::WeightInfo::expire_query())) {
+			meter.consume(next_query);
+			let Some((key, query_id, maybe_timeout_notify)) = QueryTimeouts::<T>::iter().next()
+			else {
+				break
+			};
+			let timeout = key.block_number::<BlockNumberFor<T>>();
+			if timeout >= now {
+				break
+			}
+			let responder = match Queries::<T>::get(query_id) {
+				Some(QueryStatus::Pending { responder, timeout: t, .. }) if t == timeout =>
+					responder,
+				_ => {
+					// The query was answered before timing out.
+					meter.consume(T::DbWeight::get().reads_writes(1, 1));
+					QueryTimeouts::<T>::remove(key, query_id);
+					continue
+				},
+			};
+			let Some((pallet_index, call_index)) = maybe_timeout_notify else {
+				meter.consume(T::WeightInfo::expire_query());
+				Self::remove_timed_out_query(key, query_id);
+				Self::deposit_event(Event::QueryTimedOut { query_id });
+				continue
+			};
+			// The `Call` is built by `(pallet_index: u8, call_index: u8, QueryId)`.
+			let maybe_call = (pallet_index, call_index, query_id)
+				.using_encoded(|mut bytes| <T as Config>::RuntimeCall::decode(&mut bytes));
+			let expire_weight = T::WeightInfo::expire_query_with_timeout_notify();
+			let maybe_notification = match (maybe_call, Location::try_from(responder)) {
+				(Ok(call), Ok(responder)) => {
+					let weight = call.get_dispatch_info().call_weight;
+					// Waiting for a block with more weight left than any block has would stall
+					// all later queries.
+					let fits =
+						!expire_weight.saturating_add(weight).any_gt(Self::max_idle_weight());
+					fits.then_some((call, responder, weight))
+				},
+				_ => None,
+			};
+			let Some((call, responder, weight)) = maybe_notification else {
+				meter.consume(T::WeightInfo::expire_query());
+				Self::remove_timed_out_query(key, query_id);
+				let e = Event::QueryTimeoutNotifyFailed { query_id, pallet_index, call_index };
+				Self::deposit_event(e);
+				continue
+			};
+			if !meter.can_consume(expire_weight.saturating_add(weight)) {
+				break
+			}
+			meter.consume(expire_weight);
+			Self::remove_timed_out_query(key, query_id);
+			let dispatch_origin = Origin::Response(responder).into();
+			let (actual_weight, e) = match call.dispatch(dispatch_origin) {
+				Ok(post_info) => (
+					post_info.actual_weight,
+					Event::QueryTimeoutNotified { query_id, pallet_index, call_index },
+				),
+				Err(error_and_info) => (
+					error_and_info.post_info.actual_weight,
+					Event::QueryTimeoutNotifyFailed { query_id, pallet_index, call_index },
+				),
+			};
+			Self::deposit_event(e);
+			meter.consume(actual_weight.unwrap_or(weight).min(weight));
+		}
+		meter.consumed()
+	}
+
+	/// The most weight `on_idle` can be given in any block.
+	fn max_idle_weight() -> Weight {
+		let block_weights = <T as frame_system::Config>::BlockWeights::get();
+		block_weights.max_block.saturating_sub(block_weights.base_block)
+	}
+
+	/// Remove a query which timed out at `timeout`.
+	fn remove_timed_out_query(timeout: QueryTimeout, query_id: QueryId) {
+		QueryTimeouts::<T>::remove(timeout, query_id);
+		Queries::<T>::remove(query_id);
 	}
 
 	/// Note that a particular destination to whom we would like to send a message is unknown
@@ -3672,7 +3981,12 @@ impl<T: Config> OnResponse for Pallet<T> {
 			},
 			(
 				response,
-				Some(QueryStatus::Pending { responder, maybe_notify, maybe_match_querier, .. }),
+				Some(QueryStatus::Pending {
+					responder,
+					maybe_notify,
+					maybe_match_querier,
+					timeout,
+				}),
 			) => {
 				if let Some(match_querier) = maybe_match_querier {
 					let match_querier = match Location::try_from(match_querier) {
@@ -3723,6 +4037,7 @@ impl<T: Config> OnResponse for Pallet<T> {
 							<T as Config>::RuntimeCall::decode(&mut bytes)
 						}) {
 							Queries::<T>::remove(query_id);
+							QueryTimeouts::<T>::remove(QueryTimeout::new(timeout), query_id);
 							let weight = call.get_dispatch_info().call_weight;
 							if weight.any_gt(max_weight) {
 								let e = Event::NotifyOverweight {
@@ -3810,6 +4125,24 @@ impl<T: Config> RecordXcm for Pallet<T> {
 	}
 }
 
+impl<T: Config> TraceXcm for Pallet<T> {
+	fn should_trace() -> bool {
+		ShouldTraceXcm::<T>::get()
+	}
+
+	fn set_trace_xcm(enabled: bool) {
+		ShouldTraceXcm::<T>::put(enabled);
+	}
+
+	fn take_trace() -> Vec<InstructionTrace> {
+		XcmTrace::<T>::take()
+	}
+
+	fn trace(step: InstructionTrace) {
+		XcmTrace::<T>::append(step);
+	}
+}
+
 /// Ensure that the origin `o` represents an XCM (`Transact`) origin.
 ///
 /// Returns `Ok` with the location of the XCM sender or an `Err` otherwise.
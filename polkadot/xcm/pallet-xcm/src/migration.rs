@@ -455,6 +455,85 @@ pub mod v1 {
 	>;
 }
 
+pub mod v2 {
+	use super::*;
+	use crate::{Queries, QueryStatus, QueryTimeout, QueryTimeouts};
+	use frame_support::{
+		migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+		weights::WeightMeter,
+	};
+	use xcm::latest::QueryId;
+
+	/// The pallet identifier of the migrations, see [`MigrationId`].
+	pub const PALLET_MIGRATIONS_ID: &[u8; 10] = b"pallet-xcm";
+
+	/// Index the pending notify queries by their timeout, so that they expire once timed out like
+	/// the ones registered after the upgrade. They have no timeout notification.
+	///
+	/// One query is indexed per step, so that any number of them fits in the blocks.
+	pub struct LazyMigrationV1ToV2<T>(core::marker::PhantomData<T>);
+
+	impl<T: Config> SteppedMigration for LazyMigrationV1ToV2<T> {
+		type Cursor = QueryId;
+		type Identifier = MigrationId<10>;
+
+		fn id() -> Self::Identifier {
+			MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 1, version_to: 2 }
+		}
+
+		fn step(
+			mut cursor: Option<Self::Cursor>,
+			meter: &mut WeightMeter,
+		) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+			if Pallet::<T>::on_chain_storage_version() != Self::id().version_from as u16 {
+				return Ok(None)
+			}
+
+			// Reads: Get query, Query indexed, Writes: Index query
+			let required = T::DbWeight::get().reads_writes(2, 1);
+			if meter.remaining().any_lt(required) {
+				return Err(SteppedMigrationError::InsufficientWeight { required })
+			}
+
+			while meter.try_consume(required).is_ok() {
+				let mut iter = match cursor {
+					Some(last_key) =>
+						Queries::<T>::iter_from(Queries::<T>::hashed_key_for(last_key)),
+					None => Queries::<T>::iter(),
+				};
+				let Some((query_id, query)) = iter.next() else {
+					StorageVersion::new(Self::id().version_to as u16).put::<Pallet<T>>();
+					return Ok(None)
+				};
+				if let QueryStatus::Pending { maybe_notify: Some(_), timeout, .. } = query {
+					// Queries registered after the upgrade are already indexed, maybe with a
+					// timeout notification to keep.
+					let key = QueryTimeout::new(timeout);
+					if !QueryTimeouts::<T>::contains_key(&key, query_id) {
+						QueryTimeouts::<T>::insert(key, query_id, None::<(u8, u8)>);
+					}
+				}
+				cursor = Some(query_id);
+			}
+
+			Ok(cursor)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: alloc::vec::Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			for (query_id, query) in Queries::<T>::iter() {
+				if let QueryStatus::Pending { maybe_notify: Some(_), timeout, .. } = query {
+					ensure!(
+						QueryTimeouts::<T>::contains_key(QueryTimeout::new(timeout), query_id),
+						"must index all pending notify `Queries`."
+					);
+				}
+			}
+			Ok(())
+		}
+	}
+}
+
 /// When adding a new XCM version, we need to run this migration for `pallet_xcm` to ensure that all
 /// previously stored data with subkey prefix `XCM_VERSION-1` (and below) are migrated to the
 /// `XCM_VERSION`.
@@ -80,6 +80,7 @@ pub mod pallet_test_notifier {
 		QueryPrepared(QueryId),
 		NotifyQueryPrepared(QueryId),
 		ResponseReceived(Location, QueryId, Response),
+		QueryTimedOut(Location, QueryId),
 	}
 
 	#[pallet::error]
@@ -136,6 +137,23 @@ pub mod pallet_test_notifier {
 			Self::deposit_event(Event::<T>::ResponseReceived(responder, query_id, response));
 			Ok(())
 		}
+
+		#[pallet::call_index(3)]
+		#[pallet::weight(Weight::from_parts(1_000_000, 1_000_000))]
+		pub fn notification_timed_out(origin: OriginFor<T>, query_id: QueryId) -> DispatchResult {
+			let responder = ensure_response(<T as Config>::RuntimeOrigin::from(origin))?;
+			Self::deposit_event(Event::<T>::QueryTimedOut(responder, query_id));
+			Ok(())
+		}
+
+		#[pallet::call_index(4)]
+		#[pallet::weight(Weight::MAX)]
+		pub fn heavy_notification_timed_out(
+			origin: OriginFor<T>,
+			query_id: QueryId,
+		) -> DispatchResult {
+			Self::notification_timed_out(origin, query_id)
+		}
 	}
 }
 
@@ -529,6 +547,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = XcmPallet;
+	type XcmTracer = XcmPallet;
 }
 
 /// Converts a local signed origin into an XCM location. Forms the basis for local origins
@@ -24,14 +24,14 @@ use crate::{
 	mock::*,
 	pallet::{LockedFungibles, RemoteLockedFungibles, SupportedVersion},
 	AssetTraps, AuthorizedAliasers, Config, CurrentMigration, Error, ExecuteControllerWeightInfo,
-	LatestVersionedLocation, MaxAuthorizedAliases, Pallet, Queries, QueryStatus, RecordedXcm,
-	RemoteLockedFungibleRecord, ShouldRecordXcm, VersionDiscoveryQueue, VersionMigrationStage,
-	VersionNotifiers, VersionNotifyTargets, WeightInfo,
+	LatestVersionedLocation, MaxAuthorizedAliases, Pallet, Queries, QueryStatus, QueryTimeouts,
+	RecordedXcm, RemoteLockedFungibleRecord, ShouldRecordXcm, VersionDiscoveryQueue,
+	VersionMigrationStage, VersionNotifiers, VersionNotifyTargets, WeightInfo,
 };
 use bounded_collections::BoundedVec;
 use frame_support::{
 	assert_err_ignore_postinfo, assert_noop, assert_ok,
-	traits::{ContainsPair, Currency, Hooks},
+	traits::{ContainsPair, Currency, Hooks, StorageVersion},
 	weights::Weight,
 };
 use polkadot_parachain_primitives::primitives::Id as ParaId;
@@ -193,6 +193,263 @@ fn report_outcome_works() {
 	});
 }
 
+#[test]
+fn timed_out_notify_queries_are_expired_on_idle() {
+	let notify = RuntimeCall::TestNotifier(pallet_test_notifier::Call::notification_received {
+		query_id: 0,
+		response: Default::default(),
+	});
+	let timeout_notify =
+		RuntimeCall::TestNotifier(pallet_test_notifier::Call::notification_timed_out {
+			query_id: 0,
+		});
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let responder = Location::from(Parachain(OTHER_PARA_ID));
+		let query_id = XcmPallet::new_notify_query(responder.clone(), notify.clone(), 10, Here);
+		let query_id_with_timeout_notify = XcmPallet::new_notify_query_with_timeout_notify(
+			responder.clone(),
+			notify.clone(),
+			timeout_notify,
+			20,
+			Here,
+		);
+		// Queries without a notification are only ever removed by taking their response.
+		let query_id_without_notify = XcmPallet::new_query(responder.clone(), 10, Here);
+
+		// Queries time out after their timeout block.
+		XcmPallet::on_idle(10, Weight::MAX);
+		assert_eq!(Queries::<Test>::iter().count(), 3);
+
+		XcmPallet::on_idle(11, Weight::MAX);
+		assert_eq!(last_event(), RuntimeEvent::XcmPallet(crate::Event::QueryTimedOut { query_id }));
+		assert!(Queries::<Test>::get(query_id).is_none());
+		assert!(Queries::<Test>::get(query_id_without_notify).is_some());
+
+		XcmPallet::on_idle(21, Weight::MAX);
+		assert_eq!(
+			last_events(2),
+			vec![
+				RuntimeEvent::TestNotifier(pallet_test_notifier::Event::QueryTimedOut(
+					responder.clone(),
+					query_id_with_timeout_notify,
+				)),
+				RuntimeEvent::XcmPallet(crate::Event::QueryTimeoutNotified {
+					query_id: query_id_with_timeout_notify,
+					pallet_index: 5,
+					call_index: 3,
+				}),
+			]
+		);
+		assert_eq!(Queries::<Test>::iter_keys().collect::<Vec<_>>(), vec![query_id_without_notify]);
+		assert_eq!(QueryTimeouts::<Test>::iter().count(), 0);
+
+		// Queries expire in the order of their timeouts, whatever the order they were registered
+		// in, and without going through the blocks in between.
+		let later_query_id =
+			XcmPallet::new_notify_query(responder.clone(), notify.clone(), 1_000, Here);
+		let query_id = XcmPallet::new_notify_query(responder, notify, 30, Here);
+		XcmPallet::on_idle(31, Weight::MAX);
+		assert_eq!(last_event(), RuntimeEvent::XcmPallet(crate::Event::QueryTimedOut { query_id }));
+		assert_eq!(Queries::<Test>::iter_keys().count(), 2);
+		XcmPallet::on_idle(1_001, Weight::MAX);
+		assert_eq!(
+			last_event(),
+			RuntimeEvent::XcmPallet(crate::Event::QueryTimedOut { query_id: later_query_id })
+		);
+	});
+}
+
+#[test]
+fn timeout_notifications_heavier_than_any_block_fail() {
+	let notify = RuntimeCall::TestNotifier(pallet_test_notifier::Call::notification_received {
+		query_id: 0,
+		response: Default::default(),
+	});
+	let heavy_timeout_notify =
+		RuntimeCall::TestNotifier(pallet_test_notifier::Call::heavy_notification_timed_out {
+			query_id: 0,
+		});
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let responder = Location::from(Parachain(OTHER_PARA_ID));
+		let heavy_query_id = XcmPallet::new_notify_query_with_timeout_notify(
+			responder.clone(),
+			notify.clone(),
+			heavy_timeout_notify,
+			10,
+			Here,
+		);
+		let query_id = XcmPallet::new_notify_query(responder, notify, 10, Here);
+
+		// The heavy notification is dropped rather than holding back the queries behind it.
+		XcmPallet::on_idle(11, Weight::MAX);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::XcmPallet(crate::Event::QueryTimeoutNotifyFailed {
+				query_id: heavy_query_id,
+				pallet_index: 5,
+				call_index: 4,
+			})));
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::XcmPallet(crate::Event::QueryTimedOut { query_id })));
+		assert_eq!(Queries::<Test>::iter().count(), 0);
+		assert_eq!(QueryTimeouts::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn answered_notify_queries_do_not_time_out() {
+	let notify = RuntimeCall::TestNotifier(pallet_test_notifier::Call::notification_received {
+		query_id: 0,
+		response: Default::default(),
+	});
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let responder = Location::from(Parachain(OTHER_PARA_ID));
+		let query_id = XcmPallet::new_notify_query(responder.clone(), notify, 10, Here);
+		let message = Xcm(vec![QueryResponse {
+			query_id,
+			response: Response::ExecutionResult(None),
+			max_weight: Weight::from_parts(1_000_000, 1_000_000),
+			querier: Some(Here.into()),
+		}]);
+		let mut hash = fake_message_hash(&message);
+		let r = XcmExecutor::<XcmConfig>::prepare_and_execute(
+			responder,
+			message,
+			&mut hash,
+			Weight::from_parts(1_000_000_000, 1_000_000_000),
+			Weight::zero(),
+		);
+		assert_eq!(r, Outcome::Complete { used: Weight::from_parts(1_000, 1_000) });
+		assert_eq!(QueryTimeouts::<Test>::iter().count(), 0);
+
+		XcmPallet::on_idle(11, Weight::MAX);
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::XcmPallet(crate::Event::QueryTimedOut { .. })
+		)));
+	});
+}
+
+#[test]
+fn expiring_queries_respects_the_weight_limit() {
+	let notify = RuntimeCall::TestNotifier(pallet_test_notifier::Call::notification_received {
+		query_id: 0,
+		response: Default::default(),
+	});
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		for _ in 0..3 {
+			XcmPallet::new_notify_query(Parachain(OTHER_PARA_ID), notify.clone(), 10, Here);
+		}
+		let expire_query_weight = <Test as Config>::WeightInfo::expire_query();
+
+		// No query fits.
+		assert_eq!(XcmPallet::on_idle(11, Weight::zero()), Weight::zero());
+		assert_eq!(Queries::<Test>::iter().count(), 3);
+
+		let weight_limit = expire_query_weight.saturating_mul(2);
+		assert_eq!(XcmPallet::on_idle(11, weight_limit), weight_limit);
+		assert_eq!(Queries::<Test>::iter().count(), 1);
+
+		XcmPallet::on_idle(12, Weight::MAX);
+		assert_eq!(Queries::<Test>::iter().count(), 0);
+		assert_eq!(QueryTimeouts::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn migration_to_v2_indexes_pending_notify_queries_by_timeout() {
+	use frame_support::{migrations::SteppedMigration, weights::WeightMeter};
+
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let pending = |maybe_notify, timeout| QueryStatus::Pending {
+			responder: Location::from(Parachain(OTHER_PARA_ID)).into(),
+			maybe_match_querier: None,
+			maybe_notify,
+			timeout,
+		};
+		// Queries registered before the upgrade were never indexed by their timeout.
+		Queries::<Test>::insert(0, pending(Some((5, 2)), 20));
+		Queries::<Test>::insert(1, pending(Some((5, 2)), 10));
+		Queries::<Test>::insert(2, pending(None, 5));
+
+		StorageVersion::new(1).put::<XcmPallet>();
+
+		let mut cursor = None;
+		for _ in 0..10 {
+			cursor = crate::migration::v2::LazyMigrationV1ToV2::<Test>::step(
+				cursor,
+				&mut WeightMeter::new(),
+			)
+			.unwrap();
+			if cursor.is_none() {
+				break
+			}
+		}
+		assert!(cursor.is_none());
+		assert_eq!(StorageVersion::get::<XcmPallet>(), 2);
+
+		let timeouts = QueryTimeouts::<Test>::iter()
+			.map(|(timeout, query_id, maybe_timeout_notify)| {
+				(timeout.block_number::<u64>(), query_id, maybe_timeout_notify)
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(timeouts, vec![(10, 1, None), (20, 0, None)]);
+
+		// They now expire like the queries registered after the upgrade, the ones which timed out
+		// before it first.
+		System::set_block_number(15);
+		XcmPallet::on_idle(15, Weight::MAX);
+		assert_eq!(
+			last_event(),
+			RuntimeEvent::XcmPallet(crate::Event::QueryTimedOut { query_id: 1 })
+		);
+		XcmPallet::on_idle(21, Weight::MAX);
+		assert_eq!(Queries::<Test>::iter_keys().collect::<Vec<_>>(), vec![2]);
+	});
+}
+
+#[test]
+fn migration_to_v2_keeps_timeout_notify_of_queries_registered_after_the_upgrade() {
+	use frame_support::{migrations::SteppedMigration, weights::WeightMeter};
+
+	let notify = RuntimeCall::TestNotifier(pallet_test_notifier::Call::notification_received {
+		query_id: 0,
+		response: Default::default(),
+	});
+	let timeout_notify =
+		RuntimeCall::TestNotifier(pallet_test_notifier::Call::notification_timed_out {
+			query_id: 0,
+		});
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		StorageVersion::new(1).put::<XcmPallet>();
+
+		// Registered after the upgrade, before the migration reached it.
+		let query_id = XcmPallet::new_notify_query_with_timeout_notify(
+			Parachain(OTHER_PARA_ID),
+			notify,
+			timeout_notify,
+			10,
+			Here,
+		);
+		let timeout = crate::QueryTimeout::new(10u64);
+		let maybe_timeout_notify = QueryTimeouts::<Test>::get(timeout, query_id);
+		assert!(maybe_timeout_notify.is_some());
+
+		let mut cursor = None;
+		for _ in 0..10 {
+			cursor = crate::migration::v2::LazyMigrationV1ToV2::<Test>::step(
+				cursor,
+				&mut WeightMeter::new(),
+			)
+			.unwrap();
+			if cursor.is_none() {
+				break
+			}
+		}
+		assert!(cursor.is_none());
+		assert_eq!(QueryTimeouts::<Test>::get(timeout, query_id), maybe_timeout_notify);
+	});
+}
+
 #[test]
 fn custom_querier_works() {
 	let balances = vec![
@@ -18,5 +18,8 @@
 //!
 //! E.g. types that implement the [`xcm_executor::traits::AssetExchange`] trait.
 
+mod multi_asset_adapter;
+pub use multi_asset_adapter::MultiAssetExchangeAdapter;
+
 mod single_asset_adapter;
 pub use single_asset_adapter::SingleAssetExchangeAdapter;
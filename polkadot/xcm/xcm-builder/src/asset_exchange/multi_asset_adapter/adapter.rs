@@ -0,0 +1,513 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Multi asset exchange adapter.
+
+extern crate alloc;
+use alloc::{vec, vec::Vec};
+use core::{marker::PhantomData, ops::ControlFlow};
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::{tokens::fungibles, Get},
+	weights::RuntimeDbWeight,
+};
+use pallet_asset_conversion::{QuotePrice, SwapCredit, WeightInfo};
+use sp_runtime::DispatchError;
+use xcm::prelude::*;
+use xcm_executor::{
+	traits::{AssetExchange, MatchesFungibles},
+	AssetsInHolding,
+};
+
+const LOG_TARGET: &str = "xcm::MultiAssetExchangeAdapter";
+
+/// An adapter from [`pallet_asset_conversion::SwapCredit`] and
+/// [`pallet_asset_conversion::QuotePrice`] to [`xcm_executor::traits::AssetExchange`], swapping
+/// through several pools if there's no direct pool between two assets.
+///
+/// Routes go from the given asset to the wanted asset through any of the `Intermediaries`, up to
+/// the maximal swap path length of `AssetConversion`. The route with the best quote is taken.
+/// Searching routes quotes pools, each quote reading a pool's reserves. `MaxQuotes` bounds the
+/// number of quotes made for one exchange, and the search stops once they are used up. The weight
+/// of `ExchangeAsset` should include [`Self::exchange_weight`], which accounts for these reads and
+/// for the swaps along the longest routes.
+///
+/// Several fungible assets can be given and wanted:
+/// - If `maximal`, all of `give` is swapped. Either all given assets are swapped for the single
+///   wanted asset, or the given assets are paired with the wanted assets in order, in which case
+///   there must be as many of them. Each wanted asset must be obtained at least in the wanted
+///   amount.
+/// - If not `maximal`, the exact amount of each wanted asset is obtained, in order, by swapping the
+///   first given asset with enough balance left and a route to the wanted asset. What's left of
+///   `give` is returned along with the wanted assets.
+///
+/// Quotes when not `maximal` ignore the amounts of `give`, like the ones of
+/// [`crate::SingleAssetExchangeAdapter`]: each wanted asset is quoted from the first given asset
+/// with a route to it. This lets the executor quote with a zero amount of the asset it would give,
+/// as it does for delivery fees.
+///
+/// Either all swaps are made or none is. Non-fungible assets are not supported.
+///
+/// The quotes of consecutive swaps through the same pool don't account for each other, so a
+/// quote for several assets may be off if their routes share pools.
+pub struct MultiAssetExchangeAdapter<
+	AssetConversion,
+	Fungibles,
+	Matcher,
+	Intermediaries,
+	MaxQuotes,
+	AccountId,
+>(PhantomData<(AssetConversion, Fungibles, Matcher, Intermediaries, MaxQuotes, AccountId)>);
+
+/// A fungible asset both known to XCM and to `AssetConversion`.
+struct Leg<AssetKind> {
+	id: AssetId,
+	kind: AssetKind,
+	amount: u128,
+}
+
+impl<AssetConversion, Fungibles, Matcher, Intermediaries, MaxQuotes, AccountId>
+	MultiAssetExchangeAdapter<
+		AssetConversion,
+		Fungibles,
+		Matcher,
+		Intermediaries,
+		MaxQuotes,
+		AccountId,
+	>
+where
+	AssetConversion: SwapCredit<
+			AccountId,
+			Balance = u128,
+			AssetKind = Fungibles::AssetId,
+			Credit = fungibles::Credit<AccountId, Fungibles>,
+		> + QuotePrice<Balance = u128, AssetKind = Fungibles::AssetId>,
+	Fungibles: fungibles::Balanced<AccountId, Balance = u128>,
+	Matcher: MatchesFungibles<Fungibles::AssetId, Fungibles::Balance>,
+	Intermediaries: Get<Vec<Fungibles::AssetId>>,
+	MaxQuotes: Get<u32>,
+{
+	/// The weight of searching routes and swapping for `swaps` pairs of given and wanted assets,
+	/// to be added to the weight of `ExchangeAsset` in runtimes using this adapter.
+	///
+	/// Up to `MaxQuotes` quotes are made, each reading the balances of a pool in both of its
+	/// assets. Each swap is charged as a swap along a path of the maximal length.
+	pub fn exchange_weight<SwapWeight: WeightInfo, DbWeight: Get<RuntimeDbWeight>>(
+		swaps: u32,
+	) -> Weight {
+		let max_path_len = <AssetConversion as SwapCredit<AccountId>>::max_path_len();
+		let swap = SwapWeight::swap_exact_tokens_for_tokens(max_path_len)
+			.max(SwapWeight::swap_tokens_for_exact_tokens(max_path_len));
+		DbWeight::get()
+			.reads(u64::from(MaxQuotes::get()).saturating_mul(2))
+			.saturating_add(swap.saturating_mul(swaps.into()))
+	}
+
+	/// Match XCM assets to the assets `AssetConversion` handles. `None` if any of them can't be
+	/// matched.
+	fn legs<'a>(assets: impl Iterator<Item = &'a Asset>) -> Option<Vec<Leg<Fungibles::AssetId>>> {
+		assets
+			.map(|asset| {
+				Matcher::matches_fungibles(asset)
+					.map(|(kind, amount)| Leg { id: asset.id.clone(), kind, amount })
+					.map_err(|error| {
+						tracing::trace!(
+							target: LOG_TARGET,
+							?asset,
+							?error,
+							"Could not map XCM asset to FRAME asset.",
+						);
+					})
+					.ok()
+			})
+			.collect()
+	}
+
+	/// Swap `give` for `want`, or only quote the swaps if `execute` is `false`.
+	///
+	/// Returns the assets obtained, along with the unused assets of `give`.
+	fn exchange_legs(
+		give: &[Leg<Fungibles::AssetId>],
+		want: &[Leg<Fungibles::AssetId>],
+		maximal: bool,
+		execute: bool,
+	) -> Option<(Vec<Asset>, Vec<Asset>)> {
+		if give.is_empty() || want.is_empty() {
+			return None
+		}
+		let mut quotes_left = MaxQuotes::get();
+
+		if maximal {
+			if want.len() != 1 && want.len() != give.len() {
+				tracing::trace!(
+					target: LOG_TARGET,
+					give = give.len(),
+					want = want.len(),
+					"Can't pair given and wanted assets.",
+				);
+				return None
+			}
+
+			let mut received = vec![0u128; want.len()];
+			for (index, given) in give.iter().enumerate() {
+				let want_index = if want.len() == 1 { 0 } else { index };
+				let wanted = &want[want_index];
+				let out = if given.kind == wanted.kind {
+					given.amount
+				} else {
+					let (path, quoted) = Self::best_route(
+						&given.kind,
+						&wanted.kind,
+						given.amount,
+						true,
+						&mut quotes_left,
+					)?;
+					if execute {
+						Self::swap_exact_in(path, given.amount)?
+					} else {
+						quoted
+					}
+				};
+				received[want_index] = received[want_index].saturating_add(out);
+			}
+
+			if want.iter().zip(&received).any(|(wanted, received)| *received < wanted.amount) {
+				tracing::debug!(target: LOG_TARGET, ?received, "Obtained less than wanted.");
+				return None
+			}
+
+			let obtained = want
+				.iter()
+				.zip(received)
+				.map(|(wanted, amount)| (wanted.id.clone(), amount).into());
+			Some((obtained.collect(), Vec::new()))
+		} else {
+			let mut balances: Vec<u128> = give.iter().map(|given| given.amount).collect();
+			for wanted in want {
+				let mut obtained = false;
+				for (given, balance) in give.iter().zip(balances.iter_mut()) {
+					if given.kind == wanted.kind {
+						if *balance >= wanted.amount {
+							*balance -= wanted.amount;
+							obtained = true;
+							break
+						}
+						continue
+					}
+					if *balance == 0 {
+						continue
+					}
+					let Some((path, needed)) = Self::best_route(
+						&given.kind,
+						&wanted.kind,
+						wanted.amount,
+						false,
+						&mut quotes_left,
+					) else {
+						continue
+					};
+					if needed > *balance {
+						continue
+					}
+					let spent = if execute {
+						Self::swap_exact_out(path, *balance, wanted.amount)?
+					} else {
+						needed
+					};
+					*balance = balance.saturating_sub(spent);
+					obtained = true;
+					break
+				}
+
+				if !obtained {
+					tracing::debug!(
+						target: LOG_TARGET,
+						wanted = ?wanted.id,
+						"No given asset can be swapped for the wanted asset.",
+					);
+					return None
+				}
+			}
+
+			let obtained = want.iter().map(|wanted| (wanted.id.clone(), wanted.amount).into());
+			let left = give
+				.iter()
+				.zip(balances)
+				.filter(|(_, balance)| *balance > 0)
+				.map(|(given, balance)| (given.id.clone(), balance).into());
+			Some((obtained.collect(), left.collect()))
+		}
+	}
+
+	/// The amounts of `give` required to obtain exactly `want`, whatever the amounts of `give`.
+	fn quote_required(
+		give: &[Leg<Fungibles::AssetId>],
+		want: &[Leg<Fungibles::AssetId>],
+	) -> Option<Vec<Asset>> {
+		if give.is_empty() || want.is_empty() {
+			return None
+		}
+		let mut quotes_left = MaxQuotes::get();
+
+		let mut required = vec![0u128; give.len()];
+		for wanted in want {
+			let found = give.iter().enumerate().find_map(|(index, given)| {
+				if given.kind == wanted.kind {
+					return Some((index, wanted.amount))
+				}
+				Self::best_route(&given.kind, &wanted.kind, wanted.amount, false, &mut quotes_left)
+					.map(|(_, needed)| (index, needed))
+			});
+			let Some((index, needed)) = found else {
+				tracing::debug!(
+					target: LOG_TARGET,
+					wanted = ?wanted.id,
+					"No given asset can be swapped for the wanted asset.",
+				);
+				return None
+			};
+			required[index] = required[index].saturating_add(needed);
+		}
+
+		let required = give
+			.iter()
+			.zip(required)
+			.filter(|(_, required)| *required > 0)
+			.map(|(given, required)| (given.id.clone(), required).into());
+		Some(required.collect())
+	}
+
+	/// Find the route from `from` to `to` with the best quote, trying shorter routes first.
+	///
+	/// If `maximal`, `amount` is the amount of `from` to swap and the route giving the most of
+	/// `to` is returned, along with that amount. Otherwise `amount` is the amount of `to` wanted
+	/// and the route needing the least of `from` is returned, along with that amount.
+	fn best_route(
+		from: &Fungibles::AssetId,
+		to: &Fungibles::AssetId,
+		amount: u128,
+		maximal: bool,
+		quotes_left: &mut u32,
+	) -> Option<(Vec<Fungibles::AssetId>, u128)> {
+		let intermediaries: Vec<_> = Intermediaries::get()
+			.into_iter()
+			.filter(|intermediary| intermediary != from && intermediary != to)
+			.collect();
+		let max_path_len = <AssetConversion as SwapCredit<AccountId>>::max_path_len() as usize;
+
+		let mut best: Option<(Vec<Fungibles::AssetId>, u128)> = None;
+		for hops in 0..=intermediaries.len() {
+			if hops + 2 > max_path_len {
+				break
+			}
+
+			let mut prefix = vec![from.clone()];
+			let flow = Self::visit_paths(&mut prefix, &intermediaries, hops, &mut |route| {
+				if *quotes_left == 0 {
+					return ControlFlow::Break(())
+				}
+				let mut path = route.to_vec();
+				path.push(to.clone());
+				let Some(quoted) = Self::quote_path(&path, amount, maximal, quotes_left) else {
+					return ControlFlow::Continue(())
+				};
+				let better = best.as_ref().map_or(true, |(_, best_quoted)| {
+					if maximal {
+						quoted > *best_quoted
+					} else {
+						quoted < *best_quoted
+					}
+				});
+				if better {
+					best = Some((path, quoted));
+				}
+				ControlFlow::Continue(())
+			});
+			if flow.is_break() {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?from,
+					?to,
+					"Ran out of quotes while searching routes.",
+				);
+				break
+			}
+		}
+
+		best
+	}
+
+	/// Call `visit` with `prefix` extended by each ordering of `hops` distinct `intermediaries`,
+	/// one after the other, until `visit` breaks.
+	fn visit_paths(
+		prefix: &mut Vec<Fungibles::AssetId>,
+		intermediaries: &[Fungibles::AssetId],
+		hops: usize,
+		visit: &mut impl FnMut(&[Fungibles::AssetId]) -> ControlFlow<()>,
+	) -> ControlFlow<()> {
+		if hops == 0 {
+			return visit(prefix)
+		}
+		for intermediary in intermediaries {
+			if prefix.contains(intermediary) {
+				continue
+			}
+			prefix.push(intermediary.clone());
+			let flow = Self::visit_paths(prefix, intermediaries, hops - 1, visit);
+			prefix.pop();
+			if flow.is_break() {
+				return flow
+			}
+		}
+		ControlFlow::Continue(())
+	}
+
+	/// Quote swapping along `path`, pool by pool. See [`Self::best_route`] for the meaning of
+	/// `amount` and of the result.
+	fn quote_path(
+		path: &[Fungibles::AssetId],
+		amount: u128,
+		maximal: bool,
+		quotes_left: &mut u32,
+	) -> Option<u128> {
+		let mut quote = |asset1: &Fungibles::AssetId, asset2: &Fungibles::AssetId, amount: u128| {
+			*quotes_left = quotes_left.checked_sub(1)?;
+			if maximal {
+				<AssetConversion as QuotePrice>::quote_price_exact_tokens_for_tokens(
+					asset1.clone(),
+					asset2.clone(),
+					amount,
+					true, // Include fee.
+				)
+			} else {
+				<AssetConversion as QuotePrice>::quote_price_tokens_for_exact_tokens(
+					asset1.clone(),
+					asset2.clone(),
+					amount,
+					true, // Include fee.
+				)
+			}
+		};
+
+		if maximal {
+			path.windows(2)
+				.try_fold(amount, |amount, pair| quote(&pair[0], &pair[1], amount))
+		} else {
+			path.windows(2)
+				.rev()
+				.try_fold(amount, |amount, pair| quote(&pair[0], &pair[1], amount))
+		}
+	}
+
+	/// Swap exactly `amount_in` along `path`, returning the amount obtained.
+	fn swap_exact_in(path: Vec<Fungibles::AssetId>, amount_in: u128) -> Option<u128> {
+		let credit_in = Fungibles::issue(path[0].clone(), amount_in);
+		let credit_out =
+			<AssetConversion as SwapCredit<_>>::swap_exact_tokens_for_tokens(path, credit_in, None)
+				.map_err(|(credit_in, error)| {
+					tracing::debug!(target: LOG_TARGET, ?error, "Could not perform the swap");
+					drop(credit_in);
+				})
+				.ok()?;
+		Some(credit_out.peek())
+	}
+
+	/// Swap at most `balance` along `path` to obtain exactly `amount_out`, returning the amount
+	/// spent.
+	fn swap_exact_out(
+		path: Vec<Fungibles::AssetId>,
+		balance: u128,
+		amount_out: u128,
+	) -> Option<u128> {
+		let credit_in = Fungibles::issue(path[0].clone(), balance);
+		let (_, credit_change) = <AssetConversion as SwapCredit<_>>::swap_tokens_for_exact_tokens(
+			path, credit_in, amount_out,
+		)
+		.map_err(|(credit_in, error)| {
+			tracing::debug!(target: LOG_TARGET, ?error, "Could not perform the swap");
+			drop(credit_in);
+		})
+		.ok()?;
+		Some(balance.saturating_sub(credit_change.peek()))
+	}
+}
+
+impl<AssetConversion, Fungibles, Matcher, Intermediaries, MaxQuotes, AccountId> AssetExchange
+	for MultiAssetExchangeAdapter<
+		AssetConversion,
+		Fungibles,
+		Matcher,
+		Intermediaries,
+		MaxQuotes,
+		AccountId,
+	>
+where
+	AssetConversion: SwapCredit<
+			AccountId,
+			Balance = u128,
+			AssetKind = Fungibles::AssetId,
+			Credit = fungibles::Credit<AccountId, Fungibles>,
+		> + QuotePrice<Balance = u128, AssetKind = Fungibles::AssetId>,
+	Fungibles: fungibles::Balanced<AccountId, Balance = u128>,
+	Matcher: MatchesFungibles<Fungibles::AssetId, Fungibles::Balance>,
+	Intermediaries: Get<Vec<Fungibles::AssetId>>,
+	MaxQuotes: Get<u32>,
+{
+	fn exchange_asset(
+		_: Option<&Location>,
+		give: AssetsInHolding,
+		want: &Assets,
+		maximal: bool,
+	) -> Result<AssetsInHolding, AssetsInHolding> {
+		// We don't allow non-fungible assets.
+		if give.non_fungible_assets_iter().next().is_some() {
+			return Err(give)
+		}
+		let give_assets: Vec<Asset> = give.fungible_assets_iter().collect();
+		let Some(give_legs) = Self::legs(give_assets.iter()) else { return Err(give) };
+		let Some(want_legs) = Self::legs(want.inner().iter()) else { return Err(give) };
+
+		// Either all the swaps are made or none.
+		let exchanged =
+			with_transaction(|| match Self::exchange_legs(&give_legs, &want_legs, maximal, true) {
+				Some(exchanged) => TransactionOutcome::Commit(Ok(exchanged)),
+				None => TransactionOutcome::Rollback(Err(DispatchError::Other(
+					"Could not exchange assets",
+				))),
+			});
+		let Ok((obtained, left)) = exchanged else { return Err(give) };
+
+		let mut result = AssetsInHolding::new();
+		for asset in obtained.into_iter().chain(left) {
+			result.subsume(asset);
+		}
+		Ok(result)
+	}
+
+	fn quote_exchange_price(give: &Assets, want: &Assets, maximal: bool) -> Option<Assets> {
+		let give_legs = Self::legs(give.inner().iter())?;
+		let want_legs = Self::legs(want.inner().iter())?;
+
+		if maximal {
+			// The amounts of `want` resulting from swapping `give`.
+			let (obtained, _) = Self::exchange_legs(&give_legs, &want_legs, true, false)?;
+			Some(obtained.into())
+		} else {
+			// The amounts of `give` required to obtain `want`.
+			Self::quote_required(&give_legs, &want_legs).map(Into::into)
+		}
+	}
+}
@@ -0,0 +1,332 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the [`MultiAssetExchangeAdapter`] type.
+//!
+//! Uses the mock of the [`crate::SingleAssetExchangeAdapter`], with an additional pool between
+//! the native token and asset 2. Since all pools contain the native token, swaps between assets 1
+//! and 2 go through it.
+
+use super::MultiAssetExchangeAdapter;
+use crate::{
+	asset_exchange::single_asset_adapter::mock::*, Equals, MatchedConvertedConcreteId, StartsWith,
+};
+use frame_support::{
+	assert_ok, parameter_types,
+	traits::{fungible::NativeOrWithId, fungibles::Mutate, Get},
+	weights::constants::RocksDbWeight,
+};
+use pallet_asset_conversion::{SwapCredit, WeightInfo};
+use sp_runtime::traits::TryConvertInto;
+use xcm::prelude::*;
+use xcm_executor::{traits::AssetExchange, AssetsInHolding};
+
+parameter_types! {
+	pub NativeIntermediary: Vec<NativeOrWithId<u32>> = vec![NativeOrWithId::Native];
+	pub NoIntermediaries: Vec<NativeOrWithId<u32>> = vec![];
+	pub const MaxQuotes: u32 = 10;
+	pub const OneQuote: u32 = 1;
+}
+
+type Matcher = MatchedConvertedConcreteId<
+	NativeOrWithId<u32>,
+	Balance,
+	(StartsWith<TrustBackedAssetsPalletLocation>, Equals<HereLocation>),
+	LocationToAssetId,
+	TryConvertInto,
+>;
+
+type Adapter<Intermediaries, Quotes> = MultiAssetExchangeAdapter<
+	AssetConversion,
+	NativeAndAssets,
+	Matcher,
+	Intermediaries,
+	Quotes,
+	AccountId,
+>;
+type Exchanger = Adapter<NativeIntermediary, MaxQuotes>;
+type DirectPoolsExchanger = Adapter<NoIntermediaries, MaxQuotes>;
+type OneQuoteExchanger = Adapter<NativeIntermediary, OneQuote>;
+
+const ASSET_1: [Junction; 2] = [PalletInstance(2), GeneralIndex(1)];
+const ASSET_2: [Junction; 2] = [PalletInstance(2), GeneralIndex(2)];
+
+fn new_test_ext_with_two_pools() -> sp_io::TestExternalities {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let owner = 0;
+		assert_ok!(AssetsPallet::force_create(RuntimeOrigin::root(), 2, owner, false, 1));
+		assert_ok!(AssetsPallet::mint_into(2, &owner, INITIAL_BALANCE));
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(owner),
+			Box::new(NativeOrWithId::Native),
+			Box::new(NativeOrWithId::WithId(2)),
+		));
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(owner),
+			Box::new(NativeOrWithId::Native),
+			Box::new(NativeOrWithId::WithId(2)),
+			50_000_000,
+			200_000_000,
+			0,
+			0,
+			owner,
+		));
+	});
+	ext
+}
+
+// ========== Happy path ==========
+
+#[test]
+fn maximal_exchange_through_intermediary() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		let quoted = Exchanger::quote_exchange_price(
+			&(ASSET_1, 10_000_000).into(),
+			&(ASSET_2, 1).into(),
+			true,
+		)
+		.unwrap();
+		let quoted_amount = amount_of(&quoted, &ASSET_2.into());
+		assert!(quoted_amount > 0);
+
+		let assets = Exchanger::exchange_asset(
+			None,
+			vec![(ASSET_1, 10_000_000).into()].into(),
+			&vec![(ASSET_2, 1).into()].into(),
+			true, // Maximal
+		)
+		.unwrap();
+		assert_eq!(fungibles(&assets), vec![(ASSET_2.into(), quoted_amount)]);
+	});
+}
+
+#[test]
+fn minimal_exchange_through_intermediary() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		let quoted = Exchanger::quote_exchange_price(
+			&(ASSET_1, 10_000_000).into(),
+			&(ASSET_2, 2_000_000).into(),
+			false,
+		)
+		.unwrap();
+		let required = amount_of(&quoted, &ASSET_1.into());
+		assert!(required > 0 && required < 10_000_000);
+
+		let assets = Exchanger::exchange_asset(
+			None,
+			vec![(ASSET_1, 10_000_000).into()].into(),
+			&vec![(ASSET_2, 2_000_000).into()].into(),
+			false, // Minimal
+		)
+		.unwrap();
+		let mut expected =
+			vec![(ASSET_1.into(), 10_000_000 - required), (ASSET_2.into(), 2_000_000)];
+		expected.sort();
+		assert_eq!(fungibles(&assets), expected);
+	});
+}
+
+#[test]
+fn minimal_quote_ignores_give_amount() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		// The executor quotes delivery fees with a zero amount of the asset it would give.
+		let with_zero = Exchanger::quote_exchange_price(
+			&(ASSET_1, 0).into(),
+			&(ASSET_2, 2_000_000).into(),
+			false,
+		)
+		.unwrap();
+		let with_balance = Exchanger::quote_exchange_price(
+			&(ASSET_1, 10_000_000).into(),
+			&(ASSET_2, 2_000_000).into(),
+			false,
+		)
+		.unwrap();
+		assert!(amount_of(&with_zero, &ASSET_1.into()) > 0);
+		assert_eq!(with_zero, with_balance);
+
+		// Through a direct pool too.
+		let direct =
+			Exchanger::quote_exchange_price(&(ASSET_1, 0).into(), &(Here, 1_000).into(), false)
+				.unwrap();
+		assert!(amount_of(&direct, &ASSET_1.into()) > 0);
+	});
+}
+
+#[test]
+fn maximal_exchange_of_several_assets_for_one() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		let through_native = Exchanger::quote_exchange_price(
+			&(ASSET_1, 1_000_000).into(),
+			&(ASSET_2, 1).into(),
+			true,
+		)
+		.unwrap();
+		let through_native = amount_of(&through_native, &ASSET_2.into());
+
+		let assets = Exchanger::exchange_asset(
+			None,
+			vec![(ASSET_1, 1_000_000).into(), (ASSET_2, 500).into()].into(),
+			&vec![(ASSET_2, 1).into()].into(),
+			true, // Maximal
+		)
+		.unwrap();
+		// The given asset 2 is kept as is.
+		assert_eq!(fungibles(&assets), vec![(ASSET_2.into(), through_native + 500)]);
+	});
+}
+
+#[test]
+fn minimal_exchange_for_several_assets() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		let assets = Exchanger::exchange_asset(
+			None,
+			vec![(ASSET_1, 10_000_000).into()].into(),
+			&vec![(Here, 1_000_000).into(), (ASSET_2, 1_000_000).into()].into(),
+			false, // Minimal
+		)
+		.unwrap();
+		let obtained = fungibles(&assets);
+		assert_eq!(obtained.len(), 3);
+		assert!(obtained.contains(&(Here.into(), 1_000_000)));
+		assert!(obtained.contains(&(ASSET_2.into(), 1_000_000)));
+	});
+}
+
+// ========== Unhappy path ==========
+
+#[test]
+fn no_route_without_intermediaries() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		assert!(DirectPoolsExchanger::quote_exchange_price(
+			&(ASSET_1, 10_000_000).into(),
+			&(ASSET_2, 1).into(),
+			true,
+		)
+		.is_none());
+		assert!(DirectPoolsExchanger::exchange_asset(
+			None,
+			vec![(ASSET_1, 10_000_000).into()].into(),
+			&vec![(ASSET_2, 1).into()].into(),
+			true,
+		)
+		.is_err());
+	});
+}
+
+#[test]
+fn route_search_is_bounded_by_quotes() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		// Quoting the direct pool, which doesn't exist, uses up the only quote.
+		assert!(OneQuoteExchanger::exchange_asset(
+			None,
+			vec![(ASSET_1, 10_000_000).into()].into(),
+			&vec![(ASSET_2, 1).into()].into(),
+			true,
+		)
+		.is_err());
+	});
+}
+
+#[test]
+fn exchange_weight_accounts_for_quotes_and_swaps() {
+	let quotes = RocksDbWeight::get().reads(2 * u64::from(MaxQuotes::get()));
+	let max_path_len = <AssetConversion as SwapCredit<AccountId>>::max_path_len();
+	let swap = <() as WeightInfo>::swap_exact_tokens_for_tokens(max_path_len)
+		.max(<() as WeightInfo>::swap_tokens_for_exact_tokens(max_path_len));
+
+	assert_eq!(Exchanger::exchange_weight::<(), RocksDbWeight>(0), quotes);
+	assert_eq!(Exchanger::exchange_weight::<(), RocksDbWeight>(2), quotes + swap * 2);
+}
+
+#[test]
+fn failed_exchange_makes_no_swap() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		let quote_before =
+			Exchanger::quote_exchange_price(&(ASSET_1, 1_000_000).into(), &(Here, 1).into(), true);
+
+		// The first asset can be swapped, but not enough native tokens are obtained overall.
+		assert!(Exchanger::exchange_asset(
+			None,
+			vec![(ASSET_1, 1_000_000).into(), (ASSET_2, 1_000_000).into()].into(),
+			&vec![(Here, 100_000_000).into()].into(),
+			true,
+		)
+		.is_err());
+
+		let quote_after =
+			Exchanger::quote_exchange_price(&(ASSET_1, 1_000_000).into(), &(Here, 1).into(), true);
+		assert_eq!(quote_before, quote_after);
+	});
+}
+
+#[test]
+fn unpaired_assets_are_rejected() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		assert!(Exchanger::exchange_asset(
+			None,
+			vec![
+				(ASSET_1, 1_000_000).into(),
+				(ASSET_2, 1_000_000).into(),
+				(Here, 1_000_000).into()
+			]
+			.into(),
+			&vec![(ASSET_1, 1).into(), (ASSET_2, 1).into()].into(),
+			true,
+		)
+		.is_err());
+	});
+}
+
+#[test]
+fn non_fungible_asset_in_give() {
+	new_test_ext_with_two_pools().execute_with(|| {
+		assert!(Exchanger::exchange_asset(
+			None,
+			// Using `u64` here will give us a non-fungible instead of a fungible.
+			vec![(ASSET_2, 10_000_000u64).into()].into(),
+			&vec![(Here, 10_000_000).into()].into(),
+			false,
+		)
+		.is_err());
+	});
+}
+
+// ========== Helper functions ==========
+
+fn fungibles(assets: &AssetsInHolding) -> Vec<(AssetId, u128)> {
+	assets
+		.fungible_assets_iter()
+		.map(|asset| {
+			let Fungible(amount) = asset.fun else {
+				unreachable!("Asset should be fungible");
+			};
+			(asset.id, amount)
+		})
+		.collect()
+}
+
+fn amount_of(assets: &Assets, id: &AssetId) -> u128 {
+	assets
+		.inner()
+		.iter()
+		.find_map(|asset| match (&asset.id == id, &asset.fun) {
+			(true, Fungible(amount)) => Some(*amount),
+			_ => None,
+		})
+		.unwrap()
+}
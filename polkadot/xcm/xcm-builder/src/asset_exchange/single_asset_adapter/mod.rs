@@ -20,6 +20,6 @@ mod adapter;
 pub use adapter::SingleAssetExchangeAdapter;
 
 #[cfg(test)]
-mod mock;
+pub(super) mod mock;
 #[cfg(test)]
 mod tests;
@@ -24,7 +24,9 @@ use frame_support::{
 };
 use polkadot_parachain_primitives::primitives::IsSystem;
 use xcm::prelude::*;
-use xcm_executor::traits::{CheckSuspension, DenyExecution, OnResponse, Properties, ShouldExecute};
+use xcm_executor::traits::{
+	CheckRateLimit, CheckSuspension, DenyExecution, OnResponse, Properties, ShouldExecute,
+};
 
 /// Execution barrier that just takes `max_weight` from `properties.weight_credit`.
 ///
@@ -259,6 +261,9 @@ impl<InnerBarrier: ShouldExecute> ShouldExecute for TrailingSetTopicAsId<InnerBa
 
 /// Barrier condition that allows for a `SuspensionChecker` that controls whether or not the XCM
 /// executor will be suspended from executing the given XCM.
+///
+/// Suspended messages are not dropped: `ProcessMessageError::Yield` is returned, so that the
+/// message queue retries them once the suspension is lifted.
 pub struct RespectSuspension<Inner, SuspensionChecker>(PhantomData<(Inner, SuspensionChecker)>);
 impl<Inner, SuspensionChecker> ShouldExecute for RespectSuspension<Inner, SuspensionChecker>
 where
@@ -279,6 +284,38 @@ where
 	}
 }
 
+/// Barrier condition that throttles messages allowed by `Inner` whose origin exceeded its rate
+/// limits, as determined by `RateLimiter`.
+///
+/// Throttled messages are not dropped: `ProcessMessageError::Yield` is returned, so that the
+/// message queue retries them later.
+pub struct RespectRateLimits<Inner, RateLimiter>(PhantomData<(Inner, RateLimiter)>);
+impl<Inner, RateLimiter> ShouldExecute for RespectRateLimits<Inner, RateLimiter>
+where
+	Inner: ShouldExecute,
+	RateLimiter: CheckRateLimit,
+{
+	fn should_execute<Call>(
+		origin: &Location,
+		instructions: &mut [Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		// Only messages which would be executed count towards the limits.
+		Inner::should_execute(origin, instructions, max_weight, properties)?;
+		if RateLimiter::check_rate_limit(origin, max_weight) {
+			Ok(())
+		} else {
+			tracing::debug!(
+				target: "xcm::barriers",
+				?origin, ?max_weight,
+				"RespectRateLimits: origin exceeded its rate limits",
+			);
+			Err(ProcessMessageError::Yield)
+		}
+	}
+}
+
 /// Allows execution from any origin that is contained in `T` (i.e. `T::Contains(origin)`).
 ///
 /// Use only for executions from completely trusted origins, from which no permissionless messages
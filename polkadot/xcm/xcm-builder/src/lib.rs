@@ -37,15 +37,15 @@ pub use asset_conversion::{
 };
 
 mod asset_exchange;
-pub use asset_exchange::SingleAssetExchangeAdapter;
+pub use asset_exchange::{MultiAssetExchangeAdapter, SingleAssetExchangeAdapter};
 
 mod barriers;
 pub use barriers::{
 	AllowExplicitUnpaidExecutionFrom, AllowHrmpNotificationsFromRelayChain,
 	AllowKnownQueryResponses, AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom,
 	AllowUnpaidExecutionFrom, DenyRecursively, DenyReserveTransferToRelayChain, DenyThenTry,
-	IsChildSystemParachain, IsParentsOnly, IsSiblingSystemParachain, RespectSuspension,
-	TakeWeightCredit, TrailingSetTopicAsId, WithComputedOrigin,
+	IsChildSystemParachain, IsParentsOnly, IsSiblingSystemParachain, RespectRateLimits,
+	RespectSuspension, TakeWeightCredit, TrailingSetTopicAsId, WithComputedOrigin,
 };
 
 mod controller;
@@ -104,6 +104,9 @@ pub use nonfungibles_adapter::{
 	NonFungiblesAdapter, NonFungiblesMutateAdapter, NonFungiblesTransferAdapter,
 };
 
+mod nonfungibles_v2_adapter;
+pub use nonfungibles_v2_adapter::{NonFungibleData, NonFungiblesV2Adapter};
+
 mod nonfungible_adapter;
 pub use nonfungible_adapter::{
 	NonFungibleAdapter, NonFungibleMutateAdapter, NonFungibleTransferAdapter,
@@ -0,0 +1,333 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Adapter to work with [`frame_support::traits::tokens::nonfungibles_v2`] through XCM, exporting
+//! and importing the metadata and attributes of items.
+
+use crate::{AssetChecking, MintLocation};
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use core::{fmt::Debug, marker::PhantomData, result};
+use frame_support::{
+	ensure,
+	traits::{tokens::nonfungibles_v2, Get},
+};
+use xcm::latest::prelude::*;
+use xcm_executor::{
+	asset_data::AssetData,
+	traits::{ConvertLocation, Error as MatchError, MatchesNonFungibles, TransactAsset},
+};
+
+const LOG_TARGET: &str = "xcm::nonfungibles_v2_adapter";
+
+/// The maximum number of attributes imported along with an item.
+pub const MAX_IMPORTED_ATTRIBUTES: usize = 16;
+
+/// The data of a non-fungible item, exported and imported by [`NonFungiblesV2Adapter`].
+///
+/// This is the SCALE encoded [`xcm_executor::asset_data::AssetData`] of the item. Chains
+/// exchanging non-fungible items must both use it to keep their data.
+#[derive(Clone, Debug, Decode, Default, Encode, Eq, PartialEq)]
+pub struct NonFungibleData {
+	/// The metadata of the collection of the item.
+	pub collection_metadata: Option<Vec<u8>>,
+	/// The metadata of the item.
+	pub metadata: Option<Vec<u8>>,
+	/// The keys and values of the attributes of the item.
+	pub attributes: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// [`TransactAsset`] implementation that allows the use of a [`nonfungibles_v2`] implementation,
+/// like `pallet-nfts`, for handling an asset in the XCM executor.
+///
+/// The metadata and attributes of items are only exported and imported by runtimes opting in with
+/// `ExportsData`, otherwise [`TransactAsset::export_asset_data`] and
+/// [`TransactAsset::import_asset_data`] return [`XcmError::Unimplemented`]. They are exported as
+/// [`NonFungibleData`], and imported for derivative items, whose location is on another chain,
+/// only from that chain, their reserve. XCM cannot carry them along with the items yet, see
+/// [`xcm_executor::asset_data`]. When imported:
+/// - the metadata of the collection is only set if the local collection has none, so the metadata
+///   of collections is not overwritten by items of other chains;
+/// - the metadata of the item is set;
+/// - the attributes of the item are set with [`nonfungibles_v2::Mutate::set_attribute`], which
+///   `pallet-nfts` stores in its pallet namespace, so the attributes are carried along only once.
+///   Items with more than [`MAX_IMPORTED_ATTRIBUTES`] attributes have none imported.
+///
+/// `Matcher` maps the items of other chains to local collections, which must exist for the items
+/// to be deposited. Deposited items are minted with the default `ItemConfig`, with the deposit
+/// paid by the owner of the collection.
+pub struct NonFungiblesV2Adapter<
+	Assets,
+	Matcher,
+	AccountIdConverter,
+	AccountId,
+	ItemConfig,
+	CheckAsset,
+	CheckingAccount,
+	ExportsData,
+>(
+	PhantomData<(
+		Assets,
+		Matcher,
+		AccountIdConverter,
+		AccountId,
+		ItemConfig,
+		CheckAsset,
+		CheckingAccount,
+		ExportsData,
+	)>,
+);
+
+impl<
+		Assets: nonfungibles_v2::Mutate<AccountId, ItemConfig> + nonfungibles_v2::Transfer<AccountId>,
+		Matcher: MatchesNonFungibles<Assets::CollectionId, Assets::ItemId>,
+		AccountIdConverter: ConvertLocation<AccountId>,
+		AccountId: Clone + Eq,
+		ItemConfig: Default,
+		CheckAsset: AssetChecking<Assets::CollectionId>,
+		CheckingAccount: Get<Option<AccountId>>,
+		ExportsData: Get<bool>,
+	>
+	NonFungiblesV2Adapter<
+		Assets,
+		Matcher,
+		AccountIdConverter,
+		AccountId,
+		ItemConfig,
+		CheckAsset,
+		CheckingAccount,
+		ExportsData,
+	>
+{
+	fn can_accrue_checked(class: Assets::CollectionId, instance: Assets::ItemId) -> XcmResult {
+		ensure!(Assets::owner(&class, &instance).is_none(), XcmError::NotDepositable);
+		Ok(())
+	}
+	fn can_reduce_checked(class: Assets::CollectionId, instance: Assets::ItemId) -> XcmResult {
+		if let Some(checking_account) = CheckingAccount::get() {
+			// This is an asset whose teleports we track.
+			let owner = Assets::owner(&class, &instance);
+			ensure!(owner == Some(checking_account), XcmError::NotWithdrawable);
+			ensure!(Assets::can_transfer(&class, &instance), XcmError::NotWithdrawable);
+		}
+		Ok(())
+	}
+	fn accrue_checked(class: Assets::CollectionId, instance: Assets::ItemId) {
+		if let Some(checking_account) = CheckingAccount::get() {
+			let ok = Assets::mint_into(
+				&class,
+				&instance,
+				&checking_account,
+				&ItemConfig::default(),
+				true,
+			)
+			.is_ok();
+			debug_assert!(ok, "`mint_into` cannot generally fail; qed");
+		}
+	}
+	fn reduce_checked(class: Assets::CollectionId, instance: Assets::ItemId) {
+		let ok = Assets::burn(&class, &instance, None).is_ok();
+		debug_assert!(ok, "`can_check_in` must have returned `true` immediately prior; qed");
+	}
+}
+
+impl<
+		Assets: nonfungibles_v2::Mutate<AccountId, ItemConfig> + nonfungibles_v2::Transfer<AccountId>,
+		Matcher: MatchesNonFungibles<Assets::CollectionId, Assets::ItemId>,
+		AccountIdConverter: ConvertLocation<AccountId>,
+		AccountId: Clone + Eq + Debug,
+		ItemConfig: Default,
+		CheckAsset: AssetChecking<Assets::CollectionId>,
+		CheckingAccount: Get<Option<AccountId>>,
+		ExportsData: Get<bool>,
+	> TransactAsset
+	for NonFungiblesV2Adapter<
+		Assets,
+		Matcher,
+		AccountIdConverter,
+		AccountId,
+		ItemConfig,
+		CheckAsset,
+		CheckingAccount,
+		ExportsData,
+	>
+where
+	Assets::CollectionId: Debug,
+	Assets::ItemId: Debug,
+{
+	fn can_check_in(origin: &Location, what: &Asset, context: &XcmContext) -> XcmResult {
+		tracing::trace!(target: LOG_TARGET, ?origin, ?what, ?context, "can_check_in");
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		match CheckAsset::asset_checking(&class) {
+			// We track this asset's teleports to ensure no more come in than have gone out.
+			Some(MintLocation::Local) => Self::can_reduce_checked(class, instance),
+			// We track this asset's teleports to ensure no more go out than have come in.
+			Some(MintLocation::NonLocal) => Self::can_accrue_checked(class, instance),
+			_ => Ok(()),
+		}
+	}
+
+	fn check_in(origin: &Location, what: &Asset, context: &XcmContext) {
+		tracing::trace!(target: LOG_TARGET, ?origin, ?what, ?context, "check_in");
+		if let Ok((class, instance)) = Matcher::matches_nonfungibles(what) {
+			match CheckAsset::asset_checking(&class) {
+				// We track this asset's teleports to ensure no more come in than have gone out.
+				Some(MintLocation::Local) => Self::reduce_checked(class, instance),
+				// We track this asset's teleports to ensure no more go out than have come in.
+				Some(MintLocation::NonLocal) => Self::accrue_checked(class, instance),
+				_ => (),
+			}
+		}
+	}
+
+	fn can_check_out(dest: &Location, what: &Asset, context: &XcmContext) -> XcmResult {
+		tracing::trace!(target: LOG_TARGET, ?dest, ?what, ?context, "can_check_out");
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		match CheckAsset::asset_checking(&class) {
+			// We track this asset's teleports to ensure no more come in than have gone out.
+			Some(MintLocation::Local) => Self::can_accrue_checked(class, instance),
+			// We track this asset's teleports to ensure no more go out than have come in.
+			Some(MintLocation::NonLocal) => Self::can_reduce_checked(class, instance),
+			_ => Ok(()),
+		}
+	}
+
+	fn check_out(dest: &Location, what: &Asset, context: &XcmContext) {
+		tracing::trace!(target: LOG_TARGET, ?dest, ?what, ?context, "check_out");
+		if let Ok((class, instance)) = Matcher::matches_nonfungibles(what) {
+			match CheckAsset::asset_checking(&class) {
+				// We track this asset's teleports to ensure no more come in than have gone out.
+				Some(MintLocation::Local) => Self::accrue_checked(class, instance),
+				// We track this asset's teleports to ensure no more go out than have come in.
+				Some(MintLocation::NonLocal) => Self::reduce_checked(class, instance),
+				_ => (),
+			}
+		}
+	}
+
+	fn deposit_asset(what: &Asset, who: &Location, context: Option<&XcmContext>) -> XcmResult {
+		tracing::trace!(target: LOG_TARGET, ?what, ?who, ?context, "deposit_asset");
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		let who = AccountIdConverter::convert_location(who)
+			.ok_or(MatchError::AccountIdConversionFailed)?;
+		Assets::mint_into(&class, &instance, &who, &ItemConfig::default(), true).map_err(|e| {
+			tracing::debug!(target: LOG_TARGET, ?e, ?class, ?instance, ?who, "Failed to mint asset");
+			XcmError::FailedToTransactAsset(e.into())
+		})
+	}
+
+	fn withdraw_asset(
+		what: &Asset,
+		who: &Location,
+		maybe_context: Option<&XcmContext>,
+	) -> result::Result<xcm_executor::AssetsInHolding, XcmError> {
+		tracing::trace!(target: LOG_TARGET, ?what, ?who, ?maybe_context, "withdraw_asset");
+		// Check we handle this asset.
+		let who = AccountIdConverter::convert_location(who)
+			.ok_or(MatchError::AccountIdConversionFailed)?;
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		Assets::burn(&class, &instance, Some(&who)).map_err(|e| {
+			tracing::debug!(target: LOG_TARGET, ?e, ?class, ?instance, ?who, "Failed to burn asset");
+			XcmError::FailedToTransactAsset(e.into())
+		})?;
+		Ok(what.clone().into())
+	}
+
+	fn internal_transfer_asset(
+		what: &Asset,
+		from: &Location,
+		to: &Location,
+		context: &XcmContext,
+	) -> result::Result<xcm_executor::AssetsInHolding, XcmError> {
+		tracing::trace!(target: LOG_TARGET, ?what, ?from, ?to, ?context, "internal_transfer_asset");
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		let destination = AccountIdConverter::convert_location(to)
+			.ok_or(MatchError::AccountIdConversionFailed)?;
+		Assets::transfer(&class, &instance, &destination).map_err(|e| {
+			tracing::debug!(target: LOG_TARGET, ?e, ?class, ?instance, ?destination, "Failed to transfer asset");
+			XcmError::FailedToTransactAsset(e.into())
+		})?;
+		Ok(what.clone().into())
+	}
+
+	fn export_asset_data(
+		what: &Asset,
+		context: &XcmContext,
+	) -> result::Result<AssetData, XcmError> {
+		tracing::trace!(target: LOG_TARGET, ?what, ?context, "export_asset_data");
+		ensure!(ExportsData::get(), XcmError::Unimplemented);
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		let data = NonFungibleData {
+			collection_metadata: Assets::collection_metadata(&class),
+			metadata: Assets::item_metadata(&class, &instance),
+			attributes: Assets::item_attributes(&class, &instance),
+		};
+		if data == NonFungibleData::default() {
+			return Ok(AssetData::new())
+		}
+		AssetData::try_from(data.encode()).map_err(|_| {
+			tracing::debug!(target: LOG_TARGET, ?class, ?instance, "Asset data too large");
+			XcmError::ExceedsMaxMessageSize
+		})
+	}
+
+	fn import_asset_data(
+		what: &Asset,
+		data: &[u8],
+		origin: &Location,
+		context: &XcmContext,
+	) -> XcmResult {
+		tracing::trace!(target: LOG_TARGET, ?what, ?origin, ?context, "import_asset_data");
+		ensure!(ExportsData::get(), XcmError::Unimplemented);
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		// Only the reserve of derivative items may set their data.
+		let reserve = what.id.0.chain_location();
+		ensure!(
+			reserve != Location::here() && &reserve == origin,
+			XcmError::UntrustedReserveLocation
+		);
+		let data = NonFungibleData::decode(&mut &data[..]).map_err(|e| {
+			tracing::debug!(target: LOG_TARGET, ?e, ?class, ?instance, "Failed to decode asset data");
+			XcmError::FailedToDecode
+		})?;
+		ensure!(
+			data.attributes.len() <= MAX_IMPORTED_ATTRIBUTES,
+			XcmError::FailedToTransactAsset("Too many attributes")
+		);
+		let map_err = |e: sp_runtime::DispatchError| {
+			tracing::debug!(target: LOG_TARGET, ?e, ?class, ?instance, "Failed to import asset data");
+			XcmError::FailedToTransactAsset(e.into())
+		};
+		if let Some(metadata) = data.collection_metadata {
+			if Assets::collection_metadata(&class).is_none() {
+				Assets::set_collection_metadata(None, &class, &metadata).map_err(map_err)?;
+			}
+		}
+		if let Some(metadata) = data.metadata {
+			Assets::set_item_metadata(None, &class, &instance, &metadata).map_err(map_err)?;
+		}
+		for (key, value) in data.attributes {
+			Assets::set_attribute(&class, &instance, &key, &value).map_err(map_err)?;
+		}
+		Ok(())
+	}
+}
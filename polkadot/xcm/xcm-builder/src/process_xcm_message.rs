@@ -20,7 +20,7 @@ use codec::{Decode, DecodeLimit, FullCodec, MaxEncodedLen};
 use core::{fmt::Debug, marker::PhantomData};
 use frame_support::{
 	dispatch::GetDispatchInfo,
-	traits::{ProcessMessage, ProcessMessageError},
+	traits::{Get, ProcessMessage, ProcessMessageError},
 };
 use scale_info::TypeInfo;
 use sp_weights::{Weight, WeightMeter};
@@ -29,14 +29,19 @@ use xcm::{prelude::*, MAX_XCM_DECODE_DEPTH};
 const LOG_TARGET: &str = "xcm::process-message";
 
 /// A message processor that delegates execution to an `XcmExecutor`.
-pub struct ProcessXcmMessage<MessageOrigin, XcmExecutor, Call>(
-	PhantomData<(MessageOrigin, XcmExecutor, Call)>,
+///
+/// Messages yielded by the barrier of the executor, e.g. because their origin is throttled, are
+/// kept to be processed again later. They are only charged `YieldWeight`, which should cover the
+/// checks made by the barrier, rather than the weight of the whole message.
+pub struct ProcessXcmMessage<MessageOrigin, XcmExecutor, Call, YieldWeight = ()>(
+	PhantomData<(MessageOrigin, XcmExecutor, Call, YieldWeight)>,
 );
 impl<
 		MessageOrigin: Into<Location> + FullCodec + MaxEncodedLen + Clone + Eq + PartialEq + TypeInfo + Debug,
 		XcmExecutor: ExecuteXcm<Call>,
 		Call: Decode + GetDispatchInfo,
-	> ProcessMessage for ProcessXcmMessage<MessageOrigin, XcmExecutor, Call>
+		YieldWeight: Get<Weight>,
+	> ProcessMessage for ProcessXcmMessage<MessageOrigin, XcmExecutor, Call, YieldWeight>
 {
 	type Origin = MessageOrigin;
 
@@ -104,18 +109,24 @@ impl<
 				);
 				(used, Ok(false))
 			},
-			// In the error-case we assume the worst case and consume all possible weight.
+			// In the error-case we assume the worst case and consume all possible weight, unless the
+			// message is only yielded.
 			Outcome::Error { error } => {
 				tracing::trace!(
 					target: LOG_TARGET,
 					"XCM message execution error: {error:?}",
 				);
-				let error = match error {
-					xcm::latest::Error::ExceedsStackLimit => ProcessMessageError::StackLimitReached,
-					_ => ProcessMessageError::Unsupported,
+				let (consumed, error) = match error {
+					xcm::latest::Error::ExceedsStackLimit =>
+						(required, ProcessMessageError::StackLimitReached),
+					// The barrier yielded, the message should be processed again later. Only the
+					// checks of the barrier were made.
+					xcm::latest::Error::Barrier =>
+						(YieldWeight::get().min(required), ProcessMessageError::Yield),
+					_ => (required, ProcessMessageError::Unsupported),
 				};
 
-				(required, Err(error))
+				(consumed, Err(error))
 			},
 		};
 		meter.consume(consumed);
@@ -15,6 +15,7 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::marker::PhantomData;
+use xcm::VersionedXcm;
 use xcm_executor::traits::Properties;
 
 use super::*;
@@ -741,6 +742,161 @@ fn suspension_should_work() {
 	assert_eq!(r, Ok(()));
 }
 
+#[test]
+fn rate_limits_should_work() {
+	TestRateLimiter::set_limit(Some(15));
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+	type Barrier =
+		RespectRateLimits<AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>, TestRateLimiter>;
+
+	let mut message =
+		Xcm::<()>(vec![TransferAsset { assets: (Parent, 100).into(), beneficiary: Here.into() }]);
+	let r = Barrier::should_execute(
+		&Parent.into(),
+		message.inner_mut(),
+		Weight::from_parts(10, 10),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Ok(()));
+
+	// Messages rejected by the inner barrier don't use up the limit.
+	let r = Barrier::should_execute(
+		&Parachain(1).into(),
+		message.inner_mut(),
+		Weight::from_parts(5, 5),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Err(ProcessMessageError::Unsupported));
+	assert_eq!(RATE_LIMIT.with(|l| l.get()), Some(5));
+
+	// Over the limit.
+	let r = Barrier::should_execute(
+		&Parent.into(),
+		message.inner_mut(),
+		Weight::from_parts(10, 10),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Err(ProcessMessageError::Yield));
+
+	// Within the limit again.
+	let r = Barrier::should_execute(
+		&Parent.into(),
+		message.inner_mut(),
+		Weight::from_parts(5, 5),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Ok(()));
+	TestRateLimiter::set_limit(None);
+}
+
+#[test]
+fn throttled_messages_are_not_consumed() {
+	use frame_support::traits::ProcessMessage;
+	use sp_weights::WeightMeter;
+
+	parameter_types! {
+		pub const RateLimitCheck: Weight = Weight::from_parts(2, 2);
+	}
+	type Processor =
+		ProcessXcmMessage<Location, XcmExecutor<TestConfig>, TestCall, RateLimitCheck>;
+
+	// we'll let them have message execution for free.
+	AllowUnpaidFrom::set(vec![[Parachain(1)].into()]);
+	// Child parachain #1 owns 1000 tokens held by us in reserve.
+	add_asset(Parachain(1), (Here, 1000));
+	let beneficiary = AccountIndex64 { index: 3, network: None };
+	let message = VersionedXcm::from(Xcm::<TestCall>(vec![TransferAsset {
+		assets: (Here, 100u128).into(),
+		beneficiary: [beneficiary].into(),
+	}]))
+	.encode();
+
+	// The message would exceed the limit, so it is yielded and kept for a later retry.
+	TestRateLimiter::set_limit(Some(5));
+	let mut meter = WeightMeter::new();
+	assert_err!(
+		Processor::process_message(&message, Parachain(1).into(), &mut meter, &mut [0; 32]),
+		ProcessMessageError::Yield,
+	);
+	// Only the check of the rate limit is charged.
+	assert_eq!(meter.consumed(), RateLimitCheck::get());
+	assert_eq!(asset_list(beneficiary), vec![]);
+	assert_eq!(asset_list(Parachain(1)), vec![(Here, 1000u128).into()]);
+
+	// Once the limit allows it, the same message is executed.
+	TestRateLimiter::set_limit(Some(15));
+	assert_eq!(
+		Processor::process_message(&message, Parachain(1).into(), &mut meter, &mut [0; 32]),
+		Ok(true),
+	);
+	assert_eq!(asset_list(beneficiary), vec![(Here, 100u128).into()]);
+	assert_eq!(asset_list(Parachain(1)), vec![(Here, 900u128).into()]);
+	TestRateLimiter::set_limit(None);
+}
+
+#[test]
+fn suspended_messages_are_not_consumed() {
+	use frame_support::traits::ProcessMessage;
+	use sp_weights::WeightMeter;
+
+	type Processor = ProcessXcmMessage<Location, XcmExecutor<TestConfig>, TestCall>;
+
+	AllowUnpaidFrom::set(vec![[Parachain(1)].into()]);
+	add_asset(Parachain(1), (Here, 1000));
+	let beneficiary = AccountIndex64 { index: 3, network: None };
+	let message = VersionedXcm::from(Xcm::<TestCall>(vec![TransferAsset {
+		assets: (Here, 100u128).into(),
+		beneficiary: [beneficiary].into(),
+	}]))
+	.encode();
+
+	// `RespectSuspension` yields, so the message is kept for a later retry.
+	TestSuspender::set_suspended(true);
+	let mut meter = WeightMeter::new();
+	assert_err!(
+		Processor::process_message(&message, Parachain(1).into(), &mut meter, &mut [0; 32]),
+		ProcessMessageError::Yield,
+	);
+	assert_eq!(asset_list(beneficiary), vec![]);
+	assert_eq!(asset_list(Parachain(1)), vec![(Here, 1000u128).into()]);
+
+	// Once the suspension is lifted, the same message is executed.
+	TestSuspender::set_suspended(false);
+	assert_eq!(
+		Processor::process_message(&message, Parachain(1).into(), &mut meter, &mut [0; 32]),
+		Ok(true),
+	);
+	assert_eq!(asset_list(beneficiary), vec![(Here, 100u128).into()]);
+	assert_eq!(asset_list(Parachain(1)), vec![(Here, 900u128).into()]);
+}
+
+#[test]
+fn denied_messages_are_consumed() {
+	use frame_support::traits::ProcessMessage;
+	use sp_weights::WeightMeter;
+
+	type Processor = ProcessXcmMessage<Location, XcmExecutor<TestConfig>, TestCall>;
+
+	// No barrier allows messages of child parachain #2.
+	add_asset(Parachain(2), (Here, 1000));
+	let beneficiary = AccountIndex64 { index: 3, network: None };
+	let message = VersionedXcm::from(Xcm::<TestCall>(vec![TransferAsset {
+		assets: (Here, 100u128).into(),
+		beneficiary: [beneficiary].into(),
+	}]))
+	.encode();
+
+	// The message is reported as processed without success, so the queue drops it.
+	let mut meter = WeightMeter::new();
+	assert_eq!(
+		Processor::process_message(&message, Parachain(2).into(), &mut meter, &mut [0; 32]),
+		Ok(false),
+	);
+	assert!(meter.consumed().any_gt(Weight::zero()));
+	assert_eq!(asset_list(beneficiary), vec![]);
+	assert_eq!(asset_list(Parachain(2)), vec![(Here, 1000u128).into()]);
+}
+
 #[test]
 fn allow_subscriptions_from_should_work() {
 	// allow only parent
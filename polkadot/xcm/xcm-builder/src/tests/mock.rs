@@ -17,7 +17,9 @@
 //! Mock implementations to test XCM builder configuration types.
 
 use crate::{
-	barriers::{AllowSubscriptionsFrom, RespectSuspension, TrailingSetTopicAsId},
+	barriers::{
+		AllowSubscriptionsFrom, RespectRateLimits, RespectSuspension, TrailingSetTopicAsId,
+	},
 	test_utils::*,
 	EnsureDecodableXcm,
 };
@@ -43,8 +45,8 @@ pub use frame_support::{
 pub use xcm::latest::{prelude::*, QueryId, Weight};
 pub use xcm_executor::{
 	traits::{
-		AssetExchange, AssetLock, CheckSuspension, ConvertOrigin, DenyExecution, Enact, ExportXcm,
-		FeeManager, FeeReason, LockError, OnResponse, Properties, QueryHandler,
+		AssetExchange, AssetLock, CheckRateLimit, CheckSuspension, ConvertOrigin, DenyExecution,
+		Enact, ExportXcm, FeeManager, FeeReason, LockError, OnResponse, Properties, QueryHandler,
 		QueryResponseStatus, TransactAsset,
 	},
 	AssetsInHolding, Config,
@@ -136,6 +138,7 @@ thread_local! {
 	)>> = RefCell::new(None);
 	pub static SEND_PRICE: RefCell<Assets> = RefCell::new(Assets::new());
 	pub static SUSPENDED: Cell<bool> = Cell::new(false);
+	pub static RATE_LIMIT: Cell<Option<u64>> = Cell::new(None);
 }
 pub fn sent_xcm() -> Vec<(Location, opaque::Xcm, XcmHash)> {
 	SENT_XCM.with(|q| (*q.borrow()).clone())
@@ -506,6 +509,27 @@ impl TestSuspender {
 	}
 }
 
+/// Allows a total `ref_time` across all origins, or everything if no limit is set.
+pub struct TestRateLimiter;
+impl CheckRateLimit for TestRateLimiter {
+	fn check_rate_limit(_origin: &Location, max_weight: Weight) -> bool {
+		RATE_LIMIT.with(|limit| match limit.get() {
+			None => true,
+			Some(remaining) if max_weight.ref_time() <= remaining => {
+				limit.set(Some(remaining - max_weight.ref_time()));
+				true
+			},
+			Some(_) => false,
+		})
+	}
+}
+
+impl TestRateLimiter {
+	pub fn set_limit(limit: Option<u64>) {
+		RATE_LIMIT.with(|l| l.set(limit));
+	}
+}
+
 pub type TestBarrier = (
 	TakeWeightCredit,
 	AllowKnownQueryResponses<TestResponseHandler>,
@@ -748,7 +772,9 @@ impl Config for TestConfig {
 	type IsReserve = TestIsReserve;
 	type IsTeleporter = TestIsTeleporter;
 	type UniversalLocation = ExecutorUniversalLocation;
-	type Barrier = TrailingSetTopicAsId<RespectSuspension<TestBarrier, TestSuspender>>;
+	type Barrier = TrailingSetTopicAsId<
+		RespectSuspension<RespectRateLimits<TestBarrier, TestRateLimiter>, TestSuspender>,
+	>;
 	type Weigher = FixedWeightBounds<UnitWeightCost, TestCall, MaxInstructions>;
 	type Trader = FixedRateOfFungible<WeightPrice, ()>;
 	type ResponseHandler = TestResponseHandler;
@@ -770,6 +796,7 @@ impl Config for TestConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = ();
+	type XcmTracer = ();
 }
 
 pub fn fungible_multi_asset(location: Location, amount: u128) -> Asset {
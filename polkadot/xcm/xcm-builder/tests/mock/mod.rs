@@ -196,6 +196,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = XcmPallet;
+	type XcmTracer = XcmPallet;
 }
 
 /// Converts a local signed origin into an XCM location. Forms the basis for local origins
@@ -400,6 +400,7 @@ fn recursive_xcm_execution_fail() {
 		type HrmpChannelAcceptedHandler = ();
 		type HrmpChannelClosingHandler = ();
 		type XcmRecorder = XcmPallet;
+		type XcmTracer = XcmPallet;
 	}
 
 	let para_acc: AccountId = ParaId::from(PARA_ID).into_account_truncating();
@@ -0,0 +1,335 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A chain, made of its runtime and its state, on which runtime APIs can be called.
+
+use std::{borrow::Cow, path::PathBuf};
+
+use assets_common::runtime_api::{FungiblesAccessError, FungiblesApi};
+use codec::{Decode, Encode, Input};
+use frame_support::{storage::storage_prefix, Blake2_128Concat, StorageHasher};
+use remote_externalities::{Builder, Mode, OfflineConfig, RemoteExternalities, SnapshotConfig};
+use sc_executor::WasmExecutor;
+use sp_api::RuntimeApiInfo;
+use sp_core::{
+	storage::well_known_keys,
+	traits::{CallContext, RuntimeCode, WrappedRuntimeCode},
+	OpaqueMetadata,
+};
+use sp_externalities::Extensions;
+use sp_runtime::{generic, traits::BlakeTwo256, AccountId32, OpaqueExtrinsic};
+use sp_state_machine::{Backend, OverlayedChanges, StateMachine};
+use sp_version::RuntimeVersion;
+use xcm::prelude::*;
+use xcm_runtime_apis::{
+	dry_run::{CallDryRunEffects, DryRunApi, Error as DryRunError, XcmDryRunEffects},
+	fees::Error as FeesError,
+};
+
+use crate::{
+	events::{Event, EventDecoder, METADATA_VERSION},
+	Error, Result,
+};
+
+const LOG_TARGET: &str = "xcm::dry-run-orchestrator";
+
+/// The block type used for loading snapshots, shared by the relay chain and parachains.
+pub type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+/// Host functions of both relay chain and parachain runtimes.
+type HostFunctions = (
+	cumulus_primitives_proof_size_hostfunction::storage_proof_size::HostFunctions,
+	sp_io::SubstrateHostFunctions,
+);
+
+/// The configuration of a chain.
+#[derive(Clone, Debug)]
+pub struct ChainConfig {
+	/// The name of the chain, used for referring to it.
+	pub name: String,
+	/// The para id of the chain, or `None` for the relay chain.
+	pub para_id: Option<u32>,
+	/// The state snapshot, created by `remote-externalities`.
+	pub snapshot: PathBuf,
+	/// The runtime to use instead of the one in the snapshot.
+	///
+	/// No migrations are run, so the runtime must be compatible with the state.
+	pub runtime: Option<PathBuf>,
+}
+
+/// The runtime of a chain and its state.
+struct Runtime {
+	ext: RemoteExternalities<Block>,
+	executor: WasmExecutor<HostFunctions>,
+	code: Vec<u8>,
+	code_hash: Vec<u8>,
+	heap_pages: Option<u64>,
+}
+
+impl Runtime {
+	/// Call a runtime API, keeping the changes it makes to the state.
+	fn call(&mut self, method: &str, args: &[u8]) -> core::result::Result<Vec<u8>, String> {
+		let wrapped_code = WrappedRuntimeCode(Cow::Borrowed(&self.code));
+		let runtime_code = RuntimeCode {
+			code_fetcher: &wrapped_code,
+			heap_pages: self.heap_pages,
+			hash: self.code_hash.clone(),
+		};
+		let mut overlay = OverlayedChanges::default();
+		let result = StateMachine::new(
+			&self.ext.backend,
+			&mut overlay,
+			&self.executor,
+			method,
+			args,
+			&mut Extensions::default(),
+			&runtime_code,
+			CallContext::Offchain,
+		)
+		.execute()
+		.map_err(|e| e.to_string())?;
+
+		let changes = overlay.drain_storage_changes(&self.ext.backend, self.ext.state_version)?;
+		self.ext
+			.backend
+			.apply_transaction(changes.transaction_storage_root, changes.transaction);
+		Ok(result)
+	}
+
+	fn storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.ext.backend.storage(key).ok().flatten()
+	}
+}
+
+/// A chain loaded from a state snapshot.
+pub struct Chain {
+	name: String,
+	location: Location,
+	runtime: Runtime,
+	version: RuntimeVersion,
+	events: EventDecoder,
+}
+
+impl Chain {
+	/// Load a chain from its configuration.
+	pub async fn load(config: ChainConfig) -> Result<Self> {
+		let load_error = |reason: String| Error::Load { chain: config.name.clone(), reason };
+
+		log::info!(target: LOG_TARGET, "Loading chain `{}` from {:?}", config.name, config.snapshot);
+		let ext = Builder::<Block>::new()
+			.mode(Mode::Offline(OfflineConfig {
+				state_snapshot: SnapshotConfig::new(config.snapshot.clone()),
+			}))
+			.build()
+			.await
+			.map_err(|e| load_error(e.into()))?;
+
+		let code = match &config.runtime {
+			Some(path) => std::fs::read(path)
+				.map_err(|e| load_error(format!("Failed to read {path:?}: {e}")))?,
+			None => ext
+				.backend
+				.storage(well_known_keys::CODE)
+				.ok()
+				.flatten()
+				.ok_or_else(|| load_error("No runtime code in the snapshot".into()))?,
+		};
+		let heap_pages = ext
+			.backend
+			.storage(well_known_keys::HEAP_PAGES)
+			.ok()
+			.flatten()
+			.and_then(|pages| u64::decode(&mut &pages[..]).ok());
+
+		let mut runtime = Runtime {
+			ext,
+			executor: WasmExecutor::builder().build(),
+			code_hash: sp_core::blake2_256(&code).to_vec(),
+			code,
+			heap_pages,
+		};
+
+		let version = runtime.call("Core_version", &[]).map_err(load_error)?;
+		let version = RuntimeVersion::decode(&mut &version[..])
+			.map_err(|e| load_error(format!("Failed to decode the runtime version: {e}")))?;
+		let metadata = runtime
+			.call("Metadata_metadata_at_version", &METADATA_VERSION.encode())
+			.map_err(load_error)?;
+		let metadata = Option::<OpaqueMetadata>::decode(&mut &metadata[..])
+			.map_err(|e| load_error(format!("Failed to decode the metadata: {e}")))?
+			.ok_or_else(|| load_error(format!("Metadata V{METADATA_VERSION} not supported")))?;
+		let events = EventDecoder::new(&metadata).map_err(load_error)?;
+
+		let location =
+			config.para_id.map_or(Location::here(), |id| Location::new(0, [Parachain(id)]));
+		Ok(Self { name: config.name, location, runtime, version, events })
+	}
+
+	/// The name of the chain.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// The location of the chain, relative to the relay chain.
+	pub fn location(&self) -> &Location {
+		&self.location
+	}
+
+	/// Dry-run an extrinsic.
+	///
+	/// `origin` and `call` are the SCALE-encoded `OriginCaller` and `RuntimeCall` of the runtime.
+	pub fn dry_run_call(
+		&mut self,
+		origin: &[u8],
+		call: &[u8],
+		result_xcms_version: XcmVersion,
+	) -> Result<CallDryRunEffects<Event>> {
+		const METHOD: &str = "DryRunApi_dry_run_call";
+		let mut args = [origin, call].concat();
+		match self.version.api_version(&<dyn DryRunApi<Block, (), (), ()>>::ID) {
+			None => return Err(self.unsupported("DryRunApi")),
+			// Older versions always return the XCMs in the latest version.
+			Some(1) => {},
+			Some(_) => result_xcms_version.encode_to(&mut args),
+		}
+		let result = self.call(METHOD, &args)?;
+		self.decode_dry_run(METHOD, &result, |input, events| {
+			let execution_result = Decode::decode(input).map_err(|e| e.to_string())?;
+			let emitted_events = events.decode_events(input)?;
+			let local_xcm = Decode::decode(input).map_err(|e| e.to_string())?;
+			let forwarded_xcms = Decode::decode(input).map_err(|e| e.to_string())?;
+			Ok(CallDryRunEffects { execution_result, emitted_events, local_xcm, forwarded_xcms })
+		})
+	}
+
+	/// Dry-run an XCM program.
+	pub fn dry_run_xcm(
+		&mut self,
+		origin: VersionedLocation,
+		xcm: VersionedXcm<()>,
+	) -> Result<XcmDryRunEffects<Event>> {
+		const METHOD: &str = "DryRunApi_dry_run_xcm";
+		if self.version.api_version(&<dyn DryRunApi<Block, (), (), ()>>::ID).is_none() {
+			return Err(self.unsupported("DryRunApi"))
+		}
+		// `VersionedXcm<()>` is encoded like a `VersionedXcm<RuntimeCall>`.
+		let result = self.call(METHOD, &(origin, xcm).encode())?;
+		self.decode_dry_run(METHOD, &result, |input, events| {
+			let execution_result = Decode::decode(input).map_err(|e| e.to_string())?;
+			let emitted_events = events.decode_events(input)?;
+			let forwarded_xcms = Decode::decode(input).map_err(|e| e.to_string())?;
+			Ok(XcmDryRunEffects { execution_result, emitted_events, forwarded_xcms })
+		})
+	}
+
+	/// The price of `weight` in the given asset.
+	pub fn weight_to_asset_fee(&mut self, weight: Weight, asset: VersionedAssetId) -> Result<u128> {
+		const METHOD: &str = "XcmPaymentApi_query_weight_to_asset_fee";
+		let result = self.call(METHOD, &(weight, asset).encode())?;
+		self.decode::<core::result::Result<u128, FeesError>>(METHOD, &result)?
+			.map_err(|e| self.runtime_call_error(METHOD, format!("{e:?}")))
+	}
+
+	/// The fees for delivering `message` to `destination`.
+	pub fn delivery_fees(
+		&mut self,
+		destination: VersionedLocation,
+		message: VersionedXcm<()>,
+	) -> Result<VersionedAssets> {
+		const METHOD: &str = "XcmPaymentApi_query_delivery_fees";
+		let result = self.call(METHOD, &(destination, message).encode())?;
+		self.decode::<core::result::Result<VersionedAssets, FeesError>>(METHOD, &result)?
+			.map_err(|e| self.runtime_call_error(METHOD, format!("{e:?}")))
+	}
+
+	/// The assets held by `account`.
+	///
+	/// All assets are returned if the runtime supports `FungiblesApi`, otherwise only the free
+	/// balance of the native token.
+	pub fn balances(&mut self, account: &AccountId32) -> Result<Vec<Asset>> {
+		const METHOD: &str = "FungiblesApi_query_account_balances";
+		match self.version.api_version(&<dyn FungiblesApi<Block, AccountId32>>::ID) {
+			Some(version) if version >= 2 => {
+				let result = self.call(METHOD, &account.encode())?;
+				let assets = self
+					.decode::<core::result::Result<VersionedAssets, FungiblesAccessError>>(
+						METHOD, &result,
+					)?
+					.map_err(|e| self.runtime_call_error(METHOD, format!("{e:?}")))?;
+				let assets = Assets::try_from(assets)
+					.map_err(|()| self.decode_error(METHOD, "Unsupported XCM version".into()))?;
+				Ok(assets.into_inner())
+			},
+			_ => {
+				let key = [
+					&storage_prefix(b"System", b"Account")[..],
+					&Blake2_128Concat::hash(account.as_ref())[..],
+				]
+				.concat();
+				let free = self
+					.runtime
+					.storage(&key)
+					.and_then(|info| {
+						frame_system::AccountInfo::<u32, pallet_balances::AccountData<u128>>::decode(
+							&mut &info[..],
+						)
+						.ok()
+					})
+					.map_or(0, |info| info.data.free);
+				Ok(if free > 0 { vec![(Here, free).into()] } else { Vec::new() })
+			},
+		}
+	}
+
+	fn call(&mut self, method: &'static str, args: &[u8]) -> Result<Vec<u8>> {
+		log::debug!(target: LOG_TARGET, "Calling `{method}` on `{}`", self.name);
+		self.runtime
+			.call(method, args)
+			.map_err(|reason| self.runtime_call_error(method, reason))
+	}
+
+	fn decode<T: Decode>(&self, method: &'static str, mut input: &[u8]) -> Result<T> {
+		T::decode(&mut input).map_err(|e| self.decode_error(method, e.to_string()))
+	}
+
+	/// Decode the result of a `DryRunApi` method, which contains runtime events.
+	fn decode_dry_run<T>(
+		&self,
+		method: &'static str,
+		mut input: &[u8],
+		decode_effects: impl FnOnce(&mut &[u8], &EventDecoder) -> core::result::Result<T, String>,
+	) -> Result<T> {
+		let result = match input.read_byte() {
+			Ok(0) => decode_effects(&mut input, &self.events).map(Ok),
+			Ok(1) => DryRunError::decode(&mut input).map(Err).map_err(|e| e.to_string()),
+			_ => Err("Invalid `Result` encoding".into()),
+		}
+		.map_err(|reason| self.decode_error(method, reason))?;
+		result.map_err(|error| Error::DryRun { chain: self.name.clone(), error })
+	}
+
+	fn runtime_call_error(&self, method: &'static str, reason: String) -> Error {
+		Error::RuntimeCall { chain: self.name.clone(), method, reason }
+	}
+
+	fn decode_error(&self, method: &'static str, reason: String) -> Error {
+		Error::Decode { chain: self.name.clone(), method, reason }
+	}
+
+	fn unsupported(&self, api: &'static str) -> Error {
+		Error::Unsupported { chain: self.name.clone(), api }
+	}
+}
@@ -0,0 +1,137 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding of runtime events without knowing the runtime types, using the runtime metadata.
+
+use std::collections::BTreeSet;
+
+use codec::{Compact, Decode};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use scale_info::PortableRegistry;
+use scale_value::ValueDef;
+use serde::Serialize;
+use sp_core::H256;
+use xcm::prelude::*;
+
+/// The metadata version used for decoding events. It is the first one describing the outer event
+/// enum of the runtime.
+pub(crate) const METADATA_VERSION: u32 = 15;
+
+/// An event emitted by a runtime.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+	/// The name of the pallet which emitted the event.
+	pub pallet: String,
+	/// The name of the event.
+	pub name: String,
+	/// The event, with its fields, in a human-readable form.
+	pub details: String,
+	/// Whether the event was emitted by `pallet-xcm`.
+	#[serde(skip)]
+	from_xcm_pallet: bool,
+	/// The encoded event.
+	#[serde(skip)]
+	encoded: Vec<u8>,
+}
+
+impl Event {
+	/// The origin and the assets, if this is a `pallet-xcm` event about trapping assets.
+	pub fn trapped_assets(&self) -> Option<(Location, VersionedAssets)> {
+		if !self.from_xcm_pallet || self.name != "AssetsTrapped" {
+			return None
+		}
+		// Skip the pallet and the variant index.
+		let (_hash, origin, assets) =
+			<(H256, Location, VersionedAssets)>::decode(&mut self.encoded.get(2..)?).ok()?;
+		Some((origin, assets))
+	}
+}
+
+/// Decodes the events of a runtime.
+pub(crate) struct EventDecoder {
+	types: PortableRegistry,
+	/// The type of the outer event enum, `RuntimeEvent`.
+	event_ty: u32,
+	/// The indices of the instances of `pallet-xcm`.
+	xcm_pallets: BTreeSet<u8>,
+}
+
+impl EventDecoder {
+	/// Create a decoder from the encoded runtime metadata.
+	pub fn new(metadata: &[u8]) -> Result<Self, String> {
+		let RuntimeMetadataPrefixed(_, metadata) =
+			Decode::decode(&mut &metadata[..]).map_err(|e| e.to_string())?;
+		let RuntimeMetadata::V15(metadata) = metadata else {
+			return Err(format!("Expected metadata V{METADATA_VERSION}"))
+		};
+
+		let xcm_pallets = metadata
+			.pallets
+			.iter()
+			.filter(|pallet| {
+				pallet
+					.event
+					.as_ref()
+					.and_then(|event| metadata.types.resolve(event.ty.id))
+					.and_then(|ty| ty.path.segments.first())
+					.map_or(false, |krate| krate == "pallet_xcm")
+			})
+			.map(|pallet| pallet.index)
+			.collect();
+
+		Ok(Self {
+			event_ty: metadata.outer_enums.event_enum_ty.id,
+			types: metadata.types,
+			xcm_pallets,
+		})
+	}
+
+	/// Decode a `Vec<RuntimeEvent>`.
+	pub fn decode_events(&self, input: &mut &[u8]) -> Result<Vec<Event>, String> {
+		let Compact(len) = Compact::<u32>::decode(input).map_err(|e| e.to_string())?;
+		(0..len).map(|_| self.decode_event(input)).collect()
+	}
+
+	fn decode_event(&self, input: &mut &[u8]) -> Result<Event, String> {
+		let start = *input;
+		let value = scale_value::scale::decode_as_type(input, self.event_ty, &self.types)
+			.map_err(|e| e.to_string())?;
+		let encoded = start[..start.len() - input.len()].to_vec();
+
+		// `RuntimeEvent` has a variant per pallet, wrapping the event enum of the pallet.
+		let ValueDef::Variant(pallet) = value.value else {
+			return Err("`RuntimeEvent` is not an enum".into())
+		};
+		let Some(event) = pallet.values.into_values().next() else {
+			return Err(format!("Event of pallet `{}` is empty", pallet.name))
+		};
+		let name = match &event.value {
+			ValueDef::Variant(variant) => variant.name.clone(),
+			_ => String::new(),
+		};
+
+		Ok(Event {
+			pallet: pallet.name,
+			name,
+			details: event.to_string(),
+			from_xcm_pallet: encoded
+				.first()
+				.map_or(false, |index| self.xcm_pallets.contains(index)),
+			encoded,
+		})
+	}
+}
@@ -0,0 +1,66 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dry-runs XCM transfers spanning several chains.
+//!
+//! The `DryRunApi` of `xcm-runtime-apis` only simulates the execution on a single chain. This
+//! crate loads the runtime and a state snapshot of every chain involved in a transfer, dry-runs
+//! the initial extrinsic or XCM on the first chain and then dry-runs every forwarded XCM on its
+//! destination, hop by hop, until no more messages are sent.
+//!
+//! The state changes of every hop are kept, so later hops see the effects of earlier ones, and
+//! the [`Report`] contains the balances of the watched accounts before and after the transfer,
+//! along with the fees paid and the assets trapped at each hop.
+//!
+//! State snapshots are the ones created by `remote-externalities`. All chains are expected to be
+//! part of the same consensus system: either its relay chain, or a parachain of it. Messages
+//! sent to other destinations are reported, but not followed.
+
+mod chain;
+mod events;
+mod orchestrator;
+mod report;
+
+pub use chain::{Block, Chain, ChainConfig};
+pub use events::Event;
+pub use orchestrator::{Orchestrator, Start, DEFAULT_MAX_HOPS};
+pub use report::{Balances, Delivery, Hop, Report, TrappedAssets};
+
+/// Errors which can happen while dry-running a transfer.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// A chain could not be loaded.
+	#[error("Failed to load chain `{chain}`: {reason}")]
+	Load { chain: String, reason: String },
+	/// Calling a runtime API failed.
+	#[error("Calling `{method}` on chain `{chain}` failed: {reason}")]
+	RuntimeCall { chain: String, method: &'static str, reason: String },
+	/// The result of a runtime API call could not be decoded.
+	#[error("Failed to decode the result of `{method}` on chain `{chain}`: {reason}")]
+	Decode { chain: String, method: &'static str, reason: String },
+	/// The runtime of a chain does not provide a required runtime API.
+	#[error("Chain `{chain}` does not support `{api}`")]
+	Unsupported { chain: String, api: &'static str },
+	/// The dry-run API returned an error.
+	#[error("Dry-running on chain `{chain}` failed: {error:?}")]
+	DryRun { chain: String, error: xcm_runtime_apis::dry_run::Error },
+	/// No chain with the given name was loaded.
+	#[error("Unknown chain `{0}`")]
+	UnknownChain(String),
+}
+
+/// Result type of this crate.
+pub type Result<T> = core::result::Result<T, Error>;
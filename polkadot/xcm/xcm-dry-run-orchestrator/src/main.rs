@@ -0,0 +1,152 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Command line interface for dry-running multi-hop XCM transfers.
+//!
+//! For example, a reserve transfer from Penpal to another parachain through Asset Hub:
+//!
+//! ```text
+//! xcm-dry-run-orchestrator \
+//!     --chain name=relay,snapshot=westend.snap \
+//!     --chain name=asset-hub,para-id=1000,snapshot=asset-hub.snap \
+//!     --chain name=penpal,para-id=2000,snapshot=penpal.snap \
+//!     --chain name=other,para-id=2001,snapshot=other.snap \
+//!     --from penpal --origin-caller 0x... --call 0x... \
+//!     --account penpal=5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY
+//! ```
+
+use std::{path::PathBuf, str::FromStr};
+
+use clap::Parser;
+use codec::{Decode, DecodeLimit};
+use sp_core::Bytes;
+use sp_runtime::AccountId32;
+use xcm::{prelude::*, MAX_XCM_DECODE_DEPTH};
+
+use xcm_dry_run_orchestrator::{Chain, ChainConfig, Orchestrator, Start, DEFAULT_MAX_HOPS};
+
+#[derive(Debug, Parser)]
+#[command(about = "Dry-run an XCM transfer across several chains, from state snapshots")]
+struct Cli {
+	/// A chain, as `name=<name>,snapshot=<path>[,para-id=<id>][,runtime=<path>]`.
+	///
+	/// Without `para-id`, the chain is the relay chain. Without `runtime`, the runtime in the
+	/// snapshot is used.
+	#[arg(long = "chain", value_parser = parse_chain, required = true)]
+	chains: Vec<ChainConfig>,
+
+	/// The name of the chain on which the transfer starts.
+	#[arg(long)]
+	from: String,
+
+	/// The hex-encoded `OriginCaller` dispatching `--call`.
+	#[arg(long, value_parser = parse_hex, requires = "call", conflicts_with_all = ["origin", "xcm"])]
+	origin_caller: Option<Bytes>,
+
+	/// The hex-encoded `RuntimeCall` starting the transfer.
+	#[arg(long, value_parser = parse_hex, requires = "origin_caller")]
+	call: Option<Bytes>,
+
+	/// The XCM version of the messages forwarded by `--call`.
+	#[arg(long, default_value_t = XCM_VERSION)]
+	xcm_version: XcmVersion,
+
+	/// The hex-encoded `VersionedLocation` executing `--xcm`.
+	#[arg(long, value_parser = parse_hex, requires = "xcm")]
+	origin: Option<Bytes>,
+
+	/// The hex-encoded `VersionedXcm` starting the transfer.
+	#[arg(long, value_parser = parse_hex, requires = "origin", required_unless_present = "call")]
+	xcm: Option<Bytes>,
+
+	/// An account to report the balances of, as `<chain>=<account>`.
+	#[arg(long = "account", value_parser = parse_account)]
+	accounts: Vec<(String, AccountId32)>,
+
+	/// The maximum number of hops followed from the first chain.
+	#[arg(long, default_value_t = DEFAULT_MAX_HOPS)]
+	max_hops: u32,
+
+	/// Print the report as JSON.
+	#[arg(long)]
+	json: bool,
+}
+
+fn parse_chain(s: &str) -> Result<ChainConfig, String> {
+	let (mut name, mut para_id, mut snapshot, mut runtime) = (None, None, None, None);
+	for field in s.split(',') {
+		match field.split_once('=') {
+			Some(("name", value)) => name = Some(value.to_string()),
+			Some(("para-id", value)) =>
+				para_id = Some(value.parse().map_err(|e| format!("Invalid para id: {e}"))?),
+			Some(("snapshot", value)) => snapshot = Some(PathBuf::from(value)),
+			Some(("runtime", value)) => runtime = Some(PathBuf::from(value)),
+			_ => return Err(format!("Invalid chain field `{field}`")),
+		}
+	}
+	Ok(ChainConfig {
+		name: name.ok_or("Missing chain name")?,
+		para_id,
+		snapshot: snapshot.ok_or("Missing chain snapshot")?,
+		runtime,
+	})
+}
+
+fn parse_account(s: &str) -> Result<(String, AccountId32), String> {
+	let (chain, account) = s.split_once('=').ok_or("Expected `<chain>=<account>`")?;
+	let account = AccountId32::from_str(account).map_err(|e| format!("Invalid account: {e}"))?;
+	Ok((chain.to_string(), account))
+}
+
+fn parse_hex(s: &str) -> Result<Bytes, String> {
+	Bytes::from_str(s).map_err(|e| format!("Invalid hex: {e:?}"))
+}
+
+fn decode<T: Decode>(bytes: Bytes, what: &str) -> Result<T, String> {
+	T::decode_all_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut &bytes[..])
+		.map_err(|e| format!("Invalid {what}: {e}"))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+	sp_tracing::try_init_simple();
+	let cli = Cli::parse();
+
+	let start = match (cli.origin_caller, cli.call, cli.origin, cli.xcm) {
+		(Some(origin), Some(call), _, _) =>
+			Start::Call { origin: origin.0, call: call.0, xcm_version: cli.xcm_version },
+		(_, _, Some(origin), Some(xcm)) =>
+			Start::Xcm { origin: decode(origin, "origin")?, xcm: decode(xcm, "XCM")? },
+		_ => return Err("Either `--call` or `--xcm` must be given".into()),
+	};
+
+	let mut chains = Vec::with_capacity(cli.chains.len());
+	for config in cli.chains {
+		chains.push(Chain::load(config).await?);
+	}
+
+	let report = Orchestrator::new(chains).with_max_hops(cli.max_hops).run(
+		&cli.from,
+		start,
+		&cli.accounts,
+	)?;
+	if cli.json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+	} else {
+		print!("{report}");
+	}
+	Ok(())
+}
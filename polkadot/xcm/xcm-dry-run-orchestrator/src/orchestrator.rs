@@ -0,0 +1,329 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Feeds the messages forwarded by a chain to their destination, hop by hop.
+
+use std::collections::VecDeque;
+
+use sp_runtime::AccountId32;
+use xcm::prelude::*;
+
+use crate::{Balances, Chain, Delivery, Error, Event, Hop, Report, Result, TrappedAssets};
+
+const LOG_TARGET: &str = "xcm::dry-run-orchestrator";
+
+/// The default maximum number of hops followed from the first chain.
+pub const DEFAULT_MAX_HOPS: u32 = 16;
+
+/// What to dry-run on the first chain.
+#[derive(Clone, Debug)]
+pub enum Start {
+	/// An extrinsic. The origin and the call are SCALE-encoded, as their types are specific to the
+	/// runtime of the chain.
+	Call {
+		/// The encoded `OriginCaller`.
+		origin: Vec<u8>,
+		/// The encoded `RuntimeCall`.
+		call: Vec<u8>,
+		/// The XCM version of the forwarded messages.
+		xcm_version: XcmVersion,
+	},
+	/// An XCM program.
+	Xcm {
+		/// The origin of the program, relative to the chain.
+		origin: VersionedLocation,
+		/// The program.
+		xcm: VersionedXcm<()>,
+	},
+}
+
+/// A message waiting to be dry-run on a chain.
+struct Pending {
+	depth: u32,
+	chain: usize,
+	origin: Location,
+	xcm: VersionedXcm<()>,
+}
+
+/// Dry-runs transfers across several chains.
+pub struct Orchestrator {
+	chains: Vec<Chain>,
+	max_hops: u32,
+}
+
+impl Orchestrator {
+	/// Create an orchestrator for the given chains.
+	pub fn new(chains: Vec<Chain>) -> Self {
+		Self { chains, max_hops: DEFAULT_MAX_HOPS }
+	}
+
+	/// Set the maximum number of hops followed from the first chain.
+	pub fn with_max_hops(mut self, max_hops: u32) -> Self {
+		self.max_hops = max_hops;
+		self
+	}
+
+	/// Dry-run `start` on the chain named `chain`, then all messages it leads to.
+	///
+	/// The balances of `accounts`, given as pairs of chain name and account, are reported before
+	/// and after the transfer.
+	pub fn run(
+		&mut self,
+		chain: &str,
+		start: Start,
+		accounts: &[(String, AccountId32)],
+	) -> Result<Report> {
+		let first = self.chain_index(chain)?;
+		let watched = accounts
+			.iter()
+			.map(|(chain, account)| Ok((self.chain_index(chain)?, account.clone())))
+			.collect::<Result<Vec<_>>>()?;
+		let before = watched
+			.iter()
+			.map(|(chain, account)| self.chains[*chain].balances(account))
+			.collect::<Result<Vec<_>>>()?;
+
+		let mut hops = Vec::new();
+		let mut pending = VecDeque::new();
+		match start {
+			Start::Call { origin, call, xcm_version } => {
+				let effects = self.chains[first].dry_run_call(&origin, &call, xcm_version)?;
+				let (error, weight_used) = match effects.execution_result {
+					Ok(info) => (None, info.actual_weight),
+					Err(e) => (Some(format!("{:?}", e.error)), e.post_info.actual_weight),
+				};
+				let deliveries = self.route(first, 0, effects.forwarded_xcms, &mut pending);
+				hops.push(Hop {
+					depth: 0,
+					chain: self.chains[first].name().into(),
+					origin: None,
+					error,
+					weight_used,
+					execution_fees: None,
+					deliveries,
+					trapped_assets: trapped_assets(&effects.emitted_events),
+					events: effects.emitted_events,
+				});
+			},
+			Start::Xcm { origin, xcm } => {
+				let origin = origin.try_into().map_err(|()| Error::Decode {
+					chain: chain.into(),
+					method: "DryRunApi_dry_run_xcm",
+					reason: "Unsupported origin version".into(),
+				})?;
+				pending.push_back(Pending { depth: 0, chain: first, origin, xcm });
+			},
+		}
+
+		let mut truncated = false;
+		while let Some(message) = pending.pop_front() {
+			if message.depth > self.max_hops {
+				truncated = true;
+				break
+			}
+			hops.push(self.dry_run_message(message, &mut pending)?);
+		}
+
+		let balances = watched
+			.into_iter()
+			.zip(before)
+			.map(|((chain, account), before)| {
+				let after = self.chains[chain].balances(&account)?;
+				Ok(Balances { chain: self.chains[chain].name().into(), account, before, after })
+			})
+			.collect::<Result<_>>()?;
+
+		Ok(Report { hops, balances, truncated })
+	}
+
+	/// Dry-run a message on its destination chain.
+	fn dry_run_message(
+		&mut self,
+		Pending { depth, chain: index, origin, xcm }: Pending,
+		pending: &mut VecDeque<Pending>,
+	) -> Result<Hop> {
+		let chain = &mut self.chains[index];
+		log::info!(target: LOG_TARGET, "Dry-running hop {depth} on `{}`", chain.name());
+
+		// Use the version of the message, which the chain is known to support.
+		let versioned_origin = VersionedLocation::from(origin.clone())
+			.into_version(xcm.identify_version())
+			.unwrap_or_else(|()| origin.clone().into());
+		let effects = chain.dry_run_xcm(versioned_origin, xcm.clone())?;
+		let (error, weight_used) = match effects.execution_result {
+			Outcome::Complete { used } => (None, used),
+			Outcome::Incomplete { used, error } => (Some(format!("{error:?}")), used),
+			Outcome::Error { error } => (Some(format!("{error:?}")), Weight::zero()),
+		};
+		let execution_fees = fee_asset(&xcm).and_then(|id| {
+			let versioned_id =
+				VersionedAssetId::from(id.clone()).into_version(xcm.identify_version());
+			versioned_id
+				.ok()
+				.and_then(|versioned_id| {
+					chain
+						.weight_to_asset_fee(weight_used, versioned_id)
+						.inspect_err(|e| log::warn!(target: LOG_TARGET, "{e}"))
+						.ok()
+				})
+				.map(|amount| (id, amount).into())
+		});
+
+		let deliveries = self.route(index, depth, effects.forwarded_xcms, pending);
+		Ok(Hop {
+			depth,
+			chain: self.chains[index].name().into(),
+			origin: Some(origin),
+			error,
+			weight_used: Some(weight_used),
+			execution_fees,
+			deliveries,
+			trapped_assets: trapped_assets(&effects.emitted_events),
+			events: effects.emitted_events,
+		})
+	}
+
+	/// Queue the messages forwarded by the chain at `from` for their destination.
+	fn route(
+		&mut self,
+		from: usize,
+		depth: u32,
+		forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
+		pending: &mut VecDeque<Pending>,
+	) -> Vec<Delivery> {
+		let mut deliveries = Vec::new();
+		for (destination, messages) in forwarded_xcms {
+			let Ok(latest_destination) = Location::try_from(destination.clone()) else {
+				log::warn!(target: LOG_TARGET, "Unsupported destination version: {destination:?}");
+				continue
+			};
+			let target =
+				resolve(self.chains[from].location(), &latest_destination).and_then(|target| {
+					self.chains.iter().position(|chain| chain.location() == &target)
+				});
+
+			for message in messages {
+				let fees = self.chains[from]
+					.delivery_fees(destination.clone(), message.clone())
+					.inspect_err(|e| log::warn!(target: LOG_TARGET, "{e}"))
+					.ok()
+					.and_then(|fees| Assets::try_from(fees).ok());
+				deliveries.push(Delivery {
+					destination: latest_destination.clone(),
+					chain: target.map(|target| self.chains[target].name().into()),
+					fees,
+				});
+				if let Some(target) = target {
+					pending.push_back(Pending {
+						depth: depth + 1,
+						chain: target,
+						origin: origin_at(
+							self.chains[from].location(),
+							self.chains[target].location(),
+						),
+						xcm: message,
+					});
+				}
+			}
+		}
+		deliveries
+	}
+
+	fn chain_index(&self, name: &str) -> Result<usize> {
+		self.chains
+			.iter()
+			.position(|chain| chain.name() == name)
+			.ok_or_else(|| Error::UnknownChain(name.into()))
+	}
+}
+
+/// Resolve `destination`, relative to the chain at `from`, to a location relative to the relay
+/// chain. Returns `None` for destinations outside of the consensus system of the relay chain.
+fn resolve(from: &Location, destination: &Location) -> Option<Location> {
+	from.clone()
+		.appended_with(destination.clone())
+		.ok()
+		.filter(|target| target.parents == 0)
+}
+
+/// The location of the chain at `from` as seen by the chain at `to`, both relative to the relay
+/// chain.
+fn origin_at(from: &Location, to: &Location) -> Location {
+	Location::new(to.interior().len() as u8, from.interior().clone())
+}
+
+/// The asset in which the execution of `xcm` is paid.
+fn fee_asset(xcm: &VersionedXcm<()>) -> Option<AssetId> {
+	let xcm = Xcm::<()>::try_from(xcm.clone()).ok()?;
+	xcm.0.into_iter().find_map(|instruction| match instruction {
+		BuyExecution { fees, .. } => Some(fees.id),
+		PayFees { asset } => Some(asset.id),
+		_ => None,
+	})
+}
+
+fn trapped_assets(events: &[Event]) -> Vec<TrappedAssets> {
+	events
+		.iter()
+		.filter_map(Event::trapped_assets)
+		.filter_map(|(origin, assets)| {
+			Some(TrappedAssets { origin, assets: Assets::try_from(assets).ok()? })
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn destinations_are_resolved_relative_to_the_relay_chain() {
+		let relay = Location::here();
+		let asset_hub = Location::new(0, [Parachain(1000)]);
+		let penpal = Location::new(0, [Parachain(2000)]);
+
+		assert_eq!(resolve(&relay, &Location::new(0, [Parachain(1000)])), Some(asset_hub.clone()));
+		assert_eq!(resolve(&penpal, &Location::new(1, [Parachain(1000)])), Some(asset_hub.clone()));
+		assert_eq!(resolve(&asset_hub, &Location::parent()), Some(relay.clone()));
+		// Bridged destinations are not part of the consensus system.
+		assert_eq!(
+			resolve(&asset_hub, &Location::new(2, [GlobalConsensus(ByGenesis([0; 32]))])),
+			None
+		);
+	}
+
+	#[test]
+	fn origins_are_relative_to_the_receiving_chain() {
+		let relay = Location::here();
+		let asset_hub = Location::new(0, [Parachain(1000)]);
+		let penpal = Location::new(0, [Parachain(2000)]);
+
+		assert_eq!(origin_at(&penpal, &asset_hub), Location::new(1, [Parachain(2000)]));
+		assert_eq!(origin_at(&penpal, &relay), Location::new(0, [Parachain(2000)]));
+		assert_eq!(origin_at(&relay, &asset_hub), Location::parent());
+	}
+
+	#[test]
+	fn fee_asset_is_the_first_one_paying_for_execution() {
+		let xcm = Xcm::<()>::builder_unsafe()
+			.withdraw_asset((Parent, 100u128))
+			.pay_fees((Parent, 10u128))
+			.buy_execution((Here, 10u128), Unlimited)
+			.build();
+		assert_eq!(fee_asset(&VersionedXcm::from(xcm)), Some(Parent.into()));
+		assert_eq!(fee_asset(&VersionedXcm::from(Xcm::<()>::new())), None);
+	}
+}
@@ -0,0 +1,145 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The report of a dry-run transfer.
+
+use core::fmt;
+
+use serde::Serialize;
+use sp_runtime::AccountId32;
+use xcm::prelude::*;
+
+use crate::Event;
+
+/// What happened on every hop of a transfer.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+	/// The hops, in the order they were dry-run.
+	pub hops: Vec<Hop>,
+	/// The balances of the watched accounts.
+	pub balances: Vec<Balances>,
+	/// Whether some messages were not followed because the maximum number of hops was reached.
+	pub truncated: bool,
+}
+
+/// The execution of an extrinsic or an XCM program on a chain.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hop {
+	/// The number of hops from the first chain.
+	pub depth: u32,
+	/// The name of the chain.
+	pub chain: String,
+	/// The origin of the XCM program, relative to the chain, or `None` for the initial
+	/// extrinsic.
+	pub origin: Option<Location>,
+	/// The error of the execution, if it failed.
+	pub error: Option<String>,
+	/// The weight used by the execution, if known.
+	pub weight_used: Option<Weight>,
+	/// The fees paid for executing the XCM program, if they could be determined.
+	///
+	/// These are the fees for the weight used, in the asset used in the first `BuyExecution` or
+	/// `PayFees` instruction of the program.
+	pub execution_fees: Option<Asset>,
+	/// The messages sent to other chains.
+	pub deliveries: Vec<Delivery>,
+	/// The assets trapped during the execution.
+	pub trapped_assets: Vec<TrappedAssets>,
+	/// The events emitted during the execution.
+	pub events: Vec<Event>,
+}
+
+/// A message sent by a hop.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delivery {
+	/// The destination, relative to the sending chain.
+	pub destination: Location,
+	/// The name of the destination chain, or `None` if it was not loaded.
+	pub chain: Option<String>,
+	/// The fees for delivering the message, if they could be determined.
+	pub fees: Option<Assets>,
+}
+
+/// Assets trapped during the execution of an XCM program.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrappedAssets {
+	/// The origin the assets can be claimed by.
+	pub origin: Location,
+	/// The trapped assets.
+	pub assets: Assets,
+}
+
+/// The balances of a watched account.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Balances {
+	/// The name of the chain.
+	pub chain: String,
+	/// The account.
+	pub account: AccountId32,
+	/// The assets held before the transfer.
+	pub before: Vec<Asset>,
+	/// The assets held after the transfer.
+	pub after: Vec<Asset>,
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for hop in &self.hops {
+			write!(f, "Hop {} on `{}`", hop.depth, hop.chain)?;
+			if let Some(origin) = &hop.origin {
+				write!(f, " from {origin:?}")?;
+			}
+			match &hop.error {
+				Some(error) => writeln!(f, ": failed with {error}")?,
+				None => writeln!(f, ": succeeded")?,
+			}
+			if let Some(weight) = hop.weight_used {
+				writeln!(f, "  weight used: {weight:?}")?;
+			}
+			if let Some(fees) = &hop.execution_fees {
+				writeln!(f, "  execution fees: {fees:?}")?;
+			}
+			for delivery in &hop.deliveries {
+				let chain = delivery.chain.as_deref().unwrap_or("not loaded");
+				write!(f, "  sent to {:?} ({chain})", delivery.destination)?;
+				match &delivery.fees {
+					Some(fees) => writeln!(f, ", delivery fees: {fees:?}")?,
+					None => writeln!(f)?,
+				}
+			}
+			for trapped in &hop.trapped_assets {
+				writeln!(f, "  trapped {:?} for {:?}", trapped.assets, trapped.origin)?;
+			}
+			for event in &hop.events {
+				writeln!(f, "  event {}::{}", event.pallet, event.details)?;
+			}
+		}
+		if self.truncated {
+			writeln!(f, "Maximum number of hops reached, some messages were not followed")?;
+		}
+		for balances in &self.balances {
+			writeln!(f, "Balances of {} on `{}`", balances.account, balances.chain)?;
+			writeln!(f, "  before: {:?}", balances.before)?;
+			writeln!(f, "  after: {:?}", balances.after)?;
+		}
+		Ok(())
+	}
+}
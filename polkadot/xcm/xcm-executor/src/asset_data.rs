@@ -0,0 +1,33 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The data of assets, like the metadata and attributes of non-fungible assets, which asset
+//! transactors can export from and import into their chain, see
+//! [`crate::traits::TransactAsset::export_asset_data`].
+//!
+//! XCM has no way to carry this data along with the assets in transfer messages yet, so the
+//! executor does not call these hooks: chains would need to agree on a carrier, through an RFC
+//! and a new XCM version, rather than overloading existing instructions.
+
+use frame_support::{parameter_types, BoundedVec};
+
+parameter_types! {
+	/// The maximum length of the data of an asset.
+	pub MaxAssetDataLength: u32 = 4096;
+}
+
+/// The data of an asset, as exported by its asset transactor.
+pub type AssetData = BoundedVec<u8, MaxAssetDataLength>;
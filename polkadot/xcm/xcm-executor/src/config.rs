@@ -18,7 +18,7 @@ use crate::traits::{
 	AssetExchange, AssetLock, CallDispatcher, ClaimAssets, ConvertOrigin, DropAssets, EventEmitter,
 	ExportXcm, FeeManager, HandleHrmpChannelAccepted, HandleHrmpChannelClosing,
 	HandleHrmpNewChannelOpenRequest, OnResponse, ProcessTransaction, RecordXcm, ShouldExecute,
-	TransactAsset, VersionChangeNotifier, WeightBounds, WeightTrader,
+	TraceXcm, TransactAsset, VersionChangeNotifier, WeightBounds, WeightTrader,
 };
 use frame_support::{
 	dispatch::{GetDispatchInfo, Parameter, PostDispatchInfo},
@@ -134,4 +134,7 @@ pub trait Config {
 	type HrmpChannelClosingHandler: HandleHrmpChannelClosing;
 	/// Allows recording the last executed XCM (used by dry-run runtime APIs).
 	type XcmRecorder: RecordXcm;
+	/// Allows tracing the state of the executor after each instruction (used by dry-run runtime
+	/// APIs).
+	type XcmTracer: TraceXcm;
 }
@@ -24,13 +24,14 @@ use core::{fmt::Debug, marker::PhantomData};
 use frame_support::{
 	dispatch::GetDispatchInfo,
 	ensure,
-	traits::{Contains, ContainsPair, Defensive, Get, PalletsInfoAccess},
+	traits::{Contains, ContainsPair, Defensive, Get, PalletsInfoAccess, ProcessMessageError},
 };
 use sp_core::defer;
 use sp_io::hashing::blake2_128;
 use sp_weights::Weight;
 use xcm::latest::{prelude::*, AssetTransferFilter};
 
+pub mod asset_data;
 pub mod traits;
 use traits::{
 	validate_export, AssetExchange, AssetLock, CallDispatcher, ClaimAssets, ConvertOrigin,
@@ -40,7 +41,7 @@ use traits::{
 	XcmAssetTransfers,
 };
 
-pub use traits::RecordXcm;
+pub use traits::{InstructionTrace, RecordXcm, TraceXcm};
 
 mod assets;
 pub use assets::AssetsInHolding;
@@ -100,6 +101,9 @@ pub struct XcmExecutor<Config: config::Config> {
 	message_weight: Weight,
 	asset_claimer: Option<Location>,
 	already_paid_fees: bool,
+	/// Whether the instructions are traced through `Config::XcmTracer`. Only read once per
+	/// message, as it is only enabled under certain conditions (mainly during dry-running).
+	should_trace: bool,
 	_config: PhantomData<Config>,
 }
 
@@ -273,9 +277,16 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 				"Barrier blocked execution",
 			);
 
-			return Outcome::Incomplete {
-				used: xcm_weight,         // Weight consumed before the error
-				error: XcmError::Barrier, // The error that occurred
+			return match e {
+				// The message was not executed and should be retried later, e.g. because its
+				// origin is suspended by `RespectSuspension` or throttled by `RespectRateLimits`.
+				// No weight is reported as used, so that `ProcessXcmMessage` keeps the message in
+				// the queue rather than consuming it as it does for any other barrier error.
+				ProcessMessageError::Yield => Outcome::Error { error: XcmError::Barrier },
+				_ => Outcome::Incomplete {
+					used: xcm_weight,         // Weight consumed before the error
+					error: XcmError::Barrier, // The error that occurred
+				},
 			};
 		}
 
@@ -371,6 +382,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 			message_weight: Weight::zero(),
 			asset_claimer: None,
 			already_paid_fees: false,
+			should_trace: Config::XcmTracer::should_trace(),
 			_config: PhantomData,
 		}
 	}
@@ -825,6 +837,12 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		for (i, mut instr) in xcm.0.into_iter().enumerate() {
 			match &mut result {
 				r @ Ok(()) => {
+					// We only want to trace under certain conditions (mainly only during
+					// dry-running), so as to not degrade regular performance.
+					let trace = self.should_trace.then(|| {
+						let weight = Config::Weigher::instr_weight(&mut instr).unwrap_or_default();
+						(instr.clone().into::<()>(), weight, self.total_surplus)
+					});
 					// Initialize the recursion count only the first time we hit this code in our
 					// potential recursive execution.
 					let inst_res = recursion_count::using_once(&mut 1, || {
@@ -848,6 +866,18 @@ impl<Config: config::Config> XcmExecutor<Config> {
 
 						self.process_instruction(instr)
 					});
+					if let Some((instruction, weight, surplus_before)) = trace {
+						let refunded = self.total_surplus.saturating_sub(surplus_before);
+						Config::XcmTracer::trace(InstructionTrace {
+							index: i as u32,
+							instruction,
+							origin: self.origin_ref().cloned(),
+							holding: self.holding.clone().into(),
+							fees: self.fees.clone().into(),
+							weight_used: weight.saturating_sub(refunded),
+							error: inst_res.as_ref().err().cloned(),
+						});
+					}
 					if let Err(error) = inst_res {
 						tracing::debug!(
 							target: "xcm::process",
@@ -42,7 +42,9 @@ pub use on_response::{OnResponse, QueryHandler, QueryResponseStatus, VersionChan
 mod process_transaction;
 pub use process_transaction::ProcessTransaction;
 mod should_execute;
-pub use should_execute::{CheckSuspension, DenyExecution, Properties, ShouldExecute};
+pub use should_execute::{
+	CheckRateLimit, CheckSuspension, DenyExecution, Properties, ShouldExecute,
+};
 mod transact_asset;
 pub use transact_asset::TransactAsset;
 mod hrmp;
@@ -51,12 +53,14 @@ pub use hrmp::{
 };
 mod event_emitter;
 mod record_xcm;
+mod trace_xcm;
 mod weight;
 pub use event_emitter::EventEmitter;
 
 pub use record_xcm::RecordXcm;
 #[deprecated = "Use `sp_runtime::traits::` instead"]
 pub use sp_runtime::traits::{Identity, TryConvertInto as JustTry};
+pub use trace_xcm::{InstructionTrace, TraceXcm};
 pub use weight::{WeightBounds, WeightTrader};
 
 pub mod prelude {
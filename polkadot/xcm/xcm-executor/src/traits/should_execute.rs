@@ -34,6 +34,10 @@ pub struct Properties {
 ///
 /// Can be amalgamated into a tuple to have multiple trials. If any of the tuple elements returns
 /// `Ok(())`, the execution stops. Else, `Err(_)` is returned if all elements reject the message.
+///
+/// Returning `Err(ProcessMessageError::Yield)` defers the message instead of rejecting it: the
+/// executor reports `Outcome::Error`, which `ProcessXcmMessage` turns back into `Yield`, so that the
+/// message queue keeps the message and retries it later. Any other error drops the message.
 pub trait ShouldExecute {
 	/// Returns `Ok(())` if the given `message` may be executed.
 	///
@@ -128,6 +132,16 @@ impl CheckSuspension for Tuple {
 	}
 }
 
+/// Trait to determine whether the origin of a given XCM is within its rate limits.
+///
+/// Implementers are expected to be stateful: the usage of the origin is recorded every time a
+/// message is allowed, so that later messages can be throttled.
+pub trait CheckRateLimit {
+	/// Returns whether `origin` may execute a message of up to `max_weight`, recording the usage
+	/// if it may.
+	fn check_rate_limit(origin: &Location, max_weight: Weight) -> bool;
+}
+
 /// Trait to determine whether the execution engine should not execute a given XCM.
 ///
 /// Can be amalgamated into a tuple to have multiple traits. If any of the tuple elements returns
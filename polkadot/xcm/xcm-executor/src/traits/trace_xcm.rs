@@ -0,0 +1,70 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Trait for tracing the execution of XCMs instruction by instruction and a dummy implementation.
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use xcm::latest::{Assets, Error as XcmError, Instruction, Location, Weight};
+
+/// The state of the executor after executing an instruction.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct InstructionTrace {
+	/// The index of the instruction in the program it is part of. The error handler and the
+	/// appendix are programs of their own, so their indices start again at 0.
+	pub index: u32,
+	/// The instruction.
+	pub instruction: Instruction<()>,
+	/// The origin after executing the instruction.
+	pub origin: Option<Location>,
+	/// The holding register after executing the instruction.
+	pub holding: Assets,
+	/// The fees register after executing the instruction.
+	pub fees: Assets,
+	/// The weight of the instruction, less any weight it refunded.
+	pub weight_used: Weight,
+	/// The error the instruction failed with, if any.
+	pub error: Option<XcmError>,
+}
+
+/// Trait for tracing the execution of XCMs.
+pub trait TraceXcm {
+	/// Whether or not we should trace executed instructions.
+	///
+	/// This is read once per message, when the executor is created.
+	fn should_trace() -> bool;
+	/// Enable or disable tracing. Disabling tracing doesn't clear the trace.
+	fn set_trace_xcm(enabled: bool);
+	/// Take the trace of all instructions executed since the last call, leaving it empty.
+	fn take_trace() -> Vec<InstructionTrace>;
+	/// Record the execution of an instruction.
+	fn trace(step: InstructionTrace);
+}
+
+impl TraceXcm for () {
+	fn should_trace() -> bool {
+		false
+	}
+
+	fn set_trace_xcm(_: bool) {}
+
+	fn take_trace() -> Vec<InstructionTrace> {
+		Vec::new()
+	}
+
+	fn trace(_: InstructionTrace) {}
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::AssetsInHolding;
+use crate::{asset_data::AssetData, AssetsInHolding};
 use core::result::Result;
 use xcm::latest::{Asset, Error as XcmError, Location, Result as XcmResult, XcmContext};
 
@@ -137,6 +137,29 @@ pub trait TransactAsset {
 			result => result,
 		}
 	}
+
+	/// The data of the non-fungible asset `what`, like its metadata and attributes.
+	///
+	/// Meant to be handed over to `import_asset_data` of the asset transactor of the chain the
+	/// asset is transferred to. The executor does not carry it yet, see [`crate::asset_data`]. An
+	/// empty vector means the asset has no data.
+	fn export_asset_data(_what: &Asset, _context: &XcmContext) -> Result<AssetData, XcmError> {
+		Err(XcmError::Unimplemented)
+	}
+
+	/// Apply the `data` exported by `export_asset_data` on the sending chain to the non-fungible
+	/// asset `what`, which was just deposited.
+	///
+	/// `origin` is the chain the data comes from. Implementations must only accept it from the
+	/// chains they trust with the data of `what`, like its reserve.
+	fn import_asset_data(
+		_what: &Asset,
+		_data: &[u8],
+		_origin: &Location,
+		_context: &XcmContext,
+	) -> XcmResult {
+		Err(XcmError::Unimplemented)
+	}
 }
 
 #[impl_trait_for_tuples::impl_for_tuples(30)]
@@ -247,6 +270,44 @@ impl TransactAsset for Tuple {
 		);
 		Err(XcmError::AssetNotFound)
 	}
+
+	fn export_asset_data(what: &Asset, context: &XcmContext) -> Result<AssetData, XcmError> {
+		for_tuples!( #(
+			match Tuple::export_asset_data(what, context) {
+				Err(XcmError::AssetNotFound) | Err(XcmError::Unimplemented) => (),
+				r => return r,
+			}
+		)* );
+		tracing::trace!(
+			target: "xcm::TransactAsset::export_asset_data",
+			?what,
+			?context,
+			"did not export asset data",
+		);
+		Err(XcmError::AssetNotFound)
+	}
+
+	fn import_asset_data(
+		what: &Asset,
+		data: &[u8],
+		origin: &Location,
+		context: &XcmContext,
+	) -> XcmResult {
+		for_tuples!( #(
+			match Tuple::import_asset_data(what, data, origin, context) {
+				Err(XcmError::AssetNotFound) | Err(XcmError::Unimplemented) => (),
+				r => return r,
+			}
+		)* );
+		tracing::trace!(
+			target: "xcm::TransactAsset::import_asset_data",
+			?what,
+			?origin,
+			?context,
+			"did not import asset data",
+		);
+		Err(XcmError::AssetNotFound)
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,126 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Conversion of XCMs between versions.
+
+use xcm::{prelude::*, v3, v4, v5, Version};
+
+use crate::{Error, Result};
+
+/// An XCM converted to another version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conversion {
+	/// The converted XCM.
+	pub xcm: VersionedXcm<()>,
+	/// The top-level instructions not preserved by the conversion, i.e. which are different when
+	/// converted back to the original version.
+	///
+	/// For example, converting a `Transact` without `fallback_max_weight` to version 4 requires a
+	/// weight, which is set to the maximum.
+	pub lossy_instructions: Vec<usize>,
+}
+
+/// Convert `xcm` to `version`.
+///
+/// Fails with [`Error::Conversion`], listing the offending instructions, if some instructions
+/// cannot be expressed in `version`.
+pub fn convert(xcm: &VersionedXcm<()>, version: Version) -> Result<Conversion> {
+	let original = xcm.identify_version();
+	let Ok(converted) = xcm.clone().into_version(version) else {
+		let instructions = instructions(xcm)
+			.enumerate()
+			.filter(|(_, instruction)| instruction.clone().into_version(version).is_err())
+			.map(|(i, _)| i)
+			.collect();
+		return Err(Error::Conversion { version, instructions })
+	};
+
+	let lossy_instructions = if converted.clone().into_version(original).as_ref() == Ok(xcm) {
+		Vec::new()
+	} else {
+		instructions(xcm)
+			.enumerate()
+			.filter(|(_, instruction)| {
+				let round_trip = instruction
+					.clone()
+					.into_version(version)
+					.and_then(|converted| converted.into_version(original));
+				round_trip.as_ref() != Ok(instruction)
+			})
+			.map(|(i, _)| i)
+			.collect()
+	};
+	Ok(Conversion { xcm: converted, lossy_instructions })
+}
+
+/// The top-level instructions of `xcm`, each in an XCM of its own.
+pub(crate) fn instructions(xcm: &VersionedXcm<()>) -> impl Iterator<Item = VersionedXcm<()>> {
+	let instructions: Vec<VersionedXcm<()>> = match xcm {
+		VersionedXcm::V3(xcm) =>
+			xcm.0.iter().map(|i| VersionedXcm::V3(v3::Xcm(vec![i.clone()]))).collect(),
+		VersionedXcm::V4(xcm) =>
+			xcm.0.iter().map(|i| VersionedXcm::V4(v4::Xcm(vec![i.clone()]))).collect(),
+		VersionedXcm::V5(xcm) =>
+			xcm.0.iter().map(|i| VersionedXcm::V5(v5::Xcm(vec![i.clone()]))).collect(),
+	};
+	instructions.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lossless_conversion_works() {
+		let xcm: VersionedXcm<()> = Xcm::<()>::builder_unsafe()
+			.withdraw_asset((Parent, 100u128))
+			.buy_execution((Parent, 100u128), Unlimited)
+			.build()
+			.into();
+
+		let conversion = convert(&xcm, 3).unwrap();
+		assert_eq!(conversion.xcm.identify_version(), 3);
+		assert!(conversion.lossy_instructions.is_empty());
+		assert_eq!(convert(&conversion.xcm, 5).unwrap().xcm, xcm);
+	}
+
+	#[test]
+	fn lossy_instructions_are_reported() {
+		let xcm: VersionedXcm<()> = Xcm::<()>(vec![
+			ClearOrigin,
+			Transact {
+				origin_kind: OriginKind::SovereignAccount,
+				fallback_max_weight: None,
+				call: vec![1, 2, 3].into(),
+			},
+		])
+		.into();
+
+		let conversion = convert(&xcm, 4).unwrap();
+		assert_eq!(conversion.lossy_instructions, vec![1]);
+	}
+
+	#[test]
+	fn unsupported_instructions_are_reported() {
+		let xcm: VersionedXcm<()> =
+			Xcm::<()>(vec![ClearOrigin, PayFees { asset: (Parent, 100u128).into() }]).into();
+
+		assert!(matches!(
+			convert(&xcm, 4),
+			Err(Error::Conversion { version: 4, instructions }) if instructions == vec![1]
+		));
+	}
+}
@@ -0,0 +1,66 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tooling for reviewing SCALE-encoded XCMs, like the ones found in governance proposals.
+//!
+//! A [`VersionedXcm`] of any supported version can be:
+//! - printed in a human-readable form with a [`Printer`], which also decodes the calls of
+//!   `Transact` instructions when given the metadata of the runtime executing them,
+//! - converted to another version with [`convert`], which reports the instructions that the
+//!   conversion does not preserve,
+//! - checked for common mistakes with [`lint`].
+//!
+//! The calls of `Transact` instructions are never decoded as part of the XCM: they are kept as
+//! their encoded form, so XCMs can be inspected without knowing the runtime executing them.
+
+mod convert;
+mod lint;
+mod printer;
+
+pub use convert::{convert, Conversion};
+pub use lint::{lint, Finding, Lint};
+pub use printer::{Printer, METADATA_VERSION};
+
+use codec::DecodeLimit;
+use xcm::{Version, VersionedXcm, MAX_XCM_DECODE_DEPTH};
+
+/// Errors which can happen while inspecting an XCM.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The XCM could not be decoded.
+	#[error("Failed to decode the XCM: {0}")]
+	Decode(codec::Error),
+	/// The runtime metadata could not be used to decode calls.
+	#[error("Invalid metadata: {0}")]
+	Metadata(String),
+	/// The XCM could not be printed.
+	#[error("Failed to print the XCM: {0}")]
+	Print(String),
+	/// The XCM could not be converted to a version, because of the given top-level instructions.
+	#[error(
+		"Cannot convert the XCM to version {version}, because of instructions {instructions:?}"
+	)]
+	Conversion { version: Version, instructions: Vec<usize> },
+}
+
+/// Result type of this crate.
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Decode a SCALE-encoded [`VersionedXcm`], with the same limits as the executor.
+pub fn decode(mut encoded: &[u8]) -> Result<VersionedXcm<()>> {
+	VersionedXcm::decode_all_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut encoded)
+		.map_err(Error::Decode)
+}
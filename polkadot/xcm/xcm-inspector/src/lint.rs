@@ -0,0 +1,361 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection of common mistakes in XCMs.
+//!
+//! XCMs are linted in the latest version, and so are the XCMs they send to other chains, with
+//! the instructions prepended by the executor in mind.
+
+use core::fmt;
+
+use xcm::{latest::AssetTransferFilter, prelude::*};
+
+use crate::{convert, Result};
+
+/// A common mistake.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lint {
+	/// An XCM executed on another chain neither pays for its execution with `BuyExecution` or
+	/// `PayFees`, nor requests `UnpaidExecution`, so it is rejected by the usual barriers.
+	MissingFeePayment,
+	/// The weight bought by `BuyExecution` and left unused is never refunded with
+	/// `RefundSurplus`, so the fees paid for it are lost.
+	MissingRefundSurplus,
+	/// The assets refunded by `RefundSurplus` are never deposited, so they are trapped.
+	RefundNotDeposited,
+	/// An asset filter uses the `All` or `AllOf` wildcards, which are not bounded in the number
+	/// of assets matched, so the instruction is weighed for the maximum number of assets.
+	UnboundedWildcard,
+	/// A `Transact` is executed after the origin was cleared, so it fails with `BadOrigin`.
+	TransactWithoutOrigin,
+}
+
+impl Lint {
+	/// The name of the lint.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::MissingFeePayment => "missing-fee-payment",
+			Self::MissingRefundSurplus => "missing-refund-surplus",
+			Self::RefundNotDeposited => "refund-not-deposited",
+			Self::UnboundedWildcard => "unbounded-wildcard",
+			Self::TransactWithoutOrigin => "transact-without-origin",
+		}
+	}
+
+	/// A description of the mistake.
+	pub fn description(&self) -> &'static str {
+		match self {
+			Self::MissingFeePayment =>
+				"the program neither pays for its execution with `BuyExecution` or `PayFees`, nor \
+				requests `UnpaidExecution`",
+			Self::MissingRefundSurplus =>
+				"the unused weight bought by `BuyExecution` is not refunded with `RefundSurplus`",
+			Self::RefundNotDeposited =>
+				"the assets refunded by `RefundSurplus` are not deposited, so they will be trapped",
+			Self::UnboundedWildcard =>
+				"the `All` and `AllOf` wildcards are unbounded, use `AllCounted` or `AllOfCounted`",
+			Self::TransactWithoutOrigin =>
+				"`Transact` is executed after the origin was cleared, so it will fail",
+		}
+	}
+}
+
+/// A mistake found in an XCM.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+	/// The mistake.
+	pub lint: Lint,
+	/// The indices of the instruction the mistake is about, from the top-level XCM through the
+	/// nested ones. For [`Lint::MissingFeePayment`], the instruction sending the XCM instead, or
+	/// none for the top-level XCM.
+	pub path: Vec<usize>,
+}
+
+impl fmt::Display for Finding {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let path = self.path.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("/");
+		match (self.lint, path.is_empty()) {
+			(Lint::MissingFeePayment, true) => write!(f, "program")?,
+			(Lint::MissingFeePayment, false) => write!(f, "program sent by instruction {path}")?,
+			_ => write!(f, "instruction {path}")?,
+		}
+		write!(f, ": {} [{}]", self.lint.description(), self.lint.name())
+	}
+}
+
+/// Lint `xcm`, which is executed locally, e.g. with `pallet_xcm::execute`, if `local`, or sent to
+/// another chain otherwise.
+///
+/// Fails if `xcm` cannot be converted to the latest version.
+pub fn lint(xcm: &VersionedXcm<()>, local: bool) -> Result<Vec<Finding>> {
+	let xcm = Xcm::<()>::try_from(convert(xcm, XCM_VERSION)?.xcm)
+		.expect("the XCM was converted to the latest version; qed");
+	let mut linter = Linter { findings: Vec::new(), path: Vec::new() };
+	linter.lint(&xcm.0, !local, false);
+	Ok(linter.findings)
+}
+
+struct Linter {
+	findings: Vec<Finding>,
+	/// The path of the instruction being linted.
+	path: Vec<usize>,
+}
+
+impl Linter {
+	fn report(&mut self, lint: Lint) {
+		self.findings.push(Finding { lint, path: self.path.clone() });
+	}
+
+	/// Lint a program, which must pay for its execution if `remote`, and starts with the origin
+	/// cleared if `origin_cleared`.
+	fn lint(&mut self, program: &[Instruction<()>], remote: bool, mut origin_cleared: bool) {
+		if remote && !pays_fees(program) {
+			self.report(Lint::MissingFeePayment);
+		}
+		let appendix_deposits = program.iter().any(|instruction| match instruction {
+			SetAppendix(appendix) => appendix.0.iter().any(is_deposit),
+			_ => false,
+		});
+
+		for (i, instruction) in program.iter().enumerate() {
+			self.path.push(i);
+			let rest = &program[i + 1..];
+			match instruction {
+				BuyExecution { .. } if !rest.iter().any(is_refund) =>
+					self.report(Lint::MissingRefundSurplus),
+				RefundSurplus if !appendix_deposits && !rest.iter().any(is_deposit) =>
+					self.report(Lint::RefundNotDeposited),
+				ClearOrigin => origin_cleared = true,
+				AliasOrigin(_) => origin_cleared = false,
+				Transact { .. } if origin_cleared => self.report(Lint::TransactWithoutOrigin),
+				_ => {},
+			}
+			if asset_filters(instruction).into_iter().any(is_unbounded) {
+				self.report(Lint::UnboundedWildcard);
+			}
+			match instruction {
+				SetAppendix(xcm) | SetErrorHandler(xcm) => self.lint(&xcm.0, false, origin_cleared),
+				DepositReserveAsset { xcm, .. } |
+				InitiateReserveWithdraw { xcm, .. } |
+				InitiateTeleport { xcm, .. } |
+				TransferReserveAsset { xcm, .. } => self.lint(&xcm.0, true, true),
+				// The executor pays for the execution with `remote_fees`, or requests
+				// `UnpaidExecution`.
+				InitiateTransfer { remote_xcm, preserve_origin, .. } =>
+					self.lint(&remote_xcm.0, false, !preserve_origin),
+				ExportMessage { xcm, .. } => self.lint(&xcm.0, true, false),
+				_ => {},
+			}
+			self.path.pop();
+		}
+	}
+}
+
+/// Whether `program` pays for its execution, as expected by barriers like
+/// `AllowTopLevelPaidExecutionFrom` or `AllowExplicitUnpaidExecutionFrom`, or needs not to.
+fn pays_fees(program: &[Instruction<()>]) -> bool {
+	let first = program.iter().find(|instruction| {
+		!matches!(
+			instruction,
+			WithdrawAsset(_) |
+				ReceiveTeleportedAsset(_) |
+				ReserveAssetDeposited(_) |
+				ClaimAsset { .. } |
+				ClearOrigin | DescendOrigin(_) |
+				AliasOrigin(_) |
+				UniversalOrigin(_) |
+				SetHints { .. }
+		)
+	});
+	matches!(
+		first,
+		None | Some(
+			BuyExecution { .. } |
+				PayFees { .. } |
+				UnpaidExecution { .. } |
+				// Responses and notifications are allowed by their own barriers.
+				QueryResponse { .. } |
+				SubscribeVersion { .. } |
+				UnsubscribeVersion |
+				HrmpNewChannelOpenRequest { .. } |
+				HrmpChannelAccepted { .. } |
+				HrmpChannelClosing { .. }
+		)
+	)
+}
+
+fn is_refund(instruction: &Instruction<()>) -> bool {
+	match instruction {
+		RefundSurplus => true,
+		SetAppendix(appendix) => appendix.0.iter().any(is_refund),
+		_ => false,
+	}
+}
+
+/// Whether `instruction` moves assets out of holding.
+fn is_deposit(instruction: &Instruction<()>) -> bool {
+	matches!(
+		instruction,
+		DepositAsset { .. } |
+			DepositReserveAsset { .. } |
+			InitiateReserveWithdraw { .. } |
+			InitiateTeleport { .. } |
+			InitiateTransfer { .. } |
+			BurnAsset(_)
+	)
+}
+
+fn asset_filters(instruction: &Instruction<()>) -> Vec<&AssetFilter> {
+	match instruction {
+		DepositAsset { assets, .. } |
+		DepositReserveAsset { assets, .. } |
+		InitiateReserveWithdraw { assets, .. } |
+		InitiateTeleport { assets, .. } |
+		ReportHolding { assets, .. } => vec![assets],
+		ExchangeAsset { give, .. } => vec![give],
+		InitiateTransfer { remote_fees, assets, .. } => remote_fees
+			.iter()
+			.chain(assets.iter())
+			.map(|filter| match filter {
+				AssetTransferFilter::Teleport(assets) |
+				AssetTransferFilter::ReserveDeposit(assets) |
+				AssetTransferFilter::ReserveWithdraw(assets) => assets,
+			})
+			.collect(),
+		_ => Vec::new(),
+	}
+}
+
+fn is_unbounded(filter: &AssetFilter) -> bool {
+	matches!(filter, Wild(All | AllOf { .. }))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn lints(xcm: Xcm<()>, local: bool) -> Vec<(Lint, Vec<usize>)> {
+		lint(&xcm.into(), local)
+			.unwrap()
+			.into_iter()
+			.map(|finding| (finding.lint, finding.path))
+			.collect()
+	}
+
+	#[test]
+	fn well_formed_programs_pass() {
+		let xcm = Xcm::<()>::builder()
+			.withdraw_asset((Parent, 100u128))
+			.buy_execution((Parent, 100u128), Unlimited)
+			.set_appendix(
+				Xcm::builder_unsafe()
+					.refund_surplus()
+					.deposit_asset(AllCounted(1), [1u8; 32])
+					.build(),
+			)
+			.deposit_reserve_asset(
+				AllCounted(1),
+				(Parent, Parachain(2000)),
+				Xcm::builder_unsafe()
+					.pay_fees((Parent, 10u128))
+					.deposit_asset(AllCounted(1), [1u8; 32])
+					.build(),
+			)
+			.build();
+
+		assert_eq!(lints(xcm, false), vec![]);
+	}
+
+	#[test]
+	fn missing_fee_payment_is_detected() {
+		let xcm = Xcm::<()>::builder_unsafe()
+			.withdraw_asset((Parent, 100u128))
+			.pay_fees((Parent, 10u128))
+			.initiate_teleport(
+				AllCounted(1),
+				Parent,
+				Xcm::builder_unsafe().deposit_asset(AllCounted(1), [1u8; 32]).build(),
+			)
+			.build();
+
+		assert_eq!(lints(xcm.clone(), false), vec![(Lint::MissingFeePayment, vec![2])]);
+
+		let xcm = Xcm::<()>(xcm.0[2..].to_vec());
+		assert_eq!(lints(xcm.clone(), true), vec![(Lint::MissingFeePayment, vec![0])]);
+		assert_eq!(
+			lints(xcm, false),
+			vec![(Lint::MissingFeePayment, vec![]), (Lint::MissingFeePayment, vec![0])]
+		);
+	}
+
+	#[test]
+	fn refund_mistakes_are_detected() {
+		let xcm = Xcm::<()>::builder()
+			.withdraw_asset((Parent, 100u128))
+			.buy_execution((Parent, 100u128), Unlimited)
+			.deposit_asset(AllCounted(1), [1u8; 32])
+			.build();
+		assert_eq!(lints(xcm, false), vec![(Lint::MissingRefundSurplus, vec![1])]);
+
+		let xcm = Xcm::<()>::builder()
+			.withdraw_asset((Parent, 100u128))
+			.buy_execution((Parent, 100u128), Unlimited)
+			.deposit_asset(AllCounted(1), [1u8; 32])
+			.refund_surplus()
+			.build();
+		assert_eq!(lints(xcm, false), vec![(Lint::RefundNotDeposited, vec![3])]);
+	}
+
+	#[test]
+	fn unbounded_wildcards_are_detected() {
+		let xcm = Xcm::<()>::builder()
+			.withdraw_asset((Parent, 100u128))
+			.pay_fees((Parent, 10u128))
+			.initiate_transfer(
+				Parent,
+				Some(AssetTransferFilter::ReserveWithdraw(Wild(AllCounted(1)))),
+				false,
+				vec![AssetTransferFilter::ReserveWithdraw(Wild(All))],
+				Xcm::new(),
+			)
+			.deposit_asset(Wild(AllOf { id: Parent.into(), fun: WildFungible }), [1u8; 32])
+			.build();
+
+		assert_eq!(
+			lints(xcm, false),
+			vec![(Lint::UnboundedWildcard, vec![2]), (Lint::UnboundedWildcard, vec![3])]
+		);
+	}
+
+	#[test]
+	fn transact_without_origin_is_detected() {
+		let transact = Transact {
+			origin_kind: OriginKind::SovereignAccount,
+			fallback_max_weight: None,
+			call: vec![1, 2, 3].into(),
+		};
+		let xcm = Xcm::<()>(vec![
+			UnpaidExecution { weight_limit: Unlimited, check_origin: None },
+			transact.clone(),
+			ClearOrigin,
+			transact.clone(),
+			AliasOrigin(Parent.into()),
+			transact,
+		]);
+
+		assert_eq!(lints(xcm, false), vec![(Lint::TransactWithoutOrigin, vec![3])]);
+	}
+}
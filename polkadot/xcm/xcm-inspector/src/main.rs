@@ -0,0 +1,139 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Command line interface for inspecting SCALE-encoded XCMs.
+//!
+//! For example, to review the XCM sent by a governance proposal, with the calls it carries
+//! decoded from the metadata of the destination:
+//!
+//! ```text
+//! xcm-inspector decode 0x05... --metadata asset-hub.scale
+//! xcm-inspector lint 0x05...
+//! xcm-inspector convert 0x05... --to 4
+//! ```
+//!
+//! The XCM is read from the standard input when given as `-`.
+
+use std::{io::Read, path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand};
+use codec::Encode;
+use xcm::prelude::*;
+
+use xcm_inspector::{convert, decode, lint, Printer};
+
+#[derive(Debug, Parser)]
+#[command(about = "Decode, print, convert and lint SCALE-encoded XCMs")]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Print a hex-encoded `VersionedXcm` in a human-readable form.
+	Decode {
+		#[command(flatten)]
+		input: Input,
+	},
+	/// Convert a hex-encoded `VersionedXcm` to another version, reporting the instructions not
+	/// preserved by the conversion.
+	Convert {
+		#[command(flatten)]
+		input: Input,
+		/// The version to convert to.
+		#[arg(long)]
+		to: XcmVersion,
+	},
+	/// Check a hex-encoded `VersionedXcm` for common mistakes.
+	///
+	/// Exits with an error status if any is found.
+	Lint {
+		/// The hex-encoded `VersionedXcm`, or `-` to read it from the standard input.
+		xcm: String,
+		/// The XCM is executed locally, e.g. with `pallet_xcm::execute`, and needs not to pay
+		/// for its execution.
+		#[arg(long)]
+		local: bool,
+	},
+}
+
+#[derive(Debug, clap::Args)]
+struct Input {
+	/// The hex-encoded `VersionedXcm`, or `-` to read it from the standard input.
+	xcm: String,
+	/// The SCALE or hex-encoded metadata of the runtime executing the XCM, to decode the calls of
+	/// `Transact` instructions.
+	#[arg(long)]
+	metadata: Option<PathBuf>,
+}
+
+impl Input {
+	fn printer(&self) -> Result<Printer, Box<dyn std::error::Error>> {
+		let Some(path) = &self.metadata else { return Ok(Printer::new()) };
+		let metadata = std::fs::read(path)?;
+		let metadata = match metadata.strip_prefix(b"0x") {
+			Some(hex) => parse_hex(std::str::from_utf8(hex)?.trim())?,
+			None => metadata,
+		};
+		Ok(Printer::new().with_metadata(&metadata)?)
+	}
+}
+
+fn parse_hex(s: &str) -> Result<Vec<u8>, String> {
+	array_bytes::hex2bytes(s).map_err(|e| format!("Invalid hex: {e:?}"))
+}
+
+fn read_xcm(xcm: &str) -> Result<VersionedXcm<()>, Box<dyn std::error::Error>> {
+	let mut input = xcm.to_string();
+	if xcm == "-" {
+		input.clear();
+		std::io::stdin().read_to_string(&mut input)?;
+	}
+	Ok(decode(&parse_hex(input.trim())?)?)
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+	match Cli::parse().command {
+		Command::Decode { input } => {
+			let xcm = read_xcm(&input.xcm)?;
+			println!("{}", input.printer()?.print(&xcm)?);
+		},
+		Command::Convert { input, to } => {
+			let xcm = read_xcm(&input.xcm)?;
+			let conversion = convert(&xcm, to)?;
+			println!("{}", input.printer()?.print(&conversion.xcm)?);
+			println!("Encoded: {}", array_bytes::bytes2hex("0x", conversion.xcm.encode()));
+			if !conversion.lossy_instructions.is_empty() {
+				eprintln!(
+					"Lossy conversion: instructions {:?} change when converted back to version {}",
+					conversion.lossy_instructions,
+					xcm.identify_version(),
+				);
+			}
+		},
+		Command::Lint { xcm, local } => {
+			let findings = lint(&read_xcm(&xcm)?, local)?;
+			for finding in &findings {
+				println!("{finding}");
+			}
+			if !findings.is_empty() {
+				return Ok(ExitCode::FAILURE)
+			}
+		},
+	}
+	Ok(ExitCode::SUCCESS)
+}
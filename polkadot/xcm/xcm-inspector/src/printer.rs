@@ -0,0 +1,423 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Printing of XCMs in a human-readable form.
+//!
+//! XCMs are decoded into [`Value`]s from the type information of [`VersionedXcm`], so all versions
+//! are printed the same way, and calls are decoded from the type information of the runtime
+//! metadata.
+
+use codec::{Decode, Encode};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use scale_info::{PortableRegistry, Registry, TypeDef, TypeDefPrimitive};
+use scale_value::{Composite, Primitive, Value, ValueDef};
+use xcm::VersionedXcm;
+
+use crate::{Error, Result};
+
+/// The metadata version used for decoding calls. It is the first one describing the outer call
+/// enum of the runtime.
+pub const METADATA_VERSION: u32 = 15;
+
+const INDENT: &str = "    ";
+
+/// Decodes the calls of a runtime.
+struct CallDecoder {
+	types: PortableRegistry,
+	/// The type of the outer call enum, `RuntimeCall`.
+	call_ty: u32,
+}
+
+/// Prints XCMs in a human-readable form.
+///
+/// Locations are printed as `Location(<parents>, <junctions>)` and byte arrays as hex. Nested
+/// XCMs are printed with one instruction per line.
+pub struct Printer {
+	types: PortableRegistry,
+	/// The type of [`VersionedXcm`] in `types`.
+	xcm_ty: u32,
+	calls: Option<CallDecoder>,
+}
+
+impl Default for Printer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Printer {
+	/// Create a printer which prints the calls of `Transact` instructions as hex.
+	pub fn new() -> Self {
+		let mut registry = Registry::new();
+		let xcm_ty = registry.register_type(&scale_info::meta_type::<VersionedXcm<()>>()).id;
+		Self { types: registry.into(), xcm_ty, calls: None }
+	}
+
+	/// Decode the calls of `Transact` instructions with the encoded runtime metadata, as returned
+	/// by `state_getMetadata`.
+	///
+	/// Calls which cannot be decoded are still printed as hex.
+	pub fn with_metadata(mut self, metadata: &[u8]) -> Result<Self> {
+		let RuntimeMetadataPrefixed(_, metadata) =
+			Decode::decode(&mut &metadata[..]).map_err(|e| Error::Metadata(e.to_string()))?;
+		let RuntimeMetadata::V15(metadata) = metadata else {
+			return Err(Error::Metadata(format!("Expected metadata V{METADATA_VERSION}")))
+		};
+		self.calls = Some(CallDecoder {
+			call_ty: metadata.outer_enums.call_enum_ty.id,
+			types: metadata.types,
+		});
+		Ok(self)
+	}
+
+	/// Print `xcm` in a human-readable form.
+	pub fn print(&self, xcm: &VersionedXcm<()>) -> Result<String> {
+		let encoded = xcm.encode();
+		let value = scale_value::scale::decode_as_type(&mut &encoded[..], self.xcm_ty, &self.types)
+			.map_err(|e| Error::Print(e.to_string()))?;
+		let mut out = String::new();
+		self.write(&mut out, &self.types, &value, 0);
+		Ok(out)
+	}
+
+	fn write(&self, out: &mut String, types: &PortableRegistry, value: &Value<u32>, indent: usize) {
+		if let Some(bytes) = bytes(types, value) {
+			out.push_str(&array_bytes::bytes2hex("0x", bytes));
+			return
+		}
+		if is_xcm(types, value.context) {
+			self.write_xcm(out, types, value, indent);
+			return
+		}
+		match &value.value {
+			ValueDef::Composite(composite) => {
+				if let Some((parents, interior)) = location(composite) {
+					out.push_str(&format!("Location({parents}, "));
+					self.write_interior(out, types, interior, indent);
+					out.push(')');
+					return
+				}
+				match composite {
+					// Newtypes, like `AssetId`, are printed as their inner value.
+					Composite::Unnamed(values)
+						if values.len() == 1 && !is_sequence(types, value) =>
+						self.write(out, types, &values[0], indent),
+					Composite::Unnamed(values) => {
+						let (open, close) =
+							if is_sequence(types, value) { ("[", "]") } else { ("(", ")") };
+						self.write_unnamed(out, types, values, open, close, indent);
+					},
+					Composite::Named(fields) => {
+						let multiline = fields.iter().any(|(_, value)| contains_xcm(types, value));
+						self.write_named(out, types, fields, multiline, indent, false);
+					},
+				}
+			},
+			ValueDef::Variant(variant) => {
+				out.push_str(&variant.name);
+				match &variant.values {
+					Composite::Unnamed(values) if values.is_empty() => {},
+					Composite::Unnamed(values) =>
+						self.write_unnamed(out, types, values, "(", ")", indent),
+					Composite::Named(fields) => {
+						let is_transact = variant.name == "Transact";
+						let multiline = (is_transact && self.calls.is_some()) ||
+							fields.iter().any(|(_, value)| contains_xcm(types, value));
+						out.push(' ');
+						self.write_named(out, types, fields, multiline, indent, is_transact);
+					},
+				}
+			},
+			ValueDef::Primitive(_) | ValueDef::BitSequence(_) => out.push_str(&value.to_string()),
+		}
+	}
+
+	fn write_xcm(
+		&self,
+		out: &mut String,
+		types: &PortableRegistry,
+		value: &Value<u32>,
+		indent: usize,
+	) {
+		// `Xcm` is a newtype around the instructions.
+		let mut instructions = value;
+		while let ValueDef::Composite(Composite::Unnamed(values)) = &instructions.value {
+			match values.as_slice() {
+				[inner] if !is_sequence(types, instructions) => instructions = inner,
+				_ => break,
+			}
+		}
+		let ValueDef::Composite(composite) = &instructions.value else {
+			return self.write(out, types, instructions, indent)
+		};
+		if composite.is_empty() {
+			out.push_str("[]");
+			return
+		}
+		out.push_str("[\n");
+		for instruction in composite.values() {
+			push_indent(out, indent + 1);
+			self.write(out, types, instruction, indent + 1);
+			out.push_str(",\n");
+		}
+		push_indent(out, indent);
+		out.push(']');
+	}
+
+	fn write_interior(
+		&self,
+		out: &mut String,
+		types: &PortableRegistry,
+		interior: &Value<u32>,
+		indent: usize,
+	) {
+		let ValueDef::Variant(variant) = &interior.value else {
+			return self.write(out, types, interior, indent)
+		};
+		if variant.values.is_empty() {
+			out.push_str(&variant.name);
+			return
+		}
+		// The junctions are either the fields of the variant, or an array in its only field.
+		let mut junctions: Vec<&Value<u32>> = variant.values.values().collect();
+		if let [array] = junctions[..] {
+			match &array.value {
+				ValueDef::Composite(composite) if is_sequence(types, array) =>
+					junctions = composite.values().collect(),
+				_ => {},
+			}
+		}
+		out.push('[');
+		for (i, junction) in junctions.into_iter().enumerate() {
+			if i > 0 {
+				out.push_str(", ");
+			}
+			self.write(out, types, junction, indent);
+		}
+		out.push(']');
+	}
+
+	fn write_unnamed(
+		&self,
+		out: &mut String,
+		types: &PortableRegistry,
+		values: &[Value<u32>],
+		open: &str,
+		close: &str,
+		indent: usize,
+	) {
+		out.push_str(open);
+		for (i, value) in values.iter().enumerate() {
+			if i > 0 {
+				out.push_str(", ");
+			}
+			self.write(out, types, value, indent);
+		}
+		out.push_str(close);
+	}
+
+	fn write_named(
+		&self,
+		out: &mut String,
+		types: &PortableRegistry,
+		fields: &[(String, Value<u32>)],
+		multiline: bool,
+		indent: usize,
+		is_transact: bool,
+	) {
+		out.push('{');
+		for (i, (name, value)) in fields.iter().enumerate() {
+			if multiline {
+				out.push('\n');
+				push_indent(out, indent + 1);
+			} else {
+				out.push_str(if i > 0 { ", " } else { " " });
+			}
+			out.push_str(name);
+			out.push_str(": ");
+			let indent = if multiline { indent + 1 } else { indent };
+			match bytes(types, value) {
+				Some(call) if is_transact && name == "call" => self.write_call(out, &call, indent),
+				_ => self.write(out, types, value, indent),
+			}
+			if multiline {
+				out.push(',');
+			}
+		}
+		if multiline {
+			out.push('\n');
+			push_indent(out, indent);
+			out.push('}');
+		} else {
+			out.push_str(" }");
+		}
+	}
+
+	/// Write an encoded call as `Pallet.call { .. }`, or as hex if it cannot be decoded.
+	fn write_call(&self, out: &mut String, call: &[u8], indent: usize) {
+		let Some(calls) = &self.calls else {
+			out.push_str(&array_bytes::bytes2hex("0x", call));
+			return
+		};
+		let mut input = call;
+		let value = scale_value::scale::decode_as_type(&mut input, calls.call_ty, &calls.types)
+			.map_err(|e| e.to_string())
+			.and_then(|value| match input.len() {
+				0 => Ok(value),
+				left => Err(format!("{left} bytes left after decoding")),
+			});
+		match value {
+			// `RuntimeCall` has a variant per pallet, wrapping the call enum of the pallet.
+			Ok(Value { value: ValueDef::Variant(pallet), .. }) if pallet.values.len() == 1 => {
+				out.push_str(&pallet.name);
+				out.push('.');
+				let call = pallet.values.values().next().expect("checked the length above; qed");
+				self.write(out, &calls.types, call, indent);
+			},
+			Ok(value) => self.write(out, &calls.types, &value, indent),
+			Err(error) => out.push_str(&format!(
+				"{} (undecodable call: {error})",
+				array_bytes::bytes2hex("0x", call)
+			)),
+		}
+	}
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+	for _ in 0..indent {
+		out.push_str(INDENT);
+	}
+}
+
+/// The `parents` and `interior` fields, if `composite` is a location.
+fn location(composite: &Composite<u32>) -> Option<(&Value<u32>, &Value<u32>)> {
+	let Composite::Named(fields) = composite else { return None };
+	match &fields[..] {
+		[(parents, value), (interior, junctions)]
+			if parents == "parents" && interior == "interior" =>
+			Some((value, junctions)),
+		_ => None,
+	}
+}
+
+/// The bytes of `value`, if it is a byte array or wraps one, like `DoubleEncoded` does.
+fn bytes(types: &PortableRegistry, value: &Value<u32>) -> Option<Vec<u8>> {
+	let ValueDef::Composite(composite) = &value.value else { return None };
+	if !is_sequence_of_u8(types, value.context) {
+		return match composite.values().collect::<Vec<_>>()[..] {
+			[inner] => bytes(types, inner),
+			_ => None,
+		}
+	}
+	composite
+		.values()
+		.map(|byte| match byte.value {
+			ValueDef::Primitive(Primitive::U128(byte)) => u8::try_from(byte).ok(),
+			_ => None,
+		})
+		.collect()
+}
+
+fn is_sequence(types: &PortableRegistry, value: &Value<u32>) -> bool {
+	matches!(
+		types.resolve(value.context).map(|ty| &ty.type_def),
+		Some(TypeDef::Sequence(_) | TypeDef::Array(_))
+	)
+}
+
+fn is_sequence_of_u8(types: &PortableRegistry, ty: u32) -> bool {
+	let param = match types.resolve(ty).map(|ty| &ty.type_def) {
+		Some(TypeDef::Sequence(sequence)) => sequence.type_param.id,
+		Some(TypeDef::Array(array)) => array.type_param.id,
+		_ => return false,
+	};
+	matches!(
+		types.resolve(param).map(|ty| &ty.type_def),
+		Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+	)
+}
+
+/// Whether `ty` is an `Xcm` of any version.
+fn is_xcm(types: &PortableRegistry, ty: u32) -> bool {
+	let Some(ty) = types.resolve(ty) else { return false };
+	let segments = &ty.path.segments;
+	segments.last().map_or(false, |name| name == "Xcm") &&
+		segments.first().map_or(false, |krate| krate == "xcm" || krate == "staging_xcm")
+}
+
+fn contains_xcm(types: &PortableRegistry, value: &Value<u32>) -> bool {
+	if is_xcm(types, value.context) {
+		return true
+	}
+	match &value.value {
+		ValueDef::Composite(composite) =>
+			composite.values().any(|value| contains_xcm(types, value)),
+		ValueDef::Variant(variant) =>
+			variant.values.values().any(|value| contains_xcm(types, value)),
+		ValueDef::Primitive(_) | ValueDef::BitSequence(_) => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use xcm::{prelude::*, v4};
+
+	#[test]
+	fn prints_instructions_locations_and_bytes() {
+		let xcm: VersionedXcm<()> = Xcm::<()>::builder()
+			.withdraw_asset((Parent, 100u128))
+			.buy_execution((Parent, 100u128), Unlimited)
+			.deposit_asset(AllCounted(1), [7u8; 32])
+			.build()
+			.into();
+
+		let expected = format!(
+			"V5([
+    WithdrawAsset([{{ id: Location(1, Here), fun: Fungible(100) }}]),
+    BuyExecution {{ fees: {{ id: Location(1, Here), fun: Fungible(100) }}, weight_limit: Unlimited }},
+    DepositAsset {{ assets: Wild(AllCounted(1)), beneficiary: Location(0, [AccountId32 {{ network: None, id: 0x{} }}]) }},
+])",
+			"07".repeat(32),
+		);
+		assert_eq!(Printer::new().print(&xcm).unwrap(), expected);
+	}
+
+	#[test]
+	fn prints_nested_xcms_on_separate_lines() {
+		let xcm: VersionedXcm<()> = VersionedXcm::V4(v4::Xcm(vec![
+			v4::Instruction::SetAppendix(v4::Xcm(vec![v4::Instruction::RefundSurplus])),
+			v4::Instruction::Transact {
+				origin_kind: OriginKind::Xcm,
+				require_weight_at_most: Weight::from_parts(1, 2),
+				call: vec![1, 2, 3].into(),
+			},
+		]));
+
+		let expected = "V4([
+    SetAppendix([
+        RefundSurplus,
+    ]),
+    Transact { origin_kind: Xcm, require_weight_at_most: { ref_time: 1, proof_size: 2 }, call: 0x010203 },
+])";
+		assert_eq!(Printer::new().print(&xcm).unwrap(), expected);
+	}
+
+	#[test]
+	fn rejects_unsupported_metadata() {
+		assert!(matches!(Printer::new().with_metadata(&[1, 2, 3]), Err(Error::Metadata(_))));
+	}
+}
@@ -23,6 +23,8 @@ use codec::{Decode, Encode};
 use frame_support::pallet_prelude::{DispatchResultWithPostInfo, TypeInfo};
 use xcm::prelude::*;
 
+pub use xcm_executor::InstructionTrace;
+
 /// Effects of dry-running an extrinsic.
 #[derive(Encode, Decode, Debug, TypeInfo)]
 pub struct CallDryRunEffects<Event> {
@@ -47,6 +49,20 @@ pub struct XcmDryRunEffects<Event> {
 	pub forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
 }
 
+/// Effects of dry-running an XCM program, along with the state of the executor after each
+/// executed instruction.
+#[derive(Encode, Decode, Debug, TypeInfo)]
+pub struct TracedXcmDryRunEffects<Event> {
+	/// The effects of the XCM program execution.
+	pub effects: XcmDryRunEffects<Event>,
+	/// The trace of every executed instruction, in execution order.
+	///
+	/// Instructions of appended programs, such as error or appendix handlers, are included, so
+	/// an `index` might appear more than once. Instructions skipped after an error are not
+	/// included, which means the last entry holds the error, if any.
+	pub trace: Vec<InstructionTrace>,
+}
+
 sp_api::decl_runtime_apis! {
 	/// API for dry-running extrinsics and XCM programs to get the programs that need to be passed to the fees API.
 	///
@@ -73,6 +89,11 @@ sp_api::decl_runtime_apis! {
 
 		/// Dry run XCM program
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<Call>) -> Result<XcmDryRunEffects<Event>, Error>;
+
+		/// Dry run XCM program, tracing the holding register, fees register, origin, weight used
+		/// and error of each executed instruction.
+		#[api_version(3)]
+		fn dry_run_xcm_with_trace(origin_location: VersionedLocation, xcm: VersionedXcm<Call>) -> Result<TracedXcmDryRunEffects<Event>, Error>;
 	}
 }
 
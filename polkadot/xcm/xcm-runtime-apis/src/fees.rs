@@ -67,8 +67,50 @@ sp_api::decl_runtime_apis! {
 		///   different senders that charge different fees.
 		fn query_delivery_fees(destination: VersionedLocation, message: VersionedXcm<()>) -> Result<VersionedAssets, Error>;
 	}
+
+	/// API for quoting the fees of a cross-chain transfer, one hop at a time.
+	///
+	/// Quoting a transfer call returns its local execution and delivery fees, as well as the
+	/// programs each destination must run. Those programs are then quoted with the
+	/// [`XcmPaymentApi`] and [`crate::dry_run::DryRunApi`] of their destination, until all hops
+	/// are covered.
+	pub trait TransferFeesApi<Call, OriginCaller>
+	where
+		Call: Encode,
+		OriginCaller: Encode
+	{
+		/// Returns the fees of dispatching the transfer `call` from `origin` on this chain.
+		///
+		/// # Arguments
+		///
+		/// * `origin`: The origin dispatching the call.
+		/// * `call`: The transfer call, such as `transfer_assets_using_type_and_then`.
+		/// * `asset`: The asset to express the local execution fee in.
+		/// * `result_xcms_version`: The version of the returned assets and programs.
+		fn query_transfer_fees(
+			origin: OriginCaller,
+			call: Call,
+			asset: VersionedAssetId,
+			result_xcms_version: Version,
+		) -> Result<TransferFees, Error>;
+	}
+}
+
+/// Fees of a transfer on the chain it is dispatched on.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub struct TransferFees {
+	/// The fee for executing the local XCM of the transfer, in the requested asset.
+	pub local_execution_fee: u128,
+	/// The fees for delivering the programs sent by the transfer.
+	pub local_delivery_fees: VersionedAssets,
+	/// The programs sent by the transfer, grouped by the destination that must run them.
+	pub forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
 }
 
+/// Errors of the [`XcmPaymentApi`] and [`TransferFeesApi`].
+///
+/// Variants are only appended, with explicit codec indices, so that the errors of both APIs keep
+/// their encoding.
 #[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
 pub enum Error {
 	/// An API part is unsupported.
@@ -94,4 +136,11 @@ pub enum Error {
 	/// Destination is known to be unroutable.
 	#[codec(index = 5)]
 	Unroutable,
+
+	/// The call is not a transfer executing a local XCM, or it failed to execute.
+	///
+	/// Only returned by the [`TransferFeesApi`]. [`XcmPaymentApi`] never returns it, so its
+	/// clients decoding the errors without this variant are not affected.
+	#[codec(index = 6)]
+	InvalidTransfer,
 }
@@ -21,13 +21,13 @@ use frame_system::RawOrigin;
 use sp_api::ProvideRuntimeApi;
 use xcm::prelude::*;
 use xcm_runtime_apis::{
-	dry_run::{CallDryRunEffects, DryRunApi},
-	fees::XcmPaymentApi,
+	dry_run::{CallDryRunEffects, DryRunApi, TracedXcmDryRunEffects},
+	fees::{Error as XcmPaymentApiError, TransferFeesApi, XcmPaymentApi},
 };
 
 mod mock;
 use mock::{
-	fake_message_hash, new_test_ext_with_balances, new_test_ext_with_balances_and_assets,
+	fake_message_hash, new_test_ext_with_balances, new_test_ext_with_balances_and_assets, Balances,
 	DeliveryFees, ExistentialDeposit, HereLocation, OriginCaller, RuntimeCall, RuntimeEvent,
 	TestClient,
 };
@@ -442,6 +442,69 @@ fn dry_run_xcm_versions() {
 	}
 }
 
+// Scenario: User `1` executes a program that fails halfway, trapping the withdrawn assets. The
+// trace of the dry run shows the state of the executor after each instruction, up to and
+// including the failing one.
+#[test]
+fn dry_run_xcm_with_trace_shows_failing_instruction() {
+	sp_tracing::init_for_tests();
+	let who = 1; // AccountId = u64.
+	let amount = 1_000u128;
+	let xcm = Xcm::<RuntimeCall>::builder_unsafe()
+		.withdraw_asset((Here, amount))
+		.clear_origin()
+		.buy_execution((Here, amount), Unlimited)
+		.expect_asset((Here, amount * 2))
+		.deposit_asset(AllCounted(1), [0u8; 32])
+		.build();
+	let balances = vec![(who, amount + ExistentialDeposit::get())];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let client = TestClient;
+		let runtime_api = client.runtime_api();
+		let origin = Location::from([AccountIndex64 { index: 1, network: None }]);
+		let TracedXcmDryRunEffects { effects, trace } = runtime_api
+			.dry_run_xcm_with_trace(
+				H256::zero(),
+				VersionedLocation::from(origin.clone()),
+				VersionedXcm::from(xcm),
+			)
+			.unwrap()
+			.unwrap();
+		assert!(matches!(
+			effects.execution_result,
+			Outcome::Incomplete { error: XcmError::ExpectationFalse, .. }
+		));
+
+		// The deposit was never executed.
+		assert_eq!(trace.iter().map(|step| step.index).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+		assert_eq!(trace[0].origin, Some(origin));
+		assert_eq!(trace[0].holding, (Here, amount).into());
+		assert_eq!(trace[1].origin, None);
+		assert!(trace[2].weight_used.any_gt(Weight::zero()));
+		// The rate of the mock trader rounds the fee of so little weight down to nothing.
+		assert_eq!(trace[2].holding, (Here, amount).into());
+		assert_eq!(trace[3].holding, trace[2].holding);
+		assert!(trace[..3].iter().all(|step| step.error.is_none()));
+		assert_eq!(trace[3].error, Some(XcmError::ExpectationFalse));
+
+		// The trace is cleared after the dry run. The barrier rejects this unpaid program, so
+		// nothing is executed.
+		let effects = runtime_api
+			.dry_run_xcm_with_trace(
+				H256::zero(),
+				VersionedLocation::from([AccountIndex64 { index: 1, network: None }]),
+				VersionedXcm::from(Xcm::<RuntimeCall>(vec![ClearOrigin])),
+			)
+			.unwrap()
+			.unwrap();
+		assert!(matches!(
+			effects.effects.execution_result,
+			Outcome::Incomplete { error: XcmError::Barrier, .. }
+		));
+		assert!(effects.trace.is_empty());
+	});
+}
+
 #[test]
 fn calling_payment_api_with_a_lower_version_works() {
 	let transfer_amount = 100u128;
@@ -464,3 +527,90 @@ fn calling_payment_api_with_a_lower_version_works() {
 		.unwrap();
 	assert!(execution_fees.is_ok());
 }
+
+// Same scenario as in `fee_estimation_for_teleport`, but the user quotes the whole transfer with a
+// single call, getting the program to quote on "AssetHub" back.
+#[test]
+fn transfer_fees_quote_for_teleport() {
+	sp_tracing::init_for_tests();
+	let who = 1; // AccountId = u64.
+	let balances = vec![(who, 100 + DeliveryFees::get() + ExistentialDeposit::get())];
+	let assets = vec![(1, who, 50)];
+	new_test_ext_with_balances_and_assets(balances, assets).execute_with(|| {
+		let client = TestClient;
+		let runtime_api = client.runtime_api();
+		let call = RuntimeCall::XcmPallet(pallet_xcm::Call::transfer_assets {
+			dest: Box::new(VersionedLocation::from((Parent, Parachain(1000)))),
+			beneficiary: Box::new(VersionedLocation::from(AccountId32 {
+				id: [0u8; 32],
+				network: None,
+			})),
+			assets: Box::new(VersionedAssets::from(vec![
+				(Here, 100u128).into(),
+				(Parent, 20u128).into(),
+			])),
+			fee_asset_item: 1, // Fees are paid with the RelayToken
+			weight_limit: Unlimited,
+		});
+		let origin = OriginCaller::system(RawOrigin::Signed(who));
+		let native_token = VersionedAssetId::from(AssetId(HereLocation::get()));
+		let fees = runtime_api
+			.query_transfer_fees(H256::zero(), origin, call, native_token.clone(), XCM_VERSION)
+			.unwrap()
+			.unwrap();
+
+		assert_eq!(fees.local_execution_fee, 440);
+		assert_eq!(fees.local_delivery_fees, VersionedAssets::from((Here, 20u128)));
+		// The dry-run is rolled back, so the delivery fees are quoted from the state before it.
+		assert_eq!(
+			Balances::free_balance(who),
+			100 + DeliveryFees::get() + ExistentialDeposit::get()
+		);
+		let send_message = Xcm::<()>::builder_unsafe()
+			.withdraw_asset((Parent, 20u128))
+			.buy_execution((Parent, 20u128), Unlimited)
+			.receive_teleported_asset(((Parent, Parachain(2000)), 100u128))
+			.clear_origin()
+			.deposit_asset(AllCounted(2), [0u8; 32])
+			.build();
+		assert_eq!(
+			fees.forwarded_xcms,
+			vec![(
+				VersionedLocation::from((Parent, Parachain(1000))),
+				vec![VersionedXcm::from(send_message)],
+			)],
+		);
+
+		// The forwarded program is then quoted on "AssetHub".
+		let (_, remote_messages) = &fees.forwarded_xcms[0];
+		let remote_execution_weight = runtime_api
+			.query_xcm_weight(H256::zero(), remote_messages[0].clone())
+			.unwrap()
+			.unwrap();
+		let remote_execution_fees = runtime_api
+			.query_weight_to_asset_fee(H256::zero(), remote_execution_weight, native_token)
+			.unwrap()
+			.unwrap();
+		assert_eq!(remote_execution_fees, 550);
+	});
+}
+
+#[test]
+fn transfer_fees_quote_rejects_calls_without_local_xcm() {
+	new_test_ext_with_balances(vec![(1, 100)]).execute_with(|| {
+		let client = TestClient;
+		let runtime_api = client.runtime_api();
+		let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+		let origin = OriginCaller::system(RawOrigin::Signed(1));
+		let result = runtime_api
+			.query_transfer_fees(
+				H256::zero(),
+				origin,
+				call,
+				VersionedAssetId::from(AssetId(HereLocation::get())),
+				XCM_VERSION,
+			)
+			.unwrap();
+		assert_eq!(result, Err(XcmPaymentApiError::InvalidTransfer));
+	});
+}
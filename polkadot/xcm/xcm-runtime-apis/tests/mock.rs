@@ -46,8 +46,11 @@ use xcm_executor::{
 
 use xcm_runtime_apis::{
 	conversions::{Error as LocationToAccountApiError, LocationToAccountApi},
-	dry_run::{CallDryRunEffects, DryRunApi, Error as XcmDryRunApiError, XcmDryRunEffects},
-	fees::{Error as XcmPaymentApiError, XcmPaymentApi},
+	dry_run::{
+		CallDryRunEffects, DryRunApi, Error as XcmDryRunApiError, TracedXcmDryRunEffects,
+		XcmDryRunEffects,
+	},
+	fees::{Error as XcmPaymentApiError, TransferFees, TransferFeesApi, XcmPaymentApi},
 	trusted_query::{Error as TrustedQueryApiError, TrustedQueryApi},
 };
 
@@ -328,6 +331,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = XcmPallet;
+	type XcmTracer = XcmPallet;
 }
 
 /// Converts a signed origin of a u64 account into a location with only the `AccountIndex64`
@@ -442,6 +446,31 @@ impl sp_api::ProvideRuntimeApi<Block> for TestClient {
 	}
 }
 
+fn weight_to_asset_fee(
+	weight: Weight,
+	asset: VersionedAssetId,
+) -> Result<u128, XcmPaymentApiError> {
+	let latest_asset_id: Result<AssetId, ()> = asset.clone().try_into();
+	match latest_asset_id {
+		Ok(asset_id) if asset_id.0 == HereLocation::get() =>
+			Ok(WeightToFee::weight_to_fee(&weight)),
+		Ok(asset_id) => {
+			log::trace!(
+				target: "xcm::XcmPaymentApi::query_weight_to_asset_fee",
+				"query_weight_to_asset_fee - unhandled asset_id: {asset_id:?}!"
+			);
+			Err(XcmPaymentApiError::AssetNotFound)
+		},
+		Err(_) => {
+			log::trace!(
+				target: "xcm::XcmPaymentApi::query_weight_to_asset_fee",
+				"query_weight_to_asset_fee - failed to convert asset: {asset:?}!"
+			);
+			Err(XcmPaymentApiError::VersionedConversionFailed)
+		},
+	}
+}
+
 sp_api::mock_impl_runtime_apis! {
 	impl TrustedQueryApi<Block> for RuntimeApi {
 		fn is_trusted_reserve(asset: VersionedAsset, location: VersionedLocation) -> Result<bool, TrustedQueryApiError> {
@@ -475,26 +504,7 @@ sp_api::mock_impl_runtime_apis! {
 		}
 
 		fn query_weight_to_asset_fee(weight: Weight, asset: VersionedAssetId) -> Result<u128, XcmPaymentApiError> {
-			let latest_asset_id: Result<AssetId, ()> = asset.clone().try_into();
-			match latest_asset_id {
-				Ok(asset_id) if asset_id.0 == HereLocation::get() => {
-					Ok(WeightToFee::weight_to_fee(&weight))
-				},
-				Ok(asset_id) => {
-					log::trace!(
-						target: "xcm::XcmPaymentApi::query_weight_to_asset_fee",
-						"query_weight_to_asset_fee - unhandled asset_id: {asset_id:?}!"
-					);
-					Err(XcmPaymentApiError::AssetNotFound)
-				},
-				Err(_) => {
-					log::trace!(
-						target: "xcm::XcmPaymentApi::query_weight_to_asset_fee",
-						"query_weight_to_asset_fee - failed to convert asset: {asset:?}!"
-					);
-					Err(XcmPaymentApiError::VersionedConversionFailed)
-				}
-			}
+			weight_to_asset_fee(weight, asset)
 		}
 
 		fn query_delivery_fees(destination: VersionedLocation, message: VersionedXcm<()>) -> Result<VersionedAssets, XcmPaymentApiError> {
@@ -502,6 +512,17 @@ sp_api::mock_impl_runtime_apis! {
 		}
 	}
 
+	impl TransferFeesApi<Block, RuntimeCall, OriginCaller> for RuntimeApi {
+		fn query_transfer_fees(
+			origin: OriginCaller,
+			call: RuntimeCall,
+			asset: VersionedAssetId,
+			result_xcms_version: XcmVersion,
+		) -> Result<TransferFees, XcmPaymentApiError> {
+			XcmPallet::query_transfer_fees::<XcmRouter, OriginCaller, RuntimeCall>(origin, call, asset, result_xcms_version, weight_to_asset_fee)
+		}
+	}
+
 	impl DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller> for RuntimeApi {
 		fn dry_run_call(
 			origin: OriginCaller,
@@ -521,5 +542,9 @@ sp_api::mock_impl_runtime_apis! {
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<XcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			pallet_xcm::Pallet::<TestRuntime>::dry_run_xcm::<TestRuntime, XcmRouter, RuntimeCall, XcmConfig>(origin_location, xcm)
 		}
+
+		fn dry_run_xcm_with_trace(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<TracedXcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
+			pallet_xcm::Pallet::<TestRuntime>::dry_run_xcm_with_trace::<TestRuntime, XcmRouter, RuntimeCall, XcmConfig>(origin_location, xcm)
+		}
 	}
 }
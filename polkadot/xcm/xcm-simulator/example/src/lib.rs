@@ -22,6 +22,10 @@ mod parachain;
 #[allow(unexpected_cfgs)]
 mod relay_chain;
 
+// We do not declare all features used by `construct_runtime`
+#[allow(unexpected_cfgs)]
+#[cfg(test)]
+mod queued;
 #[cfg(test)]
 mod tests;
 
@@ -0,0 +1,125 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Network passing its messages block by block through the real message queues.
+
+mod parachain;
+mod relay_chain;
+mod tests;
+
+use sp_runtime::BuildStorage;
+use xcm::prelude::*;
+use xcm_executor::traits::ConvertLocation;
+use xcm_simulator::{
+	decl_queued_test_network, decl_queued_test_parachain, decl_queued_test_relay_chain,
+};
+
+use crate::{child_account_id, parent_account_id, ALICE, INITIAL_BALANCE};
+
+decl_queued_test_parachain! {
+	pub struct QueuedParaA {
+		Runtime = parachain::Runtime,
+		XcmpQueue = parachain::XcmpQueue,
+		MessageQueue = parachain::MessageQueue,
+		AllPalletsWithSystem = parachain::AllPalletsWithSystem,
+		new_ext = para_ext(1),
+	}
+}
+
+decl_queued_test_parachain! {
+	pub struct QueuedParaB {
+		Runtime = parachain::Runtime,
+		XcmpQueue = parachain::XcmpQueue,
+		MessageQueue = parachain::MessageQueue,
+		AllPalletsWithSystem = parachain::AllPalletsWithSystem,
+		new_ext = para_ext(2),
+	}
+}
+
+decl_queued_test_relay_chain! {
+	pub struct QueuedRelay {
+		Runtime = relay_chain::Runtime,
+		MessageQueue = relay_chain::MessageQueue,
+		AllPalletsWithSystem = relay_chain::AllPalletsWithSystem,
+		new_ext = relay_ext(),
+	}
+}
+
+decl_queued_test_network! {
+	pub struct QueuedNet {
+		relay_chain = QueuedRelay,
+		parachains = vec![
+			(1, QueuedParaA),
+			(2, QueuedParaB),
+		],
+	}
+}
+
+pub fn sibling_account_id(para: u32) -> parachain::AccountId {
+	let location = (Parent, Parachain(para));
+	crate::parachain::location_converter::LocationConverter::convert_location(&location.into())
+		.unwrap()
+}
+
+pub fn para_ext(para_id: u32) -> sp_io::TestExternalities {
+	use parachain::{Runtime, System};
+
+	let mut t = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![
+			(ALICE, INITIAL_BALANCE),
+			(parent_account_id(), INITIAL_BALANCE),
+			(sibling_account_id(1), INITIAL_BALANCE),
+			(sibling_account_id(2), INITIAL_BALANCE),
+		],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	parachain_info::GenesisConfig::<Runtime> { parachain_id: para_id.into(), ..Default::default() }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		sp_tracing::try_init_simple();
+		System::set_block_number(1);
+	});
+	ext
+}
+
+pub fn relay_ext() -> sp_io::TestExternalities {
+	use relay_chain::{Runtime, System};
+
+	let mut t = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![
+			(ALICE, INITIAL_BALANCE),
+			(child_account_id(1), INITIAL_BALANCE),
+			(child_account_id(2), INITIAL_BALANCE),
+		],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
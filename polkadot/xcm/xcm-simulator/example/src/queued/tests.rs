@@ -0,0 +1,176 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+
+use frame_support::assert_ok;
+use xcm_simulator::{
+	queued::{self, HrmpChannelLimits, MessagingLimits},
+	TestExt,
+};
+
+const BOB: sp_runtime::AccountId32 = sp_runtime::AccountId32::new([2u8; 32]);
+const AMOUNT: u128 = 1_000;
+
+fn transfer_to_bob(asset: impl Into<Location>) -> Xcm<()> {
+	Xcm(vec![TransferAsset {
+		assets: (asset.into(), AMOUNT).into(),
+		beneficiary: AccountId32 { network: None, id: BOB.into() }.into(),
+	}])
+}
+
+fn send_from_para_a(dest: impl Into<Location>, message: Xcm<()>) -> Result<XcmHash, SendError> {
+	QueuedParaA::execute_with(|| {
+		pallet_xcm::Pallet::<parachain::Runtime>::send_xcm(Here, dest, message)
+	})
+}
+
+fn bob_balance_on_para_b() -> u128 {
+	QueuedParaB::execute_with(|| parachain::Balances::free_balance(&BOB))
+}
+
+#[test]
+fn dmp_and_ump_are_processed_block_by_block() {
+	QueuedNet::reset();
+
+	QueuedRelay::execute_with(|| {
+		assert_ok!(pallet_xcm::Pallet::<relay_chain::Runtime>::send_xcm(
+			Here,
+			Parachain(1),
+			transfer_to_bob(Parent),
+		));
+	});
+	assert_ok!(send_from_para_a(Parent, transfer_to_bob(Here)));
+
+	// The downward message is enqueued by the parachain, which sends the upward message.
+	QueuedNet::advance_block();
+	QueuedParaA::execute_with(|| assert_eq!(parachain::Balances::free_balance(&BOB), 0));
+	// The downward message is processed, the upward message is enqueued by the relay chain.
+	QueuedNet::advance_block();
+	QueuedParaA::execute_with(|| assert_eq!(parachain::Balances::free_balance(&BOB), AMOUNT));
+	QueuedRelay::execute_with(|| assert_eq!(relay_chain::Balances::free_balance(&BOB), 0));
+	// The upward message is processed.
+	QueuedNet::advance_block();
+	QueuedRelay::execute_with(|| assert_eq!(relay_chain::Balances::free_balance(&BOB), AMOUNT));
+
+	assert!(QueuedNet::is_idle());
+}
+
+#[test]
+fn hrmp_needs_an_open_channel() {
+	QueuedNet::reset();
+
+	assert!(send_from_para_a((Parent, Parachain(2)), transfer_to_bob(Parent)).is_err());
+
+	queued::open_hrmp_channel(1, 2, HrmpChannelLimits::default());
+	assert_ok!(send_from_para_a((Parent, Parachain(2)), transfer_to_bob(Parent)));
+
+	// Sent, received and processed.
+	QueuedNet::advance_blocks(2);
+	assert_eq!(bob_balance_on_para_b(), 0);
+	QueuedNet::advance_block();
+	assert_eq!(bob_balance_on_para_b(), AMOUNT);
+}
+
+#[test]
+fn hrmp_messages_wait_while_the_channel_is_full() {
+	QueuedNet::reset();
+	queued::open_hrmp_channel(
+		1,
+		2,
+		HrmpChannelLimits { max_capacity: 1, ..HrmpChannelLimits::default() },
+	);
+
+	assert_ok!(send_from_para_a((Parent, Parachain(2)), transfer_to_bob(Parent)));
+	QueuedNet::advance_block();
+	assert_eq!(queued::hrmp_channel(1, 2).unwrap().msg_count, 1);
+
+	// The first message is only received by the second parachain after the first one built its
+	// block, so the second message waits in the first parachain.
+	assert_ok!(send_from_para_a((Parent, Parachain(2)), transfer_to_bob(Parent)));
+	QueuedNet::advance_block();
+	assert_eq!(queued::hrmp_channel(1, 2).unwrap().msg_count, 0);
+	assert!(!<QueuedParaA as queued::QueuedParachain>::is_idle());
+
+	QueuedNet::advance_block();
+	assert_eq!(queued::hrmp_channel(1, 2).unwrap().msg_count, 1);
+
+	QueuedNet::advance_until_idle(10);
+	assert_eq!(bob_balance_on_para_b(), 2 * AMOUNT);
+}
+
+#[test]
+fn overloaded_recipients_suspend_hrmp_channels() {
+	use cumulus_pallet_xcmp_queue::InboundXcmpSuspended;
+	use parachain::{RuntimeOrigin, XcmpQueue};
+
+	QueuedNet::reset();
+	queued::open_hrmp_channel(1, 2, HrmpChannelLimits::default());
+	queued::open_hrmp_channel(2, 1, HrmpChannelLimits::default());
+	// The second parachain does not process its messages and suspends channels with a single
+	// page waiting.
+	QueuedParaB::execute_with(|| {
+		assert_ok!(XcmpQueue::update_resume_threshold(RuntimeOrigin::root(), 0));
+		assert_ok!(XcmpQueue::update_suspend_threshold(RuntimeOrigin::root(), 1));
+		assert_ok!(XcmpQueue::suspend_xcm_execution(RuntimeOrigin::root()));
+	});
+
+	assert_ok!(send_from_para_a((Parent, Parachain(2)), transfer_to_bob(Parent)));
+	QueuedNet::advance_blocks(2);
+	QueuedParaB::execute_with(|| {
+		assert!(InboundXcmpSuspended::<parachain::Runtime>::get().contains(&1.into()))
+	});
+	// The suspension signal is received by the first parachain.
+	QueuedNet::advance_block();
+
+	assert_ok!(send_from_para_a((Parent, Parachain(2)), transfer_to_bob(Parent)));
+	QueuedNet::advance_blocks(2);
+	assert_eq!(queued::hrmp_channel(1, 2).unwrap().msg_count, 0);
+	assert!(!<QueuedParaA as queued::QueuedParachain>::is_idle());
+	assert_eq!(bob_balance_on_para_b(), 0);
+
+	QueuedParaB::execute_with(|| {
+		assert_ok!(XcmpQueue::resume_xcm_execution(RuntimeOrigin::root()));
+	});
+	QueuedNet::advance_until_idle(20);
+	assert_eq!(bob_balance_on_para_b(), 2 * AMOUNT);
+}
+
+#[test]
+fn oversized_messages_are_rejected() {
+	QueuedNet::reset();
+	queued::set_messaging_limits(MessagingLimits {
+		max_upward_message_size: 16,
+		max_downward_message_size: 16,
+		..MessagingLimits::default()
+	});
+
+	assert_eq!(
+		send_from_para_a(Parent, transfer_to_bob(Here)),
+		Err(SendError::ExceedsMaxMessageSize)
+	);
+	QueuedRelay::execute_with(|| {
+		assert_eq!(
+			pallet_xcm::Pallet::<relay_chain::Runtime>::send_xcm(
+				Here,
+				Parachain(1),
+				transfer_to_bob(Parent),
+			),
+			Err(SendError::ExceedsMaxMessageSize)
+		);
+	});
+	assert!(QueuedNet::is_idle());
+}
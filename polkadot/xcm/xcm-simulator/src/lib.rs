@@ -20,6 +20,9 @@
 /// Used for sending messages.
 pub mod mock_message_queue;
 
+/// Simulation of a network passing messages block by block through real message queues.
+pub mod queued;
+
 extern crate alloc;
 
 pub use codec::Encode;
@@ -0,0 +1,731 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Simulation of a network passing messages block by block through the real message queues.
+//!
+//! Unlike the networks declared with [`decl_test_network`](crate::decl_test_network), which
+//! deliver and execute every message as soon as it is sent, the networks declared with
+//! [`decl_queued_test_network`](crate::decl_queued_test_network) only move messages when a block
+//! is built with `advance_block`:
+//! - the relay chain enqueues the upward messages accepted in the last parachain candidates into
+//!   its `pallet-message-queue`, which services them within its `ServiceWeight` in the following
+//!   blocks,
+//! - every parachain enqueues the downward messages into its `pallet-message-queue`, hands the
+//!   horizontal messages to `cumulus-pallet-xcmp-queue`, and sends the pages the latter releases
+//!   for the HRMP channels which have room for them.
+//!
+//! The limits the relay chain would enforce are configurable with [`set_messaging_limits`] and
+//! [`open_hrmp_channel`], and violating them fails like it would on a real network: routers
+//! refuse oversized messages, upward messages wait in the parachain until the relay chain queue
+//! has room for them, and parachains only send horizontal messages on open channels with spare
+//! capacity. Since the queues are real, so are the suspension of XCMP channels by overloaded
+//! recipients and the weight limits of the message queues.
+//!
+//! The parachain runtimes send upward messages through
+//! `cumulus_primitives_utility::ParentAsUmp<UpwardMessages<ParachainInfo>, ..>` and get the state
+//! of their HRMP channels from [`HrmpChannels`], while the relay chain runtime sends downward
+//! messages through [`DownwardRouter`].
+
+use alloc::collections::{btree_map::BTreeMap, btree_set::BTreeSet, vec_deque::VecDeque};
+use codec::Encode;
+use core::{cell::RefCell, marker::PhantomData};
+use frame_support::traits::Get;
+use frame_system::{pallet_prelude::BlockNumberFor, RunToBlockHooks};
+use sp_runtime::traits::One;
+use xcm::{latest::prelude::*, WrapVersion};
+
+use crate::{ParaId, RelayBlockNumber, TestExt};
+
+pub use cumulus_primitives_core::{
+	AggregateMessageOrigin, ChannelInfo, ChannelStatus, GetChannelInfo, MessageSendError,
+	UpwardMessageSender, XcmpMessageHandler, XcmpMessageSource,
+};
+pub use frame_support::traits::QueueFootprintQuery;
+pub use xcm_builder::InspectMessageQueues;
+
+/// The messaging limits of the relay chain configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessagingLimits {
+	/// The maximum size of an upward message.
+	pub max_upward_message_size: u32,
+	/// The maximum number of messages in the upward queue of a parachain.
+	pub max_upward_queue_count: u32,
+	/// The maximum total size of the messages in the upward queue of a parachain.
+	pub max_upward_queue_size: u32,
+	/// The maximum number of upward messages a parachain candidate may send.
+	pub max_upward_message_num_per_candidate: u32,
+	/// The maximum size of a downward message.
+	pub max_downward_message_size: u32,
+	/// The maximum number of horizontal messages a parachain candidate may send.
+	pub hrmp_max_message_num_per_candidate: u32,
+}
+
+impl Default for MessagingLimits {
+	/// The limits of Polkadot.
+	fn default() -> Self {
+		Self {
+			max_upward_message_size: 65_531,
+			max_upward_queue_count: 174_762,
+			max_upward_queue_size: 1_048_576,
+			max_upward_message_num_per_candidate: 16,
+			max_downward_message_size: 51_200,
+			hrmp_max_message_num_per_candidate: 10,
+		}
+	}
+}
+
+/// The limits of an HRMP channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HrmpChannelLimits {
+	/// The maximum number of messages in the channel.
+	pub max_capacity: u32,
+	/// The maximum total size of the messages in the channel.
+	pub max_total_size: u32,
+	/// The maximum size of a message in the channel.
+	pub max_message_size: u32,
+}
+
+impl Default for HrmpChannelLimits {
+	/// The limits of the channels between system parachains of Polkadot.
+	fn default() -> Self {
+		Self { max_capacity: 1_000, max_total_size: 102_400, max_message_size: 102_400 }
+	}
+}
+
+struct Channel {
+	limits: HrmpChannelLimits,
+	messages: VecDeque<(RelayBlockNumber, Vec<u8>)>,
+	total_size: u32,
+}
+
+#[derive(Default)]
+struct Network {
+	relay_block_number: RelayBlockNumber,
+	limits: MessagingLimits,
+	paras: BTreeSet<ParaId>,
+	/// The HRMP channels, by sender and recipient.
+	channels: BTreeMap<(ParaId, ParaId), Channel>,
+	/// The upward messages sent by the parachains, not yet part of a candidate.
+	pending_upward: BTreeMap<ParaId, VecDeque<Vec<u8>>>,
+	/// The upward messages of the last candidates, to be enqueued by the relay chain.
+	upward: Vec<(ParaId, Vec<u8>)>,
+	downward: BTreeMap<ParaId, VecDeque<(RelayBlockNumber, Vec<u8>)>>,
+}
+
+thread_local! {
+	static NETWORK: RefCell<Network> = RefCell::new(Network::default());
+}
+
+fn with_network<R>(f: impl FnOnce(&mut Network) -> R) -> R {
+	NETWORK.with(|network| f(&mut network.borrow_mut()))
+}
+
+/// Set the messaging limits of the relay chain configuration.
+pub fn set_messaging_limits(limits: MessagingLimits) {
+	with_network(|network| network.limits = limits);
+}
+
+/// The messaging limits of the relay chain configuration.
+pub fn messaging_limits() -> MessagingLimits {
+	with_network(|network| network.limits.clone())
+}
+
+/// Open an HRMP channel from `sender` to `recipient`.
+///
+/// Panics if one of the parachains is not part of the network or if the channel is already open.
+pub fn open_hrmp_channel(
+	sender: impl Into<ParaId>,
+	recipient: impl Into<ParaId>,
+	limits: HrmpChannelLimits,
+) {
+	let (sender, recipient) = (sender.into(), recipient.into());
+	with_network(|network| {
+		assert!(
+			network.paras.contains(&sender) && network.paras.contains(&recipient),
+			"Unknown parachain"
+		);
+		let channel = Channel { limits, messages: VecDeque::new(), total_size: 0 };
+		assert!(
+			network.channels.insert((sender, recipient), channel).is_none(),
+			"HRMP channel from {sender:?} to {recipient:?} is already open"
+		);
+	});
+}
+
+/// Close the HRMP channel from `sender` to `recipient`, dropping the messages in it.
+pub fn close_hrmp_channel(sender: impl Into<ParaId>, recipient: impl Into<ParaId>) {
+	with_network(|network| network.channels.remove(&(sender.into(), recipient.into())));
+}
+
+/// The state of the HRMP channel from `sender` to `recipient`, if open.
+pub fn hrmp_channel(
+	sender: impl Into<ParaId>,
+	recipient: impl Into<ParaId>,
+) -> Option<ChannelInfo> {
+	with_network(|network| {
+		let channel = network.channels.get(&(sender.into(), recipient.into()))?;
+		Some(ChannelInfo {
+			max_capacity: channel.limits.max_capacity,
+			max_total_size: channel.limits.max_total_size,
+			max_message_size: channel.limits.max_message_size,
+			msg_count: channel.messages.len() as u32,
+			total_size: channel.total_size,
+		})
+	})
+}
+
+/// The number of the last relay chain block.
+pub fn relay_block_number() -> RelayBlockNumber {
+	with_network(|network| network.relay_block_number)
+}
+
+/// The parachains of the network.
+pub fn paras() -> Vec<ParaId> {
+	with_network(|network| network.paras.iter().copied().collect())
+}
+
+/// Reset the network to the given parachains, with the default limits and without channels.
+#[doc(hidden)]
+pub fn reset(paras: impl IntoIterator<Item = ParaId>) {
+	with_network(|network| {
+		*network = Network { paras: paras.into_iter().collect(), ..Default::default() }
+	});
+}
+
+/// Start a new relay chain block, returning the upward messages to enqueue in it.
+#[doc(hidden)]
+pub fn new_relay_block() -> Vec<(ParaId, Vec<u8>)> {
+	with_network(|network| {
+		network.relay_block_number += 1;
+		core::mem::take(&mut network.upward)
+	})
+}
+
+/// The downward messages a parachain receives in its next block.
+#[doc(hidden)]
+pub fn take_downward(para: ParaId) -> Vec<(RelayBlockNumber, Vec<u8>)> {
+	with_network(|network| {
+		let relay_block_number = network.relay_block_number;
+		let queue = network.downward.entry(para).or_default();
+		let count = queue.iter().take_while(|(sent_at, _)| *sent_at < relay_block_number).count();
+		queue.drain(..count).collect()
+	})
+}
+
+/// The horizontal messages a parachain receives in its next block, ordered like the relay chain
+/// orders them.
+#[doc(hidden)]
+pub fn take_horizontal(recipient: ParaId) -> Vec<(ParaId, RelayBlockNumber, Vec<u8>)> {
+	with_network(|network| {
+		let relay_block_number = network.relay_block_number;
+		let mut messages = Vec::new();
+		for ((sender, _), channel) in
+			network.channels.iter_mut().filter(|((_, r), _)| *r == recipient)
+		{
+			while channel
+				.messages
+				.front()
+				.is_some_and(|(sent_at, _)| *sent_at < relay_block_number)
+			{
+				let (sent_at, message) = channel.messages.pop_front().expect("Checked above");
+				channel.total_size -= message.len() as u32;
+				messages.push((*sender, sent_at, message));
+			}
+		}
+		messages.sort_by_key(|(sender, sent_at, _)| (*sent_at, *sender));
+		messages
+	})
+}
+
+/// The maximum number of horizontal messages a parachain may send in a block.
+#[doc(hidden)]
+pub fn max_outbound_hrmp_messages() -> usize {
+	with_network(|network| network.limits.hrmp_max_message_num_per_candidate as usize)
+}
+
+/// Send the horizontal messages of a parachain block.
+///
+/// Panics if the relay chain would reject the candidate, like `check_outbound_hrmp` of
+/// `polkadot-runtime-parachains` does.
+#[doc(hidden)]
+pub fn send_horizontal(sender: ParaId, messages: Vec<(ParaId, Vec<u8>)>) {
+	with_network(|network| {
+		fn fail(sender: ParaId, reason: &str) -> ! {
+			panic!("The relay chain rejects the candidate of parachain {sender:?}: {reason}")
+		}
+		if messages.len() > network.limits.hrmp_max_message_num_per_candidate as usize {
+			fail(sender, "more horizontal messages than permitted")
+		}
+		if messages.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+			fail(sender, "horizontal messages not sorted by recipient")
+		}
+		let relay_block_number = network.relay_block_number;
+		for (recipient, message) in messages {
+			let Some(channel) = network.channels.get_mut(&(sender, recipient)) else {
+				fail(sender, &format!("no HRMP channel to {recipient:?}"))
+			};
+			let size = message.len() as u32;
+			if size > channel.limits.max_message_size {
+				fail(sender, &format!("message to {recipient:?} exceeds the maximum message size"))
+			}
+			if channel.total_size + size > channel.limits.max_total_size {
+				fail(sender, &format!("channel to {recipient:?} exceeds its maximum total size"))
+			}
+			if channel.messages.len() as u32 + 1 > channel.limits.max_capacity {
+				fail(sender, &format!("channel to {recipient:?} exceeds its capacity"))
+			}
+			channel.total_size += size;
+			channel.messages.push_back((relay_block_number, message));
+		}
+	});
+}
+
+/// Move the pending upward messages of a parachain into its candidate, as many as the relay
+/// chain accepts given the `count` and `size` of the messages already in its queue.
+#[doc(hidden)]
+pub fn send_upward(para: ParaId, count: u64, size: u64) {
+	with_network(|network| {
+		let limits = &network.limits;
+		let remaining_count = (limits.max_upward_queue_count as u64).saturating_sub(count);
+		let mut remaining_size = (limits.max_upward_queue_size as u64).saturating_sub(size);
+		let max_count = remaining_count.min(limits.max_upward_message_num_per_candidate as u64);
+
+		let pending = network.pending_upward.entry(para).or_default();
+		let mut sent = 0;
+		while let Some(message) = pending.front() {
+			if sent == max_count || message.len() as u64 > remaining_size {
+				break
+			}
+			remaining_size -= message.len() as u64;
+			sent += 1;
+			let message = pending.pop_front().expect("Checked above");
+			network.upward.push((para, message));
+		}
+	});
+}
+
+/// Whether no message is in transit between the chains.
+#[doc(hidden)]
+pub fn is_idle() -> bool {
+	with_network(|network| {
+		network.upward.is_empty() &&
+			network.pending_upward.values().all(VecDeque::is_empty) &&
+			network.downward.values().all(VecDeque::is_empty) &&
+			network.channels.values().all(|channel| channel.messages.is_empty())
+	})
+}
+
+/// Finalize the current block and initialize the next one, clearing the events of the
+/// finalized block.
+#[doc(hidden)]
+pub fn run_to_next_block<T, AllPalletsWithSystem>()
+where
+	T: frame_system::Config,
+	AllPalletsWithSystem: frame_support::traits::OnInitialize<BlockNumberFor<T>>
+		+ frame_support::traits::OnFinalize<BlockNumberFor<T>>,
+{
+	let next = frame_system::Pallet::<T>::block_number() + One::one();
+	frame_system::Pallet::<T>::run_to_block_with::<AllPalletsWithSystem>(
+		next,
+		RunToBlockHooks::default().before_initialize(|_| frame_system::Pallet::<T>::reset_events()),
+	);
+}
+
+/// The sender of upward messages of the parachain `T::get()`, to use with
+/// `cumulus_primitives_utility::ParentAsUmp`.
+///
+/// The messages wait in the parachain until the relay chain accepts them, like with
+/// `cumulus-pallet-parachain-system`.
+pub struct UpwardMessages<T>(PhantomData<T>);
+
+impl<T: Get<ParaId>> UpwardMessageSender for UpwardMessages<T> {
+	fn send_upward_message(msg: Vec<u8>) -> Result<(u32, XcmHash), MessageSendError> {
+		let hash = sp_io::hashing::blake2_256(&msg);
+		with_network(|network| {
+			if msg.len() > network.limits.max_upward_message_size as usize {
+				return Err(MessageSendError::TooBig)
+			}
+			let pending = network.pending_upward.entry(T::get()).or_default();
+			pending.push_back(msg);
+			Ok((pending.len() as u32, hash))
+		})
+	}
+}
+
+/// The state of the HRMP channels of the parachain `T::get()`, to use as the `ChannelInfo` of
+/// `cumulus-pallet-xcmp-queue`.
+pub struct HrmpChannels<T>(PhantomData<T>);
+
+impl<T: Get<ParaId>> GetChannelInfo for HrmpChannels<T> {
+	fn get_channel_status(id: ParaId) -> ChannelStatus {
+		match hrmp_channel(T::get(), id) {
+			None => ChannelStatus::Closed,
+			Some(info) if info.msg_count + 1 > info.max_capacity => ChannelStatus::Full,
+			Some(info) => ChannelStatus::Ready(
+				(info.max_total_size - info.total_size) as usize,
+				info.max_message_size as usize,
+			),
+		}
+	}
+
+	fn get_channel_info(id: ParaId) -> Option<ChannelInfo> {
+		hrmp_channel(T::get(), id)
+	}
+}
+
+/// XCM router sending downward messages from the relay chain to the parachains of the network.
+pub struct DownwardRouter<W>(PhantomData<W>);
+
+impl<W: WrapVersion> SendXcm for DownwardRouter<W> {
+	type Ticket = (ParaId, Vec<u8>);
+
+	fn validate(
+		dest: &mut Option<Location>,
+		msg: &mut Option<Xcm<()>>,
+	) -> SendResult<(ParaId, Vec<u8>)> {
+		let d = dest.take().ok_or(SendError::MissingArgument)?;
+		let para = match d.unpack() {
+			(0, [Parachain(id)]) if paras().contains(&(*id).into()) => ParaId::from(*id),
+			_ => {
+				*dest = Some(d);
+				return Err(SendError::NotApplicable)
+			},
+		};
+		let xcm = msg.take().ok_or(SendError::MissingArgument)?;
+		let message = W::wrap_version(&d, xcm)
+			.map_err(|()| SendError::DestinationUnsupported)?
+			.encode();
+		if message.len() > messaging_limits().max_downward_message_size as usize {
+			return Err(SendError::ExceedsMaxMessageSize)
+		}
+		Ok(((para, message), Assets::new()))
+	}
+
+	fn deliver((para, message): (ParaId, Vec<u8>)) -> Result<XcmHash, SendError> {
+		let hash = sp_io::hashing::blake2_256(&message);
+		with_network(|network| {
+			let sent_at = network.relay_block_number;
+			network.downward.entry(para).or_default().push_back((sent_at, message));
+		});
+		Ok(hash)
+	}
+}
+
+/// A relay chain of a network declared with
+/// [`decl_queued_test_network`](crate::decl_queued_test_network).
+pub trait QueuedRelayChain: TestExt {
+	/// Build a block enqueuing the given upward messages.
+	fn build_block(upward: Vec<(ParaId, Vec<u8>)>);
+	/// The number and total size of the messages in the upward queue of `para`.
+	fn upward_queue_footprint(para: ParaId) -> (u64, u64);
+	/// Whether no upward message waits to be processed.
+	fn is_idle() -> bool;
+}
+
+/// A parachain of a network declared with
+/// [`decl_queued_test_network`](crate::decl_queued_test_network).
+pub trait QueuedParachain: TestExt {
+	/// Build a block receiving the given downward and horizontal messages, returning the
+	/// horizontal messages sent by the block, at most `max_outbound` of them.
+	fn build_block(
+		downward: Vec<(RelayBlockNumber, Vec<u8>)>,
+		horizontal: Vec<(ParaId, RelayBlockNumber, Vec<u8>)>,
+		max_outbound: usize,
+	) -> Vec<(ParaId, Vec<u8>)>;
+	/// Whether no inbound message waits to be processed and no outbound message waits to be sent.
+	fn is_idle() -> bool;
+}
+
+/// Declares a relay chain for a network declared with
+/// [`decl_queued_test_network`](crate::decl_queued_test_network), enqueuing the upward messages
+/// into its `pallet-message-queue`.
+///
+/// ```ignore
+/// decl_queued_test_relay_chain! {
+///     pub struct Relay {
+///         Runtime = relay_chain::Runtime,
+///         MessageQueue = relay_chain::MessageQueue,
+///         AllPalletsWithSystem = relay_chain::AllPalletsWithSystem,
+///         new_ext = relay_ext(),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! decl_queued_test_relay_chain {
+	(
+		pub struct $name:ident {
+			Runtime = $runtime:path,
+			MessageQueue = $mq:path,
+			AllPalletsWithSystem = $all_pallets:path,
+			new_ext = $new_ext:expr,
+		}
+	) => {
+		pub struct $name;
+
+		$crate::__impl_queued_ext!($name, $new_ext);
+
+		impl $crate::queued::QueuedRelayChain for $name {
+			fn build_block(upward: Vec<($crate::ParaId, Vec<u8>)>) {
+				use $crate::{AggregateMessageOrigin, EnqueueMessage, TestExt, UmpQueueId};
+
+				$name::execute_with(|| {
+					$crate::queued::run_to_next_block::<$runtime, $all_pallets>();
+					for (para, message) in upward {
+						<$mq as EnqueueMessage<AggregateMessageOrigin>>::enqueue_message(
+							message.as_slice().try_into().expect("Upward message too long"),
+							AggregateMessageOrigin::Ump(UmpQueueId::Para(para)),
+						);
+					}
+				})
+			}
+
+			fn upward_queue_footprint(para: $crate::ParaId) -> (u64, u64) {
+				use $crate::{
+					queued::QueueFootprintQuery, AggregateMessageOrigin, TestExt, UmpQueueId,
+				};
+
+				$name::execute_with(|| {
+					let footprint = <$mq as QueueFootprintQuery<AggregateMessageOrigin>>::footprint(
+						AggregateMessageOrigin::Ump(UmpQueueId::Para(para)),
+					);
+					(footprint.storage.count, footprint.storage.size)
+				})
+			}
+
+			fn is_idle() -> bool {
+				use $crate::{
+					queued::QueueFootprintQuery, AggregateMessageOrigin, TestExt, UmpQueueId,
+				};
+
+				$name::execute_with(|| {
+					$crate::queued::paras().into_iter().all(|para| {
+						<$mq as QueueFootprintQuery<AggregateMessageOrigin>>::footprint(
+							AggregateMessageOrigin::Ump(UmpQueueId::Para(para)),
+						)
+						.ready_pages == 0
+					})
+				})
+			}
+		}
+	};
+}
+
+/// Declares a parachain for a network declared with
+/// [`decl_queued_test_network`](crate::decl_queued_test_network), enqueuing the downward
+/// messages into its `pallet-message-queue` and exchanging horizontal messages through its
+/// `cumulus-pallet-xcmp-queue`.
+///
+/// ```ignore
+/// decl_queued_test_parachain! {
+///     pub struct ParaA {
+///         Runtime = parachain::Runtime,
+///         XcmpQueue = parachain::XcmpQueue,
+///         MessageQueue = parachain::MessageQueue,
+///         AllPalletsWithSystem = parachain::AllPalletsWithSystem,
+///         new_ext = para_ext(1),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! decl_queued_test_parachain {
+	(
+		pub struct $name:ident {
+			Runtime = $runtime:path,
+			XcmpQueue = $xcmp_queue:path,
+			MessageQueue = $mq:path,
+			AllPalletsWithSystem = $all_pallets:path,
+			new_ext = $new_ext:expr,
+		}
+	) => {
+		pub struct $name;
+
+		$crate::__impl_queued_ext!($name, $new_ext);
+
+		impl $crate::queued::QueuedParachain for $name {
+			fn build_block(
+				downward: Vec<($crate::RelayBlockNumber, Vec<u8>)>,
+				horizontal: Vec<($crate::ParaId, $crate::RelayBlockNumber, Vec<u8>)>,
+				max_outbound: usize,
+			) -> Vec<($crate::ParaId, Vec<u8>)> {
+				use $crate::{
+					queued::{AggregateMessageOrigin, XcmpMessageHandler, XcmpMessageSource},
+					EnqueueMessage, TestExt, Weight,
+				};
+
+				$name::execute_with(|| {
+					$crate::queued::run_to_next_block::<$runtime, $all_pallets>();
+					for (_, message) in downward {
+						<$mq as EnqueueMessage<AggregateMessageOrigin>>::enqueue_message(
+							message.as_slice().try_into().expect("Downward message too long"),
+							AggregateMessageOrigin::Parent,
+						);
+					}
+					// Only enqueues the messages, which are processed within the weight limits
+					// of the message queue.
+					<$xcmp_queue as XcmpMessageHandler>::handle_xcmp_messages(
+						horizontal
+							.iter()
+							.map(|(sender, sent_at, data)| (*sender, *sent_at, &data[..])),
+						Weight::MAX,
+					);
+					<$xcmp_queue as XcmpMessageSource>::take_outbound_messages(max_outbound)
+				})
+			}
+
+			fn is_idle() -> bool {
+				use $crate::{
+					queued::{AggregateMessageOrigin, InspectMessageQueues, QueueFootprintQuery},
+					TestExt,
+				};
+
+				$name::execute_with(|| {
+					let ready = |origin| {
+						<$mq as QueueFootprintQuery<AggregateMessageOrigin>>::footprint(origin)
+							.ready_pages > 0
+					};
+					<$xcmp_queue as InspectMessageQueues>::get_messages().is_empty() &&
+						!ready(AggregateMessageOrigin::Parent) &&
+						!$crate::queued::paras()
+							.into_iter()
+							.any(|para| ready(AggregateMessageOrigin::Sibling(para)))
+				})
+			}
+		}
+	};
+}
+
+/// Implements the `TestExt` trait for a chain of a network declared with
+/// [`decl_queued_test_network`](crate::decl_queued_test_network), whose messages are only passed
+/// when building blocks.
+#[macro_export]
+macro_rules! __impl_queued_ext {
+	// entry point: generate ext name
+	($name:ident, $new_ext:expr) => {
+		$crate::paste::paste! {
+			$crate::__impl_queued_ext!(@impl $name, $new_ext, [<EXT_ $name:upper>]);
+		}
+	};
+	// impl
+	(@impl $name:ident, $new_ext:expr, $ext_name:ident) => {
+		thread_local! {
+			pub static $ext_name: $crate::RefCell<$crate::TestExternalities>
+				= $crate::RefCell::new($new_ext);
+		}
+
+		impl $crate::TestExt for $name {
+			fn new_ext() -> $crate::TestExternalities {
+				$new_ext
+			}
+
+			fn reset_ext() {
+				$ext_name.with(|v| *v.borrow_mut() = $new_ext);
+			}
+
+			fn execute_without_dispatch<R>(execute: impl FnOnce() -> R) -> R {
+				$ext_name.with(|v| v.borrow_mut().execute_with(execute))
+			}
+
+			/// Messages are only passed when building blocks.
+			fn dispatch_xcm_buses() {}
+		}
+	};
+}
+
+/// Declares a test network whose messages are passed block by block through the real message
+/// queues of its relay chain and parachains, declared with
+/// [`decl_queued_test_relay_chain`](crate::decl_queued_test_relay_chain) and
+/// [`decl_queued_test_parachain`](crate::decl_queued_test_parachain).
+///
+/// ```ignore
+/// decl_queued_test_network! {
+///     pub struct QueuedNet {
+///         relay_chain = Relay,
+///         parachains = vec![
+///             (1, ParaA),
+///             (2, ParaB),
+///         ],
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! decl_queued_test_network {
+	(
+		pub struct $name:ident {
+			relay_chain = $relay_chain:ty,
+			parachains = vec![ $( ($para_id:expr, $parachain:ty), )* ],
+		}
+	) => {
+		pub struct $name;
+
+		impl $name {
+			/// Reset the chains, and the network to its default limits without HRMP channels.
+			pub fn reset() {
+				use $crate::TestExt;
+
+				$crate::queued::reset([$( $crate::ParaId::from($para_id), )*]);
+				<$relay_chain>::reset_ext();
+				$( <$parachain>::reset_ext(); )*
+			}
+
+			/// Build a block on the relay chain, then a block on every parachain.
+			pub fn advance_block() {
+				use $crate::queued::{QueuedParachain, QueuedRelayChain};
+
+				<$relay_chain>::build_block($crate::queued::new_relay_block());
+				$(
+					let para = $crate::ParaId::from($para_id);
+					let horizontal = <$parachain>::build_block(
+						$crate::queued::take_downward(para),
+						$crate::queued::take_horizontal(para),
+						$crate::queued::max_outbound_hrmp_messages(),
+					);
+					$crate::queued::send_horizontal(para, horizontal);
+					let (count, size) = <$relay_chain>::upward_queue_footprint(para);
+					$crate::queued::send_upward(para, count, size);
+				)*
+			}
+
+			/// Call [`Self::advance_block`] `n` times.
+			pub fn advance_blocks(n: u32) {
+				for _ in 0..n {
+					Self::advance_block();
+				}
+			}
+
+			/// Advance blocks until all messages are processed, returning the number of blocks.
+			///
+			/// Panics if messages are still waiting after `max_blocks` blocks.
+			pub fn advance_until_idle(max_blocks: u32) -> u32 {
+				for blocks in 0..max_blocks {
+					if Self::is_idle() {
+						return blocks
+					}
+					Self::advance_block();
+				}
+				assert!(Self::is_idle(), "Messages still waiting after {} blocks", max_blocks);
+				max_blocks
+			}
+
+			/// Whether all messages are processed.
+			pub fn is_idle() -> bool {
+				use $crate::queued::{QueuedParachain, QueuedRelayChain};
+
+				$crate::queued::is_idle() &&
+					<$relay_chain>::is_idle() $( && <$parachain>::is_idle() )*
+			}
+		}
+	};
+}
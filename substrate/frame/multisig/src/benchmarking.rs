@@ -26,6 +26,15 @@ use crate::Pallet as Multisig;
 
 const SEED: u32 = 0;
 
+/// A balance large enough to pay for any deposit of this pallet.
+fn funding<T: Config>() -> BalanceOf<T> {
+	let max_signatories = T::MaxSignatories::get();
+	T::Currency::minimum_balance()
+		.saturating_add(Multisig::<T>::deposit(max_signatories as u16))
+		.saturating_add(Multisig::<T>::entity_deposit(max_signatories))
+		.saturating_mul(10u32.into())
+}
+
 fn setup_multi<T: Config>(
 	s: u32,
 	z: u32,
@@ -34,8 +43,7 @@ fn setup_multi<T: Config>(
 	for i in 0..s {
 		let signatory = account("signatory", i, SEED);
 		// Give them some balance for a possible deposit
-		let balance = BalanceOf::<T>::max_value();
-		T::Currency::make_free_balance_be(&signatory, balance);
+		T::Currency::set_balance(&signatory, funding::<T>());
 		signatories.push(signatory);
 	}
 	signatories.sort();
@@ -320,14 +328,15 @@ mod benchmarks {
 			.ok_or("multisig not created")?;
 		// The original deposit
 		let old_deposit = multisig.deposit;
-		assert_eq!(T::Currency::reserved_balance(&caller), old_deposit);
+		let reason = HoldReason::MultisigDeposit.into();
+		assert_eq!(T::Currency::balance_on_hold(&reason, &caller), old_deposit);
 
 		let additional_amount = 2u32.into();
 		let new_deposit = old_deposit.saturating_add(additional_amount);
 
-		// Reserve the additional amount from the caller's balance
-		T::Currency::reserve(&caller, additional_amount)?;
-		assert_eq!(T::Currency::reserved_balance(&caller), new_deposit);
+		// Hold the additional amount from the caller's balance
+		T::Currency::hold(&reason, &caller, additional_amount)?;
+		assert_eq!(T::Currency::balance_on_hold(&reason, &caller), new_deposit);
 		// Update the storage with the new deposit
 		Multisigs::<T>::try_mutate(
 			&multi_account_id,
@@ -355,7 +364,83 @@ mod benchmarks {
 		let multisig = Multisigs::<T>::get(multi_account_id.clone(), call_hash)
 			.ok_or("Multisig not created")?;
 		assert_eq!(multisig.deposit, old_deposit);
-		assert_eq!(T::Currency::reserved_balance(&caller), old_deposit);
+		assert_eq!(T::Currency::balance_on_hold(&reason, &caller), old_deposit);
+		Ok(())
+	}
+
+	/// `s`: Signatories, need at least 2 people
+	#[benchmark]
+	fn create_entity(s: Linear<2, { T::MaxSignatories::get() }>) -> Result<(), BenchmarkError> {
+		let (signatories, _) = setup_multi::<T>(s, 0)?;
+		let caller = signatories[0].clone();
+		let entity = Multisig::<T>::entity_account_id(&caller, 0, &Multisig::<T>::timepoint());
+		// The deposit is held from the caller, so its account is not whitelisted.
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), s as u16, signatories, 0);
+
+		assert!(Entities::<T>::contains_key(entity));
+
+		Ok(())
+	}
+
+	/// `s`: Signatories, need at least 2 people
+	/// `o`: Operations underway, all approved by a signatory which is removed
+	#[benchmark]
+	fn update_entity(
+		s: Linear<2, { T::MaxSignatories::get() }>,
+		o: Linear<0, 100>,
+	) -> Result<(), BenchmarkError> {
+		let (mut signatories, _) = setup_multi::<T>(s, 0)?;
+		let caller = signatories[0].clone();
+		let entity = Multisig::<T>::entity_account_id(&caller, 0, &Multisig::<T>::timepoint());
+		Multisig::<T>::create_entity(
+			RawOrigin::Signed(caller.clone()).into(),
+			2,
+			signatories.clone(),
+			0,
+		)?;
+		T::Currency::set_balance(&entity, funding::<T>());
+
+		let approver = signatories.pop().ok_or("signatories should have len 2 or more")?;
+		T::Currency::set_balance(&approver, funding::<T>().saturating_mul((o + 1).into()));
+		for i in 0..o {
+			Multisig::<T>::approve_as_entity(
+				RawOrigin::Signed(approver.clone()).into(),
+				entity.clone(),
+				None,
+				i.using_encoded(blake2_256),
+				Weight::zero(),
+			)?;
+		}
+		signatories.push(account("replacement", 0, SEED));
+		signatories.sort();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(entity.clone()), s as u16, signatories, o);
+
+		let details = Entities::<T>::get(&entity).ok_or("entity not created")?;
+		assert_eq!(details.threshold, s as u16);
+		assert_eq!(details.depositor, entity);
+		assert!(T::Currency::balance_on_hold(&HoldReason::EntityDeposit.into(), &caller).is_zero());
+		assert!(Multisigs::<T>::iter_prefix_values(&entity).all(|m| m.approvals.is_empty()));
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn remove_entity() -> Result<(), BenchmarkError> {
+		let (signatories, _) = setup_multi::<T>(T::MaxSignatories::get(), 0)?;
+		let caller = signatories[0].clone();
+		let entity = Multisig::<T>::entity_account_id(&caller, 0, &Multisig::<T>::timepoint());
+		Multisig::<T>::create_entity(RawOrigin::Signed(caller.clone()).into(), 2, signatories, 0)?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(entity.clone()));
+
+		assert!(!Entities::<T>::contains_key(&entity));
+		assert!(T::Currency::balance_on_hold(&HoldReason::EntityDeposit.into(), &caller).is_zero());
+
 		Ok(())
 	}
 
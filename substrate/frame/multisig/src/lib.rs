@@ -39,6 +39,15 @@
 //!   number of signed origins.
 //! * `approve_as_multi` - Approve a call from a composite origin.
 //! * `cancel_as_multi` - Cancel a call from a composite origin.
+//! * `create_entity` - Create a persistent multisig entity whose account ID does not depend on its
+//!   signatories or threshold.
+//! * `as_entity` - Approve and if possible dispatch a call from a multisig entity.
+//! * `approve_as_entity` - Approve a call from a multisig entity.
+//! * `cancel_as_entity` - Cancel a call from a multisig entity.
+//! * `update_entity` - Change the signatories and threshold of a multisig entity. Must be
+//!   dispatched by the entity itself.
+//! * `remove_entity` - Remove a multisig entity and return its deposit. Must be dispatched by the
+//!   entity itself.
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -52,7 +61,10 @@ extern crate alloc;
 use alloc::{boxed::Box, vec, vec::Vec};
 use frame::{
 	prelude::*,
-	traits::{Currency, ReservableCurrency},
+	traits::{
+		fungible::{Inspect, Mutate, MutateHold},
+		tokens::Precision,
+	},
 };
 use frame_system::RawOrigin;
 pub use weights::WeightInfo;
@@ -75,7 +87,7 @@ macro_rules! log {
 }
 
 pub type BalanceOf<T> =
-	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	<<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
 pub type BlockNumberFor<T> =
 	<<T as Config>::BlockNumberProvider as BlockNumberProvider>::BlockNumber;
@@ -112,7 +124,7 @@ where
 {
 	/// The extrinsic when the multisig operation was opened.
 	pub when: Timepoint<BlockNumber>,
-	/// The amount held in reserve of the `depositor`, to be returned once the operation ends.
+	/// The amount held on deposit of the `depositor`, to be returned once the operation ends.
 	pub deposit: Balance,
 	/// The account who opened it (i.e. the first to approve it).
 	pub depositor: AccountId,
@@ -120,6 +132,27 @@ where
 	pub approvals: BoundedVec<AccountId, MaxApprovals>,
 }
 
+/// A persistent multisig entity.
+///
+/// Unlike the composite accounts derived in [`Pallet::multi_account_id`], the account ID of an
+/// entity is fixed at creation, so its signatories and threshold can be changed without moving
+/// any funds.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxSignatories))]
+pub struct MultisigEntity<Balance, AccountId, MaxSignatories>
+where
+	MaxSignatories: Get<u32>,
+{
+	/// The number of approvals needed to dispatch a call from the entity.
+	pub threshold: u16,
+	/// The accounts which may approve calls of the entity. Always sorted.
+	pub signatories: BoundedVec<AccountId, MaxSignatories>,
+	/// The account which holds the deposit for the entity.
+	pub depositor: AccountId,
+	/// The amount held on deposit of the `depositor`.
+	pub deposit: Balance,
+}
+
 type CallHash = [u8; 32];
 
 enum CallOrHash<T: Config> {
@@ -144,9 +177,13 @@ pub mod pallet {
 			+ From<frame_system::Call<Self>>;
 
 		/// The currency mechanism.
-		type Currency: ReservableCurrency<Self::AccountId>;
+		type Currency: Mutate<Self::AccountId>
+			+ MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+		/// The overarching runtime hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
 
-		/// The base amount of currency needed to reserve for creating a multisig execution or to
+		/// The base amount of currency needed to hold for creating a multisig execution or to
 		/// store a dispatch call for later.
 		///
 		/// This is held for an additional storage item whose value size is
@@ -194,7 +231,7 @@ pub mod pallet {
 	}
 
 	/// The in-code storage version.
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -211,6 +248,26 @@ pub mod pallet {
 		Multisig<BlockNumberFor<T>, BalanceOf<T>, T::AccountId, T::MaxSignatories>,
 	>;
 
+	/// The set of multisig entities, keyed by their stable account ID.
+	#[pallet::storage]
+	pub type Entities<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		MultisigEntity<BalanceOf<T>, T::AccountId, T::MaxSignatories>,
+	>;
+
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The funds are held as deposit for an open multisig operation.
+		#[codec(index = 0)]
+		MultisigDeposit,
+		/// The funds are held as deposit for a multisig entity.
+		#[codec(index = 1)]
+		EntityDeposit,
+	}
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Threshold must be 2 or greater.
@@ -242,6 +299,16 @@ pub mod pallet {
 		MaxWeightTooLow,
 		/// The data to be stored is already stored.
 		AlreadyStored,
+		/// The threshold is greater than the number of signatories.
+		ThresholdTooHigh,
+		/// The account is not a multisig entity.
+		NotEntity,
+		/// A multisig entity with this account ID already exists.
+		EntityExists,
+		/// The sender is not a signatory of the multisig entity.
+		NotSignatory,
+		/// The multisig entity has more operations underway than the given witness.
+		TooManyOperations,
 	}
 
 	#[pallet::event]
@@ -278,6 +345,12 @@ pub mod pallet {
 			old_deposit: BalanceOf<T>,
 			new_deposit: BalanceOf<T>,
 		},
+		/// A multisig entity has been created.
+		EntityCreated { creator: T::AccountId, entity: T::AccountId, threshold: u16 },
+		/// The signatories or threshold of a multisig entity have been changed.
+		EntityUpdated { entity: T::AccountId, threshold: u16 },
+		/// A multisig entity has been removed and its deposit returned.
+		EntityRemoved { entity: T::AccountId, depositor: T::AccountId, deposit: BalanceOf<T> },
 	}
 
 	#[pallet::hooks]
@@ -351,7 +424,7 @@ pub mod pallet {
 		///
 		/// If there are enough, then dispatch the call.
 		///
-		/// Payment: `DepositBase` will be reserved if this is the first approval, plus
+		/// Payment: `DepositBase` will be held if this is the first approval, plus
 		/// `threshold` times `DepositFactor`. It is returned once this dispatch happens or
 		/// is cancelled.
 		///
@@ -374,7 +447,7 @@ pub mod pallet {
 		///
 		/// ## Complexity
 		/// - `O(S + Z + Call)`.
-		/// - Up to one balance-reserve or unreserve operation.
+		/// - Up to one balance-hold or release operation.
 		/// - One passthrough operation, one insert, both `O(S)` where `S` is the number of
 		///   signatories. `S` is capped by `MaxSignatories`, with weight being proportional.
 		/// - One call encode & hash, both of complexity `O(Z)` where `Z` is tx-len.
@@ -417,7 +490,7 @@ pub mod pallet {
 		/// Register approval for a dispatch to be made from a deterministic composite account if
 		/// approved by a total of `threshold - 1` of `other_signatories`.
 		///
-		/// Payment: `DepositBase` will be reserved if this is the first approval, plus
+		/// Payment: `DepositBase` will be held if this is the first approval, plus
 		/// `threshold` times `DepositFactor`. It is returned once this dispatch happens or
 		/// is cancelled.
 		///
@@ -435,7 +508,7 @@ pub mod pallet {
 		///
 		/// ## Complexity
 		/// - `O(S)`.
-		/// - Up to one balance-reserve or unreserve operation.
+		/// - Up to one balance-hold or release operation.
 		/// - One passthrough operation, one insert, both `O(S)` where `S` is the number of
 		///   signatories. `S` is capped by `MaxSignatories`, with weight being proportional.
 		/// - One encode & hash, both of complexity `O(S)`.
@@ -471,8 +544,8 @@ pub mod pallet {
 			)
 		}
 
-		/// Cancel a pre-existing, on-going multisig transaction. Any deposit reserved previously
-		/// for this operation will be unreserved on success.
+		/// Cancel a pre-existing, on-going multisig transaction. Any deposit held previously
+		/// for this operation will be released on success.
 		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
@@ -485,7 +558,7 @@ pub mod pallet {
 		///
 		/// ## Complexity
 		/// - `O(S)`.
-		/// - Up to one balance-reserve or unreserve operation.
+		/// - Up to one balance-hold or release operation.
 		/// - One passthrough operation, one insert, both `O(S)` where `S` is the number of
 		///   signatories. `S` is capped by `MaxSignatories`, with weight being proportional.
 		/// - One encode & hash, both of complexity `O(S)`.
@@ -510,24 +583,10 @@ pub mod pallet {
 
 			let id = Self::multi_account_id(&signatories, threshold);
 
-			let m = <Multisigs<T>>::get(&id, call_hash).ok_or(Error::<T>::NotFound)?;
-			ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
-			ensure!(m.depositor == who, Error::<T>::NotOwner);
-
-			let err_amount = T::Currency::unreserve(&m.depositor, m.deposit);
-			debug_assert!(err_amount.is_zero());
-			<Multisigs<T>>::remove(&id, &call_hash);
-
-			Self::deposit_event(Event::MultisigCancelled {
-				cancelling: who,
-				timepoint,
-				multisig: id,
-				call_hash,
-			});
-			Ok(())
+			Self::cancel(who, id, timepoint, call_hash)
 		}
 
-		/// Poke the deposit reserved for an existing multisig operation.
+		/// Poke the deposit held for an existing multisig operation.
 		///
 		/// The dispatch origin for this call must be _Signed_ and must be the original depositor of
 		/// the multisig operation.
@@ -537,7 +596,7 @@ pub mod pallet {
 		/// - `threshold`: The total number of approvals needed for this multisig.
 		/// - `other_signatories`: The accounts (other than the sender) who are part of the
 		///   multisig.
-		/// - `call_hash`: The hash of the call this deposit is reserved for.
+		/// - `call_hash`: The hash of the call this deposit is held for.
 		///
 		/// Emits `DepositPoked` if successful.
 		#[pallet::call_index(4)]
@@ -573,17 +632,19 @@ pub mod pallet {
 						return Ok(Pays::Yes.into());
 					}
 
-					// Update the reserved amount
+					// Update the held amount
+					let reason = HoldReason::MultisigDeposit.into();
 					if new_deposit > old_deposit {
 						let extra = new_deposit.saturating_sub(old_deposit);
-						T::Currency::reserve(&who, extra)?;
+						T::Currency::hold(&reason, &who, extra)?;
 					} else {
 						let excess = old_deposit.saturating_sub(new_deposit);
-						let remaining_unreserved = T::Currency::unreserve(&who, excess);
-						if !remaining_unreserved.is_zero() {
+						let released =
+							T::Currency::release(&reason, &who, excess, Precision::BestEffort)?;
+						if released != excess {
 							defensive!(
-								"Failed to unreserve for full amount for multisig. (Call Hash, Requested, Actual): ", 
-								(call_hash, excess, excess.saturating_sub(remaining_unreserved))
+								"Failed to release full amount for multisig. (Call Hash, Requested, Actual): ",
+								(call_hash, excess, released)
 							);
 						}
 					}
@@ -604,6 +665,268 @@ pub mod pallet {
 				},
 			)
 		}
+
+		/// Create a persistent multisig entity.
+		///
+		/// The account ID of the entity is derived from the sender, the `index` and the current
+		/// timepoint, and does not change when its signatories or threshold are updated later on.
+		///
+		/// Payment: `DepositBase` plus `DepositFactor` times the number of `signatories` will be
+		/// held from the sender. It is returned once the entity pays for its own storage
+		/// through `update_entity`, so that the sender only pays for the signatories it chose and
+		/// not for the ones the entity sets later on.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The number of approvals needed to dispatch a call from the entity.
+		/// - `signatories`: The accounts who can approve calls of the entity. Must be sorted and
+		/// need not contain the sender.
+		/// - `index`: A disambiguation index, in case this is called multiple times in the same
+		/// transaction.
+		///
+		/// Emits `EntityCreated` if successful.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::create_entity(signatories.len() as u32))]
+		pub fn create_entity(
+			origin: OriginFor<T>,
+			threshold: u16,
+			signatories: Vec<T::AccountId>,
+			index: u16,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let signatories = Self::ensure_valid_entity(threshold, signatories)?;
+
+			let entity = Self::entity_account_id(&who, index, &Self::timepoint());
+			ensure!(!Entities::<T>::contains_key(&entity), Error::<T>::EntityExists);
+
+			let deposit = Self::entity_deposit(signatories.len() as u32);
+			T::Currency::hold(&HoldReason::EntityDeposit.into(), &who, deposit)?;
+
+			Entities::<T>::insert(
+				&entity,
+				MultisigEntity { threshold, signatories, depositor: who.clone(), deposit },
+			);
+			Self::deposit_event(Event::EntityCreated { creator: who, entity, threshold });
+			Ok(())
+		}
+
+		/// Register approval for a dispatch to be made from a multisig entity.
+		///
+		/// If there are enough approvals, then dispatch the call.
+		///
+		/// Payment: `DepositBase` will be held if this is the first approval, plus
+		/// `threshold` times `DepositFactor`. It is returned once this dispatch happens or
+		/// is cancelled.
+		///
+		/// The dispatch origin for this call must be _Signed_ by one of the signatories of the
+		/// entity.
+		///
+		/// - `entity`: The account ID of the multisig entity.
+		/// - `maybe_timepoint`: If this is the first approval, then this must be `None`. If it is
+		/// not the first approval, then it must be `Some`, with the timepoint (block number and
+		/// transaction index) of the first approval transaction.
+		/// - `call`: The call to be executed.
+		///
+		/// Approvals given by accounts that are no longer signatories of the entity are not
+		/// counted towards its threshold.
+		#[pallet::call_index(6)]
+		#[pallet::weight({
+			let s = T::MaxSignatories::get();
+			let z = call.using_encoded(|d| d.len()) as u32;
+
+			T::WeightInfo::as_multi_create(s, z)
+				.max(T::WeightInfo::as_multi_approve(s, z))
+				.max(T::WeightInfo::as_multi_complete(s, z))
+				.saturating_add(T::DbWeight::get().reads(1))
+				.saturating_add(*max_weight)
+		})]
+		pub fn as_entity(
+			origin: OriginFor<T>,
+			entity: T::AccountId,
+			maybe_timepoint: Option<Timepoint<BlockNumberFor<T>>>,
+			call: Box<<T as Config>::RuntimeCall>,
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::operate_entity(who, entity, maybe_timepoint, CallOrHash::Call(*call), max_weight)
+		}
+
+		/// Register approval for a dispatch to be made from a multisig entity.
+		///
+		/// Payment: `DepositBase` will be held if this is the first approval, plus
+		/// `threshold` times `DepositFactor`. It is returned once this dispatch happens or
+		/// is cancelled.
+		///
+		/// The dispatch origin for this call must be _Signed_ by one of the signatories of the
+		/// entity.
+		///
+		/// - `entity`: The account ID of the multisig entity.
+		/// - `maybe_timepoint`: If this is the first approval, then this must be `None`. If it is
+		/// not the first approval, then it must be `Some`, with the timepoint (block number and
+		/// transaction index) of the first approval transaction.
+		/// - `call_hash`: The hash of the call to be executed.
+		///
+		/// NOTE: If this is the final approval, you will want to use `as_entity` instead.
+		#[pallet::call_index(7)]
+		#[pallet::weight({
+			let s = T::MaxSignatories::get();
+
+			T::WeightInfo::approve_as_multi_create(s)
+				.max(T::WeightInfo::approve_as_multi_approve(s))
+				.saturating_add(T::DbWeight::get().reads(1))
+				.saturating_add(*max_weight)
+		})]
+		pub fn approve_as_entity(
+			origin: OriginFor<T>,
+			entity: T::AccountId,
+			maybe_timepoint: Option<Timepoint<BlockNumberFor<T>>>,
+			call_hash: [u8; 32],
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::operate_entity(
+				who,
+				entity,
+				maybe_timepoint,
+				CallOrHash::Hash(call_hash),
+				max_weight,
+			)
+		}
+
+		/// Cancel a pre-existing, on-going multisig transaction of a multisig entity. Any deposit
+		/// held previously for this operation will be released on success.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the account that opened the
+		/// operation. It does not need to be a signatory of the entity anymore, and the entity
+		/// may have been removed in the meantime.
+		///
+		/// - `entity`: The account ID of the multisig entity.
+		/// - `timepoint`: The timepoint (block number and transaction index) of the first approval
+		/// transaction for this dispatch.
+		/// - `call_hash`: The hash of the call to be executed.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::cancel_as_multi(T::MaxSignatories::get()))]
+		pub fn cancel_as_entity(
+			origin: OriginFor<T>,
+			entity: T::AccountId,
+			timepoint: Timepoint<BlockNumberFor<T>>,
+			call_hash: [u8; 32],
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::cancel(who, entity, timepoint, call_hash)
+		}
+
+		/// Change the signatories and threshold of a multisig entity.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the multisig entity itself, i.e.
+		/// it must have been approved by the current signatories through `as_entity`.
+		///
+		/// From then on the entity pays for its own storage: `DepositBase` plus `DepositFactor`
+		/// times the number of new `signatories` is held from the entity and the previous
+		/// deposit is returned to its depositor.
+		///
+		/// - `threshold`: The number of approvals needed to dispatch a call from the entity.
+		/// - `signatories`: The accounts who can approve calls of the entity. Must be sorted.
+		/// - `operations`: A witness of the number of operations of the entity which are
+		/// underway. Must be at least that number.
+		///
+		/// Operations which are already underway are kept, but the approvals of accounts which
+		/// are not among the new signatories are removed from them.
+		///
+		/// Emits `EntityUpdated` if successful.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::update_entity(signatories.len() as u32, *operations))]
+		pub fn update_entity(
+			origin: OriginFor<T>,
+			threshold: u16,
+			signatories: Vec<T::AccountId>,
+			operations: u32,
+		) -> DispatchResult {
+			let entity = ensure_signed(origin)?;
+			let signatories = Self::ensure_valid_entity(threshold, signatories)?;
+			// Read one more than the witness, so that a wrong one is detected without reading all.
+			let underway = Multisigs::<T>::iter_prefix(&entity)
+				.take(operations.saturating_add(1) as usize)
+				.collect::<Vec<_>>();
+			ensure!(underway.len() <= operations as usize, Error::<T>::TooManyOperations);
+
+			Entities::<T>::try_mutate(&entity, |maybe_entity| -> DispatchResult {
+				let details = maybe_entity.as_mut().ok_or(Error::<T>::NotEntity)?;
+
+				// Release first, so that an entity which already pays for its own storage only
+				// needs to afford its new deposit.
+				let reason = HoldReason::EntityDeposit.into();
+				let released = T::Currency::release(
+					&reason,
+					&details.depositor,
+					details.deposit,
+					Precision::BestEffort,
+				)?;
+				if released != details.deposit {
+					defensive!(
+						"Failed to release full amount for multisig entity. (Entity, Requested, Actual): ",
+						(&entity, details.deposit, released)
+					);
+				}
+				let new_deposit = Self::entity_deposit(signatories.len() as u32);
+				T::Currency::hold(&reason, &entity, new_deposit)?;
+
+				// Drop the approvals of removed signatories, so that they do not count again if
+				// those are added back later on.
+				for (call_hash, mut m) in underway {
+					let approvals = m.approvals.len();
+					m.approvals.retain(|a| signatories.binary_search(a).is_ok());
+					if m.approvals.len() != approvals {
+						Multisigs::<T>::insert(&entity, call_hash, m);
+					}
+				}
+
+				details.threshold = threshold;
+				details.signatories = signatories;
+				details.depositor = entity.clone();
+				details.deposit = new_deposit;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::EntityUpdated { entity, threshold });
+			Ok(())
+		}
+
+		/// Remove a multisig entity and return its deposit to the depositor.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the multisig entity itself, i.e.
+		/// it must have been approved by the current signatories through `as_entity`.
+		///
+		/// Operations which are still underway can no longer be approved, but they can be
+		/// cancelled through `cancel_as_entity` to release their deposits.
+		///
+		/// Emits `EntityRemoved` if successful.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::remove_entity())]
+		pub fn remove_entity(origin: OriginFor<T>) -> DispatchResult {
+			let entity = ensure_signed(origin)?;
+			let details = Entities::<T>::take(&entity).ok_or(Error::<T>::NotEntity)?;
+
+			let released = T::Currency::release(
+				&HoldReason::EntityDeposit.into(),
+				&details.depositor,
+				details.deposit,
+				Precision::BestEffort,
+			)?;
+			if released != details.deposit {
+				defensive!(
+					"Failed to release full amount for multisig entity. (Entity, Requested, Actual): ",
+					(&entity, details.deposit, released)
+				);
+			}
+
+			Self::deposit_event(Event::EntityRemoved {
+				entity,
+				depositor: details.depositor,
+				deposit: released,
+			});
+			Ok(())
+		}
 	}
 }
 
@@ -618,6 +941,23 @@ impl<T: Config> Pallet<T> {
 			.expect("infinite length input; no invalid inputs for type; qed")
 	}
 
+	/// Derive the account ID of a multisig entity.
+	///
+	/// - `who`: The account creating the entity.
+	/// - `index`: A disambiguation index, in case this is called multiple times in the same
+	///   transaction.
+	/// - `timepoint`: The timepoint of the creating transaction.
+	pub fn entity_account_id(
+		who: &T::AccountId,
+		index: u16,
+		timepoint: &Timepoint<BlockNumberFor<T>>,
+	) -> T::AccountId {
+		let entropy = (b"modlpy/multient_", who, timepoint.height, timepoint.index, index)
+			.using_encoded(blake2_256);
+		Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+			.expect("infinite length input; no invalid inputs for type; qed")
+	}
+
 	fn operate(
 		who: T::AccountId,
 		threshold: u16,
@@ -635,6 +975,51 @@ impl<T: Config> Pallet<T> {
 
 		let id = Self::multi_account_id(&signatories, threshold);
 
+		Self::operate_as(
+			who,
+			id,
+			threshold,
+			&signatories,
+			maybe_timepoint,
+			call_or_hash,
+			max_weight,
+		)
+	}
+
+	fn operate_entity(
+		who: T::AccountId,
+		entity: T::AccountId,
+		maybe_timepoint: Option<Timepoint<BlockNumberFor<T>>>,
+		call_or_hash: CallOrHash<T>,
+		max_weight: Weight,
+	) -> DispatchResultWithPostInfo {
+		let details = Entities::<T>::get(&entity).ok_or(Error::<T>::NotEntity)?;
+		ensure!(details.signatories.binary_search(&who).is_ok(), Error::<T>::NotSignatory);
+
+		Self::operate_as(
+			who,
+			entity,
+			details.threshold,
+			&details.signatories,
+			maybe_timepoint,
+			call_or_hash,
+			max_weight,
+		)
+	}
+
+	/// Approve and if possible dispatch a call from `id`, which is controlled by the sorted
+	/// `signatories`, `who` being one of them.
+	fn operate_as(
+		who: T::AccountId,
+		id: T::AccountId,
+		threshold: u16,
+		signatories: &[T::AccountId],
+		maybe_timepoint: Option<Timepoint<BlockNumberFor<T>>>,
+		call_or_hash: CallOrHash<T>,
+		max_weight: Weight,
+	) -> DispatchResultWithPostInfo {
+		let other_signatories_len = signatories.len().saturating_sub(1);
+
 		// Threshold > 1; this means it's a multi-step operation. We extract the `call_hash`.
 		let (call_hash, call_len, maybe_call) = match call_or_hash {
 			CallOrHash::Call(call) => {
@@ -650,8 +1035,10 @@ impl<T: Config> Pallet<T> {
 			let timepoint = maybe_timepoint.ok_or(Error::<T>::NoTimepoint)?;
 			ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
 
-			// Ensure that either we have not yet signed or that it is at threshold.
-			let mut approvals = m.approvals.len() as u16;
+			// Ensure that either we have not yet signed or that it is at threshold. Only
+			// approvals of current signatories count; an entity may have changed them since.
+			let mut approvals =
+				m.approvals.iter().filter(|a| signatories.binary_search(a).is_ok()).count() as u16;
 			// We only bother with the approval if we're below threshold.
 			let maybe_pos = m.approvals.binary_search(&who).err().filter(|_| approvals < threshold);
 			// Bump approvals if not yet voted and the vote is needed.
@@ -670,7 +1057,12 @@ impl<T: Config> Pallet<T> {
 				// Clean up storage before executing call to avoid an possibility of reentrancy
 				// attack.
 				<Multisigs<T>>::remove(&id, call_hash);
-				T::Currency::unreserve(&m.depositor, m.deposit);
+				let _ = T::Currency::release(
+					&HoldReason::MultisigDeposit.into(),
+					&m.depositor,
+					m.deposit,
+					Precision::BestEffort,
+				);
 
 				let result = call.dispatch(RawOrigin::Signed(id.clone()).into());
 				Self::deposit_event(Event::MultisigExecuted {
@@ -723,7 +1115,7 @@ impl<T: Config> Pallet<T> {
 			// Just start the operation by recording it in storage.
 			let deposit = Self::deposit(threshold);
 
-			T::Currency::reserve(&who, deposit)?;
+			T::Currency::hold(&HoldReason::MultisigDeposit.into(), &who, deposit)?;
 
 			let initial_approvals =
 				vec![who.clone()].try_into().map_err(|_| Error::<T>::TooManySignatories)?;
@@ -755,6 +1147,50 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Cancel the operation on `id` for `call_hash` opened by `who` at `timepoint`.
+	fn cancel(
+		who: T::AccountId,
+		id: T::AccountId,
+		timepoint: Timepoint<BlockNumberFor<T>>,
+		call_hash: CallHash,
+	) -> DispatchResult {
+		let m = <Multisigs<T>>::get(&id, call_hash).ok_or(Error::<T>::NotFound)?;
+		ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
+		ensure!(m.depositor == who, Error::<T>::NotOwner);
+
+		let released = T::Currency::release(
+			&HoldReason::MultisigDeposit.into(),
+			&m.depositor,
+			m.deposit,
+			Precision::BestEffort,
+		);
+		debug_assert!(released == Ok(m.deposit));
+		<Multisigs<T>>::remove(&id, &call_hash);
+
+		Self::deposit_event(Event::MultisigCancelled {
+			cancelling: who,
+			timepoint,
+			multisig: id,
+			call_hash,
+		});
+		Ok(())
+	}
+
+	/// Check that the signatories of an entity are sorted and without duplicates and that the
+	/// threshold can be reached.
+	fn ensure_valid_entity(
+		threshold: u16,
+		signatories: Vec<T::AccountId>,
+	) -> Result<BoundedVec<T::AccountId, T::MaxSignatories>, DispatchError> {
+		ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
+		ensure!(
+			signatories.windows(2).all(|pair| pair[0] < pair[1]),
+			Error::<T>::SignatoriesOutOfOrder
+		);
+		ensure!(threshold as usize <= signatories.len(), Error::<T>::ThresholdTooHigh);
+		signatories.try_into().map_err(|_| Error::<T>::TooManySignatories.into())
+	}
+
 	/// Check that signatories is sorted and doesn't contain sender, then insert sender.
 	fn ensure_sorted_and_insert(
 		other_signatories: Vec<T::AccountId>,
@@ -783,6 +1219,13 @@ impl<T: Config> Pallet<T> {
 	pub fn deposit(threshold: u16) -> BalanceOf<T> {
 		T::DepositBase::get() + T::DepositFactor::get() * threshold.into()
 	}
+
+	/// Calculate the deposit for a multisig entity.
+	///
+	/// The deposit is calculated as `DepositBase + DepositFactor * signatories`.
+	pub fn entity_deposit(signatories: u32) -> BalanceOf<T> {
+		T::DepositBase::get() + T::DepositFactor::get() * signatories.into()
+	}
 }
 
 /// Return the weight of a dispatch call result as an `Option`.
@@ -18,7 +18,17 @@
 // Migrations for Multisig Pallet
 
 use crate::*;
-use frame::prelude::*;
+use frame::{
+	deps::frame_support::{
+		migrations::{reserve_to_hold, MigrationId, SteppedMigration, SteppedMigrationError},
+		weights::WeightMeter,
+	},
+	prelude::*,
+	traits::ReservableCurrency,
+};
+
+/// The pallet identifier of the migrations, see [`MigrationId`].
+pub const PALLET_MIGRATIONS_ID: &[u8; 15] = b"pallet-multisig";
 
 pub mod v1 {
 	use super::*;
@@ -33,8 +43,15 @@ pub mod v1 {
 		(OpaqueCall<T>, <T as frame_system::Config>::AccountId, BalanceOf<T>),
 	>;
 
+	/// Refunds and deletes the calls stored by the pallet before v1.
+	///
+	/// The deposits of the calls were reserved, so [`Config::Currency`] must still be able to
+	/// unreserve them.
 	pub struct MigrateToV1<T>(core::marker::PhantomData<T>);
-	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T>
+	where
+		T::Currency: ReservableCurrency<T::AccountId, Balance = BalanceOf<T>>,
+	{
 		#[cfg(feature = "try-runtime")]
 		fn pre_upgrade() -> Result<Vec<u8>, frame::try_runtime::TryRuntimeError> {
 			log!(info, "Number of calls to refund and delete: {}", Calls::<T>::iter().count());
@@ -43,8 +60,6 @@ pub mod v1 {
 		}
 
 		fn on_runtime_upgrade() -> Weight {
-			use frame::traits::ReservableCurrency as _;
-			let current = Pallet::<T>::in_code_storage_version();
 			let onchain = Pallet::<T>::on_chain_storage_version();
 
 			if onchain > 0 {
@@ -54,11 +69,11 @@ pub mod v1 {
 
 			let mut call_count = 0u64;
 			Calls::<T>::drain().for_each(|(_call_hash, (_data, caller, deposit))| {
-				T::Currency::unreserve(&caller, deposit);
+				<T::Currency as ReservableCurrency<_>>::unreserve(&caller, deposit);
 				call_count.saturating_inc();
 			});
 
-			current.put::<Pallet<T>>();
+			StorageVersion::new(1).put::<Pallet<T>>();
 
 			T::DbWeight::get().reads_writes(
 				// Reads: Get Calls + Get Version
@@ -78,3 +93,78 @@ pub mod v1 {
 		}
 	}
 }
+
+pub mod v2 {
+	use super::*;
+
+	/// Converts the deposits of open multisig operations from reserves of `OldCurrency` into
+	/// holds of [`Config::Currency`], one operation at a time.
+	///
+	/// `OldCurrency` must be the `ReservableCurrency` the pallet was configured with before v2.
+	pub struct LazyMigrationV1ToV2<T, OldCurrency>(core::marker::PhantomData<(T, OldCurrency)>);
+
+	impl<T: Config, OldCurrency> SteppedMigration for LazyMigrationV1ToV2<T, OldCurrency>
+	where
+		OldCurrency: ReservableCurrency<T::AccountId, Balance = BalanceOf<T>>,
+	{
+		type Cursor = (T::AccountId, [u8; 32]);
+		type Identifier = MigrationId<15>;
+
+		fn id() -> Self::Identifier {
+			MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 1, version_to: 2 }
+		}
+
+		fn step(
+			mut cursor: Option<Self::Cursor>,
+			meter: &mut WeightMeter,
+		) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+			if Pallet::<T>::on_chain_storage_version() != Self::id().version_from as u16 {
+				return Ok(None)
+			}
+
+			// Reads: Get item + Unreserve + Hold, writes: Unreserve + Hold
+			let required = T::DbWeight::get().reads_writes(3, 2);
+			if meter.remaining().any_lt(required) {
+				return Err(SteppedMigrationError::InsufficientWeight { required })
+			}
+
+			while meter.try_consume(required).is_ok() {
+				let mut iter = match cursor {
+					Some((multisig, call_hash)) => Multisigs::<T>::iter_from(
+						Multisigs::<T>::hashed_key_for(multisig, call_hash),
+					),
+					None => Multisigs::<T>::iter(),
+				};
+				let Some((multisig, call_hash, details)) = iter.next() else {
+					StorageVersion::new(Self::id().version_to as u16).put::<Pallet<T>>();
+					return Ok(None)
+				};
+
+				reserve_to_hold::<_, OldCurrency, T::Currency>(
+					&details.depositor,
+					details.deposit,
+					&HoldReason::MultisigDeposit.into(),
+				);
+				cursor = Some((multisig, call_hash));
+			}
+
+			Ok(cursor)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), frame::try_runtime::TryRuntimeError> {
+			use frame::traits::fungible::InspectHold;
+
+			for (_, _, multisig) in Multisigs::<T>::iter() {
+				ensure!(
+					T::Currency::balance_on_hold(
+						&HoldReason::MultisigDeposit.into(),
+						&multisig.depositor
+					) >= multisig.deposit,
+					"multisig deposit is not held"
+				);
+			}
+			Ok(())
+		}
+	}
+}
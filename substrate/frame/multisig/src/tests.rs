@@ -52,6 +52,8 @@ impl Contains<RuntimeCall> for TestBaseCallFilter {
 	fn contains(c: &RuntimeCall) -> bool {
 		match *c {
 			RuntimeCall::Balances(_) => true,
+			RuntimeCall::Multisig(Call::update_entity { .. }) => true,
+			RuntimeCall::Multisig(Call::remove_entity { .. }) => true,
 			// Needed for benchmarking
 			RuntimeCall::System(frame_system::Call::remark { .. }) => true,
 			_ => false,
@@ -68,6 +70,7 @@ impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
 	type Currency = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type DepositBase = MultisigDepositBase;
 	type DepositFactor = MultisigDepositFactor;
 	type MaxSignatories = ConstU32<3>;
@@ -75,7 +78,7 @@ impl Config for Test {
 	type BlockNumberProvider = frame_system::Pallet<Test>;
 }
 
-use pallet_balances::{Call as BalancesCall, Error as BalancesError};
+use pallet_balances::Call as BalancesCall;
 
 pub fn new_test_ext() -> TestState {
 	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
@@ -98,6 +101,19 @@ fn call_transfer(dest: u64, value: u64) -> Box<RuntimeCall> {
 	Box::new(RuntimeCall::Balances(BalancesCall::transfer_allow_death { dest, value }))
 }
 
+fn call_update_entity(threshold: u16, signatories: Vec<u64>, operations: u32) -> Box<RuntimeCall> {
+	Box::new(RuntimeCall::Multisig(Call::update_entity { threshold, signatories, operations }))
+}
+
+/// Create an entity of `1, 2, 3` with threshold 2, funded with 10.
+fn create_funded_entity() -> u64 {
+	let entity = Multisig::entity_account_id(&1, 0, &now());
+	assert_ok!(Multisig::create_entity(RuntimeOrigin::signed(1), 2, vec![1, 2, 3], 0));
+	assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(2), entity, 5));
+	assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(3), entity, 5));
+	entity
+}
+
 #[test]
 fn multisig_deposit_is_taken_and_returned() {
 	new_test_ext().execute_with(|| {
@@ -1001,7 +1017,399 @@ fn poke_deposit_handles_insufficient_balance() {
 		// Should fail due to insufficient balance
 		assert_noop!(
 			Multisig::poke_deposit(RuntimeOrigin::signed(4), threshold, other_signatories, hash),
-			BalancesError::<Test, _>::InsufficientBalance
+			TokenError::FundsUnavailable
+		);
+	});
+}
+
+#[test]
+fn create_entity_works() {
+	new_test_ext().execute_with(|| {
+		let entity = Multisig::entity_account_id(&1, 0, &now());
+		assert_ok!(Multisig::create_entity(RuntimeOrigin::signed(1), 2, vec![1, 2, 3], 0));
+
+		let details = Entities::<Test>::get(entity).unwrap();
+		assert_eq!(details.threshold, 2);
+		assert_eq!(details.signatories.into_inner(), vec![1, 2, 3]);
+		assert_eq!(details.depositor, 1);
+		assert_eq!(details.deposit, 4);
+		assert_eq!(Balances::reserved_balance(1), 4);
+		System::assert_last_event(Event::EntityCreated { creator: 1, entity, threshold: 2 }.into());
+
+		// The same index in the same transaction yields the same entity.
+		assert_noop!(
+			Multisig::create_entity(RuntimeOrigin::signed(1), 2, vec![1, 2, 3], 0),
+			Error::<Test>::EntityExists,
+		);
+		assert_ok!(Multisig::create_entity(RuntimeOrigin::signed(1), 2, vec![1, 2, 3], 1));
+	});
+}
+
+#[test]
+fn create_entity_checks_signatories_and_threshold() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Multisig::create_entity(RuntimeOrigin::signed(1), 1, vec![1, 2, 3], 0),
+			Error::<Test>::MinimumThreshold,
+		);
+		assert_noop!(
+			Multisig::create_entity(RuntimeOrigin::signed(1), 3, vec![1, 2], 0),
+			Error::<Test>::ThresholdTooHigh,
+		);
+		assert_noop!(
+			Multisig::create_entity(RuntimeOrigin::signed(1), 2, vec![2, 1, 3], 0),
+			Error::<Test>::SignatoriesOutOfOrder,
+		);
+		assert_noop!(
+			Multisig::create_entity(RuntimeOrigin::signed(1), 2, vec![1, 2, 2], 0),
+			Error::<Test>::SignatoriesOutOfOrder,
+		);
+		assert_noop!(
+			Multisig::create_entity(RuntimeOrigin::signed(1), 2, vec![1, 2, 3, 4], 0),
+			Error::<Test>::TooManySignatories,
+		);
+	});
+}
+
+#[test]
+fn entity_dispatch_works() {
+	new_test_ext().execute_with(|| {
+		let entity = create_funded_entity();
+		let call = call_transfer(6, 5);
+		let call_weight = call.get_dispatch_info().call_weight;
+
+		assert_noop!(
+			Multisig::as_entity(RuntimeOrigin::signed(4), entity, None, call.clone(), call_weight),
+			Error::<Test>::NotSignatory,
+		);
+		assert_noop!(
+			Multisig::as_entity(RuntimeOrigin::signed(2), 6, None, call.clone(), call_weight),
+			Error::<Test>::NotEntity,
+		);
+
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(2),
+			entity,
+			None,
+			call.clone(),
+			Weight::zero()
+		));
+		assert_eq!(Balances::reserved_balance(2), 3);
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(3),
+			entity,
+			Some(now()),
+			call,
+			call_weight
+		));
+
+		assert_eq!(Balances::free_balance(6), 5);
+		assert_eq!(Balances::free_balance(entity), 5);
+		assert_eq!(Balances::reserved_balance(2), 0);
+	});
+}
+
+#[test]
+fn cancel_as_entity_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		let entity = create_funded_entity();
+		let hash = blake2_256(&call_transfer(6, 5).encode());
+
+		assert_ok!(Multisig::approve_as_entity(
+			RuntimeOrigin::signed(2),
+			entity,
+			None,
+			hash,
+			Weight::zero()
+		));
+		assert_eq!(Balances::reserved_balance(2), 3);
+
+		assert_noop!(
+			Multisig::cancel_as_entity(RuntimeOrigin::signed(3), entity, now(), hash),
+			Error::<Test>::NotOwner,
+		);
+		assert_ok!(Multisig::cancel_as_entity(RuntimeOrigin::signed(2), entity, now(), hash));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(!Multisigs::<Test>::contains_key(entity, hash));
+	});
+}
+
+#[test]
+fn update_entity_must_be_dispatched_by_entity() {
+	new_test_ext().execute_with(|| {
+		let _ = create_funded_entity();
+
+		assert_noop!(
+			Multisig::update_entity(RuntimeOrigin::signed(1), 2, vec![1, 2], 0),
+			Error::<Test>::NotEntity,
 		);
 	});
 }
+
+#[test]
+fn update_entity_rotates_signatories() {
+	new_test_ext().execute_with(|| {
+		let entity = create_funded_entity();
+		let call = call_update_entity(2, vec![1, 2, 4], 0);
+		let call_weight = call.get_dispatch_info().call_weight;
+
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(1),
+			entity,
+			None,
+			call.clone(),
+			Weight::zero()
+		));
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(2),
+			entity,
+			Some(now()),
+			call,
+			call_weight
+		));
+		System::assert_has_event(Event::EntityUpdated { entity, threshold: 2 }.into());
+
+		// The entity now pays for its own storage.
+		let details = Entities::<Test>::get(entity).unwrap();
+		assert_eq!(details.signatories.into_inner(), vec![1, 2, 4]);
+		assert_eq!(details.depositor, entity);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(entity), 4);
+
+		// The removed signatory can no longer approve, the new one can.
+		let call = call_transfer(6, 5);
+		let call_weight = call.get_dispatch_info().call_weight;
+		assert_noop!(
+			Multisig::as_entity(RuntimeOrigin::signed(3), entity, None, call.clone(), call_weight),
+			Error::<Test>::NotSignatory,
+		);
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(4),
+			entity,
+			None,
+			call.clone(),
+			Weight::zero()
+		));
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(1),
+			entity,
+			Some(now()),
+			call,
+			call_weight
+		));
+		assert_eq!(Balances::free_balance(6), 5);
+	});
+}
+
+#[test]
+fn update_entity_paying_for_itself_only_needs_its_new_deposit() {
+	new_test_ext().execute_with(|| {
+		let entity = create_funded_entity();
+		assert_ok!(Multisig::update_entity(RuntimeOrigin::signed(entity), 2, vec![1, 2, 3], 0));
+		assert_eq!(Balances::reserved_balance(entity), 4);
+
+		// The entity can afford its new deposit, but not both deposits at once.
+		assert_ok!(Balances::transfer_keep_alive(RuntimeOrigin::signed(entity), 6, 5));
+		assert_eq!(Balances::free_balance(entity), 1);
+		assert_ok!(Multisig::update_entity(RuntimeOrigin::signed(entity), 2, vec![1, 2], 0));
+		assert_eq!(Balances::reserved_balance(entity), 3);
+	});
+}
+
+#[test]
+fn approvals_of_removed_signatories_do_not_count() {
+	new_test_ext().execute_with(|| {
+		let entity = create_funded_entity();
+		let transfer = call_transfer(6, 5);
+		let transfer_weight = transfer.get_dispatch_info().call_weight;
+		let hash = blake2_256(&transfer.encode());
+
+		// 3 approves a transfer, and is then removed from the entity.
+		assert_ok!(Multisig::approve_as_entity(
+			RuntimeOrigin::signed(3),
+			entity,
+			None,
+			hash,
+			Weight::zero()
+		));
+		let update = call_update_entity(2, vec![1, 2, 4], 1);
+		let update_weight = update.get_dispatch_info().call_weight;
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(1),
+			entity,
+			None,
+			update.clone(),
+			Weight::zero()
+		));
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(2),
+			entity,
+			Some(now()),
+			update,
+			update_weight
+		));
+		assert!(Multisigs::<Test>::get(entity, hash).unwrap().approvals.is_empty());
+
+		// The approval of 3 is gone, so a single further approval is not enough.
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(1),
+			entity,
+			Some(now()),
+			transfer.clone(),
+			transfer_weight
+		));
+		assert_eq!(Balances::free_balance(6), 0);
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(4),
+			entity,
+			Some(now()),
+			transfer,
+			transfer_weight
+		));
+		assert_eq!(Balances::free_balance(6), 5);
+		assert_eq!(Balances::reserved_balance(3), 0);
+	});
+}
+
+#[test]
+fn update_entity_checks_operations_witness() {
+	new_test_ext().execute_with(|| {
+		let entity = create_funded_entity();
+		let hash = blake2_256(&call_transfer(6, 5).encode());
+		assert_ok!(Multisig::approve_as_entity(
+			RuntimeOrigin::signed(3),
+			entity,
+			None,
+			hash,
+			Weight::zero()
+		));
+
+		assert_noop!(
+			Multisig::update_entity(RuntimeOrigin::signed(entity), 2, vec![1, 2], 0),
+			Error::<Test>::TooManyOperations,
+		);
+		assert_ok!(Multisig::update_entity(RuntimeOrigin::signed(entity), 2, vec![1, 2], 1));
+		assert!(Multisigs::<Test>::get(entity, hash).unwrap().approvals.is_empty());
+	});
+}
+
+#[test]
+fn remove_entity_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		let entity = create_funded_entity();
+		let hash = blake2_256(&call_transfer(6, 5).encode());
+		assert_ok!(Multisig::approve_as_entity(
+			RuntimeOrigin::signed(3),
+			entity,
+			None,
+			hash,
+			Weight::zero()
+		));
+
+		assert_noop!(Multisig::remove_entity(RuntimeOrigin::signed(1)), Error::<Test>::NotEntity);
+
+		let remove = Box::new(RuntimeCall::Multisig(Call::remove_entity {}));
+		let remove_weight = remove.get_dispatch_info().call_weight;
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(1),
+			entity,
+			None,
+			remove.clone(),
+			Weight::zero()
+		));
+		assert_ok!(Multisig::as_entity(
+			RuntimeOrigin::signed(2),
+			entity,
+			Some(now()),
+			remove,
+			remove_weight
+		));
+		System::assert_has_event(Event::EntityRemoved { entity, depositor: 1, deposit: 4 }.into());
+		assert!(!Entities::<Test>::contains_key(entity));
+		assert_eq!(Balances::reserved_balance(1), 0);
+
+		// Operations underway can no longer be approved, but can still be cancelled.
+		assert_noop!(
+			Multisig::approve_as_entity(
+				RuntimeOrigin::signed(2),
+				entity,
+				Some(now()),
+				hash,
+				Weight::zero()
+			),
+			Error::<Test>::NotEntity,
+		);
+		assert_ok!(Multisig::cancel_as_entity(RuntimeOrigin::signed(3), entity, now(), hash));
+		assert_eq!(Balances::reserved_balance(3), 0);
+	});
+}
+
+#[test]
+fn migrate_reserves_to_holds_works() {
+	use frame::{
+		deps::frame_support::{migrations::SteppedMigration, weights::WeightMeter},
+		traits::{fungible::InspectHold, ReservableCurrency},
+	};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<Multisig>();
+
+		let call_hash = [1u8; 32];
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::reserve(&1, 3));
+		Multisigs::<Test>::insert(
+			multi,
+			call_hash,
+			crate::Multisig {
+				when: now(),
+				deposit: 3,
+				depositor: 1,
+				approvals: vec![1].try_into().unwrap(),
+			},
+		);
+		let other_hash = [2u8; 32];
+		assert_ok!(Balances::reserve(&2, 4));
+		Multisigs::<Test>::insert(
+			multi,
+			other_hash,
+			crate::Multisig {
+				when: now(),
+				deposit: 4,
+				depositor: 2,
+				approvals: vec![2].try_into().unwrap(),
+			},
+		);
+
+		let mut cursor = None;
+		for _ in 0..10 {
+			cursor = migrations::v2::LazyMigrationV1ToV2::<Test, Balances>::step(
+				cursor,
+				&mut WeightMeter::new(),
+			)
+			.unwrap();
+			if cursor.is_none() {
+				break
+			}
+		}
+		assert!(cursor.is_none());
+
+		assert_eq!(StorageVersion::get::<Multisig>(), 2);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::MultisigDeposit.into(), &1), 3);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::MultisigDeposit.into(), &2), 4);
+		// Holds are counted in the reserved balance, so nothing is left reserved outside of them.
+		assert_eq!(Balances::reserved_balance(&1), 3);
+		assert_eq!(Balances::free_balance(1), 7);
+		assert_eq!(Balances::reserved_balance(&2), 4);
+		assert_eq!(Balances::free_balance(2), 6);
+
+		// The deposits can be released through the pallet as usual.
+		assert_ok!(Multisig::cancel_as_multi(
+			RuntimeOrigin::signed(1),
+			2,
+			vec![2, 3],
+			now(),
+			call_hash
+		));
+		assert_eq!(Balances::free_balance(1), 10);
+	});
+}
@@ -103,6 +103,8 @@ impl pallet_nfts::Config for Test {
 	type MaxTips = ConstU32<10>;
 	type MaxDeadlineDuration = ConstU64<10000>;
 	type MaxAttributesPerCall = ConstU32<2>;
+	type MaxMerkleProofLength = ConstU32<32>;
+	type MaxMintBatch = ConstU32<100>;
 	type Features = Features;
 	type OffchainSignature = Signature;
 	type OffchainPublic = AccountPublic;
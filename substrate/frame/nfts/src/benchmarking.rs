@@ -878,5 +878,63 @@ benchmarks_instance_pallet! {
 		);
 	}
 
+	mint_allowlisted {
+		let p in 0 .. T::MaxMerkleProofLength::get();
+		let (collection, caller, _) = create_collection::<T, I>();
+		let item = T::Helper::item(0);
+		let proof = (0..p).map(|i| sp_io::hashing::blake2_256(&i.encode())).collect::<Vec<_>>();
+		let root = Nfts::<T, I>::allowlist_root(Nfts::<T, I>::allowlist_leaf(&caller, &item), &proof);
+		let proof: MerkleProofOf<T, I> = proof.try_into().unwrap();
+		Nfts::<T, I>::update_mint_settings(
+			SystemOrigin::Signed(caller.clone()).into(),
+			collection,
+			MintSettings { mint_type: MintType::Allowlist(root), ..Default::default() },
+		)?;
+	}: _(SystemOrigin::Signed(caller.clone()), collection, item, proof, None)
+	verify {
+		assert_last_event::<T, I>(Event::Issued { collection, item, owner: caller }.into());
+	}
+
+	mint_batch {
+		let n in 0 .. T::MaxMintBatch::get();
+		let (collection, caller, _) = create_collection::<T, I>();
+		// Every item is paid for to an owner that is not the issuer.
+		let price = ItemPrice::<T, I>::from(1u32);
+		Nfts::<T, I>::update_mint_settings(
+			SystemOrigin::Signed(caller.clone()).into(),
+			collection,
+			MintSettings { price: Some(price), ..Default::default() },
+		)?;
+		let owner: T::AccountId = account("owner", 0, SEED);
+		T::Currency::make_free_balance_be(&owner, T::Currency::minimum_balance());
+		Nfts::<T, I>::force_collection_owner(
+			SystemOrigin::Root.into(),
+			collection,
+			T::Lookup::unlookup(owner),
+		)?;
+		let witness = MintWitness { owned_item: None, mint_price: Some(price) };
+		let items = (0..n)
+			.map(|i| {
+				let target: T::AccountId = account("target", i, SEED);
+				(T::Helper::item(i as u16), T::Lookup::unlookup(target))
+			})
+			.collect::<Vec<_>>();
+		let deposit = T::ItemDeposit::get().saturating_mul(n.into());
+	}: _(SystemOrigin::Signed(caller.clone()), collection, items, Some(witness))
+	verify {
+		assert_last_event::<T, I>(Event::BatchMinted { collection, count: n, deposit }.into());
+	}
+
+	clear_allowlist_claims {
+		let n in 0 .. 1_000;
+		let (collection, caller, _) = create_collection::<T, I>();
+		for i in 0..n {
+			AllowlistClaimed::<T, I>::insert(&collection, T::Helper::item(i as u16), ());
+		}
+	}: _(SystemOrigin::Signed(caller.clone()), collection, n)
+	verify {
+		assert_last_event::<T, I>(Event::AllowlistClaimsCleared { collection, count: n }.into());
+	}
+
 	impl_benchmark_test_suite!(Nfts, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -0,0 +1,132 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module contains helper methods to mint items from a Merkle-root allowlist committed to in
+//! the collection's [`MintType::Allowlist`] mint settings.
+
+use crate::*;
+use frame_support::{pallet_prelude::*, traits::ExistenceRequirement};
+use sp_io::hashing::blake2_256;
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Returns the allowlist leaf allocating the `item` to the account `who`.
+	pub fn allowlist_leaf(who: &T::AccountId, item: &T::ItemId) -> [u8; 32] {
+		blake2_256(&(who, item).encode())
+	}
+
+	/// Computes the Merkle root reached by folding the `proof` into the `leaf`.
+	///
+	/// Every pair of sibling hashes is sorted before being hashed together, so the proof doesn't
+	/// need to carry the position of the nodes.
+	pub fn allowlist_root(leaf: [u8; 32], proof: &[[u8; 32]]) -> MerkleRoot {
+		proof.iter().fold(leaf, |node, sibling| {
+			let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+			let mut pair = [0u8; 64];
+			pair[..32].copy_from_slice(&left);
+			pair[32..].copy_from_slice(&right);
+			blake2_256(&pair)
+		})
+	}
+
+	/// Mints the `item` allocated to `caller` by the allowlist of the `collection`.
+	///
+	/// The `proof` must lead from the `(caller, item)` leaf to the root committed to in the
+	/// collection's mint settings. Besides that, the mint window and price of the collection are
+	/// enforced the same way as for the regular `mint`.
+	///
+	/// Since the leaf binds the `item` to the `caller`, recording the claimed items is enough to
+	/// prevent an allowlist entry from being claimed twice, including after the item is burned.
+	pub(crate) fn do_mint_allowlisted(
+		caller: T::AccountId,
+		collection: T::CollectionId,
+		item: T::ItemId,
+		proof: MerkleProofOf<T, I>,
+		witness_data: Option<MintWitness<T::ItemId, DepositBalanceOf<T, I>>>,
+	) -> DispatchResult {
+		let item_config = ItemConfig { settings: Self::get_default_item_settings(&collection)? };
+
+		Self::do_mint(
+			collection,
+			item,
+			Some(caller.clone()),
+			caller.clone(),
+			item_config,
+			|collection_details, collection_config| {
+				let mint_settings = collection_config.mint_settings;
+				let now = T::BlockNumberProvider::current_block_number();
+
+				if let Some(start_block) = mint_settings.start_block {
+					ensure!(start_block <= now, Error::<T, I>::MintNotStarted);
+				}
+				if let Some(end_block) = mint_settings.end_block {
+					ensure!(end_block >= now, Error::<T, I>::MintEnded);
+				}
+
+				let MintType::Allowlist(root) = mint_settings.mint_type else {
+					return Err(Error::<T, I>::NoPermission.into())
+				};
+				let leaf = Self::allowlist_leaf(&caller, &item);
+				ensure!(Self::allowlist_root(leaf, &proof) == root, Error::<T, I>::NotOnAllowlist);
+				ensure!(
+					!AllowlistClaimed::<T, I>::contains_key(&collection, &item),
+					Error::<T, I>::AllowlistEntryClaimed
+				);
+
+				if let Some(price) = mint_settings.price {
+					let MintWitness { mint_price, .. } =
+						witness_data.ok_or(Error::<T, I>::WitnessRequired)?;
+					let mint_price = mint_price.ok_or(Error::<T, I>::BadWitness)?;
+					ensure!(mint_price >= price, Error::<T, I>::BadWitness);
+					T::Currency::transfer(
+						&caller,
+						&collection_details.owner,
+						price,
+						ExistenceRequirement::KeepAlive,
+					)?;
+				}
+
+				Ok(())
+			},
+		)?;
+
+		AllowlistClaimed::<T, I>::insert(&collection, &item, ());
+		Ok(())
+	}
+
+	/// Clears up to `limit` claimed allowlist entries of the empty `collection`, returning the
+	/// number of cleared entries.
+	///
+	/// The claims have to be cleared before the collection can be destroyed. Clearing them lets
+	/// the entries be claimed again, which the owner could allow anyway by committing to a new
+	/// allowlist.
+	pub(crate) fn do_clear_allowlist_claims(
+		collection: T::CollectionId,
+		limit: u32,
+		maybe_check_owner: Option<T::AccountId>,
+	) -> Result<u32, DispatchError> {
+		let details = Collection::<T, I>::get(&collection).ok_or(Error::<T, I>::UnknownCollection)?;
+		if let Some(check_owner) = maybe_check_owner {
+			ensure!(details.owner == check_owner, Error::<T, I>::NoPermission);
+		}
+		ensure!(details.items == 0, Error::<T, I>::CollectionNotEmpty);
+
+		let count =
+			AllowlistClaimed::<T, I>::drain_prefix(&collection).take(limit as usize).count() as u32;
+		Self::deposit_event(Event::AllowlistClaimsCleared { collection, count });
+		Ok(count)
+	}
+}
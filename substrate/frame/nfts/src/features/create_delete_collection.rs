@@ -109,6 +109,10 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				ensure!(collection_details.owner == check_owner, Error::<T, I>::NoPermission);
 			}
 			ensure!(collection_details.items == 0, Error::<T, I>::CollectionNotEmpty);
+			ensure!(
+				AllowlistClaimed::<T, I>::iter_prefix(&collection).next().is_none(),
+				Error::<T, I>::AllowlistClaimsRemaining
+			);
 			ensure!(collection_details.attributes == witness.attributes, Error::<T, I>::BadWitness);
 			ensure!(
 				collection_details.item_metadatas == witness.item_metadatas,
@@ -20,6 +20,7 @@
 
 use crate::*;
 use frame_support::{pallet_prelude::*, traits::ExistenceRequirement};
+use sp_runtime::TokenError;
 
 impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	/// Mint a new unique item with the given `collection`, `item`, and other minting configuration
@@ -108,6 +109,87 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Ok(())
 	}
 
+	/// Mints a batch of items into the `collection` on behalf of one of its issuers.
+	///
+	/// The mint settings of the collection apply to every item the same way as for the regular
+	/// `mint` called by an issuer: the mint window must be open and the mint price, if any, is paid
+	/// to the collection owner for each item. Collections minted by holders of another collection
+	/// can't be minted in batches, since every item needs its own witness.
+	///
+	/// The deposit for the whole batch is checked to be reservable from the `origin` before any
+	/// item is minted, after which every item is minted with the collection's default item
+	/// settings and its deposit taken from the `origin`.
+	///
+	/// - `origin`: The account that must be an issuer of the `collection`.
+	/// - `collection`: The collection to mint the items into.
+	/// - `items`: The new items together with the accounts they are minted into.
+	/// - `witness_data`: Confirms the mint price of a single item, if it is set.
+	pub(crate) fn do_mint_batch(
+		origin: T::AccountId,
+		collection: T::CollectionId,
+		items: Vec<(T::ItemId, T::AccountId)>,
+		witness_data: Option<MintWitness<T::ItemId, DepositBalanceOf<T, I>>>,
+	) -> DispatchResult {
+		ensure!(Collection::<T, I>::contains_key(&collection), Error::<T, I>::UnknownCollection);
+		ensure!(
+			Self::has_role(&collection, &origin, CollectionRole::Issuer),
+			Error::<T, I>::NoPermission
+		);
+
+		let collection_config = Self::get_collection_config(&collection)?;
+		let item_deposit =
+			match collection_config.is_setting_enabled(CollectionSetting::DepositRequired) {
+				true => T::ItemDeposit::get(),
+				false => Zero::zero(),
+			};
+		let count = items.len() as u32;
+		let deposit = item_deposit.saturating_mul(count.into());
+		ensure!(T::Currency::can_reserve(&origin, deposit), TokenError::FundsUnavailable);
+
+		let item_config = ItemConfig { settings: Self::get_default_item_settings(&collection)? };
+		for (item, mint_to) in items {
+			Self::do_mint(
+				collection,
+				item,
+				Some(origin.clone()),
+				mint_to,
+				item_config,
+				|collection_details, collection_config| {
+					let mint_settings = collection_config.mint_settings;
+					let now = T::BlockNumberProvider::current_block_number();
+
+					if let Some(start_block) = mint_settings.start_block {
+						ensure!(start_block <= now, Error::<T, I>::MintNotStarted);
+					}
+					if let Some(end_block) = mint_settings.end_block {
+						ensure!(end_block >= now, Error::<T, I>::MintEnded);
+					}
+					if let MintType::HolderOf(_) = mint_settings.mint_type {
+						return Err(Error::<T, I>::NoPermission.into())
+					}
+
+					if let Some(price) = mint_settings.price {
+						let MintWitness { mint_price, .. } =
+							witness_data.clone().ok_or(Error::<T, I>::WitnessRequired)?;
+						let mint_price = mint_price.ok_or(Error::<T, I>::BadWitness)?;
+						ensure!(mint_price >= price, Error::<T, I>::BadWitness);
+						T::Currency::transfer(
+							&origin,
+							&collection_details.owner,
+							price,
+							ExistenceRequirement::KeepAlive,
+						)?;
+					}
+
+					Ok(())
+				},
+			)?;
+		}
+
+		Self::deposit_event(Event::BatchMinted { collection, count, deposit });
+		Ok(())
+	}
+
 	/// Mints a new item using a pre-signed message.
 	///
 	/// This function allows minting a new item using a pre-signed message. The minting process is
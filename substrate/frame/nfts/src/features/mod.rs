@@ -15,6 +15,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod allowlist;
 pub mod approvals;
 pub mod atomic_swap;
 pub mod attributes;
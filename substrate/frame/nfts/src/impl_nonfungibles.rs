@@ -114,6 +114,29 @@ impl<T: Config<I>, I: 'static> Inspect<<T as SystemConfig>::AccountId> for Palle
 		}
 	}
 
+	fn item_metadata(collection: &Self::CollectionId, item: &Self::ItemId) -> Option<Vec<u8>> {
+		ItemMetadataOf::<T, I>::get(collection, item).map(|m| m.data.into())
+	}
+
+	fn collection_metadata(collection: &Self::CollectionId) -> Option<Vec<u8>> {
+		CollectionMetadataOf::<T, I>::get(collection).map(|m| m.data.into())
+	}
+
+	/// Returns the keys and values of the attributes of `item` of `collection` set by the
+	/// collection owner.
+	fn item_attributes(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		Attribute::<T, I>::iter_prefix((
+			collection,
+			Some(item),
+			AttributeNamespace::CollectionOwner,
+		))
+		.map(|(key, (value, _))| (key.into(), value.into()))
+		.collect()
+	}
+
 	/// Returns `true` if the `item` of `collection` may be transferred.
 	///
 	/// Default implementation is that all items are transferable.
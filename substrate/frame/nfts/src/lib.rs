@@ -217,6 +217,14 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxAttributesPerCall: Get<u32>;
 
+		/// The max depth of a Merkle proof accepted when minting from an allowlist.
+		#[pallet::constant]
+		type MaxMerkleProofLength: Get<u32>;
+
+		/// The max number of items the collection owner could mint in a single `mint_batch` call.
+		#[pallet::constant]
+		type MaxMintBatch: Get<u32>;
+
 		/// Disables some of pallet's features.
 		#[pallet::constant]
 		type Features: Get<PalletFeatures>;
@@ -414,6 +422,20 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Items which have been claimed from the allowlist of their collection.
+	///
+	/// An allowlist entry can only be claimed once, even if the item has been burned since.
+	#[pallet::storage]
+	pub type AllowlistClaimed<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::CollectionId,
+		Blake2_128Concat,
+		T::ItemId,
+		(),
+		OptionQuery,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config<I>, I: 'static = ()> {
@@ -586,6 +608,11 @@ pub mod pallet {
 			item: T::ItemId,
 			namespace: AttributeNamespace<T::AccountId>,
 		},
+		/// A batch of `count` items was minted into the `collection` by its owner, who paid
+		/// `deposit` in total.
+		BatchMinted { collection: T::CollectionId, count: u32, deposit: DepositBalanceOf<T, I> },
+		/// `count` claimed allowlist entries of the `collection` were cleared.
+		AllowlistClaimsCleared { collection: T::CollectionId, count: u32 },
 		/// A new attribute in the `Pallet` namespace was set for the `collection` or an `item`
 		/// within that `collection`.
 		PalletAttributeSet {
@@ -688,6 +715,14 @@ pub mod pallet {
 		CollectionNotEmpty,
 		/// The witness data should be provided.
 		WitnessRequired,
+		/// The provided Merkle proof doesn't match the collection's allowlist.
+		NotOnAllowlist,
+		/// Can't mint more items per one batch.
+		MaxMintBatchExceeded,
+		/// The allowlist entry of the item was already claimed.
+		AllowlistEntryClaimed,
+		/// The collection has claimed allowlist entries, which must be cleared first.
+		AllowlistClaimsRemaining,
 	}
 
 	#[pallet::call]
@@ -787,7 +822,8 @@ pub mod pallet {
 		/// The origin must conform to `ForceOrigin` or must be `Signed` and the sender must be the
 		/// owner of the `collection`.
 		///
-		/// NOTE: The collection must have 0 items to be destroyed.
+		/// NOTE: The collection must have 0 items and no claimed allowlist entries to be destroyed.
+		/// The claims are cleared with `clear_allowlist_claims`.
 		///
 		/// - `collection`: The identifier of the collection to be destroyed.
 		/// - `witness`: Information on the items minted in the collection. This must be
@@ -835,6 +871,8 @@ pub mod pallet {
 		///   the mint price is set, then it should be additionally confirmed in the `witness_data`.
 		///
 		/// Note: the deposit will be taken from the `origin` and not the `owner` of the `item`.
+		/// When the mint type is `Allowlist`, only the Issuer could use this call; everyone else
+		/// should claim their item with `mint_allowlisted`.
 		///
 		/// Emits `Issued` event when successful.
 		///
@@ -916,7 +954,13 @@ pub mod pallet {
 								value: attribute_value,
 							});
 						},
-						_ => {},
+						MintType::Allowlist(_) => {
+							ensure!(
+								Self::has_role(&collection, &caller, CollectionRole::Issuer),
+								Error::<T, I>::NoPermission
+							);
+						},
+						MintType::Public => {},
 					}
 
 					if let Some(price) = mint_settings.price {
@@ -1931,6 +1975,102 @@ pub mod pallet {
 			Self::validate_signature(&Encode::encode(&data), &signature, &signer)?;
 			Self::do_set_attributes_pre_signed(origin, data, signer)
 		}
+
+		/// Mint an item of a particular collection by proving the sender is on its allowlist.
+		///
+		/// The origin must be Signed and the collection's mint type must be `Allowlist(root)`.
+		///
+		/// - `collection`: The collection of the item to be minted.
+		/// - `item`: The identifier of the item allocated to the sender in the allowlist.
+		/// - `proof`: The Merkle proof of the `(sender, item)` leaf against the committed root.
+		/// - `witness_data`: If the mint price is set, then it should be confirmed in the
+		///   `witness_data`.
+		///
+		/// Note: the deposit will be taken from the `origin`. The minted item is always sent to
+		/// the `origin`, so each allowlist entry could only be claimed by the account it was
+		/// allocated to, and only once.
+		///
+		/// Emits `Issued` event when successful.
+		///
+		/// Weight: `O(P)` where `P = proof.len()`
+		#[pallet::call_index(39)]
+		#[pallet::weight(T::WeightInfo::mint_allowlisted(proof.len() as u32))]
+		pub fn mint_allowlisted(
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			item: T::ItemId,
+			proof: MerkleProofOf<T, I>,
+			witness_data: Option<MintWitness<T::ItemId, DepositBalanceOf<T, I>>>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			Self::do_mint_allowlisted(origin, collection, item, proof, witness_data)
+		}
+
+		/// Mint a batch of items of a particular collection.
+		///
+		/// Origin must be Signed and the sender should be the Issuer of the `collection`.
+		///
+		/// - `collection`: The collection of the items to be minted.
+		/// - `items`: The identifiers of the new items together with the accounts they are minted
+		///   into.
+		/// - `witness_data`: If the mint price is set, then it should be confirmed in the
+		///   `witness_data`. The price is paid for each item.
+		///
+		/// The mint window and price of the collection apply as for `mint`, and collections with
+		/// the `HolderOf` mint type can't be minted in batches. The items get the collection's
+		/// default item settings. Their deposits are taken from the `origin`, who must be able to
+		/// reserve the deposit for the whole batch upfront. The call fails as a whole if any of the
+		/// items can't be minted.
+		///
+		/// Emits `Issued` for each item and `BatchMinted` on success.
+		///
+		/// Weight: `O(items.len())`
+		#[pallet::call_index(40)]
+		#[pallet::weight(T::WeightInfo::mint_batch(items.len() as u32))]
+		pub fn mint_batch(
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			items: Vec<(T::ItemId, AccountIdLookupOf<T>)>,
+			witness_data: Option<MintWitness<T::ItemId, DepositBalanceOf<T, I>>>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			ensure!(
+				items.len() <= T::MaxMintBatch::get() as usize,
+				Error::<T, I>::MaxMintBatchExceeded
+			);
+			let items = items
+				.into_iter()
+				.map(|(item, mint_to)| Ok((item, T::Lookup::lookup(mint_to)?)))
+				.collect::<Result<Vec<_>, DispatchError>>()?;
+			Self::do_mint_batch(origin, collection, items, witness_data)
+		}
+
+		/// Clear the claimed allowlist entries of a collection, ahead of its destruction.
+		///
+		/// The origin must conform to `ForceOrigin` or must be `Signed` and the sender must be the
+		/// owner of the `collection`.
+		///
+		/// NOTE: The collection must have 0 items. Cleared entries can be claimed again.
+		///
+		/// - `collection`: The collection whose claims are cleared.
+		/// - `limit`: The maximum number of claims to clear.
+		///
+		/// Emits `AllowlistClaimsCleared` event when successful.
+		///
+		/// Weight: `O(limit)`
+		#[pallet::call_index(41)]
+		#[pallet::weight(T::WeightInfo::clear_allowlist_claims(*limit))]
+		pub fn clear_allowlist_claims(
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			limit: u32,
+		) -> DispatchResultWithPostInfo {
+			let maybe_check_owner = T::ForceOrigin::try_origin(origin)
+				.map(|_| None)
+				.or_else(|origin| ensure_signed(origin).map(Some).map_err(DispatchError::from))?;
+			let count = Self::do_clear_allowlist_claims(collection, limit, maybe_check_owner)?;
+			Ok(Some(T::WeightInfo::clear_allowlist_claims(count)).into())
+		}
 	}
 }
 
@@ -30,7 +30,7 @@ use pallet_balances::Error as BalancesError;
 use sp_core::{bounded::BoundedVec, Pair};
 use sp_runtime::{
 	traits::{Dispatchable, IdentifyAccount},
-	MultiSignature, MultiSigner,
+	MultiSignature, MultiSigner, TokenError,
 };
 
 type AccountIdOf<Test> = <Test as frame_system::Config>::AccountId;
@@ -992,6 +992,49 @@ fn set_collection_owner_attributes_should_work() {
 	});
 }
 
+#[test]
+fn inspect_metadata_and_attributes_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&account(1), 100);
+
+		assert_ok!(Nfts::force_create(
+			RuntimeOrigin::root(),
+			account(1),
+			collection_config_with_all_settings_enabled()
+		));
+		assert_ok!(Nfts::mint(RuntimeOrigin::signed(account(1)), 0, 0, account(1), None));
+		assert_eq!(<Nfts as Inspect<AccountIdOf<Test>>>::collection_metadata(&0), None);
+		assert_eq!(<Nfts as Inspect<AccountIdOf<Test>>>::item_metadata(&0, &0), None);
+		assert!(<Nfts as Inspect<AccountIdOf<Test>>>::item_attributes(&0, &0).is_empty());
+
+		assert_ok!(Nfts::set_collection_metadata(RuntimeOrigin::signed(account(1)), 0, bvec![1]));
+		assert_ok!(Nfts::set_metadata(RuntimeOrigin::signed(account(1)), 0, 0, bvec![2]));
+		for (item, key) in [(None, 0), (Some(0), 1), (Some(0), 2)] {
+			assert_ok!(Nfts::set_attribute(
+				RuntimeOrigin::signed(account(1)),
+				0,
+				item,
+				AttributeNamespace::CollectionOwner,
+				bvec![key],
+				bvec![key + 10],
+			));
+		}
+		// Attributes of other namespaces are not returned.
+		assert_ok!(<Nfts as Mutate<AccountIdOf<Test>, ItemConfig>>::set_attribute(
+			&0,
+			&0,
+			&[3],
+			&[13]
+		));
+
+		assert_eq!(<Nfts as Inspect<AccountIdOf<Test>>>::collection_metadata(&0), Some(vec![1]));
+		assert_eq!(<Nfts as Inspect<AccountIdOf<Test>>>::item_metadata(&0, &0), Some(vec![2]));
+		let mut attributes = <Nfts as Inspect<AccountIdOf<Test>>>::item_attributes(&0, &0);
+		attributes.sort();
+		assert_eq!(attributes, vec![(vec![1], vec![11]), (vec![2], vec![12])]);
+	});
+}
+
 #[test]
 fn set_collection_system_attributes_should_work() {
 	new_test_ext().execute_with(|| {
@@ -3875,3 +3918,299 @@ fn clear_collection_metadata_works() {
 		assert_eq!(Balances::reserved_balance(&account(1)), 10);
 	});
 }
+
+#[test]
+fn allowlist_mint_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Nfts::force_create(
+			RuntimeOrigin::root(),
+			account(1),
+			default_collection_config()
+		));
+
+		// build an allowlist of four entries
+		let leaves = [
+			Nfts::allowlist_leaf(&account(2), &10),
+			Nfts::allowlist_leaf(&account(3), &11),
+			Nfts::allowlist_leaf(&account(4), &12),
+			Nfts::allowlist_leaf(&account(5), &13),
+		];
+		let left = Nfts::allowlist_root(leaves[0], &[leaves[1]]);
+		let right = Nfts::allowlist_root(leaves[2], &[leaves[3]]);
+		let root = Nfts::allowlist_root(left, &[right]);
+		let proof_of = |i: usize| -> MerkleProofOf<Test> {
+			match i {
+				0 => bvec![leaves[1], right],
+				1 => bvec![leaves[0], right],
+				2 => bvec![leaves[3], left],
+				_ => bvec![leaves[2], left],
+			}
+		};
+
+		// the allowlist can't be used until it's committed to
+		assert_noop!(
+			Nfts::mint_allowlisted(RuntimeOrigin::signed(account(2)), 0, 10, proof_of(0), None),
+			Error::<Test>::NoPermission
+		);
+
+		assert_ok!(Nfts::update_mint_settings(
+			RuntimeOrigin::signed(account(1)),
+			0,
+			MintSettings { mint_type: MintType::Allowlist(root), ..Default::default() }
+		));
+
+		// the regular mint is reserved for the issuer
+		assert_noop!(
+			Nfts::mint(RuntimeOrigin::signed(account(2)), 0, 10, account(2), None),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Nfts::mint(RuntimeOrigin::signed(account(1)), 0, 1, account(1), None));
+
+		// wrong item, wrong account or wrong proof
+		assert_noop!(
+			Nfts::mint_allowlisted(RuntimeOrigin::signed(account(2)), 0, 11, proof_of(0), None),
+			Error::<Test>::NotOnAllowlist
+		);
+		assert_noop!(
+			Nfts::mint_allowlisted(RuntimeOrigin::signed(account(6)), 0, 10, proof_of(0), None),
+			Error::<Test>::NotOnAllowlist
+		);
+		assert_noop!(
+			Nfts::mint_allowlisted(RuntimeOrigin::signed(account(2)), 0, 10, proof_of(1), None),
+			Error::<Test>::NotOnAllowlist
+		);
+
+		assert_ok!(Nfts::mint_allowlisted(
+			RuntimeOrigin::signed(account(2)),
+			0,
+			10,
+			proof_of(0),
+			None
+		));
+		assert_ok!(Nfts::mint_allowlisted(
+			RuntimeOrigin::signed(account(5)),
+			0,
+			13,
+			proof_of(3),
+			None
+		));
+		assert_eq!(items(), vec![(account(1), 0, 1), (account(2), 0, 10), (account(5), 0, 13)]);
+
+		// can't claim twice
+		assert_noop!(
+			Nfts::mint_allowlisted(RuntimeOrigin::signed(account(2)), 0, 10, proof_of(0), None),
+			Error::<Test>::AlreadyExists
+		);
+
+		// not even once the claimed item is burned
+		assert_ok!(Nfts::burn(RuntimeOrigin::signed(account(2)), 0, 10));
+		assert_noop!(
+			Nfts::mint_allowlisted(RuntimeOrigin::signed(account(2)), 0, 10, proof_of(0), None),
+			Error::<Test>::AllowlistEntryClaimed
+		);
+
+		// validate the mint window and price
+		assert_ok!(Nfts::update_mint_settings(
+			RuntimeOrigin::signed(account(1)),
+			0,
+			MintSettings {
+				mint_type: MintType::Allowlist(root),
+				price: Some(1),
+				start_block: Some(2),
+				end_block: Some(3),
+				..Default::default()
+			}
+		));
+		System::set_block_number(1);
+		assert_noop!(
+			Nfts::mint_allowlisted(RuntimeOrigin::signed(account(3)), 0, 11, proof_of(1), None),
+			Error::<Test>::MintNotStarted
+		);
+		System::set_block_number(2);
+		assert_noop!(
+			Nfts::mint_allowlisted(RuntimeOrigin::signed(account(3)), 0, 11, proof_of(1), None),
+			Error::<Test>::WitnessRequired
+		);
+		Balances::make_free_balance_be(&account(3), 100);
+		assert_ok!(Nfts::mint_allowlisted(
+			RuntimeOrigin::signed(account(3)),
+			0,
+			11,
+			proof_of(1),
+			Some(MintWitness { mint_price: Some(1), ..Default::default() })
+		));
+		assert_eq!(Balances::total_balance(&account(3)), 99);
+		assert_eq!(Nfts::owner(0, 11).unwrap(), account(3));
+		System::set_block_number(4);
+		assert_noop!(
+			Nfts::mint_allowlisted(RuntimeOrigin::signed(account(4)), 0, 12, proof_of(2), None),
+			Error::<Test>::MintEnded
+		);
+
+		// the claims must be cleared before the collection is destroyed
+		assert_noop!(
+			Nfts::clear_allowlist_claims(RuntimeOrigin::signed(account(1)), 0, 3),
+			Error::<Test>::CollectionNotEmpty
+		);
+		assert_ok!(Nfts::burn(RuntimeOrigin::signed(account(1)), 0, 1));
+		assert_ok!(Nfts::burn(RuntimeOrigin::signed(account(3)), 0, 11));
+		assert_ok!(Nfts::burn(RuntimeOrigin::signed(account(5)), 0, 13));
+		assert_eq!(AllowlistClaimed::<Test>::iter_prefix(0).count(), 3);
+		let w = Nfts::get_destroy_witness(&0).unwrap();
+		assert_noop!(
+			Nfts::destroy(RuntimeOrigin::signed(account(1)), 0, w),
+			Error::<Test>::AllowlistClaimsRemaining
+		);
+		assert_noop!(
+			Nfts::clear_allowlist_claims(RuntimeOrigin::signed(account(3)), 0, 3),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(Nfts::clear_allowlist_claims(RuntimeOrigin::signed(account(1)), 0, 2));
+		assert!(events()
+			.contains(&Event::<Test>::AllowlistClaimsCleared { collection: 0, count: 2 }));
+		assert_eq!(AllowlistClaimed::<Test>::iter_prefix(0).count(), 1);
+		assert_ok!(Nfts::clear_allowlist_claims(RuntimeOrigin::root(), 0, 2));
+		assert_eq!(AllowlistClaimed::<Test>::iter_prefix(0).count(), 0);
+		assert_ok!(Nfts::destroy(RuntimeOrigin::signed(account(1)), 0, w));
+	});
+}
+
+#[test]
+fn mint_batch_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&account(1), 100);
+		assert_ok!(Nfts::create(
+			RuntimeOrigin::signed(account(1)),
+			account(1),
+			collection_config_with_all_settings_enabled()
+		));
+		assert_eq!(Balances::reserved_balance(&account(1)), 2);
+
+		// only an issuer can mint a batch
+		assert_noop!(
+			Nfts::mint_batch(RuntimeOrigin::signed(account(2)), 0, vec![(1, account(2))], None),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Nfts::mint_batch(
+				RuntimeOrigin::signed(account(1)),
+				0,
+				(0..101).map(|i| (i, account(2))).collect(),
+				None
+			),
+			Error::<Test>::MaxMintBatchExceeded
+		);
+
+		assert_ok!(Nfts::mint_batch(
+			RuntimeOrigin::signed(account(1)),
+			0,
+			vec![(1, account(2)), (2, account(3)), (3, account(3))],
+			None
+		));
+		assert_eq!(items(), vec![(account(2), 0, 1), (account(3), 0, 2), (account(3), 0, 3)]);
+		assert_eq!(Balances::reserved_balance(&account(1)), 5);
+		assert_eq!(Item::<Test>::get(0, 2).unwrap().deposit.account, account(1));
+		assert!(events().contains(&Event::<Test>::BatchMinted {
+			collection: 0,
+			count: 3,
+			deposit: 3
+		}));
+
+		// the batch fails as a whole
+		assert_noop!(
+			Nfts::mint_batch(
+				RuntimeOrigin::signed(account(1)),
+				0,
+				vec![(4, account(2)), (3, account(2))],
+				None
+			),
+			Error::<Test>::AlreadyExists
+		);
+
+		// the deposit for the whole batch is checked upfront
+		assert_noop!(
+			Nfts::mint_batch(
+				RuntimeOrigin::signed(account(1)),
+				0,
+				(10..108).map(|i| (i, account(2))).collect(),
+				None
+			),
+			TokenError::FundsUnavailable
+		);
+
+		// an issuer other than the owner mints with its own deposit
+		assert_ok!(Nfts::set_team(
+			RuntimeOrigin::signed(account(1)),
+			0,
+			Some(account(4)),
+			Some(account(1)),
+			Some(account(1)),
+		));
+		assert_noop!(
+			Nfts::mint_batch(RuntimeOrigin::signed(account(1)), 0, vec![(4, account(2))], None),
+			Error::<Test>::NoPermission
+		);
+		Balances::make_free_balance_be(&account(4), 100);
+		assert_ok!(Nfts::mint_batch(
+			RuntimeOrigin::signed(account(4)),
+			0,
+			vec![(4, account(2))],
+			None
+		));
+		assert_eq!(Item::<Test>::get(0, 4).unwrap().deposit.account, account(4));
+
+		// the mint window and price apply to every item
+		assert_ok!(Nfts::update_mint_settings(
+			RuntimeOrigin::signed(account(4)),
+			0,
+			MintSettings {
+				price: Some(2),
+				start_block: Some(2),
+				end_block: Some(3),
+				..Default::default()
+			}
+		));
+		System::set_block_number(1);
+		assert_noop!(
+			Nfts::mint_batch(RuntimeOrigin::signed(account(4)), 0, vec![(5, account(2))], None),
+			Error::<Test>::MintNotStarted
+		);
+		System::set_block_number(2);
+		assert_noop!(
+			Nfts::mint_batch(RuntimeOrigin::signed(account(4)), 0, vec![(5, account(2))], None),
+			Error::<Test>::WitnessRequired
+		);
+		assert_noop!(
+			Nfts::mint_batch(
+				RuntimeOrigin::signed(account(4)),
+				0,
+				vec![(5, account(2))],
+				Some(MintWitness { mint_price: Some(1), ..Default::default() })
+			),
+			Error::<Test>::BadWitness
+		);
+		assert_ok!(Nfts::mint_batch(
+			RuntimeOrigin::signed(account(4)),
+			0,
+			vec![(5, account(2)), (6, account(3))],
+			Some(MintWitness { mint_price: Some(2), ..Default::default() })
+		));
+		assert_eq!(Balances::free_balance(&account(4)), 100 - 1 - 2 - 4);
+		System::set_block_number(4);
+		assert_noop!(
+			Nfts::mint_batch(RuntimeOrigin::signed(account(4)), 0, vec![(7, account(2))], None),
+			Error::<Test>::MintEnded
+		);
+
+		// holders of another collection need a witness for every item
+		assert_ok!(Nfts::update_mint_settings(
+			RuntimeOrigin::signed(account(4)),
+			0,
+			MintSettings { mint_type: MintType::HolderOf(1), ..Default::default() }
+		));
+		assert_noop!(
+			Nfts::mint_batch(RuntimeOrigin::signed(account(4)), 0, vec![(7, account(2))], None),
+			Error::<Test>::NoPermission
+		);
+	});
+}
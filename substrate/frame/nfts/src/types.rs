@@ -88,6 +88,8 @@ pub type PreSignedAttributesOf<T, I = ()> = PreSignedAttributes<
 	<T as SystemConfig>::AccountId,
 	BlockNumberFor<T, I>,
 >;
+/// A type alias for the Merkle proof used to mint an item from an allowlist.
+pub type MerkleProofOf<T, I = ()> = BoundedVec<[u8; 32], <T as Config<I>>::MaxMerkleProofLength>;
 
 /// Information about a collection.
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -358,8 +360,17 @@ pub enum MintType<CollectionId> {
 	Public,
 	/// Only holders of items in specified collection could mint new items.
 	HolderOf(CollectionId),
+	/// Only accounts included in the allowlist committed to by the given Merkle root could mint
+	/// new items, each claiming the exact item it was allocated via `mint_allowlisted`.
+	///
+	/// The leaves of the tree are `blake2_256((account, item).encode())` and the inner nodes are
+	/// the `blake2_256` hash of the concatenation of the two sorted child hashes.
+	Allowlist(MerkleRoot),
 }
 
+/// The root of a Merkle tree committing to a mint allowlist.
+pub type MerkleRoot = [u8; 32];
+
 /// Holds the information about minting.
 #[derive(
 	Clone,
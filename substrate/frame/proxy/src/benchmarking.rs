@@ -22,8 +22,11 @@
 use super::*;
 use crate::Pallet as Proxy;
 use alloc::{boxed::Box, vec};
-use frame::benchmarking::prelude::{
-	account, benchmarks, impl_test_function, whitelisted_caller, BenchmarkError, RawOrigin,
+use frame::{
+	benchmarking::prelude::{
+		account, benchmarks, impl_test_function, whitelisted_caller, BenchmarkError, RawOrigin,
+	},
+	traits::fungible::InspectHold,
 };
 
 const SEED: u32 = 0;
@@ -36,9 +39,22 @@ fn assert_has_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
 	frame_system::Pallet::<T>::assert_has_event(generic_event.into());
 }
 
+/// A balance large enough to pay for all the deposits an account can have in this pallet.
+fn funding<T: Config>() -> BalanceOf<T> {
+	let proxy_deposit = T::ProxyDepositBase::get()
+		.saturating_add(T::ProxyDepositFactor::get().saturating_mul(T::MaxProxies::get().into()));
+	let announcement_deposit = T::AnnouncementDepositBase::get().saturating_add(
+		T::AnnouncementDepositFactor::get().saturating_mul(T::MaxPending::get().into()),
+	);
+	T::Currency::minimum_balance()
+		.saturating_add(proxy_deposit)
+		.saturating_add(announcement_deposit)
+		.saturating_mul(10u32.into())
+}
+
 fn add_proxies<T: Config>(n: u32, maybe_who: Option<T::AccountId>) -> Result<(), &'static str> {
 	let caller = maybe_who.unwrap_or_else(whitelisted_caller);
-	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+	T::Currency::set_balance(&caller, funding::<T>());
 	for i in 0..n {
 		let real = T::Lookup::unlookup(account("target", i, SEED));
 
@@ -59,12 +75,12 @@ fn add_announcements<T: Config>(
 ) -> Result<(), &'static str> {
 	let caller = maybe_who.unwrap_or_else(|| account("caller", 0, SEED));
 	let caller_lookup = T::Lookup::unlookup(caller.clone());
-	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+	T::Currency::set_balance(&caller, funding::<T>());
 	let real = if let Some(real) = maybe_real {
 		real
 	} else {
 		let real = account("real", 0, SEED);
-		T::Currency::make_free_balance_be(&real, BalanceOf::<T>::max_value() / 2u32.into());
+		T::Currency::set_balance(&real, funding::<T>());
 		Proxy::<T>::add_proxy(
 			RawOrigin::Signed(real.clone()).into(),
 			caller_lookup,
@@ -93,7 +109,7 @@ mod benchmarks {
 		add_proxies::<T>(p, None)?;
 		// In this case the caller is the "target" proxy
 		let caller: T::AccountId = account("target", p - 1, SEED);
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		T::Currency::set_balance(&caller, funding::<T>());
 		// ... and "real" is the traditional caller. This is not a typo.
 		let real: T::AccountId = whitelisted_caller();
 		let real_lookup = T::Lookup::unlookup(real);
@@ -118,7 +134,7 @@ mod benchmarks {
 		let caller: T::AccountId = account("pure", 0, SEED);
 		let delegate: T::AccountId = account("target", p - 1, SEED);
 		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
-		T::Currency::make_free_balance_be(&delegate, BalanceOf::<T>::max_value() / 2u32.into());
+		T::Currency::set_balance(&delegate, funding::<T>());
 		// ... and "real" is the traditional caller. This is not a typo.
 		let real: T::AccountId = whitelisted_caller();
 		let real_lookup = T::Lookup::unlookup(real);
@@ -153,7 +169,7 @@ mod benchmarks {
 		add_proxies::<T>(p, None)?;
 		// In this case the caller is the "target" proxy
 		let caller: T::AccountId = account("target", p - 1, SEED);
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		T::Currency::set_balance(&caller, funding::<T>());
 		// ... and "real" is the traditional caller. This is not a typo.
 		let real: T::AccountId = whitelisted_caller();
 		let real_lookup = T::Lookup::unlookup(real);
@@ -184,7 +200,7 @@ mod benchmarks {
 		// In this case the caller is the "target" proxy
 		let caller: T::AccountId = account("target", p - 1, SEED);
 		let caller_lookup = T::Lookup::unlookup(caller.clone());
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		T::Currency::set_balance(&caller, funding::<T>());
 		// ... and "real" is the traditional caller. This is not a typo.
 		let real: T::AccountId = whitelisted_caller();
 		let real_lookup = T::Lookup::unlookup(real.clone());
@@ -214,7 +230,7 @@ mod benchmarks {
 		add_proxies::<T>(p, None)?;
 		// In this case the caller is the "target" proxy
 		let caller: T::AccountId = account("target", p - 1, SEED);
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		T::Currency::set_balance(&caller, funding::<T>());
 		// ... and "real" is the traditional caller. This is not a typo.
 		let real: T::AccountId = whitelisted_caller();
 		let real_lookup = T::Lookup::unlookup(real.clone());
@@ -316,7 +332,7 @@ mod benchmarks {
 	fn kill_pure(p: Linear<0, { T::MaxProxies::get() - 2 }>) -> Result<(), BenchmarkError> {
 		let caller: T::AccountId = whitelisted_caller();
 		let caller_lookup = T::Lookup::unlookup(caller.clone());
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		T::Currency::set_balance(&caller, funding::<T>());
 		Pallet::<T>::create_pure(
 			RawOrigin::Signed(whitelisted_caller()).into(),
 			T::ProxyType::default(),
@@ -353,9 +369,9 @@ mod benchmarks {
 		let account_3: T::AccountId = account("account", 3, SEED);
 
 		// Fund accounts
-		T::Currency::make_free_balance_be(&account_1, BalanceOf::<T>::max_value() / 100u8.into());
-		T::Currency::make_free_balance_be(&account_2, BalanceOf::<T>::max_value() / 100u8.into());
-		T::Currency::make_free_balance_be(&account_3, BalanceOf::<T>::max_value() / 100u8.into());
+		T::Currency::set_balance(&account_1, funding::<T>());
+		T::Currency::set_balance(&account_2, funding::<T>());
+		T::Currency::set_balance(&account_3, funding::<T>());
 
 		// Add proxy relationships
 		Proxy::<T>::add_proxy(
@@ -372,7 +388,7 @@ mod benchmarks {
 		)?;
 		let (proxies, initial_proxy_deposit) = Proxies::<T>::get(&account_2);
 		assert!(!initial_proxy_deposit.is_zero());
-		assert_eq!(initial_proxy_deposit, T::Currency::reserved_balance(&account_2));
+		assert_eq!(initial_proxy_deposit, T::Currency::total_balance_on_hold(&account_2));
 
 		// Create announcement
 		Proxy::<T>::announce(
@@ -384,17 +400,22 @@ mod benchmarks {
 		assert!(!initial_announcement_deposit.is_zero());
 		assert_eq!(
 			initial_announcement_deposit.saturating_add(initial_proxy_deposit),
-			T::Currency::reserved_balance(&account_2)
+			T::Currency::total_balance_on_hold(&account_2)
 		);
 
-		// Artificially inflate deposits and reserve the extra amount
+		// Artificially inflate deposits and hold the extra amount
 		let extra_proxy_deposit = initial_proxy_deposit; // Double the deposit
 		let extra_announcement_deposit = initial_announcement_deposit; // Double the deposit
 		let total = extra_proxy_deposit.saturating_add(extra_announcement_deposit);
 
-		T::Currency::reserve(&account_2, total)?;
+		T::Currency::hold(&HoldReason::ProxyDeposit.into(), &account_2, extra_proxy_deposit)?;
+		T::Currency::hold(
+			&HoldReason::AnnouncementDeposit.into(),
+			&account_2,
+			extra_announcement_deposit,
+		)?;
 
-		let initial_reserved = T::Currency::reserved_balance(&account_2);
+		let initial_reserved = T::Currency::total_balance_on_hold(&account_2);
 		assert_eq!(initial_reserved, total.saturating_add(total)); // Double
 
 		// Update storage with increased deposits
@@ -431,7 +452,7 @@ mod benchmarks {
 		assert_eq!(final_proxy_deposit, initial_proxy_deposit);
 		assert_eq!(final_announcement_deposit, initial_announcement_deposit);
 
-		let final_reserved = T::Currency::reserved_balance(&account_2);
+		let final_reserved = T::Currency::total_balance_on_hold(&account_2);
 		assert_eq!(final_reserved, initial_reserved.saturating_sub(total));
 
 		// Verify events
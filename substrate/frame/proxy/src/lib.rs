@@ -30,6 +30,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod benchmarking;
+pub mod migrations;
 mod tests;
 pub mod weights;
 
@@ -37,7 +38,11 @@ extern crate alloc;
 use alloc::{boxed::Box, vec};
 use frame::{
 	prelude::*,
-	traits::{Currency, InstanceFilter, ReservableCurrency},
+	traits::{
+		fungible::{Inspect, Mutate, MutateHold},
+		tokens::Precision,
+		InstanceFilter, ReservableCurrency,
+	},
 };
 pub use pallet::*;
 pub use weights::WeightInfo;
@@ -45,7 +50,7 @@ pub use weights::WeightInfo;
 type CallHashOf<T> = <<T as Config>::CallHasher as Hash>::Output;
 
 type BalanceOf<T> =
-	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	<<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
 pub type BlockNumberFor<T> =
 	<<T as Config>::BlockNumberProvider as BlockNumberProvider>::BlockNumber;
@@ -112,7 +117,11 @@ pub enum DepositKind {
 pub mod pallet {
 	use super::*;
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configuration trait.
@@ -131,7 +140,17 @@ pub mod pallet {
 			+ IsType<<Self as frame_system::Config>::RuntimeCall>;
 
 		/// The currency mechanism.
-		type Currency: ReservableCurrency<Self::AccountId>;
+		type Currency: Mutate<Self::AccountId>
+			+ MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+		/// The currency the deposits were reserved with before they moved to holds.
+		///
+		/// Only used to return the deposits of the pure proxies spawned before that, see
+		/// [`LegacyPureDeposits`].
+		type OldCurrency: ReservableCurrency<Self::AccountId, Balance = BalanceOf<Self>>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
 
 		/// A kind of proxy; specified with the proxy and passed in to the `IsProxyable` filter.
 		/// The instance filter determines whether a given call may be proxied under this type.
@@ -145,7 +164,7 @@ pub mod pallet {
 			+ Default
 			+ MaxEncodedLen;
 
-		/// The base amount of currency needed to reserve for creating a proxy.
+		/// The base amount of currency needed to hold for creating a proxy.
 		///
 		/// This is held for an additional storage item whose value size is
 		/// `sizeof(Balance)` bytes and whose key size is `sizeof(AccountId)` bytes.
@@ -174,7 +193,7 @@ pub mod pallet {
 		/// The type of hash used for hashing the call.
 		type CallHasher: Hash;
 
-		/// The base amount of currency needed to reserve for creating an announcement.
+		/// The base amount of currency needed to hold for creating an announcement.
 		///
 		/// This is held when a new storage item holding a `Balance` is created (typically 16
 		/// bytes).
@@ -296,7 +315,8 @@ pub mod pallet {
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// WARNING: This may be called on accounts created by `pure`, however if done, then
-		/// the unreserved fees will be inaccessible. **All access to this account will be lost.**
+		/// the deposits held for it will be inaccessible. **All access to this account will be
+		/// lost.**
 		#[pallet::call_index(3)]
 		#[pallet::weight(T::WeightInfo::remove_proxies(T::MaxProxies::get()))]
 		pub fn remove_proxies(origin: OriginFor<T>) -> DispatchResult {
@@ -342,7 +362,7 @@ pub mod pallet {
 				vec![proxy_def].try_into().map_err(|_| Error::<T>::TooMany)?;
 
 			let deposit = T::ProxyDepositBase::get() + T::ProxyDepositFactor::get();
-			T::Currency::reserve(&who, deposit)?;
+			T::Currency::hold(&HoldReason::ProxyDeposit.into(), &who, deposit)?;
 
 			Proxies::<T>::insert(&pure, (bounded_proxies, deposit));
 			Self::deposit_event(Event::PureCreated {
@@ -389,7 +409,19 @@ pub mod pallet {
 			ensure!(proxy == who, Error::<T>::NoPermission);
 
 			let (_, deposit) = Proxies::<T>::take(&who);
-			T::Currency::unreserve(&spawner, deposit);
+			let legacy_deposit = LegacyPureDeposits::<T>::take(&who).unwrap_or_default();
+			let missing = T::OldCurrency::unreserve(&spawner, legacy_deposit);
+			if !missing.is_zero() {
+				defensive!(
+					"Failed to unreserve the full legacy deposit",
+					(legacy_deposit, missing)
+				);
+			}
+			Self::release_deposit(
+				&spawner,
+				deposit.saturating_sub(legacy_deposit),
+				HoldReason::ProxyDeposit,
+			);
 
 			Ok(())
 		}
@@ -438,6 +470,7 @@ pub mod pallet {
 					T::AnnouncementDepositBase::get(),
 					T::AnnouncementDepositFactor::get(),
 					pending.len(),
+					HoldReason::AnnouncementDeposit,
 				)
 				.map(|d| {
 					d.expect("Just pushed; pending.len() > 0; rejig_deposit returns Some; qed")
@@ -574,6 +607,7 @@ pub mod pallet {
 					T::ProxyDepositBase::get(),
 					T::ProxyDepositFactor::get(),
 					proxies.len(),
+					HoldReason::ProxyDeposit,
 				)?;
 
 				match maybe_new_deposit {
@@ -615,6 +649,7 @@ pub mod pallet {
 					T::AnnouncementDepositBase::get(),
 					T::AnnouncementDepositFactor::get(),
 					announcements.len(),
+					HoldReason::AnnouncementDeposit,
 				)?;
 
 				match maybe_new_deposit {
@@ -709,6 +744,17 @@ pub mod pallet {
 		NoSelfProxy,
 	}
 
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The funds are held as deposit for the proxies of an account.
+		#[codec(index = 0)]
+		ProxyDeposit,
+		/// The funds are held as deposit for the announcements of a proxy.
+		#[codec(index = 1)]
+		AnnouncementDeposit,
+	}
+
 	/// The set of account proxies. Maps the account which has delegated to the accounts
 	/// which are being delegated to, together with the amount held on deposit.
 	#[pallet::storage]
@@ -739,6 +785,16 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The part of the deposit of a pure proxy which is still reserved with
+	/// [`Config::OldCurrency`] on its spawner.
+	///
+	/// The spawner of a pure proxy is not stored, so the migration to holds cannot move these
+	/// deposits. They are returned to the spawner by [`Pallet::kill_pure`], which is given the
+	/// spawner and checks it against the pure account.
+	#[pallet::storage]
+	pub type LegacyPureDeposits<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
 	#[pallet::view_functions]
 	impl<T: Config> Pallet<T> {
 		/// Check if a `RuntimeCall` is allowed for a given `ProxyType`.
@@ -831,9 +887,13 @@ impl<T: Config> Pallet<T> {
 			proxies.try_insert(i, proxy_def).map_err(|_| Error::<T>::TooMany)?;
 			let new_deposit = Self::deposit(proxies.len() as u32);
 			if new_deposit > *deposit {
-				T::Currency::reserve(delegator, new_deposit - *deposit)?;
+				T::Currency::hold(
+					&HoldReason::ProxyDeposit.into(),
+					delegator,
+					new_deposit - *deposit,
+				)?;
 			} else if new_deposit < *deposit {
-				T::Currency::unreserve(delegator, *deposit - new_deposit);
+				Self::release_proxy_deposit(delegator, *deposit, *deposit - new_deposit);
 			}
 			*deposit = new_deposit;
 			Self::deposit_event(Event::<T>::ProxyAdded {
@@ -871,9 +931,13 @@ impl<T: Config> Pallet<T> {
 			proxies.remove(i);
 			let new_deposit = Self::deposit(proxies.len() as u32);
 			if new_deposit > old_deposit {
-				T::Currency::reserve(delegator, new_deposit - old_deposit)?;
+				T::Currency::hold(
+					&HoldReason::ProxyDeposit.into(),
+					delegator,
+					new_deposit - old_deposit,
+				)?;
 			} else if new_deposit < old_deposit {
-				T::Currency::unreserve(delegator, old_deposit - new_deposit);
+				Self::release_proxy_deposit(delegator, old_deposit, old_deposit - new_deposit);
 			}
 			if !proxies.is_empty() {
 				*x = Some((proxies, new_deposit))
@@ -902,24 +966,53 @@ impl<T: Config> Pallet<T> {
 		base: BalanceOf<T>,
 		factor: BalanceOf<T>,
 		len: usize,
+		reason: HoldReason,
 	) -> Result<Option<BalanceOf<T>>, DispatchError> {
 		let new_deposit =
 			if len == 0 { BalanceOf::<T>::zero() } else { base + factor * (len as u32).into() };
 		if new_deposit > old_deposit {
-			T::Currency::reserve(who, new_deposit.saturating_sub(old_deposit))?;
+			T::Currency::hold(&reason.into(), who, new_deposit.saturating_sub(old_deposit))?;
 		} else if new_deposit < old_deposit {
-			let excess = old_deposit.saturating_sub(new_deposit);
-			let remaining_unreserved = T::Currency::unreserve(who, excess);
-			if !remaining_unreserved.is_zero() {
-				defensive!(
-					"Failed to unreserve full amount. (Requested, Actual)",
-					(excess, excess.saturating_sub(remaining_unreserved))
-				);
+			let amount = old_deposit.saturating_sub(new_deposit);
+			match reason {
+				HoldReason::ProxyDeposit => Self::release_proxy_deposit(who, old_deposit, amount),
+				HoldReason::AnnouncementDeposit => Self::release_deposit(who, amount, reason),
 			}
 		}
 		Ok(if len == 0 { None } else { Some(new_deposit) })
 	}
 
+	/// Release `amount` of the deposit held from `who` for `reason`.
+	///
+	/// Whatever part of it is not held is unreserved with [`Config::OldCurrency`] instead, in case
+	/// it was taken before the deposits moved to holds and not migrated yet.
+	fn release_deposit(who: &T::AccountId, amount: BalanceOf<T>, reason: HoldReason) {
+		if amount.is_zero() {
+			return
+		}
+		let released = T::Currency::release(&reason.into(), who, amount, Precision::BestEffort)
+			.unwrap_or_default();
+		let missing = T::OldCurrency::unreserve(who, amount.saturating_sub(released));
+		if !missing.is_zero() {
+			defensive!("Failed to release full amount. (Requested, Missing)", (amount, missing));
+		}
+	}
+
+	/// Release `amount` of the proxy deposit `old_deposit` of `delegator`.
+	///
+	/// The part of the deposit of a pure proxy recorded in [`LegacyPureDeposits`] is reserved on
+	/// its spawner rather than on `delegator`, so it is left to [`Pallet::kill_pure`] and only the
+	/// rest is released.
+	fn release_proxy_deposit(
+		delegator: &T::AccountId,
+		old_deposit: BalanceOf<T>,
+		amount: BalanceOf<T>,
+	) {
+		let legacy_deposit = LegacyPureDeposits::<T>::get(delegator).unwrap_or_default();
+		let held = old_deposit.saturating_sub(legacy_deposit);
+		Self::release_deposit(delegator, amount.min(held), HoldReason::ProxyDeposit);
+	}
+
 	fn edit_announcements<
 		F: FnMut(&Announcement<T::AccountId, CallHashOf<T>, BlockNumberFor<T>>) -> bool,
 	>(
@@ -937,6 +1030,7 @@ impl<T: Config> Pallet<T> {
 				T::AnnouncementDepositBase::get(),
 				T::AnnouncementDepositFactor::get(),
 				pending.len(),
+				HoldReason::AnnouncementDeposit,
 			)?
 			.map(|deposit| (pending, deposit));
 			Ok(())
@@ -991,6 +1085,6 @@ impl<T: Config> Pallet<T> {
 	/// - `delegator`: The delegator account.
 	pub fn remove_all_proxy_delegates(delegator: &T::AccountId) {
 		let (_, old_deposit) = Proxies::<T>::take(&delegator);
-		T::Currency::unreserve(&delegator, old_deposit);
+		Self::release_proxy_deposit(delegator, old_deposit, old_deposit);
 	}
 }
@@ -0,0 +1,192 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migrations for the Proxy pallet.
+
+use crate::*;
+#[cfg(feature = "try-runtime")]
+use alloc::vec::Vec;
+use frame::{
+	deps::frame_support::{
+		migrations::{reserve_to_hold, MigrationId, SteppedMigration, SteppedMigrationError},
+		weights::WeightMeter,
+	},
+	prelude::*,
+	traits::fungible::InspectHold,
+};
+
+/// The log target of the migrations.
+const LOG_TARGET: &str = "runtime::proxy";
+
+/// The pallet identifier of the migrations, see [`MigrationId`].
+pub const PALLET_MIGRATIONS_ID: &[u8; 12] = b"pallet-proxy";
+
+pub mod v1 {
+	use super::*;
+
+	/// The progress of [`LazyMigrationV0ToV1`].
+	#[derive(Decode, Encode, MaxEncodedLen, Eq, PartialEq, RuntimeDebug)]
+	pub enum MigrationState<AccountId> {
+		/// The proxy deposits were converted up to and including this delegator.
+		Proxies(AccountId),
+		/// The proxy deposits were all converted, and the announcement deposits up to and
+		/// including this delegate, if any.
+		Announcements(Option<AccountId>),
+	}
+
+	/// Converts the proxy and announcement deposits from reserves of [`Config::OldCurrency`] into
+	/// holds of [`Config::Currency`], one account at a time.
+	///
+	/// The deposit of a pure proxy is reserved on its spawner rather than on the pure account
+	/// itself, and the spawner is not stored. So whatever part of a proxy deposit is not reserved
+	/// on the delegator is left reserved and recorded in [`LegacyPureDeposits`], from where
+	/// [`Pallet::kill_pure`] returns it to the spawner.
+	///
+	/// A reserve may be shared with other pallets, so only the part of it which is not backing a
+	/// hold is moved, less the announcement deposit of the same account. In particular, the
+	/// deposits of pallets which were migrated to holds before are never taken.
+	pub struct LazyMigrationV0ToV1<T>(PhantomData<T>);
+
+	impl<T: Config> SteppedMigration for LazyMigrationV0ToV1<T> {
+		type Cursor = MigrationState<T::AccountId>;
+		type Identifier = MigrationId<12>;
+
+		fn id() -> Self::Identifier {
+			MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 0, version_to: 1 }
+		}
+
+		fn step(
+			mut cursor: Option<Self::Cursor>,
+			meter: &mut WeightMeter,
+		) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+			if Pallet::<T>::on_chain_storage_version() != Self::id().version_from as u16 {
+				return Ok(None)
+			}
+
+			let required = Self::step_weight();
+			if meter.remaining().any_lt(required) {
+				return Err(SteppedMigrationError::InsufficientWeight { required })
+			}
+
+			while meter.try_consume(required).is_ok() {
+				let next = match cursor {
+					None => Self::proxies_step(None),
+					Some(MigrationState::Proxies(last_key)) => Self::proxies_step(Some(last_key)),
+					Some(MigrationState::Announcements(maybe_last_key)) =>
+						Self::announcements_step(maybe_last_key),
+				};
+				match next {
+					Some(next) => cursor = Some(next),
+					None => {
+						StorageVersion::new(Self::id().version_to as u16).put::<Pallet<T>>();
+						return Ok(None)
+					},
+				}
+			}
+
+			Ok(cursor)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), frame::try_runtime::TryRuntimeError> {
+			for (who, (_, deposit)) in Proxies::<T>::iter() {
+				let legacy_deposit = LegacyPureDeposits::<T>::get(&who).unwrap_or_default();
+				ensure!(
+					T::Currency::balance_on_hold(&HoldReason::ProxyDeposit.into(), &who) >=
+						deposit.saturating_sub(legacy_deposit),
+					"proxy deposit is not held"
+				);
+			}
+			for (who, (_, deposit)) in Announcements::<T>::iter() {
+				ensure!(
+					T::Currency::balance_on_hold(&HoldReason::AnnouncementDeposit.into(), &who) >=
+						deposit,
+					"announcement deposit is not held"
+				);
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config> LazyMigrationV0ToV1<T> {
+		// The weight of converting a single deposit.
+		fn step_weight() -> Weight {
+			T::DbWeight::get().reads_writes(
+				// Reads: Get item + Announcements + Account + Holds
+				4, // Writes: Account + Holds + Legacy pure deposit
+				3,
+			)
+		}
+
+		// Convert the deposit of the delegator following `maybe_last_key`.
+		fn proxies_step(
+			maybe_last_key: Option<T::AccountId>,
+		) -> Option<MigrationState<T::AccountId>> {
+			let mut iter = match maybe_last_key {
+				Some(last_key) => Proxies::<T>::iter_from(Proxies::<T>::hashed_key_for(last_key)),
+				None => Proxies::<T>::iter(),
+			};
+			let Some((who, (_, deposit))) = iter.next() else {
+				return Some(MigrationState::Announcements(None))
+			};
+
+			// The announcement deposit of `who` is reserved by this pallet as well and is only
+			// migrated afterwards, so it is not taken for the proxy deposit.
+			let (_, announcement_deposit) = Announcements::<T>::get(&who);
+			let unheld = T::OldCurrency::reserved_balance(&who)
+				.saturating_sub(T::Currency::total_balance_on_hold(&who));
+			let reserved = deposit.min(unheld.saturating_sub(announcement_deposit));
+			reserve_to_hold::<_, T::OldCurrency, T::Currency>(
+				&who,
+				reserved,
+				&HoldReason::ProxyDeposit.into(),
+			);
+			let missing = deposit.saturating_sub(reserved);
+			if !missing.is_zero() {
+				frame::log::info!(
+					target: LOG_TARGET,
+					"{:?} of the deposit of {:?} is reserved elsewhere, leaving it to `kill_pure`",
+					missing,
+					who
+				);
+				LegacyPureDeposits::<T>::insert(&who, missing);
+			}
+
+			Some(MigrationState::Proxies(who))
+		}
+
+		// Convert the deposit of the delegate following `maybe_last_key`.
+		fn announcements_step(
+			maybe_last_key: Option<T::AccountId>,
+		) -> Option<MigrationState<T::AccountId>> {
+			let mut iter = match maybe_last_key {
+				Some(last_key) =>
+					Announcements::<T>::iter_from(Announcements::<T>::hashed_key_for(last_key)),
+				None => Announcements::<T>::iter(),
+			};
+			let (who, (_, deposit)) = iter.next()?;
+
+			reserve_to_hold::<_, T::OldCurrency, T::Currency>(
+				&who,
+				deposit,
+				&HoldReason::AnnouncementDeposit.into(),
+			);
+
+			Some(MigrationState::Announcements(Some(who)))
+		}
+	}
+}
@@ -22,7 +22,7 @@
 use super::*;
 use crate as proxy;
 use alloc::{vec, vec::Vec};
-use frame::testing_prelude::*;
+use frame::{testing_prelude::*, traits::Currency};
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -119,6 +119,8 @@ impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
 	type Currency = Balances;
+	type OldCurrency = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type ProxyType = ProxyType;
 	type ProxyDepositBase = ProxyDepositBase;
 	type ProxyDepositFactor = ProxyDepositFactor;
@@ -133,7 +135,7 @@ impl Config for Test {
 
 use super::{Call as ProxyCall, Event as ProxyEvent};
 use frame_system::Call as SystemCall;
-use pallet_balances::{Call as BalancesCall, Error as BalancesError, Event as BalancesEvent};
+use pallet_balances::Call as BalancesCall;
 use pallet_utility::{Call as UtilityCall, Event as UtilityEvent};
 
 type SystemError = frame_system::Error<Test>;
@@ -401,10 +403,8 @@ fn filtering_works() {
 			ProxyEvent::ProxyExecuted { result: Err(SystemError::CallFiltered.into()) }.into(),
 		);
 		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(2), 1, None, call.clone()));
-		expect_events(vec![
-			BalancesEvent::<Test>::Unreserved { who: 1, amount: 5 }.into(),
-			ProxyEvent::ProxyExecuted { result: Ok(()) }.into(),
-		]);
+		System::assert_last_event(ProxyEvent::ProxyExecuted { result: Ok(()) }.into());
+		assert_eq!(Balances::reserved_balance(1), 0);
 	});
 }
 
@@ -489,7 +489,7 @@ fn cannot_add_proxy_without_balance() {
 		assert_eq!(Balances::reserved_balance(5), 2);
 		assert_noop!(
 			Proxy::add_proxy(RuntimeOrigin::signed(5), 4, ProxyType::Any, 0),
-			DispatchError::ConsumerRemaining,
+			TokenError::FundsUnavailable,
 		);
 	});
 }
@@ -712,10 +712,7 @@ fn poke_deposit_handles_insufficient_balance() {
 		ProxyDepositBase::set(10);
 
 		// Poking should fail due to insufficient balance
-		assert_noop!(
-			Proxy::poke_deposit(RuntimeOrigin::signed(5)),
-			BalancesError::<Test, _>::InsufficientBalance,
-		);
+		assert_noop!(Proxy::poke_deposit(RuntimeOrigin::signed(5)), TokenError::FundsUnavailable,);
 
 		// Original deposit should remain unchanged
 		assert_eq!(Balances::reserved_balance(5), initial_deposit);
@@ -823,3 +820,179 @@ fn poke_deposit_fails_for_unsigned_origin() {
 		assert_noop!(Proxy::poke_deposit(RuntimeOrigin::none()), DispatchError::BadOrigin,);
 	});
 }
+
+#[test]
+fn legacy_deposits_are_released_by_the_pallet() {
+	use frame::traits::{fungible::InspectHold, ReservableCurrency};
+
+	new_test_ext().execute_with(|| {
+		// 1 has a deposit that is still reserved.
+		let proxies: BoundedVec<_, ConstU32<4>> =
+			vec![ProxyDefinition { delegate: 2, proxy_type: ProxyType::Any, delay: 0 }]
+				.try_into()
+				.unwrap();
+		assert_ok!(Balances::reserve(&1, 2));
+		Proxies::<Test>::insert(1, (proxies, 2));
+		assert_ok!(Proxy::remove_proxies(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 10);
+
+		// 3 spawned a pure proxy whose deposit it has reserved, as left by the migration.
+		let pure = Proxy::pure_account(&3, &ProxyType::Any, 0, None);
+		let proxies: BoundedVec<_, ConstU32<4>> =
+			vec![ProxyDefinition { delegate: 3, proxy_type: ProxyType::Any, delay: 0 }]
+				.try_into()
+				.unwrap();
+		assert_ok!(Balances::reserve(&3, 2));
+		Proxies::<Test>::insert(pure, (proxies, 2));
+		LegacyPureDeposits::<Test>::insert(pure, 2);
+		Balances::make_free_balance_be(&pure, 10);
+
+		// Only the part of the deposit held from the pure proxy itself is released.
+		assert_ok!(Proxy::add_proxy(RuntimeOrigin::signed(pure), 4, ProxyType::Any, 0));
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ProxyDeposit.into(), &pure), 1);
+		assert_ok!(Proxy::remove_proxies(RuntimeOrigin::signed(pure)));
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ProxyDeposit.into(), &pure), 0);
+		assert_eq!(Balances::free_balance(pure), 10);
+		assert_eq!(Balances::reserved_balance(3), 2);
+
+		// The spawner still gets the reserved deposit back.
+		assert_ok!(Proxy::kill_pure(RuntimeOrigin::signed(pure), 3, ProxyType::Any, 0, 1, 0));
+		assert_eq!(LegacyPureDeposits::<Test>::get(pure), None);
+		assert_eq!(Balances::free_balance(3), 10);
+	});
+}
+
+#[test]
+fn migrate_reserves_to_holds_works() {
+	use frame::{
+		deps::frame_support::{migrations::SteppedMigration, weights::WeightMeter},
+		traits::{fungible::InspectHold, ReservableCurrency},
+	};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<Proxy>();
+
+		let proxies: BoundedVec<_, ConstU32<4>> =
+			vec![ProxyDefinition { delegate: 2, proxy_type: ProxyType::Any, delay: 0 }]
+				.try_into()
+				.unwrap();
+		assert_ok!(Balances::reserve(&1, 2));
+		Proxies::<Test>::insert(1, (proxies, 2));
+		let announcements: BoundedVec<_, ConstU32<2>> =
+			vec![Announcement { real: 1, call_hash: H256::repeat_byte(1), height: 1 }]
+				.try_into()
+				.unwrap();
+		assert_ok!(Balances::reserve(&2, 2));
+		Announcements::<Test>::insert(2, (announcements, 2));
+		// 3 has a proxy of its own, and spawned a pure proxy whose deposit it has reserved too.
+		let proxies: BoundedVec<_, ConstU32<4>> =
+			vec![ProxyDefinition { delegate: 4, proxy_type: ProxyType::Any, delay: 0 }]
+				.try_into()
+				.unwrap();
+		Proxies::<Test>::insert(3, (proxies, 2));
+		let pure = Proxy::pure_account(&3, &ProxyType::Any, 0, None);
+		let proxies: BoundedVec<_, ConstU32<4>> =
+			vec![ProxyDefinition { delegate: 3, proxy_type: ProxyType::Any, delay: 0 }]
+				.try_into()
+				.unwrap();
+		Proxies::<Test>::insert(pure, (proxies, 2));
+		assert_ok!(Balances::reserve(&3, 4));
+
+		let mut cursor = None;
+		for _ in 0..10 {
+			cursor =
+				migrations::v1::LazyMigrationV0ToV1::<Test>::step(cursor, &mut WeightMeter::new())
+					.unwrap();
+			if cursor.is_none() {
+				break
+			}
+		}
+		assert!(cursor.is_none());
+
+		assert_eq!(StorageVersion::get::<Proxy>(), 1);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ProxyDeposit.into(), &1), 2);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::AnnouncementDeposit.into(), &2), 2);
+		// Holds are counted in the reserved balance, so nothing is left reserved outside of them.
+		assert_eq!(Balances::reserved_balance(1), 2);
+		assert_eq!(Balances::reserved_balance(2), 2);
+		// The deposit of the pure proxy stays reserved on its spawner, which is not known yet.
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ProxyDeposit.into(), &3), 2);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ProxyDeposit.into(), &pure), 0);
+		assert_eq!(Balances::reserved_balance(3), 4);
+		assert_eq!(LegacyPureDeposits::<Test>::get(pure), Some(2));
+
+		// The deposits can be released through the pallet as usual.
+		assert_ok!(Proxy::remove_proxies(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::free_balance(1), 10);
+		assert_ok!(Proxy::remove_announcement(RuntimeOrigin::signed(2), 1, H256::repeat_byte(1)));
+		assert_eq!(Balances::free_balance(2), 10);
+		// Killing the pure proxy proves its spawner, which gets the reserved deposit back.
+		assert_ok!(Proxy::kill_pure(RuntimeOrigin::signed(pure), 3, ProxyType::Any, 0, 1, 0));
+		assert_eq!(LegacyPureDeposits::<Test>::get(pure), None);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ProxyDeposit.into(), &3), 2);
+		assert_eq!(Balances::reserved_balance(3), 2);
+		assert_ok!(Proxy::remove_proxies(RuntimeOrigin::signed(3)));
+		assert_eq!(Balances::free_balance(3), 10);
+	});
+}
+
+#[test]
+fn migrate_reserves_to_holds_leaves_shared_reserves() {
+	use frame::{
+		deps::frame_support::{migrations::SteppedMigration, weights::WeightMeter},
+		traits::{
+			fungible::{InspectHold, MutateHold},
+			ReservableCurrency,
+		},
+	};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<Proxy>();
+
+		// 1 has a proxy and an announcement deposit, and 3 reserved by another pallet.
+		let proxies: BoundedVec<_, ConstU32<4>> =
+			vec![ProxyDefinition { delegate: 2, proxy_type: ProxyType::Any, delay: 0 }]
+				.try_into()
+				.unwrap();
+		Proxies::<Test>::insert(1, (proxies, 2));
+		let announcements: BoundedVec<_, ConstU32<2>> =
+			vec![Announcement { real: 3, call_hash: H256::repeat_byte(1), height: 1 }]
+				.try_into()
+				.unwrap();
+		Announcements::<Test>::insert(1, (announcements, 2));
+		assert_ok!(Balances::reserve(&1, 7));
+		// The deposit of a pure proxy of 4 is reserved on 4, while the pure proxy itself has a
+		// deposit of 3 held already, say by a pallet migrated before.
+		let pure = Proxy::pure_account(&4, &ProxyType::Any, 0, None);
+		let proxies: BoundedVec<_, ConstU32<4>> =
+			vec![ProxyDefinition { delegate: 4, proxy_type: ProxyType::Any, delay: 0 }]
+				.try_into()
+				.unwrap();
+		Proxies::<Test>::insert(pure, (proxies, 2));
+		assert_ok!(Balances::reserve(&4, 2));
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(4), pure, 5));
+		assert_ok!(Balances::hold(&HoldReason::AnnouncementDeposit.into(), &pure, 3));
+
+		let mut cursor = None;
+		for _ in 0..10 {
+			cursor =
+				migrations::v1::LazyMigrationV0ToV1::<Test>::step(cursor, &mut WeightMeter::new())
+					.unwrap();
+			if cursor.is_none() {
+				break
+			}
+		}
+		assert!(cursor.is_none());
+
+		// Only the deposits of this pallet are held, the other reserve of 1 is left alone.
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ProxyDeposit.into(), &1), 2);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::AnnouncementDeposit.into(), &1), 2);
+		assert_eq!(Balances::reserved_balance(1), 7);
+		// The hold of the pure proxy is not taken for the deposit reserved on its spawner.
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ProxyDeposit.into(), &pure), 0);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::AnnouncementDeposit.into(), &pure), 3);
+		assert_eq!(Balances::reserved_balance(pure), 3);
+		assert_eq!(LegacyPureDeposits::<Test>::get(pure), Some(2));
+		assert_eq!(Balances::reserved_balance(4), 2);
+	});
+}
@@ -21,7 +21,7 @@ use super::*;
 
 use crate::Pallet;
 use alloc::{boxed::Box, vec, vec::Vec};
-use frame::benchmarking::prelude::*;
+use frame::{benchmarking::prelude::*, traits::fungible::InspectHold};
 
 const SEED: u32 = 0;
 const DEFAULT_DELAY: u32 = 0;
@@ -34,10 +34,17 @@ fn assert_has_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
 	frame_system::Pallet::<T>::assert_has_event(generic_event.into());
 }
 
-fn get_total_deposit<T: Config>(
-	bounded_friends: &FriendsOf<T>,
-) -> Option<<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance>
-{
+/// A balance large enough to pay for all the deposits an account can have in this pallet.
+fn funding<T: Config>() -> BalanceOf<T> {
+	let config_deposit = T::ConfigDepositBase::get()
+		.saturating_add(T::FriendDepositFactor::get().saturating_mul(T::MaxFriends::get().into()));
+	T::Currency::minimum_balance()
+		.saturating_add(config_deposit)
+		.saturating_add(T::RecoveryDeposit::get())
+		.saturating_mul(10u32.into())
+}
+
+fn get_total_deposit<T: Config>(bounded_friends: &FriendsOf<T>) -> Option<BalanceOf<T>> {
 	let friend_deposit = T::FriendDepositFactor::get()
 		.checked_mul(&bounded_friends.len().saturated_into())
 		.unwrap();
@@ -53,10 +60,7 @@ fn generate_friends<T: Config>(num: u32) -> Vec<<T as frame_system::Config>::Acc
 
 	for friend in 0..friends.len() {
 		// Top up accounts of friends
-		T::Currency::make_free_balance_be(
-			&friends.get(friend).unwrap(),
-			BalanceOf::<T>::max_value(),
-		);
+		T::Currency::set_balance(&friends.get(friend).unwrap(), funding::<T>());
 	}
 
 	friends
@@ -69,7 +73,7 @@ fn add_caller_and_generate_friends<T: Config>(
 	// Create friends
 	let mut friends = generate_friends::<T>(num - 1);
 
-	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	T::Currency::set_balance(&caller, funding::<T>());
 
 	friends.push(caller);
 
@@ -80,7 +84,7 @@ fn add_caller_and_generate_friends<T: Config>(
 }
 
 fn insert_recovery_config_with_max_friends<T: Config>(account: &T::AccountId) {
-	T::Currency::make_free_balance_be(&account, BalanceOf::<T>::max_value());
+	T::Currency::set_balance(&account, funding::<T>());
 
 	let n = T::MaxFriends::get();
 
@@ -98,8 +102,8 @@ fn insert_recovery_config_with_max_friends<T: Config>(account: &T::AccountId) {
 		threshold: n as u16,
 	};
 
-	// Reserve deposit for recovery
-	T::Currency::reserve(&account, total_deposit).unwrap();
+	// Hold deposit for recovery
+	T::Currency::hold(&HoldReason::ConfigDeposit.into(), &account, total_deposit).unwrap();
 
 	<Recoverable<T>>::insert(&account, recovery_config);
 }
@@ -114,7 +118,8 @@ fn setup_active_recovery_with_max_friends<T: Config>(
 	let bounded_friends: FriendsOf<T> = friends.try_into().unwrap();
 
 	let initial_recovery_deposit = T::RecoveryDeposit::get();
-	T::Currency::reserve(caller, initial_recovery_deposit).unwrap();
+	T::Currency::hold(&HoldReason::RecoveryDeposit.into(), caller, initial_recovery_deposit)
+		.unwrap();
 
 	let active_recovery = ActiveRecovery {
 		created: DEFAULT_DELAY.into(),
@@ -160,7 +165,7 @@ mod benchmarks {
 	#[benchmark]
 	fn create_recovery(n: Linear<1, { T::MaxFriends::get() }>) {
 		let caller: T::AccountId = whitelisted_caller();
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		T::Currency::set_balance(&caller, funding::<T>());
 
 		// Create friends
 		let friends = generate_friends::<T>(n);
@@ -174,7 +179,7 @@ mod benchmarks {
 	#[benchmark]
 	fn initiate_recovery() {
 		let caller: T::AccountId = whitelisted_caller();
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		T::Currency::set_balance(&caller, funding::<T>());
 
 		let lost_account: T::AccountId = account("lost_account", 0, SEED);
 		let lost_account_lookup = T::Lookup::unlookup(lost_account.clone());
@@ -214,8 +219,8 @@ mod benchmarks {
 		// Create the recovery config storage item
 		<Recoverable<T>>::insert(&lost_account, recovery_config.clone());
 
-		// Reserve deposit for recovery
-		T::Currency::reserve(&caller, total_deposit).unwrap();
+		// Hold deposit for recovery
+		T::Currency::hold(&HoldReason::ConfigDeposit.into(), &caller, total_deposit).unwrap();
 
 		// Create an active recovery status
 		let recovery_status = ActiveRecovery {
@@ -240,7 +245,7 @@ mod benchmarks {
 		let lost_account: T::AccountId = account("lost_account", 0, SEED);
 		let lost_account_lookup = T::Lookup::unlookup(lost_account.clone());
 
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		T::Currency::set_balance(&caller, funding::<T>());
 
 		// Create friends
 		let friends = generate_friends::<T>(n);
@@ -259,8 +264,8 @@ mod benchmarks {
 		// Create the recovery config storage item
 		<Recoverable<T>>::insert(&lost_account, recovery_config.clone());
 
-		// Reserve deposit for recovery
-		T::Currency::reserve(&caller, total_deposit).unwrap();
+		// Hold deposit for recovery
+		T::Currency::hold(&HoldReason::RecoveryDeposit.into(), &caller, total_deposit).unwrap();
 
 		// Create an active recovery status
 		let recovery_status = ActiveRecovery {
@@ -285,8 +290,8 @@ mod benchmarks {
 		let rescuer_account: T::AccountId = account("rescuer_account", 0, SEED);
 		let rescuer_account_lookup = T::Lookup::unlookup(rescuer_account.clone());
 
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
-		T::Currency::make_free_balance_be(&rescuer_account, BalanceOf::<T>::max_value());
+		T::Currency::set_balance(&caller, funding::<T>());
+		T::Currency::set_balance(&rescuer_account, funding::<T>());
 
 		// Create friends
 		let friends = generate_friends::<T>(n);
@@ -305,8 +310,9 @@ mod benchmarks {
 		// Create the recovery config storage item
 		<Recoverable<T>>::insert(&caller, recovery_config.clone());
 
-		// Reserve deposit for recovery
-		T::Currency::reserve(&caller, total_deposit).unwrap();
+		// Hold deposit of the rescuer for the active recovery
+		T::Currency::hold(&HoldReason::RecoveryDeposit.into(), &rescuer_account, total_deposit)
+			.unwrap();
 
 		// Create an active recovery status
 		let recovery_status = ActiveRecovery {
@@ -329,7 +335,7 @@ mod benchmarks {
 	fn remove_recovery(n: Linear<1, { T::MaxFriends::get() }>) {
 		let caller: T::AccountId = whitelisted_caller();
 
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		T::Currency::set_balance(&caller, funding::<T>());
 
 		// Create friends
 		let friends = generate_friends::<T>(n);
@@ -348,8 +354,8 @@ mod benchmarks {
 		// Create the recovery config storage item
 		<Recoverable<T>>::insert(&caller, recovery_config);
 
-		// Reserve deposit for recovery
-		T::Currency::reserve(&caller, total_deposit).unwrap();
+		// Hold deposit for recovery
+		T::Currency::hold(&HoldReason::ConfigDeposit.into(), &caller, total_deposit).unwrap();
 
 		#[extrinsic_call]
 		_(RawOrigin::Signed(caller.clone()));
@@ -380,7 +386,7 @@ mod benchmarks {
 		let lost_account: T::AccountId = account("lost_account", 0, SEED);
 
 		// Fund caller account
-		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		T::Currency::set_balance(&caller, funding::<T>());
 
 		// 1. Setup recovery config for caller
 		insert_recovery_config_with_max_friends::<T>(&caller);
@@ -393,7 +399,7 @@ mod benchmarks {
 		let initial_config_deposit = initial_config.deposit;
 		let initial_recovery_deposit = T::RecoveryDeposit::get();
 		assert_eq!(
-			T::Currency::reserved_balance(&caller),
+			T::Currency::total_balance_on_hold(&caller),
 			initial_config_deposit.saturating_add(initial_recovery_deposit)
 		);
 
@@ -403,7 +409,8 @@ mod benchmarks {
 
 		<Recoverable<T>>::try_mutate(&caller, |maybe_config| -> Result<(), BenchmarkError> {
 			let config = maybe_config.as_mut().unwrap();
-			T::Currency::reserve(
+			T::Currency::hold(
+				&HoldReason::ConfigDeposit.into(),
 				&caller,
 				increased_config_deposit.saturating_sub(initial_config_deposit),
 			)?;
@@ -417,7 +424,8 @@ mod benchmarks {
 			&caller,
 			|maybe_recovery| -> Result<(), BenchmarkError> {
 				let recovery = maybe_recovery.as_mut().unwrap();
-				T::Currency::reserve(
+				T::Currency::hold(
+					&HoldReason::RecoveryDeposit.into(),
 					&caller,
 					increased_recovery_deposit.saturating_sub(initial_recovery_deposit),
 				)?;
@@ -429,7 +437,7 @@ mod benchmarks {
 
 		// 5. Verify increased deposits
 		assert_eq!(
-			T::Currency::reserved_balance(&caller),
+			T::Currency::total_balance_on_hold(&caller),
 			increased_config_deposit.saturating_add(increased_recovery_deposit)
 		);
 
@@ -438,7 +446,7 @@ mod benchmarks {
 
 		// 6. Assert final state
 		assert_eq!(
-			T::Currency::reserved_balance(&caller),
+			T::Currency::total_balance_on_hold(&caller),
 			initial_config_deposit.saturating_add(initial_recovery_deposit)
 		);
 
@@ -465,5 +473,69 @@ mod benchmarks {
 		Ok(())
 	}
 
+	#[benchmark]
+	fn update_recovery(n: Linear<1, { T::MaxFriends::get() }>) -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::set_balance(&caller, funding::<T>());
+
+		// Start from the smallest configuration so the deposit has to grow
+		Pallet::<T>::create_recovery(
+			RawOrigin::Signed(caller.clone()).into(),
+			generate_friends::<T>(1),
+			1,
+			DEFAULT_DELAY.into(),
+		)?;
+
+		let friends = generate_friends::<T>(n);
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller.clone()),
+			friends,
+			n as u16,
+			DEFAULT_DELAY.into(),
+			Some(DEFAULT_DELAY.into()),
+		);
+
+		assert_last_event::<T>(
+			Event::RecoveryUpdated {
+				account: caller,
+				threshold: n as u16,
+				delay_period: DEFAULT_DELAY.into(),
+				veto_period: Some(DEFAULT_DELAY.into()),
+			}
+			.into(),
+		);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn finalize_recovery() {
+		let caller: T::AccountId = whitelisted_caller();
+		let lost_account: T::AccountId = account("lost_account", 0, SEED);
+		let lost_account_lookup = T::Lookup::unlookup(lost_account.clone());
+
+		T::Currency::set_balance(&caller, funding::<T>());
+
+		// Create a claimed recovery whose veto period has passed
+		setup_active_recovery_with_max_friends::<T>(&caller, &lost_account);
+		VetoPeriods::<T>::insert(
+			&lost_account,
+			BlockNumberFromProviderOf::<T>::from(DEFAULT_DELAY),
+		);
+		PendingRecoveries::<T>::insert(
+			&lost_account,
+			&caller,
+			T::BlockNumberProvider::current_block_number(),
+		);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), lost_account_lookup);
+
+		assert_last_event::<T>(
+			Event::AccountRecovered { lost_account, rescuer_account: caller }.into(),
+		);
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
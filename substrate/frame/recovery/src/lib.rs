@@ -39,6 +39,12 @@
 //!   be successfully recovered.
 //! * `delay_period` - The minimum number of blocks after the beginning of the recovery process that
 //!   need to pass before the account can be successfully recovered.
+//! * `veto_period` - Optionally, the number of blocks a successful claim has to wait before the
+//!   account is actually recovered. During this window the account owner can still veto the
+//!   recovery.
+//!
+//! The configuration can be changed at any time with `update_recovery`, as long as no recovery
+//! process is active for the account.
 //!
 //! There is a configurable deposit that all users need to pay to create a recovery
 //! configuration. This deposit is composed of a base deposit plus a multiplier for
@@ -132,13 +138,16 @@
 //!
 //! * `claim_recovery` - Claim access to the account that you have successfully completed the
 //!   recovery process for.
+//! * `finalize_recovery` - Get access to an account whose claim was waiting for its veto period.
 //! * `as_recovered` - Send a transaction as an account that you have recovered. See other functions
 //!   below.
 //!
 //! #### For the Recoverable Account
 //!
 //! * `close_recovery` - Close an active recovery process for your account and reclaim the recovery
-//!   deposit.
+//!   deposit. This also vetoes a claim that is waiting for its veto period.
+//! * `update_recovery` - Change the friends, threshold, delay period and veto period of the
+//!   recovery configuration.
 //! * `remove_recovery` - Remove the recovery configuration from the account, making it
 //!   un-recoverable.
 //!
@@ -156,7 +165,10 @@ use alloc::{boxed::Box, vec::Vec};
 
 use frame::{
 	prelude::*,
-	traits::{Currency, ReservableCurrency},
+	traits::{
+		fungible::{Inspect, Mutate, MutateHold},
+		tokens::{Fortitude, Precision, Restriction},
+	},
 };
 
 pub use pallet::*;
@@ -164,6 +176,7 @@ pub use weights::WeightInfo;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 
 #[cfg(test)]
 mod mock;
@@ -173,7 +186,7 @@ pub mod weights;
 
 pub type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
 pub type BalanceOf<T> =
-	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	<<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 pub type BlockNumberFromProviderOf<T> =
 	<<T as Config>::BlockNumberProvider as BlockNumberProvider>::BlockNumber;
 pub type FriendsOf<T> =
@@ -184,7 +197,7 @@ pub type FriendsOf<T> =
 pub struct ActiveRecovery<BlockNumber, Balance, Friends> {
 	/// The block number when the recovery process started.
 	pub created: BlockNumber,
-	/// The amount held in reserve of the `depositor`,
+	/// The amount held on deposit of the `depositor`,
 	/// to be returned once this recovery process is closed.
 	pub deposit: Balance,
 	/// The friends which have vouched so far. Always sorted.
@@ -197,7 +210,7 @@ pub struct RecoveryConfig<BlockNumber, Balance, Friends> {
 	/// The minimum number of blocks since the start of the recovery process before the account
 	/// can be recovered.
 	pub delay_period: BlockNumber,
-	/// The amount held in reserve of the `depositor`,
+	/// The amount held on deposit of the `depositor`,
 	/// to be returned once this configuration is removed.
 	pub deposit: Balance,
 	/// The list of friends which can help recover an account. Always sorted.
@@ -229,7 +242,11 @@ pub enum DepositKind<T: Config> {
 pub mod pallet {
 	use super::*;
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configuration trait.
@@ -273,9 +290,13 @@ pub mod pallet {
 		type BlockNumberProvider: BlockNumberProvider;
 
 		/// The currency mechanism.
-		type Currency: ReservableCurrency<Self::AccountId>;
+		type Currency: Mutate<Self::AccountId>
+			+ MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
 
-		/// The base amount of currency needed to reserve for creating a recovery configuration.
+		/// The base amount of currency needed to hold for creating a recovery configuration.
 		///
 		/// This is held for an additional storage item whose value size is
 		/// `2 + sizeof(BlockNumber, Balance)` bytes.
@@ -299,7 +320,7 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxFriends: Get<u32>;
 
-		/// The base amount of currency needed to reserve for starting a recovery.
+		/// The base amount of currency needed to hold for starting a recovery.
 		///
 		/// This is primarily held for deterring malicious recovery attempts, and should
 		/// have a value large enough that a bad actor would choose not to place this
@@ -330,6 +351,22 @@ pub mod pallet {
 		AccountRecovered { lost_account: T::AccountId, rescuer_account: T::AccountId },
 		/// A recovery process has been removed for an account.
 		RecoveryRemoved { lost_account: T::AccountId },
+		/// The recovery configuration of an account has been updated.
+		RecoveryUpdated {
+			account: T::AccountId,
+			threshold: u16,
+			delay_period: BlockNumberFromProviderOf<T>,
+			veto_period: Option<BlockNumberFromProviderOf<T>>,
+		},
+		/// The recovery of lost account by rescuer account has been claimed and can be finalized
+		/// from `executable_at`, unless it is vetoed before.
+		RecoveryPending {
+			lost_account: T::AccountId,
+			rescuer_account: T::AccountId,
+			executable_at: BlockNumberFromProviderOf<T>,
+		},
+		/// The pending recovery of lost account by rescuer account has been vetoed.
+		RecoveryVetoed { lost_account: T::AccountId, rescuer_account: T::AccountId },
 		/// A deposit has been updated.
 		DepositPoked {
 			who: T::AccountId,
@@ -373,6 +410,23 @@ pub mod pallet {
 		AlreadyProxy,
 		/// Some internal state is broken.
 		BadState,
+		/// The recovery has already been claimed and is waiting for the veto period
+		AlreadyPending,
+		/// There is no claimed recovery waiting for the veto period
+		NotPending,
+		/// The veto period of the lost account has not passed yet
+		VetoPeriod,
+	}
+
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The funds are held as deposit for a recovery configuration.
+		#[codec(index = 0)]
+		ConfigDeposit,
+		/// The funds are held as deposit for an active recovery.
+		#[codec(index = 1)]
+		RecoveryDeposit,
 	}
 
 	/// The set of recoverable accounts and their recovery configuration.
@@ -400,6 +454,28 @@ pub mod pallet {
 		ActiveRecovery<BlockNumberFromProviderOf<T>, BalanceOf<T>, FriendsOf<T>>,
 	>;
 
+	/// The veto period of recoverable accounts which opted into one.
+	///
+	/// A claimed recovery of such an account only takes effect after this many blocks.
+	#[pallet::storage]
+	pub type VetoPeriods<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, BlockNumberFromProviderOf<T>>;
+
+	/// Claimed recoveries waiting for the veto period of the lost account to pass.
+	///
+	/// First account is the account to be recovered, and the second account
+	/// is the user trying to recover the account. The value is the block number from which the
+	/// recovery can be finalized.
+	#[pallet::storage]
+	pub type PendingRecoveries<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		BlockNumberFromProviderOf<T>,
+	>;
+
 	/// The list of allowed proxy accounts.
 	///
 	/// Map from the user who can access it to the recovered account.
@@ -469,7 +545,7 @@ pub mod pallet {
 		/// Create a recovery configuration for your account. This makes your account recoverable.
 		///
 		/// Payment: `ConfigDepositBase` + `FriendDepositFactor` * #_of_friends balance
-		/// will be reserved for storing the recovery configuration. This deposit is returned
+		/// will be held for storing the recovery configuration. This deposit is returned
 		/// in full when the user calls `remove_recovery`.
 		///
 		/// The dispatch origin for this call must be _Signed_.
@@ -494,16 +570,11 @@ pub mod pallet {
 			// Check account is not already set up for recovery
 			ensure!(!<Recoverable<T>>::contains_key(&who), Error::<T>::AlreadyRecoverable);
 			// Check user input is valid
-			ensure!(threshold >= 1, Error::<T>::ZeroThreshold);
-			ensure!(!friends.is_empty(), Error::<T>::NotEnoughFriends);
-			ensure!(threshold as usize <= friends.len(), Error::<T>::NotEnoughFriends);
-			let bounded_friends: FriendsOf<T> =
-				friends.try_into().map_err(|_| Error::<T>::MaxFriends)?;
-			ensure!(Self::is_sorted_and_unique(&bounded_friends), Error::<T>::NotSorted);
+			let bounded_friends = Self::ensure_valid_friends(friends, threshold)?;
 			// Calculate total deposit required
 			let total_deposit = Self::get_recovery_config_deposit(bounded_friends.len())?;
-			// Reserve the deposit
-			T::Currency::reserve(&who, total_deposit)?;
+			// Hold the deposit
+			T::Currency::hold(&HoldReason::ConfigDeposit.into(), &who, total_deposit)?;
 			// Create the recovery configuration
 			let recovery_config = RecoveryConfig {
 				delay_period,
@@ -520,7 +591,7 @@ pub mod pallet {
 
 		/// Initiate the process for recovering a recoverable account.
 		///
-		/// Payment: `RecoveryDeposit` balance will be reserved for initiating the
+		/// Payment: `RecoveryDeposit` balance will be held for initiating the
 		/// recovery process. This deposit will always be repatriated to the account
 		/// trying to be recovered. See `close_recovery`.
 		///
@@ -546,7 +617,7 @@ pub mod pallet {
 			);
 			// Take recovery deposit
 			let recovery_deposit = T::RecoveryDeposit::get();
-			T::Currency::reserve(&who, recovery_deposit)?;
+			T::Currency::hold(&HoldReason::RecoveryDeposit.into(), &who, recovery_deposit)?;
 			// Create an active recovery status
 			let recovery_status = ActiveRecovery {
 				created: T::BlockNumberProvider::current_block_number(),
@@ -615,6 +686,10 @@ pub mod pallet {
 		/// who has successfully completed the account recovery process: collected
 		/// `threshold` or more vouches, waited `delay_period` blocks since initiation.
 		///
+		/// If the lost account has set a veto period, the account is not recovered right away.
+		/// Instead, the recovery becomes pending and can be finalized with `finalize_recovery`
+		/// once the veto period has passed, unless the lost account closes it before.
+		///
 		/// Parameters:
 		/// - `account`: The lost account that you want to claim has been successfully recovered by
 		///   you.
@@ -645,14 +720,24 @@ pub mod pallet {
 				recovery_config.threshold as usize <= active_recovery.friends.len(),
 				Error::<T>::Threshold
 			);
-			frame_system::Pallet::<T>::inc_consumers(&who).map_err(|_| Error::<T>::BadState)?;
-			// Create the recovery storage item
-			Proxy::<T>::insert(&who, &account);
-			Self::deposit_event(Event::<T>::AccountRecovered {
-				lost_account: account,
-				rescuer_account: who,
-			});
-			Ok(())
+			// If the lost account has a veto period, only schedule the recovery for now
+			if let Some(veto_period) = VetoPeriods::<T>::get(&account) {
+				ensure!(
+					!PendingRecoveries::<T>::contains_key(&account, &who),
+					Error::<T>::AlreadyPending
+				);
+				let executable_at = current_block_number
+					.checked_add(&veto_period)
+					.ok_or(ArithmeticError::Overflow)?;
+				PendingRecoveries::<T>::insert(&account, &who, executable_at);
+				Self::deposit_event(Event::<T>::RecoveryPending {
+					lost_account: account,
+					rescuer_account: who,
+					executable_at,
+				});
+				return Ok(())
+			}
+			Self::recover(account, who)
 		}
 
 		/// As the controller of a recoverable account, close an active recovery
@@ -661,6 +746,9 @@ pub mod pallet {
 		/// Payment: By calling this function, the recoverable account will receive
 		/// the recovery deposit `RecoveryDeposit` placed by the rescuer.
 		///
+		/// If the rescuer has already claimed the recovery and it is still waiting for the veto
+		/// period, this vetoes the recovery.
+		///
 		/// The dispatch origin for this call must be _Signed_ and must be a
 		/// recoverable account with an active recovery process for it.
 		///
@@ -677,15 +765,25 @@ pub mod pallet {
 			// Take the active recovery process started by the rescuer for this account.
 			let active_recovery =
 				<ActiveRecoveries<T>>::take(&who, &rescuer).ok_or(Error::<T>::NotStarted)?;
-			// Move the reserved funds from the rescuer to the rescued account.
+			// Move the held funds from the rescuer to the rescued account.
 			// Acts like a slashing mechanism for those who try to maliciously recover accounts.
-			let res = T::Currency::repatriate_reserved(
+			let res = T::Currency::transfer_on_hold(
+				&HoldReason::RecoveryDeposit.into(),
 				&rescuer,
 				&who,
 				active_recovery.deposit,
-				BalanceStatus::Free,
+				Precision::BestEffort,
+				Restriction::Free,
+				Fortitude::Force,
 			);
 			debug_assert!(res.is_ok());
+			// Closing the recovery vetoes a claim that is still waiting for its veto period.
+			if PendingRecoveries::<T>::take(&who, &rescuer).is_some() {
+				Self::deposit_event(Event::<T>::RecoveryVetoed {
+					lost_account: who.clone(),
+					rescuer_account: rescuer.clone(),
+				});
+			}
 			Self::deposit_event(Event::<T>::RecoveryClosed {
 				lost_account: who,
 				rescuer_account: rescuer,
@@ -698,7 +796,7 @@ pub mod pallet {
 		/// NOTE: The user must make sure to call `close_recovery` on all active
 		/// recovery attempts before calling this function else it will fail.
 		///
-		/// Payment: By calling this function the recoverable account will unreserve
+		/// Payment: By calling this function the recoverable account will get back
 		/// their recovery configuration deposit.
 		/// (`ConfigDepositBase` + `FriendDepositFactor` * #_of_friends)
 		///
@@ -714,8 +812,10 @@ pub mod pallet {
 			// Take the recovery configuration for this account.
 			let recovery_config = <Recoverable<T>>::take(&who).ok_or(Error::<T>::NotRecoverable)?;
 
-			// Unreserve the initial deposit for the recovery configuration.
-			T::Currency::unreserve(&who, recovery_config.deposit);
+			VetoPeriods::<T>::remove(&who);
+
+			// Release the initial deposit for the recovery configuration.
+			Self::release_deposit(&who, recovery_config.deposit, HoldReason::ConfigDeposit);
 			Self::deposit_event(Event::<T>::RecoveryRemoved { lost_account: who });
 			Ok(())
 		}
@@ -759,7 +859,7 @@ pub mod pallet {
 		/// - If the caller has initiated any active recoveries, and provides the account in
 		/// `maybe_account`, checks and adjusts those deposits
 		///
-		/// If any deposit is updated, the difference will be reserved/unreserved from the caller's
+		/// If any deposit is updated, the difference will be held/released from the caller's
 		/// account.
 		///
 		/// The transaction is made free if any deposit is updated and paid otherwise.
@@ -786,6 +886,96 @@ pub mod pallet {
 
 			Ok(if deposit_updated { Pays::No } else { Pays::Yes }.into())
 		}
+
+		/// Update the recovery configuration of your account.
+		///
+		/// The deposit is adjusted to the new number of friends: any difference is held from or
+		/// released to the caller.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be a recoverable account
+		/// without any active recovery process.
+		///
+		/// Parameters:
+		/// - `friends`: The new list of friends. Should be ordered and contain no duplicate values.
+		/// - `threshold`: The new number of friends that must vouch for a recovery attempt.
+		/// - `delay_period`: The new number of blocks after a recovery attempt is initialized that
+		///   needs to pass before the account can be recovered.
+		/// - `veto_period`: If set, a successful claim only becomes effective after this many
+		///   blocks, during which the account can still veto it with `close_recovery`.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::update_recovery(friends.len() as u32))]
+		pub fn update_recovery(
+			origin: OriginFor<T>,
+			friends: Vec<T::AccountId>,
+			threshold: u16,
+			delay_period: BlockNumberFromProviderOf<T>,
+			veto_period: Option<BlockNumberFromProviderOf<T>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut recovery_config =
+				<Recoverable<T>>::get(&who).ok_or(Error::<T>::NotRecoverable)?;
+			// The friends and threshold must not change under an ongoing recovery.
+			let mut active_recoveries = <ActiveRecoveries<T>>::iter_prefix_values(&who);
+			ensure!(active_recoveries.next().is_none(), Error::<T>::StillActive);
+			let bounded_friends = Self::ensure_valid_friends(friends, threshold)?;
+
+			let old_deposit = recovery_config.deposit;
+			let new_deposit = Self::get_recovery_config_deposit(bounded_friends.len())?;
+			if new_deposit > old_deposit {
+				T::Currency::hold(
+					&HoldReason::ConfigDeposit.into(),
+					&who,
+					new_deposit.saturating_sub(old_deposit),
+				)?;
+			} else {
+				Self::release_deposit(
+					&who,
+					old_deposit.saturating_sub(new_deposit),
+					HoldReason::ConfigDeposit,
+				);
+			}
+
+			recovery_config.delay_period = delay_period;
+			recovery_config.deposit = new_deposit;
+			recovery_config.friends = bounded_friends;
+			recovery_config.threshold = threshold;
+			<Recoverable<T>>::insert(&who, recovery_config);
+			VetoPeriods::<T>::set(&who, veto_period);
+
+			Self::deposit_event(Event::<T>::RecoveryUpdated {
+				account: who,
+				threshold,
+				delay_period,
+				veto_period,
+			});
+			Ok(())
+		}
+
+		/// Finalize a claimed recovery once the veto period of the lost account has passed.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be the rescuer who claimed
+		/// the recovery with `claim_recovery`.
+		///
+		/// Parameters:
+		/// - `account`: The lost account that you claimed.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::finalize_recovery())]
+		pub fn finalize_recovery(
+			origin: OriginFor<T>,
+			account: AccountIdLookupOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let account = T::Lookup::lookup(account)?;
+			let executable_at =
+				PendingRecoveries::<T>::get(&account, &who).ok_or(Error::<T>::NotPending)?;
+			ensure!(
+				executable_at <= T::BlockNumberProvider::current_block_number(),
+				Error::<T>::VetoPeriod
+			);
+			ensure!(!Proxy::<T>::contains_key(&who), Error::<T>::AlreadyProxy);
+			PendingRecoveries::<T>::remove(&account, &who);
+			Self::recover(account, who)
+		}
 	}
 }
 
@@ -800,6 +990,32 @@ impl<T: Config> Pallet<T> {
 		friends.binary_search(&friend).is_ok()
 	}
 
+	/// Check the friends and threshold of a recovery configuration.
+	fn ensure_valid_friends(
+		friends: Vec<T::AccountId>,
+		threshold: u16,
+	) -> Result<FriendsOf<T>, DispatchError> {
+		ensure!(threshold >= 1, Error::<T>::ZeroThreshold);
+		ensure!(!friends.is_empty(), Error::<T>::NotEnoughFriends);
+		ensure!(threshold as usize <= friends.len(), Error::<T>::NotEnoughFriends);
+		let bounded_friends: FriendsOf<T> =
+			friends.try_into().map_err(|_| Error::<T>::MaxFriends)?;
+		ensure!(Self::is_sorted_and_unique(&bounded_friends), Error::<T>::NotSorted);
+		Ok(bounded_friends)
+	}
+
+	/// Give `rescuer` access to `lost`.
+	fn recover(lost: T::AccountId, rescuer: T::AccountId) -> DispatchResult {
+		frame_system::Pallet::<T>::inc_consumers(&rescuer).map_err(|_| Error::<T>::BadState)?;
+		// Create the recovery storage item
+		Proxy::<T>::insert(&rescuer, &lost);
+		Self::deposit_event(Event::<T>::AccountRecovered {
+			lost_account: lost,
+			rescuer_account: rescuer,
+		});
+		Ok(())
+	}
+
 	/// Helper function to calculate recovery config deposit
 	/// Total deposit is base fee + number of friends * factor fee
 	fn get_recovery_config_deposit(friends_count: usize) -> Result<BalanceOf<T>, DispatchError> {
@@ -811,7 +1027,7 @@ impl<T: Config> Pallet<T> {
 			.ok_or(ArithmeticError::Overflow.into())
 	}
 
-	/// Helper function to poke the deposit reserved for creating a recovery config
+	/// Helper function to poke the deposit held for creating a recovery config
 	fn poke_recovery_config_deposit(who: &T::AccountId) -> Result<bool, DispatchError> {
 		<Recoverable<T>>::try_mutate(&who, |maybe_config| -> Result<bool, DispatchError> {
 			let Some(config) = maybe_config.as_mut() else { return Ok(false) };
@@ -822,18 +1038,12 @@ impl<T: Config> Pallet<T> {
 				return Ok(false);
 			}
 
+			let reason = HoldReason::ConfigDeposit;
 			if new_deposit > old_deposit {
 				let extra = new_deposit.saturating_sub(old_deposit);
-				T::Currency::reserve(&who, extra)?;
+				T::Currency::hold(&reason.into(), &who, extra)?;
 			} else {
-				let excess = old_deposit.saturating_sub(new_deposit);
-				let remaining_unreserved = T::Currency::unreserve(&who, excess);
-				if !remaining_unreserved.is_zero() {
-					defensive!(
-						"Failed to unreserve full amount. (Requested, Actual)",
-						(excess, excess.saturating_sub(remaining_unreserved))
-					);
-				}
+				Self::release_deposit(&who, old_deposit.saturating_sub(new_deposit), reason);
 			}
 			config.deposit = new_deposit;
 
@@ -847,7 +1057,7 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
-	/// Helper function to poke the deposit reserved for an active recovery
+	/// Helper function to poke the deposit held for an active recovery
 	fn poke_active_recovery_deposit(
 		who: &T::AccountId,
 		lost_account: &T::AccountId,
@@ -866,18 +1076,12 @@ impl<T: Config> Pallet<T> {
 				}
 
 				// Update deposit
+				let reason = HoldReason::RecoveryDeposit;
 				if new_deposit > old_deposit {
 					let extra = new_deposit.saturating_sub(old_deposit);
-					T::Currency::reserve(who, extra)?;
+					T::Currency::hold(&reason.into(), who, extra)?;
 				} else {
-					let excess = old_deposit.saturating_sub(new_deposit);
-					let remaining_unreserved = T::Currency::unreserve(who, excess);
-					if !remaining_unreserved.is_zero() {
-						defensive!(
-							"Failed to unreserve full amount. (Requested, Actual)",
-							(excess, excess.saturating_sub(remaining_unreserved))
-						);
-					}
+					Self::release_deposit(who, old_deposit.saturating_sub(new_deposit), reason);
 				}
 				recovery.deposit = new_deposit;
 
@@ -891,4 +1095,13 @@ impl<T: Config> Pallet<T> {
 			},
 		)
 	}
+
+	/// Release `amount` of the deposit held from `who` for `reason`.
+	fn release_deposit(who: &T::AccountId, amount: BalanceOf<T>, reason: HoldReason) {
+		let released = T::Currency::release(&reason.into(), who, amount, Precision::BestEffort)
+			.unwrap_or_default();
+		if released != amount {
+			defensive!("Failed to release full amount. (Requested, Actual)", (amount, released));
+		}
+	}
 }
@@ -0,0 +1,165 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migrations for the Recovery pallet.
+
+use crate::*;
+use frame::{
+	deps::frame_support::{
+		migrations::{reserve_to_hold, MigrationId, SteppedMigration, SteppedMigrationError},
+		weights::WeightMeter,
+	},
+	traits::ReservableCurrency,
+};
+
+/// The pallet identifier of the migrations, see [`MigrationId`].
+pub const PALLET_MIGRATIONS_ID: &[u8; 15] = b"pallet-recovery";
+
+pub mod v1 {
+	use super::*;
+
+	/// The progress of [`LazyMigrationV0ToV1`].
+	#[derive(Decode, Encode, MaxEncodedLen, Eq, PartialEq, RuntimeDebug)]
+	pub enum MigrationState<AccountId> {
+		/// The configuration deposits were converted up to and including this account.
+		Recoverable(AccountId),
+		/// The configuration deposits were all converted, and the active recovery deposits up to
+		/// and including this lost account and rescuer, if any.
+		ActiveRecoveries(Option<(AccountId, AccountId)>),
+	}
+
+	/// Converts the recovery configuration and active recovery deposits from reserves of
+	/// `OldCurrency` into holds of [`Config::Currency`], one deposit at a time.
+	///
+	/// `OldCurrency` must be the `ReservableCurrency` the pallet was configured with before v1.
+	pub struct LazyMigrationV0ToV1<T, OldCurrency>(core::marker::PhantomData<(T, OldCurrency)>);
+
+	impl<T: Config, OldCurrency> SteppedMigration for LazyMigrationV0ToV1<T, OldCurrency>
+	where
+		OldCurrency: ReservableCurrency<T::AccountId, Balance = BalanceOf<T>>,
+	{
+		type Cursor = MigrationState<T::AccountId>;
+		type Identifier = MigrationId<15>;
+
+		fn id() -> Self::Identifier {
+			MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 0, version_to: 1 }
+		}
+
+		fn step(
+			mut cursor: Option<Self::Cursor>,
+			meter: &mut WeightMeter,
+		) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+			if Pallet::<T>::on_chain_storage_version() != Self::id().version_from as u16 {
+				return Ok(None)
+			}
+
+			// Reads: Get item + Unreserve + Hold, writes: Unreserve + Hold
+			let required = T::DbWeight::get().reads_writes(3, 2);
+			if meter.remaining().any_lt(required) {
+				return Err(SteppedMigrationError::InsufficientWeight { required })
+			}
+
+			while meter.try_consume(required).is_ok() {
+				let next = match cursor {
+					None => Self::recoverable_step(None),
+					Some(MigrationState::Recoverable(last_key)) =>
+						Self::recoverable_step(Some(last_key)),
+					Some(MigrationState::ActiveRecoveries(maybe_last_key)) =>
+						Self::active_recoveries_step(maybe_last_key),
+				};
+				match next {
+					Some(next) => cursor = Some(next),
+					None => {
+						StorageVersion::new(Self::id().version_to as u16).put::<Pallet<T>>();
+						return Ok(None)
+					},
+				}
+			}
+
+			Ok(cursor)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), frame::try_runtime::TryRuntimeError> {
+			use frame::traits::fungible::InspectHold;
+
+			for (who, config) in Recoverable::<T>::iter() {
+				ensure!(
+					T::Currency::balance_on_hold(&HoldReason::ConfigDeposit.into(), &who) >=
+						config.deposit,
+					"recovery configuration deposit is not held"
+				);
+			}
+			for (_, rescuer, recovery) in ActiveRecoveries::<T>::iter() {
+				ensure!(
+					T::Currency::balance_on_hold(&HoldReason::RecoveryDeposit.into(), &rescuer) >=
+						recovery.deposit,
+					"active recovery deposit is not held"
+				);
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config, OldCurrency> LazyMigrationV0ToV1<T, OldCurrency>
+	where
+		OldCurrency: ReservableCurrency<T::AccountId, Balance = BalanceOf<T>>,
+	{
+		// Convert the configuration deposit of the account following `maybe_last_key`.
+		fn recoverable_step(
+			maybe_last_key: Option<T::AccountId>,
+		) -> Option<MigrationState<T::AccountId>> {
+			let mut iter = match maybe_last_key {
+				Some(last_key) =>
+					Recoverable::<T>::iter_from(Recoverable::<T>::hashed_key_for(last_key)),
+				None => Recoverable::<T>::iter(),
+			};
+			let Some((who, config)) = iter.next() else {
+				return Some(MigrationState::ActiveRecoveries(None))
+			};
+
+			reserve_to_hold::<_, OldCurrency, T::Currency>(
+				&who,
+				config.deposit,
+				&HoldReason::ConfigDeposit.into(),
+			);
+
+			Some(MigrationState::Recoverable(who))
+		}
+
+		// Convert the deposit of the active recovery following `maybe_last_key`.
+		fn active_recoveries_step(
+			maybe_last_key: Option<(T::AccountId, T::AccountId)>,
+		) -> Option<MigrationState<T::AccountId>> {
+			let mut iter = match maybe_last_key {
+				Some((lost, rescuer)) => ActiveRecoveries::<T>::iter_from(
+					ActiveRecoveries::<T>::hashed_key_for(lost, rescuer),
+				),
+				None => ActiveRecoveries::<T>::iter(),
+			};
+			let (lost, rescuer, recovery) = iter.next()?;
+
+			reserve_to_hold::<_, OldCurrency, T::Currency>(
+				&rescuer,
+				recovery.deposit,
+				&HoldReason::RecoveryDeposit.into(),
+			);
+
+			Some(MigrationState::ActiveRecoveries(Some((lost, rescuer))))
+		}
+	}
+}
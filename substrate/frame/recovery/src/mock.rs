@@ -64,6 +64,7 @@ impl Config for Test {
 	type RuntimeCall = RuntimeCall;
 	type BlockNumberProvider = System;
 	type Currency = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type ConfigDepositBase = ConfigDepositBase;
 	type FriendDepositFactor = FriendDepositFactor;
 	type MaxFriends = MaxFriends;
@@ -780,9 +780,212 @@ fn poke_deposit_handles_insufficient_balance() {
 		// Should fail due to insufficient balance
 		assert_noop!(
 			Recovery::poke_deposit(RuntimeOrigin::signed(5), None),
-			pallet_balances::Error::<Test>::InsufficientBalance
+			TokenError::FundsUnavailable
 		);
 		// Original deposit should remain unchanged
 		assert_eq!(Balances::reserved_balance(5), 13);
 	});
 }
+
+#[test]
+fn update_recovery_works() {
+	new_test_ext().execute_with(|| {
+		// Not recoverable yet
+		assert_noop!(
+			Recovery::update_recovery(RuntimeOrigin::signed(5), vec![2, 3], 2, 10, None),
+			Error::<Test>::NotRecoverable
+		);
+		assert_ok!(Recovery::create_recovery(RuntimeOrigin::signed(5), vec![2, 3, 4], 3, 10));
+		assert_eq!(Balances::reserved_balance(5), 13);
+
+		// Input is checked like on creation
+		assert_noop!(
+			Recovery::update_recovery(RuntimeOrigin::signed(5), vec![3, 2], 2, 10, None),
+			Error::<Test>::NotSorted
+		);
+		assert_noop!(
+			Recovery::update_recovery(RuntimeOrigin::signed(5), vec![2, 3], 3, 10, None),
+			Error::<Test>::NotEnoughFriends
+		);
+
+		// Fewer friends release part of the deposit
+		assert_ok!(Recovery::update_recovery(RuntimeOrigin::signed(5), vec![2, 3], 2, 20, Some(5)));
+		System::assert_last_event(
+			Event::<Test>::RecoveryUpdated {
+				account: 5,
+				threshold: 2,
+				delay_period: 20,
+				veto_period: Some(5),
+			}
+			.into(),
+		);
+		let config = Recovery::recovery_config(&5).unwrap();
+		assert_eq!(config.friends.into_inner(), vec![2, 3]);
+		assert_eq!(config.threshold, 2);
+		assert_eq!(config.delay_period, 20);
+		assert_eq!(config.deposit, 12);
+		assert_eq!(Balances::reserved_balance(5), 12);
+		assert_eq!(VetoPeriods::<Test>::get(&5), Some(5));
+
+		// More friends hold more deposit, and the veto period can be removed again
+		assert_ok!(Recovery::update_recovery(
+			RuntimeOrigin::signed(5),
+			vec![1, 2, 3, 4],
+			3,
+			10,
+			None
+		));
+		assert_eq!(Recovery::recovery_config(&5).unwrap().deposit, 14);
+		assert_eq!(Balances::reserved_balance(5), 14);
+		assert_eq!(VetoPeriods::<Test>::get(&5), None);
+
+		// Not while a recovery is active
+		assert_ok!(Recovery::initiate_recovery(RuntimeOrigin::signed(1), 5));
+		assert_noop!(
+			Recovery::update_recovery(RuntimeOrigin::signed(5), vec![2, 3], 2, 10, None),
+			Error::<Test>::StillActive
+		);
+	});
+}
+
+#[test]
+fn veto_period_delays_recovery() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Recovery::create_recovery(RuntimeOrigin::signed(5), vec![2, 3, 4], 2, 0));
+		assert_ok!(Recovery::update_recovery(
+			RuntimeOrigin::signed(5),
+			vec![2, 3, 4],
+			2,
+			0,
+			Some(10)
+		));
+		assert_ok!(Recovery::initiate_recovery(RuntimeOrigin::signed(1), 5));
+		assert_ok!(Recovery::vouch_recovery(RuntimeOrigin::signed(2), 5, 1));
+		assert_ok!(Recovery::vouch_recovery(RuntimeOrigin::signed(3), 5, 1));
+
+		// Nothing to finalize before the claim
+		assert_noop!(
+			Recovery::finalize_recovery(RuntimeOrigin::signed(1), 5),
+			Error::<Test>::NotPending
+		);
+
+		// The claim only schedules the recovery
+		assert_ok!(Recovery::claim_recovery(RuntimeOrigin::signed(1), 5));
+		System::assert_last_event(
+			Event::<Test>::RecoveryPending {
+				lost_account: 5,
+				rescuer_account: 1,
+				executable_at: 11,
+			}
+			.into(),
+		);
+		assert_eq!(Recovery::proxy(&1), None);
+		assert_noop!(
+			Recovery::claim_recovery(RuntimeOrigin::signed(1), 5),
+			Error::<Test>::AlreadyPending
+		);
+
+		// Too early to finalize
+		System::run_to_block::<AllPalletsWithSystem>(10);
+		assert_noop!(
+			Recovery::finalize_recovery(RuntimeOrigin::signed(1), 5),
+			Error::<Test>::VetoPeriod
+		);
+
+		// The veto period has passed
+		System::run_to_block::<AllPalletsWithSystem>(11);
+		assert_ok!(Recovery::finalize_recovery(RuntimeOrigin::signed(1), 5));
+		System::assert_last_event(
+			Event::<Test>::AccountRecovered { lost_account: 5, rescuer_account: 1 }.into(),
+		);
+		assert_eq!(Recovery::proxy(&1), Some(5));
+		assert!(PendingRecoveries::<Test>::get(&5, &1).is_none());
+	});
+}
+
+#[test]
+fn close_recovery_vetoes_pending_recovery() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Recovery::create_recovery(RuntimeOrigin::signed(5), vec![2, 3, 4], 2, 0));
+		assert_ok!(Recovery::update_recovery(
+			RuntimeOrigin::signed(5),
+			vec![2, 3, 4],
+			2,
+			0,
+			Some(10)
+		));
+		assert_ok!(Recovery::initiate_recovery(RuntimeOrigin::signed(1), 5));
+		assert_ok!(Recovery::vouch_recovery(RuntimeOrigin::signed(2), 5, 1));
+		assert_ok!(Recovery::vouch_recovery(RuntimeOrigin::signed(3), 5, 1));
+		assert_ok!(Recovery::claim_recovery(RuntimeOrigin::signed(1), 5));
+
+		// The account owner notices the claim and vetoes it
+		assert_ok!(Recovery::close_recovery(RuntimeOrigin::signed(5), 1));
+		System::assert_has_event(
+			Event::<Test>::RecoveryVetoed { lost_account: 5, rescuer_account: 1 }.into(),
+		);
+		// The rescuer lost their deposit
+		assert_eq!(Balances::total_balance(&1), 90);
+		assert!(PendingRecoveries::<Test>::get(&5, &1).is_none());
+
+		System::run_to_block::<AllPalletsWithSystem>(11);
+		assert_noop!(
+			Recovery::finalize_recovery(RuntimeOrigin::signed(1), 5),
+			Error::<Test>::NotPending
+		);
+		assert_eq!(Recovery::proxy(&1), None);
+
+		// Removing the configuration also removes the veto period
+		assert_ok!(Recovery::remove_recovery(RuntimeOrigin::signed(5)));
+		assert_eq!(VetoPeriods::<Test>::get(&5), None);
+	});
+}
+
+#[test]
+fn migrate_reserves_to_holds_works() {
+	use frame::{
+		deps::frame_support::{migrations::SteppedMigration, weights::WeightMeter},
+		traits::{fungible::InspectHold, ReservableCurrency},
+	};
+
+	new_test_ext().execute_with(|| {
+		let friends: FriendsOf<Test> = bounded_vec![2, 3, 4];
+		assert_ok!(Balances::reserve(&5, 13));
+		Recoverable::<Test>::insert(
+			5,
+			RecoveryConfig { delay_period: 10, deposit: 13, friends, threshold: 3 },
+		);
+		assert_ok!(Balances::reserve(&1, 10));
+		ActiveRecoveries::<Test>::insert(
+			5,
+			1,
+			ActiveRecovery { created: 1, deposit: 10, friends: Default::default() },
+		);
+
+		let mut cursor = None;
+		for _ in 0..10 {
+			cursor = migrations::v1::LazyMigrationV0ToV1::<Test, Balances>::step(
+				cursor,
+				&mut WeightMeter::new(),
+			)
+			.unwrap();
+			if cursor.is_none() {
+				break
+			}
+		}
+		assert!(cursor.is_none());
+
+		assert_eq!(StorageVersion::get::<Recovery>(), 1);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ConfigDeposit.into(), &5), 13);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::RecoveryDeposit.into(), &1), 10);
+		// Holds are counted in the reserved balance, so nothing is left reserved outside of them.
+		assert_eq!(Balances::reserved_balance(5), 13);
+		assert_eq!(Balances::reserved_balance(1), 10);
+
+		// The deposits can be released through the pallet as usual.
+		assert_ok!(Recovery::close_recovery(RuntimeOrigin::signed(5), 1));
+		assert_eq!(Balances::total_balance(&1), 90);
+		assert_ok!(Recovery::remove_recovery(RuntimeOrigin::signed(5)));
+		assert_eq!(Balances::free_balance(5), 110);
+	});
+}
@@ -175,4 +175,44 @@ impl<T: frame_system::Config> pallet_treasury::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Storage: AssetRate ConversionRateToNative (r:1 w:0)
+	/// Proof: AssetRate ConversionRateToNative (max_values: None, max_size: Some(1237), added: 3712, mode: MaxEncodedLen)
+	/// Storage: Treasury RecurringSpendCount (r:1 w:1)
+	/// Proof: Treasury RecurringSpendCount (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: Treasury RecurringSpends (r:0 w:1)
+	/// Proof: Treasury RecurringSpends (max_values: None, max_size: Some(1847), added: 4322, mode: MaxEncodedLen)
+	fn spend_recurring() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `4702`
+		Weight::from_parts(222_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 4702))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: Treasury RecurringSpends (r:1 w:1)
+	/// Proof: Treasury RecurringSpends (max_values: None, max_size: Some(1847), added: 4322, mode: MaxEncodedLen)
+	/// Storage: Treasury SpendCount (r:1 w:1)
+	/// Proof: Treasury SpendCount (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: Treasury Spends (r:0 w:1)
+	/// Proof: Treasury Spends (max_values: None, max_size: Some(1848), added: 4323, mode: MaxEncodedLen)
+	fn claim_recurring_spend() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `5312`
+		Weight::from_parts(222_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 5312))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: Treasury RecurringSpends (r:1 w:1)
+	/// Proof: Treasury RecurringSpends (max_values: None, max_size: Some(1847), added: 4322, mode: MaxEncodedLen)
+	/// Storage: AssetRate ConversionRateToNative (r:1 w:0)
+	/// Proof: AssetRate ConversionRateToNative (max_values: None, max_size: Some(1237), added: 3712, mode: MaxEncodedLen)
+	fn cancel_recurring_spend() -> Weight {
+		// Proof Size summary in bytes:
+		//  Estimated: `5312`
+		Weight::from_parts(222_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 5312))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 }
@@ -378,4 +378,10 @@ impl<T: frame_system::Config> pallet_xcm::WeightInfo for WeightInfo<T> {
 	fn remove_authorized_alias() -> Weight {
 		Weight::from_parts(100_000, 0)
 	}
+	fn expire_query() -> Weight {
+		Weight::from_parts(100_000, 0)
+	}
+	fn expire_query_with_timeout_notify() -> Weight {
+		Weight::from_parts(100_000, 0)
+	}
 }
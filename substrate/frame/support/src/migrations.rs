@@ -19,8 +19,9 @@ use crate::{
 	defensive,
 	storage::{storage_prefix, transactional::with_transaction_opaque_err},
 	traits::{
-		Defensive, GetStorageVersion, NoStorageVersionSet, PalletInfoAccess, SafeMode,
-		StorageVersion,
+		fungible::{InspectHold, MutateHold},
+		Defensive, GetStorageVersion, NoStorageVersionSet, PalletInfoAccess,
+		ReservableCurrency, SafeMode, StorageVersion,
 	},
 	weights::{RuntimeDbWeight, Weight, WeightMeter},
 };
@@ -31,7 +32,7 @@ use impl_trait_for_tuples::impl_for_tuples;
 use sp_arithmetic::traits::Bounded;
 use sp_core::Get;
 use sp_io::{hashing::twox_128, storage::clear_prefix, KillStorageResult};
-use sp_runtime::traits::Zero;
+use sp_runtime::traits::{Saturating, Zero};
 
 /// Handles storage migration pallet versioning.
 ///
@@ -482,6 +483,45 @@ impl<P: Get<&'static str>, S: Get<&'static str>, DbWeight: Get<RuntimeDbWeight>>
 	}
 }
 
+/// Moves `amount` of `who` from a reserve of `OldCurrency` to a hold of `NewCurrency` with
+/// `reason`, and returns the amount which is now held.
+///
+/// This is meant for migrations of pallets replacing a [`ReservableCurrency`] by a [`MutateHold`]
+/// for their deposits. Holds are usually part of the reserved balance, so only the reserve which
+/// is not backing any hold of `NewCurrency` is moved, and never more than that: the deposits of
+/// pallets which were migrated already stay held whatever the order of the migrations. Whatever
+/// could not be unreserved is not held either, and any part of `amount` which cannot be held is
+/// left in the free balance of `who`.
+pub fn reserve_to_hold<AccountId, OldCurrency, NewCurrency>(
+	who: &AccountId,
+	amount: OldCurrency::Balance,
+	reason: &NewCurrency::Reason,
+) -> OldCurrency::Balance
+where
+	AccountId: core::fmt::Debug,
+	OldCurrency: ReservableCurrency<AccountId>,
+	NewCurrency: MutateHold<AccountId, Balance = OldCurrency::Balance>,
+{
+	let unheld = OldCurrency::reserved_balance(who)
+		.saturating_sub(NewCurrency::total_balance_on_hold(who));
+	let capped = amount.min(unheld);
+	if capped < amount {
+		log::warn!("{:?} of the deposit of {:?} was not reserved", amount - capped, who);
+	}
+	if capped.is_zero() {
+		return Zero::zero()
+	}
+	let missing = OldCurrency::unreserve(who, capped);
+	let amount = capped.saturating_sub(missing);
+	match NewCurrency::hold(reason, who, amount) {
+		Ok(()) => amount,
+		Err(e) => {
+			log::error!("Failed to hold {:?} of {:?}: {:?}", amount, who, e);
+			Zero::zero()
+		},
+	}
+}
+
 /// A migration that can proceed in multiple steps.
 pub trait SteppedMigration {
 	/// The cursor type that stores the progress (aka. state) of this migration.
@@ -147,6 +147,31 @@ pub trait Inspect<AccountId> {
 			.and_then(|v| V::decode(&mut &v[..]).ok())
 	}
 
+	/// Returns the metadata of `item` of `collection`.
+	///
+	/// By default this is `None`; no metadata is defined.
+	fn item_metadata(_collection: &Self::CollectionId, _item: &Self::ItemId) -> Option<Vec<u8>> {
+		None
+	}
+
+	/// Returns the metadata of `collection`.
+	///
+	/// By default this is `None`; no metadata is defined.
+	fn collection_metadata(_collection: &Self::CollectionId) -> Option<Vec<u8>> {
+		None
+	}
+
+	/// Returns the keys and values of all attributes of `item` of `collection` which `attribute`
+	/// returns.
+	///
+	/// By default this is empty; no attributes are defined.
+	fn item_attributes(
+		_collection: &Self::CollectionId,
+		_item: &Self::ItemId,
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		Vec::new()
+	}
+
 	/// Returns `true` if the `item` of `collection` may be transferred.
 	///
 	/// Default implementation is that all items are transferable.
@@ -350,6 +350,128 @@ mod benchmarks {
 		Ok(())
 	}
 
+	/// This benchmark is short-circuited if `SpendOrigin` cannot provide
+	/// a successful origin, in which case `spend_recurring` is un-callable and can use weight=0.
+	#[benchmark]
+	fn spend_recurring() -> Result<(), BenchmarkError> {
+		let origin =
+			T::SpendOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let (asset_kind, amount, beneficiary, beneficiary_lookup) =
+			create_spend_arguments::<T, _>(SEED);
+		T::BalanceConverter::ensure_successful(asset_kind.clone());
+		let period: BlockNumberFor<T, I> = 1u32.into();
+
+		#[extrinsic_call]
+		_(
+			origin as T::RuntimeOrigin,
+			Box::new(asset_kind.clone()),
+			amount,
+			Box::new(beneficiary_lookup),
+			None,
+			period,
+			1,
+		);
+
+		let valid_from = T::BlockNumberProvider::current_block_number();
+		assert_last_event::<T, I>(
+			Event::RecurringSpendApproved {
+				index: 0,
+				asset_kind,
+				amount,
+				beneficiary,
+				valid_from,
+				period,
+				periods: 1,
+			}
+			.into(),
+		);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn claim_recurring_spend() -> Result<(), BenchmarkError> {
+		let (asset_kind, amount, _, beneficiary_lookup) = create_spend_arguments::<T, _>(SEED);
+		T::BalanceConverter::ensure_successful(asset_kind.clone());
+
+		let spend_exists = if let Ok(origin) = T::SpendOrigin::try_successful_origin() {
+			Treasury::<T, _>::spend_recurring(
+				origin,
+				Box::new(asset_kind),
+				amount,
+				Box::new(beneficiary_lookup),
+				None,
+				1u32.into(),
+				1,
+			)?;
+
+			true
+		} else {
+			false
+		};
+
+		let caller: T::AccountId = account("caller", 0, SEED);
+
+		#[block]
+		{
+			let res = Treasury::<T, _>::claim_recurring_spend(
+				RawOrigin::Signed(caller.clone()).into(),
+				0u32,
+			);
+
+			if spend_exists {
+				assert_ok!(res);
+			} else {
+				assert_err!(res, crate::Error::<T, _>::InvalidIndex);
+			}
+		}
+
+		if spend_exists {
+			assert!(Spends::<T, I>::get(0).is_some());
+			assert_last_event::<T, I>(Event::RecurringSpendCompleted { index: 0 }.into());
+		}
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn cancel_recurring_spend() -> Result<(), BenchmarkError> {
+		let (asset_kind, amount, _, beneficiary_lookup) = create_spend_arguments::<T, _>(SEED);
+		T::BalanceConverter::ensure_successful(asset_kind.clone());
+		let spend_exists = if let Ok(origin) = T::SpendOrigin::try_successful_origin() {
+			Treasury::<T, _>::spend_recurring(
+				origin,
+				Box::new(asset_kind),
+				amount,
+				Box::new(beneficiary_lookup),
+				None,
+				1u32.into(),
+				1,
+			)?;
+			assert!(RecurringSpends::<T, I>::get(0).is_some());
+
+			true
+		} else {
+			false
+		};
+
+		let origin =
+			T::RejectOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[block]
+		{
+			let res = Treasury::<T, _>::cancel_recurring_spend(origin as T::RuntimeOrigin, 0u32);
+
+			if spend_exists {
+				assert_ok!(res);
+			} else {
+				assert_err!(res, crate::Error::<T, _>::InvalidIndex);
+			}
+		}
+
+		assert!(RecurringSpends::<T, I>::get(0).is_none());
+		Ok(())
+	}
+
 	impl_benchmark_test_suite!(
 		Treasury,
 		crate::tests::ExtBuilder::default().build(),
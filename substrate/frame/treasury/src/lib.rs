@@ -69,6 +69,12 @@
 //! [`pallet::Config::Paymaster`]. To claim these spends, the `payout` dispatchable should be called
 //! within some temporal bounds, starting from the moment they become valid and within one
 //! [`pallet::Config::PayoutPeriod`].
+//!
+//! Recurring spends, approved with the `spend_recurring` dispatchable, pay a fixed amount once
+//! per period for a given number of periods. Every installment that became due can be turned
+//! into a regular spend with `claim_recurring_spend`, after which it is paid out and checked with
+//! `payout` and `check_status` like any other spend. The remaining installments can be cancelled
+//! with `cancel_recurring_spend`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -90,8 +96,8 @@ use scale_info::TypeInfo;
 use alloc::{boxed::Box, collections::btree_map::BTreeMap};
 use sp_runtime::{
 	traits::{
-		AccountIdConversion, BlockNumberProvider, CheckedAdd, One, Saturating, StaticLookup,
-		UniqueSaturatedInto, Zero,
+		AccountIdConversion, BlockNumberProvider, CheckedAdd, CheckedMul, One, Saturating,
+		StaticLookup, UniqueSaturatedInto, Zero,
 	},
 	PerThing, Permill, RuntimeDebug,
 };
@@ -100,8 +106,11 @@ use frame_support::{
 	dispatch::{DispatchResult, DispatchResultWithPostInfo},
 	ensure, print,
 	traits::{
-		tokens::Pay, Currency, ExistenceRequirement::KeepAlive, Get, Imbalance, OnUnbalanced,
-		ReservableCurrency, WithdrawReasons,
+		fungible::MutateHold,
+		tokens::{ConversionFromAssetBalance, Pay, Precision},
+		Currency,
+		ExistenceRequirement::KeepAlive,
+		Get, Imbalance, OnUnbalanced, ReservableCurrency, WithdrawReasons,
 	},
 	weights::Weight,
 	BoundedVec, PalletId,
@@ -159,7 +168,7 @@ pub struct Proposal<AccountId, Balance> {
 	value: Balance,
 	/// The account to whom the payment should be made if the proposal is accepted.
 	beneficiary: AccountId,
-	/// The amount held on deposit (reserved) for making this proposal.
+	/// The amount held on deposit for making this proposal.
 	bond: Balance,
 }
 
@@ -196,6 +205,29 @@ pub struct SpendStatus<AssetKind, AssetBalance, Beneficiary, BlockNumber, Paymen
 /// Index of an approved treasury spend.
 pub type SpendIndex = u32;
 
+/// Info regarding an approved recurring treasury spend.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, MaxEncodedLen, RuntimeDebug, TypeInfo)]
+pub struct RecurringSpendStatus<AssetKind, AssetBalance, Beneficiary, BlockNumber> {
+	/// The kind of asset to be spent.
+	asset_kind: AssetKind,
+	/// The asset amount paid per period.
+	amount: AssetBalance,
+	/// The beneficiary of the spend.
+	beneficiary: Beneficiary,
+	/// The block number from which the first installment can be claimed.
+	valid_from: BlockNumber,
+	/// The number of blocks between two installments.
+	period: BlockNumber,
+	/// The total number of installments.
+	periods: u32,
+	/// The number of installments which have already been turned into spends.
+	claimed: u32,
+}
+
+/// Index of an approved recurring treasury spend.
+pub type RecurringSpendIndex = u32;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -206,7 +238,11 @@ pub mod pallet {
 	};
 	use frame_system::pallet_prelude::{ensure_signed, OriginFor};
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
 	#[pallet::config]
@@ -214,6 +250,17 @@ pub mod pallet {
 		/// The staking balance.
 		type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
 
+		/// The currency holding the bonds of proposals, which were reserved with
+		/// [`Config::Currency`] before.
+		type NativeBalance: MutateHold<
+			Self::AccountId,
+			Balance = BalanceOf<Self, I>,
+			Reason = Self::RuntimeHoldReason,
+		>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason<I>>;
+
 		/// Origin from which rejections must come.
 		type RejectOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
@@ -352,6 +399,26 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// The count of recurring spends that have been made.
+	#[pallet::storage]
+	pub type RecurringSpendCount<T, I = ()> = StorageValue<_, RecurringSpendIndex, ValueQuery>;
+
+	/// Recurring spends that have been approved and still have installments to be claimed.
+	// Hasher: Twox safe since `RecurringSpendIndex` is an internal count based index.
+	#[pallet::storage]
+	pub type RecurringSpends<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Twox64Concat,
+		RecurringSpendIndex,
+		RecurringSpendStatus<
+			T::AssetKind,
+			AssetBalanceOf<T, I>,
+			T::Beneficiary,
+			BlockNumberFor<T, I>,
+		>,
+		OptionQuery,
+	>;
+
 	/// The blocknumber for the last triggered spend period.
 	#[pallet::storage]
 	pub type LastSpendPeriod<T, I = ()> = StorageValue<_, BlockNumberFor<T, I>, OptionQuery>;
@@ -414,6 +481,26 @@ pub mod pallet {
 		/// A spend was processed and removed from the storage. It might have been successfully
 		/// paid or it may have expired.
 		SpendProcessed { index: SpendIndex },
+		/// A new recurring asset spend has been approved.
+		RecurringSpendApproved {
+			index: RecurringSpendIndex,
+			asset_kind: T::AssetKind,
+			amount: AssetBalanceOf<T, I>,
+			beneficiary: T::Beneficiary,
+			valid_from: BlockNumberFor<T, I>,
+			period: BlockNumberFor<T, I>,
+			periods: u32,
+		},
+		/// An installment of a recurring spend has been turned into the spend `spend_index`.
+		RecurringSpendClaimed {
+			index: RecurringSpendIndex,
+			installment: u32,
+			spend_index: SpendIndex,
+		},
+		/// All installments of a recurring spend have been claimed.
+		RecurringSpendCompleted { index: RecurringSpendIndex },
+		/// The remaining installments of a recurring spend were cancelled.
+		RecurringSpendCancelled { index: RecurringSpendIndex },
 	}
 
 	/// Error for the treasury pallet.
@@ -442,6 +529,16 @@ pub mod pallet {
 		NotAttempted,
 		/// The payment has neither failed nor succeeded yet.
 		Inconclusive,
+		/// A recurring spend must have a non-zero period and number of periods.
+		InvalidSchedule,
+	}
+
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason<I: 'static = ()> {
+		/// The funds are held as the bond of a proposal.
+		#[codec(index = 0)]
+		ProposalBond,
 	}
 
 	#[pallet::hooks]
@@ -664,47 +761,9 @@ pub mod pallet {
 					.map_err(|_| Error::<T, I>::FailedToConvertBalance)?;
 
 			ensure!(native_amount <= max_amount, Error::<T, I>::InsufficientPermission);
+			ensure_within_spend_limit::<T, I>(max_amount, native_amount)?;
 
-			with_context::<SpendContext<BalanceOf<T, I>>, _>(|v| {
-				let context = v.or_default();
-				// We group based on `max_amount`, to distinguish between different kind of
-				// origins. (assumes that all origins have different `max_amount`)
-				//
-				// Worst case is that we reject some "valid" request.
-				let spend = context.spend_in_context.entry(max_amount).or_default();
-
-				// Ensure that we don't overflow nor use more than `max_amount`
-				if spend.checked_add(&native_amount).map(|s| s > max_amount).unwrap_or(true) {
-					Err(Error::<T, I>::InsufficientPermission)
-				} else {
-					*spend = spend.saturating_add(native_amount);
-					Ok(())
-				}
-			})
-			.unwrap_or(Ok(()))?;
-
-			let index = SpendCount::<T, I>::get();
-			Spends::<T, I>::insert(
-				index,
-				SpendStatus {
-					asset_kind: *asset_kind.clone(),
-					amount,
-					beneficiary: beneficiary.clone(),
-					valid_from,
-					expire_at,
-					status: PaymentState::Pending,
-				},
-			);
-			SpendCount::<T, I>::put(index + 1);
-
-			Self::deposit_event(Event::AssetSpendApproved {
-				index,
-				asset_kind: *asset_kind,
-				amount,
-				beneficiary,
-				valid_from,
-				expire_at,
-			});
+			Self::insert_spend(*asset_kind, amount, beneficiary, valid_from, expire_at);
 			Ok(())
 		}
 
@@ -839,6 +898,209 @@ pub mod pallet {
 			Self::deposit_event(Event::<T, I>::AssetSpendVoided { index });
 			Ok(())
 		}
+
+		/// Propose and approve a recurring spend of treasury funds.
+		///
+		/// ## Dispatch Origin
+		///
+		/// Must be [`Config::SpendOrigin`] with the `Success` value being at least the total
+		/// `amount * periods` of `asset_kind` in the native asset. The amount of `asset_kind` is
+		/// converted for assertion using the [`Config::BalanceConverter`].
+		///
+		/// ## Details
+		///
+		/// Create an approved recurring spend, paying `amount` of `asset_kind` to a designated
+		/// beneficiary once every `period` blocks, `periods` times. Each installment becomes
+		/// claimable at `valid_from + n * period` and has to be turned into a regular spend with
+		/// the `claim_recurring_spend` dispatchable, which is then paid out with `payout`.
+		///
+		/// ### Parameters
+		/// - `asset_kind`: An indicator of the specific asset class to be spent.
+		/// - `amount`: The amount to be transferred to the `beneficiary` per period.
+		/// - `beneficiary`: The beneficiary of the spend.
+		/// - `valid_from`: The block number from which the first installment can be claimed. If
+		///   `None`, the first installment can be claimed immediately after approval.
+		/// - `period`: The number of blocks between two installments.
+		/// - `periods`: The total number of installments.
+		///
+		/// ## Events
+		///
+		/// Emits [`Event::RecurringSpendApproved`] if successful.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::spend_recurring())]
+		pub fn spend_recurring(
+			origin: OriginFor<T>,
+			asset_kind: Box<T::AssetKind>,
+			#[pallet::compact] amount: AssetBalanceOf<T, I>,
+			beneficiary: Box<BeneficiaryLookupOf<T, I>>,
+			valid_from: Option<BlockNumberFor<T, I>>,
+			period: BlockNumberFor<T, I>,
+			periods: u32,
+		) -> DispatchResult {
+			let max_amount = T::SpendOrigin::ensure_origin(origin)?;
+			let beneficiary = T::BeneficiaryLookup::lookup(*beneficiary)?;
+			ensure!(!period.is_zero() && periods > 0, Error::<T, I>::InvalidSchedule);
+
+			let valid_from =
+				valid_from.unwrap_or_else(|| T::BlockNumberProvider::current_block_number());
+			let native_amount = Self::recurring_native_amount(&asset_kind, amount, periods)?;
+			ensure!(native_amount <= max_amount, Error::<T, I>::InsufficientPermission);
+			ensure_within_spend_limit::<T, I>(max_amount, native_amount)?;
+
+			let index = RecurringSpendCount::<T, I>::get();
+			RecurringSpends::<T, I>::insert(
+				index,
+				RecurringSpendStatus {
+					asset_kind: *asset_kind.clone(),
+					amount,
+					beneficiary: beneficiary.clone(),
+					valid_from,
+					period,
+					periods,
+					claimed: 0,
+				},
+			);
+			RecurringSpendCount::<T, I>::put(index + 1);
+
+			Self::deposit_event(Event::RecurringSpendApproved {
+				index,
+				asset_kind: *asset_kind,
+				amount,
+				beneficiary,
+				valid_from,
+				period,
+				periods,
+			});
+			Ok(())
+		}
+
+		/// Turn the next due installment of a recurring spend into a regular spend.
+		///
+		/// ## Dispatch Origin
+		///
+		/// Must be signed.
+		///
+		/// ## Details
+		///
+		/// The created spend is valid from the block at which the installment became due and has
+		/// to be claimed with `payout` within one [`Config::PayoutPeriod`] from now. Once the
+		/// last installment is claimed, the recurring spend is removed from the storage.
+		///
+		/// ### Parameters
+		/// - `index`: The recurring spend index.
+		///
+		/// ## Events
+		///
+		/// Emits [`Event::AssetSpendApproved`] and [`Event::RecurringSpendClaimed`] if
+		/// successful, and [`Event::RecurringSpendCompleted`] if it was the last installment.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::claim_recurring_spend())]
+		pub fn claim_recurring_spend(
+			origin: OriginFor<T>,
+			index: RecurringSpendIndex,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let mut recurring =
+				RecurringSpends::<T, I>::get(index).ok_or(Error::<T, I>::InvalidIndex)?;
+			let now = T::BlockNumberProvider::current_block_number();
+
+			let installment = recurring.claimed;
+			let due = recurring
+				.valid_from
+				.saturating_add(recurring.period.saturating_mul(installment.into()));
+			ensure!(now >= due, Error::<T, I>::EarlyPayout);
+			let expire_at = now.saturating_add(T::PayoutPeriod::get());
+
+			let spend_index = Self::insert_spend(
+				recurring.asset_kind.clone(),
+				recurring.amount,
+				recurring.beneficiary.clone(),
+				due,
+				expire_at,
+			);
+			Self::deposit_event(Event::RecurringSpendClaimed { index, installment, spend_index });
+
+			recurring.claimed.saturating_inc();
+			if recurring.claimed >= recurring.periods {
+				RecurringSpends::<T, I>::remove(index);
+				Self::deposit_event(Event::RecurringSpendCompleted { index });
+			} else {
+				RecurringSpends::<T, I>::insert(index, recurring);
+			}
+			Ok(())
+		}
+
+		/// Cancel the remaining installments of a recurring spend.
+		///
+		/// ## Dispatch Origin
+		///
+		/// Must be either [`Config::RejectOrigin`] or [`Config::SpendOrigin`] with the `Success`
+		/// value being at least the total amount of the recurring spend in the native asset, i.e.
+		/// an origin that could have approved it.
+		///
+		/// ## Details
+		///
+		/// Installments which have already been claimed are regular spends and can be voided
+		/// with `void_spend`.
+		///
+		/// ### Parameters
+		/// - `index`: The recurring spend index.
+		///
+		/// ## Events
+		///
+		/// Emits [`Event::RecurringSpendCancelled`] if successful.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::cancel_recurring_spend())]
+		pub fn cancel_recurring_spend(
+			origin: OriginFor<T>,
+			index: RecurringSpendIndex,
+		) -> DispatchResult {
+			let maybe_max_amount = match T::RejectOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(T::SpendOrigin::ensure_origin(origin)?),
+			};
+			let recurring =
+				RecurringSpends::<T, I>::get(index).ok_or(Error::<T, I>::InvalidIndex)?;
+
+			if let Some(max_amount) = maybe_max_amount {
+				let native_amount = Self::recurring_native_amount(
+					&recurring.asset_kind,
+					recurring.amount,
+					recurring.periods,
+				)?;
+				ensure!(native_amount <= max_amount, Error::<T, I>::InsufficientPermission);
+			}
+
+			RecurringSpends::<T, I>::remove(index);
+			Self::deposit_event(Event::<T, I>::RecurringSpendCancelled { index });
+			Ok(())
+		}
+	}
+
+	/// Ensure that the origin with the spend limit `max_amount` does not spend more than that in
+	/// total within the current dispatch context, accounting for `native_amount`.
+	fn ensure_within_spend_limit<T: Config<I>, I: 'static>(
+		max_amount: BalanceOf<T, I>,
+		native_amount: BalanceOf<T, I>,
+	) -> DispatchResult {
+		with_context::<SpendContext<BalanceOf<T, I>>, _>(|v| {
+			let context = v.or_default();
+			// We group based on `max_amount`, to distinguish between different kind of
+			// origins. (assumes that all origins have different `max_amount`)
+			//
+			// Worst case is that we reject some "valid" request.
+			let spend = context.spend_in_context.entry(max_amount).or_default();
+
+			// Ensure that we don't overflow nor use more than `max_amount`
+			if spend.checked_add(&native_amount).map(|s| s > max_amount).unwrap_or(true) {
+				Err(Error::<T, I>::InsufficientPermission)
+			} else {
+				*spend = spend.saturating_add(native_amount);
+				Ok(())
+			}
+		})
+		.unwrap_or(Ok(()))?;
+		Ok(())
 	}
 }
 
@@ -899,6 +1161,70 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Approvals::<T, I>::get()
 	}
 
+	/// Insert a new approved spend and return its index.
+	fn insert_spend(
+		asset_kind: T::AssetKind,
+		amount: AssetBalanceOf<T, I>,
+		beneficiary: T::Beneficiary,
+		valid_from: BlockNumberFor<T, I>,
+		expire_at: BlockNumberFor<T, I>,
+	) -> SpendIndex {
+		let index = SpendCount::<T, I>::get();
+		Spends::<T, I>::insert(
+			index,
+			SpendStatus {
+				asset_kind: asset_kind.clone(),
+				amount,
+				beneficiary: beneficiary.clone(),
+				valid_from,
+				expire_at,
+				status: PaymentState::Pending,
+			},
+		);
+		SpendCount::<T, I>::put(index + 1);
+
+		Self::deposit_event(Event::AssetSpendApproved {
+			index,
+			asset_kind,
+			amount,
+			beneficiary,
+			valid_from,
+			expire_at,
+		});
+		index
+	}
+
+	/// The total amount of a recurring spend in the native asset.
+	fn recurring_native_amount(
+		asset_kind: &T::AssetKind,
+		amount: AssetBalanceOf<T, I>,
+		periods: u32,
+	) -> Result<BalanceOf<T, I>, Error<T, I>> {
+		T::BalanceConverter::from_asset_balance(amount, asset_kind.clone())
+			.ok()
+			.and_then(|per_period| per_period.checked_mul(&periods.into()))
+			.ok_or(Error::<T, I>::FailedToConvertBalance)
+	}
+
+	/// Return the `bond` of a proposal to its `proposer`.
+	///
+	/// Whatever part of it is not held is unreserved with [`Config::Currency`] instead, in case
+	/// the bond was not migrated to a hold yet.
+	fn release_bond(proposer: &T::AccountId, bond: BalanceOf<T, I>) {
+		if bond.is_zero() {
+			return
+		}
+		let released = T::NativeBalance::release(
+			&HoldReason::<I>::ProposalBond.into(),
+			proposer,
+			bond,
+			Precision::BestEffort,
+		)
+		.unwrap_or_default();
+		let err_amount = T::Currency::unreserve(proposer, bond.saturating_sub(released));
+		debug_assert!(err_amount.is_zero());
+	}
+
 	/// Spend some money! returns number of approvals before spend.
 	pub fn spend_funds(
 		spend_periods_passed: BlockNumberFor<T, I>,
@@ -924,8 +1250,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 						Proposals::<T, I>::remove(index);
 
 						// return their deposit.
-						let err_amount = T::Currency::unreserve(&p.proposer, p.bond);
-						debug_assert!(err_amount.is_zero());
+						Self::release_bond(&p.proposer, p.bond);
 
 						// provide the allocation.
 						imbalance.subsume(T::Currency::deposit_creating(&p.beneficiary, p.value));
@@ -1003,6 +1328,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
 		Self::try_state_proposals()?;
 		Self::try_state_spends()?;
+		Self::try_state_recurring_spends()?;
 
 		Ok(())
 	}
@@ -1076,6 +1402,31 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
 		Ok(())
 	}
+
+	/// ## Invariants of recurring spend storage items
+	///
+	/// 1. Each entry in [`RecurringSpends`] should be saved under a key strictly less than
+	/// current [`RecurringSpendCount`].
+	/// 2. For each recurring spend contained in [`RecurringSpends`] we should have
+	/// recurring.claimed < recurring.periods.
+	#[cfg(any(feature = "try-runtime", test))]
+	fn try_state_recurring_spends() -> Result<(), sp_runtime::TryRuntimeError> {
+		let current_recurring_spend_count = RecurringSpendCount::<T, I>::get();
+
+		RecurringSpends::<T, I>::iter().try_for_each(|(index, recurring)| -> DispatchResult {
+			ensure!(
+				current_recurring_spend_count > index,
+				"`RecurringSpendCount` should by strictly greater than any RecurringSpendIndex used as a key for `RecurringSpends`."
+			);
+			ensure!(
+				recurring.claimed < recurring.periods,
+				"Completed recurring spends must be removed from storage."
+			);
+			Ok(())
+		})?;
+
+		Ok(())
+	}
 }
 
 impl<T: Config<I>, I: 'static> OnUnbalanced<NegativeImbalanceOf<T, I>> for Pallet<T, I> {
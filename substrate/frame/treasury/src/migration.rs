@@ -133,3 +133,90 @@ pub mod cleanup_proposals {
 		}
 	}
 }
+
+pub mod v1 {
+	use super::*;
+	use frame_support::{
+		migrations::{reserve_to_hold, MigrationId, SteppedMigration, SteppedMigrationError},
+		traits::{GetStorageVersion, PalletInfoAccess, StorageVersion},
+		weights::WeightMeter,
+		StorageHasher, Twox128,
+	};
+
+	/// The pallet identifier of the migrations, see [`MigrationId`].
+	pub const PALLET_MIGRATIONS_ID: &[u8; 15] = b"pallet-treasury";
+
+	/// Converts the bonds of the remaining proposals from reserves of [`Config::Currency`] into
+	/// holds of [`Config::NativeBalance`], one proposal at a time.
+	///
+	/// The cursor is the index of the last converted proposal. The identifier includes the hash of
+	/// the pallet name, so that the migration can be scheduled for several instances.
+	pub struct LazyMigrationV0ToV1<T, I = ()>(PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> SteppedMigration for LazyMigrationV0ToV1<T, I> {
+		type Cursor = ProposalIndex;
+		type Identifier = (MigrationId<15>, [u8; 16]);
+
+		fn id() -> Self::Identifier {
+			(
+				MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 0, version_to: 1 },
+				Twox128::hash(Pallet::<T, I>::name().as_bytes()),
+			)
+		}
+
+		fn step(
+			mut cursor: Option<Self::Cursor>,
+			meter: &mut WeightMeter,
+		) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+			let (id, _) = Self::id();
+			if Pallet::<T, I>::on_chain_storage_version() != id.version_from as u16 {
+				return Ok(None)
+			}
+
+			// Reads: Get proposal + Unreserve + Hold, writes: Unreserve + Hold
+			let required = T::DbWeight::get().reads_writes(3, 2);
+			if meter.remaining().any_lt(required) {
+				return Err(SteppedMigrationError::InsufficientWeight { required })
+			}
+
+			while meter.try_consume(required).is_ok() {
+				#[allow(deprecated)]
+				let mut iter = match cursor {
+					Some(last_index) =>
+						Proposals::<T, I>::iter_from(Proposals::<T, I>::hashed_key_for(last_index)),
+					None => Proposals::<T, I>::iter(),
+				};
+				let Some((index, proposal)) = iter.next() else {
+					StorageVersion::new(id.version_to as u16).put::<Pallet<T, I>>();
+					return Ok(None)
+				};
+
+				reserve_to_hold::<_, T::Currency, T::NativeBalance>(
+					&proposal.proposer,
+					proposal.bond,
+					&HoldReason::<I>::ProposalBond.into(),
+				);
+				cursor = Some(index);
+			}
+
+			Ok(cursor)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			use frame_support::traits::fungible::InspectHold;
+
+			#[allow(deprecated)]
+			for (_, proposal) in Proposals::<T, I>::iter() {
+				ensure!(
+					T::NativeBalance::balance_on_hold(
+						&HoldReason::<I>::ProposalBond.into(),
+						&proposal.proposer
+					) >= proposal.bond,
+					"proposal bond is not held"
+				);
+			}
+			Ok(())
+		}
+	}
+}
@@ -180,6 +180,8 @@ impl<N: Get<u64>> ConversionFromAssetBalance<u64, u32, u64> for MulBy<N> {
 impl Config for Test {
 	type PalletId = TreasuryPalletId;
 	type Currency = pallet_balances::Pallet<Test>;
+	type NativeBalance = pallet_balances::Pallet<Test>;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type RejectOrigin = frame_system::EnsureRoot<u128>;
 	type RuntimeEvent = RuntimeEvent;
 	type SpendPeriod = ConstU64<2>;
@@ -1046,3 +1048,245 @@ fn multiple_spend_periods_work() {
 		assert_eq!(LastSpendPeriod::<Test>::get(), Some(8));
 	});
 }
+
+#[test]
+fn spend_recurring_origin_permissioning_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			Treasury::spend_recurring(
+				RuntimeOrigin::signed(1),
+				Box::new(1),
+				1,
+				Box::new(6),
+				None,
+				3,
+				2
+			),
+			BadOrigin
+		);
+		// the total amount of all periods is checked against the origin's limit.
+		assert_noop!(
+			Treasury::spend_recurring(
+				RuntimeOrigin::signed(10),
+				Box::new(1),
+				1,
+				Box::new(6),
+				None,
+				3,
+				3
+			),
+			Error::<Test, _>::InsufficientPermission
+		);
+		assert_noop!(
+			Treasury::spend_recurring(
+				RuntimeOrigin::signed(10),
+				Box::new(1),
+				1,
+				Box::new(6),
+				None,
+				0,
+				2
+			),
+			Error::<Test, _>::InvalidSchedule
+		);
+		assert_noop!(
+			Treasury::spend_recurring(
+				RuntimeOrigin::signed(10),
+				Box::new(1),
+				1,
+				Box::new(6),
+				None,
+				3,
+				0
+			),
+			Error::<Test, _>::InvalidSchedule
+		);
+	});
+}
+
+#[test]
+fn spend_recurring_claim_and_payout_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		// approve `1` coin of asset `1` to beneficiary `6` every `3` blocks, `2` times.
+		assert_ok!(Treasury::spend_recurring(
+			RuntimeOrigin::signed(10),
+			Box::new(1),
+			1,
+			Box::new(6),
+			None,
+			3,
+			2
+		));
+		System::assert_last_event(
+			Event::<Test, _>::RecurringSpendApproved {
+				index: 0,
+				asset_kind: 1,
+				amount: 1,
+				beneficiary: 6,
+				valid_from: 1,
+				period: 3,
+				periods: 2,
+			}
+			.into(),
+		);
+
+		// the first installment becomes a regular spend and is paid out.
+		assert_ok!(Treasury::claim_recurring_spend(RuntimeOrigin::signed(1), 0));
+		System::assert_has_event(
+			Event::<Test, _>::AssetSpendApproved {
+				index: 0,
+				asset_kind: 1,
+				amount: 1,
+				beneficiary: 6,
+				valid_from: 1,
+				expire_at: 6,
+			}
+			.into(),
+		);
+		System::assert_last_event(
+			Event::<Test, _>::RecurringSpendClaimed { index: 0, installment: 0, spend_index: 0 }
+				.into(),
+		);
+		assert_ok!(Treasury::payout(RuntimeOrigin::signed(1), 0));
+		assert_eq!(paid(6, 1), 1);
+
+		// the second installment is not due yet.
+		assert_noop!(
+			Treasury::claim_recurring_spend(RuntimeOrigin::signed(1), 0),
+			Error::<Test, _>::EarlyPayout
+		);
+
+		System::set_block_number(4);
+		assert_ok!(Treasury::claim_recurring_spend(RuntimeOrigin::signed(1), 0));
+		System::assert_last_event(Event::<Test, _>::RecurringSpendCompleted { index: 0 }.into());
+		assert_eq!(RecurringSpends::<Test, _>::get(0), None);
+		assert_ok!(Treasury::payout(RuntimeOrigin::signed(1), 1));
+		assert_eq!(paid(6, 1), 2);
+
+		// all installments have been claimed.
+		assert_noop!(
+			Treasury::claim_recurring_spend(RuntimeOrigin::signed(1), 0),
+			Error::<Test, _>::InvalidIndex
+		);
+		assert_ok!(Treasury::do_try_state());
+	});
+}
+
+#[test]
+fn late_recurring_spend_claim_is_valid_for_payout_period() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(<Test as Config>::PayoutPeriod::get(), 5);
+		System::set_block_number(1);
+		assert_ok!(Treasury::spend_recurring(
+			RuntimeOrigin::signed(10),
+			Box::new(1),
+			1,
+			Box::new(6),
+			Some(2),
+			1,
+			2
+		));
+
+		// the installment due at block `2` is claimed well after its payout period.
+		System::set_block_number(20);
+		assert_ok!(Treasury::claim_recurring_spend(RuntimeOrigin::signed(1), 0));
+		let spend = Spends::<Test, _>::get(0).unwrap();
+		assert_eq!(spend.valid_from, 2);
+		assert_eq!(spend.expire_at, 25);
+		assert_ok!(Treasury::payout(RuntimeOrigin::signed(1), 0));
+		assert_eq!(paid(6, 1), 1);
+	});
+}
+
+#[test]
+fn cancel_recurring_spend_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		// total of `8` native coins.
+		assert_ok!(Treasury::spend_recurring(
+			RuntimeOrigin::signed(11),
+			Box::new(1),
+			2,
+			Box::new(6),
+			None,
+			3,
+			2
+		));
+		assert_ok!(Treasury::claim_recurring_spend(RuntimeOrigin::signed(1), 0));
+
+		assert_noop!(Treasury::cancel_recurring_spend(RuntimeOrigin::signed(1), 0), BadOrigin);
+		// an origin which could not have approved the spend cannot cancel it.
+		assert_noop!(
+			Treasury::cancel_recurring_spend(RuntimeOrigin::signed(10), 0),
+			Error::<Test, _>::InsufficientPermission
+		);
+		assert_ok!(Treasury::cancel_recurring_spend(RuntimeOrigin::signed(11), 0));
+		System::assert_last_event(Event::<Test, _>::RecurringSpendCancelled { index: 0 }.into());
+		assert_eq!(RecurringSpends::<Test, _>::get(0), None);
+		// the already claimed installment is not affected.
+		assert!(Spends::<Test, _>::get(0).is_some());
+
+		System::set_block_number(4);
+		assert_noop!(
+			Treasury::claim_recurring_spend(RuntimeOrigin::signed(1), 0),
+			Error::<Test, _>::InvalidIndex
+		);
+
+		// the reject origin can cancel any recurring spend.
+		assert_ok!(Treasury::spend_recurring(
+			RuntimeOrigin::signed(11),
+			Box::new(1),
+			2,
+			Box::new(6),
+			None,
+			3,
+			2
+		));
+		assert_ok!(Treasury::cancel_recurring_spend(RuntimeOrigin::root(), 1));
+		assert_noop!(
+			Treasury::cancel_recurring_spend(RuntimeOrigin::root(), 1),
+			Error::<Test, _>::InvalidIndex
+		);
+	});
+}
+
+#[test]
+fn migration_moves_proposal_bonds_to_holds() {
+	use crate::migration::v1::LazyMigrationV0ToV1;
+	use frame_support::{
+		migrations::SteppedMigration,
+		traits::{fungible::InspectHold, GetStorageVersion, StorageVersion},
+		weights::WeightMeter,
+	};
+
+	ExtBuilder::default().build().execute_with(|| {
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		StorageVersion::new(0).put::<Treasury>();
+
+		// a proposal with its bond still reserved.
+		assert_ok!(Balances::reserve(&1, 10));
+		Proposals::<Test>::insert(0, Proposal { proposer: 1, value: 50, beneficiary: 3, bond: 10 });
+		ProposalCount::<Test>::put(1);
+		assert_ok!(Approvals::<Test>::try_append(0));
+
+		let mut cursor = None;
+		loop {
+			cursor = LazyMigrationV0ToV1::<Test>::step(cursor, &mut WeightMeter::new()).unwrap();
+			if cursor.is_none() {
+				break
+			}
+		}
+
+		assert_eq!(Treasury::on_chain_storage_version(), StorageVersion::new(1));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::ProposalBond.into(), &1), 10);
+
+		// the bond is released when the proposal is paid out.
+		go_to_block(2);
+		assert_eq!(Balances::free_balance(3), 50);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 98);
+	});
+}